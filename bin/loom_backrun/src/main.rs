@@ -48,7 +48,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.mempool_events_channel())
         .produce(blockchain.compose_channel())
         .produce(blockchain.pool_health_monitor_channel())
-        .start()
+        .start_registered()
     {
         Err(e) => {
             error!("{}", e)
@@ -69,7 +69,7 @@ async fn main() -> Result<()> {
         .access(blockchain.nonce_and_balance())
         .consume(blockchain.compose_channel())
         .produce(blockchain.compose_channel())
-        .start()
+        .start_registered()
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -88,7 +88,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.market_events_channel())
         .consume(blockchain.compose_channel())
         .produce(blockchain.compose_channel())
-        .start()
+        .start_registered()
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -107,7 +107,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.market_events_channel())
         .consume(blockchain.compose_channel())
         .produce(blockchain.compose_channel())
-        .start()
+        .start_registered()
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -125,7 +125,7 @@ async fn main() -> Result<()> {
         .consume(blockchain.market_events_channel())
         .consume(blockchain.compose_channel())
         .produce(blockchain.compose_channel())
-        .start()
+        .start_registered()
     {
         Ok(r) => {
             worker_task_vec.extend(r);
@@ -143,7 +143,7 @@ async fn main() -> Result<()> {
         .access(blockchain.market_state())
         .consume(blockchain.compose_channel())
         .consume(blockchain.market_events_channel())
-        .start()
+        .start_registered()
     {
         Err(e) => {
             panic!("State health monitor actor failed : {}", e)
@@ -160,7 +160,7 @@ async fn main() -> Result<()> {
         .access(blockchain.latest_block())
         .consume(blockchain.compose_channel())
         .consume(blockchain.market_events_channel())
-        .start()
+        .start_registered()
     {
         Err(e) => {
             panic!("Stuffing txs monitor actor failed : {}", e)
@@ -174,7 +174,7 @@ async fn main() -> Result<()> {
     // Recording InfluxDB metrics
     if let Some(influxdb_config) = influxdb_config {
         let mut influxdb_writer_actor = InfluxDbWriterActor::new(influxdb_config.url, influxdb_config.database, influxdb_config.tags);
-        match influxdb_writer_actor.consume(blockchain.influxdb_write_channel()).start() {
+        match influxdb_writer_actor.consume(blockchain.influxdb_write_channel()).start_registered() {
             Err(e) => {
                 panic!("InfluxDB writer actor failed : {}", e)
             }
@@ -188,7 +188,7 @@ async fn main() -> Result<()> {
         match block_latency_recorder_actor
             .consume(blockchain.new_block_headers_channel())
             .produce(blockchain.influxdb_write_channel())
-            .start()
+            .start_registered()
         {
             Err(e) => {
                 panic!("Block latency recorder actor failed : {}", e)
@@ -108,7 +108,8 @@ where
     if let Some(influxdb_config) = topology_config.influxdb {
         bc_actors
             .with_influxdb_writer(influxdb_config.url, influxdb_config.database, influxdb_config.tags)?
-            .with_block_latency_recorder()?;
+            .with_block_latency_recorder()?
+            .with_actors_stats_recorder()?;
     }
 
     bc_actors.wait().await;
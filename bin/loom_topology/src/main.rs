@@ -0,0 +1,67 @@
+use clap::Parser;
+use eyre::Result;
+use loom::core::topology::{Topology, TopologyConfig};
+use std::time::Duration;
+use tracing::{error, info};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Parser, Debug)]
+#[command(name = "loom_topology", version, about = "Run loom actors described in topology config", long_about = None)]
+struct Args {
+    #[arg(long, default_value = "config.toml")]
+    config: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("debug,tokio_tungstenite=off,tungstenite=off,alloy_rpc_client=off"),
+    )
+    .format_timestamp_micros()
+    .init();
+
+    let args = Args::parse();
+
+    let topology_config = TopologyConfig::load_from_file(args.config)?;
+    let (topology, mut worker_task_vec) = Topology::from(topology_config).await?;
+    let shutdown_token = topology.shutdown_token();
+
+    info!("Topology started with {} workers", worker_task_vec.len());
+
+    // Checking workers, logging if some close
+    let mut workers_task = tokio::task::spawn(async move {
+        while !worker_task_vec.is_empty() {
+            let (result, _index, remaining_futures) = futures::future::select_all(worker_task_vec).await;
+            match result {
+                Ok(work_result) => match work_result {
+                    Ok(s) => {
+                        info!("ActorWorker {_index} finished : {s}")
+                    }
+                    Err(e) => {
+                        error!("ActorWorker {_index} error : {e}")
+                    }
+                },
+                Err(e) => {
+                    error!("ActorWorker join error {_index} : {e}")
+                }
+            }
+            worker_task_vec = remaining_futures;
+        }
+    });
+
+    tokio::select! {
+        _ = &mut workers_task => {
+            info!("All workers finished")
+        }
+        _ = tokio::signal::ctrl_c() => {
+            info!("Ctrl-C received, shutting down");
+            shutdown_token.cancel();
+            if tokio::time::timeout(SHUTDOWN_TIMEOUT, workers_task).await.is_err() {
+                error!("Workers did not finish in {} seconds, exiting", SHUTDOWN_TIMEOUT.as_secs())
+            }
+        }
+    }
+
+    Ok(())
+}
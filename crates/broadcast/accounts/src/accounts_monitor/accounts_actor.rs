@@ -16,12 +16,14 @@ use loom_types_entities::{AccountNonceAndBalanceState, BlockHistory};
 use loom_types_events::MarketEvents;
 use tokio::sync::broadcast::error::RecvError;
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 use tracing::debug;
 
 pub async fn nonce_and_balance_fetcher_worker<P, T, N>(
     client: P,
     accounts_state: SharedState<AccountNonceAndBalanceState>,
     only_once: bool,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -50,7 +52,10 @@ where
             break;
         }
 
-        sleep(Duration::from_secs(20)).await;
+        tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            _ = sleep(Duration::from_secs(20)) => {}
+        }
     }
     Ok("Nonce and balance fetcher finished".to_string())
 }
@@ -59,11 +64,15 @@ pub async fn nonce_and_balance_monitor_worker(
     accounts_state: SharedState<AccountNonceAndBalanceState>,
     block_history_state: SharedState<BlockHistory>,
     market_events_rx: Broadcaster<MarketEvents>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     let mut market_events = market_events_rx.subscribe().await;
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Nonce and balance monitor worker stopped".to_string())
+            }
             msg = market_events.recv() => {
                 let market_event_msg : Result<MarketEvents, RecvError> = msg;
                 if let Ok(market_event_msg) = market_event_msg {
@@ -199,6 +208,10 @@ where
     P: Provider<T, N> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let mut handles = Vec::new();
 
         if self.with_fetcher {
@@ -206,6 +219,7 @@ where
                 self.client.clone(),
                 self.accounts_nonce_and_balance.clone().unwrap(),
                 self.only_once,
+                token.clone(),
             ));
 
             if self.only_once {
@@ -224,6 +238,7 @@ where
             self.accounts_nonce_and_balance.clone().unwrap(),
             self.block_history.clone().unwrap(),
             self.market_events.clone().unwrap(),
+            token,
         ));
         handles.push(monitor_task);
 
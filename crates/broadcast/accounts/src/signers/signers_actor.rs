@@ -2,10 +2,10 @@ use alloy_consensus::TxEnvelope;
 use alloy_rlp::Encodable;
 use eyre::{eyre, Result};
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_types_events::{MessageTxCompose, RlpState, TxCompose, TxComposeData, TxState};
@@ -66,11 +66,15 @@ async fn sign_task(sign_request: TxComposeData, compose_channel_tx: Broadcaster<
 async fn request_listener_worker(
     compose_channel_rx: Broadcaster<MessageTxCompose>,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    let mut compose_channel_rx: Receiver<MessageTxCompose> = compose_channel_rx.subscribe().await;
+    let mut compose_channel_rx: BroadcasterReceiver<MessageTxCompose> = compose_channel_rx.subscribe().await;
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Signers worker stopped".to_string())
+            }
             msg = compose_channel_rx.recv() => {
                 let compose_request_msg : Result<MessageTxCompose, RecvError> = msg;
                 match compose_request_msg {
@@ -112,8 +116,15 @@ impl TxSignersActor {
 
 impl Actor for TxSignersActor {
     fn start(&self) -> ActorResult {
-        let task =
-            tokio::task::spawn(request_listener_worker(self.compose_channel_rx.clone().unwrap(), self.compose_channel_tx.clone().unwrap()));
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(request_listener_worker(
+            self.compose_channel_rx.clone().unwrap(),
+            self.compose_channel_tx.clone().unwrap(),
+            token,
+        ));
 
         Ok(vec![task])
     }
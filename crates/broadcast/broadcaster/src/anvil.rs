@@ -7,10 +7,10 @@ use alloy_rpc_types::BlockTransactions;
 use alloy_transport::Transport;
 use eyre::Result;
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
-use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
+use loom_core_actors::{Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
 use loom_node_debug_provider::AnvilProviderExt;
@@ -43,15 +43,22 @@ where
     Ok(())
 }
 
-async fn anvil_broadcaster_worker<P, T>(client: P, bundle_rx: Broadcaster<MessageTxCompose>) -> WorkerResult
+async fn anvil_broadcaster_worker<P, T>(
+    client: P,
+    bundle_rx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult
 where
     T: Transport + Clone,
     P: Provider<T, Ethereum> + AnvilProviderExt<T, Ethereum> + Send + Sync + Clone + 'static,
 {
-    let mut bundle_rx: Receiver<MessageTxCompose> = bundle_rx.subscribe().await;
+    let mut bundle_rx: BroadcasterReceiver<MessageTxCompose> = bundle_rx.subscribe().await;
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Anvil broadcaster worker stopped".to_string())
+            }
             msg = bundle_rx.recv() => {
                 let broadcast_msg : Result<MessageTxCompose, RecvError> = msg;
                 match broadcast_msg {
@@ -113,7 +120,11 @@ where
     P: Provider<T, Ethereum> + AnvilProviderExt<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::task::spawn(anvil_broadcaster_worker(self.client.clone(), self.tx_compose_rx.clone().unwrap()));
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(anvil_broadcaster_worker(self.client.clone(), self.tx_compose_rx.clone().unwrap(), token));
         Ok(vec![task])
     }
 
@@ -6,12 +6,15 @@ use alloy_provider::Provider;
 use alloy_transport::Transport;
 use eyre::{eyre, Result};
 use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 use loom_broadcast_flashbots::Flashbots;
 use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
+use loom_types_entities::{CurveTipsPolicy, TipsPolicy};
 use loom_types_events::{BestTxCompose, MessageTxCompose, RlpState, TxCompose, TxComposeData};
 
 async fn broadcast_task<P, T>(broadcast_request: TxComposeData, client: Arc<Flashbots<P, T>>) -> Result<()>
@@ -43,8 +46,10 @@ where
 async fn flashbots_broadcaster_worker<P, T>(
     client: Arc<Flashbots<P, T>>,
     smart_mode: bool,
+    tips_policy: Arc<dyn TipsPolicy>,
     bundle_rx: Broadcaster<MessageTxCompose>,
     allow_broadcast: bool,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -54,9 +59,24 @@ where
 
     let mut current_block: u64 = 0;
     let mut best_request: BestTxCompose = Default::default();
+    let mut broadcast_tasks: JoinSet<Result<()>> = JoinSet::new();
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                info!("Flushing {} in-flight broadcast tasks", broadcast_tasks.len());
+                while let Some(result) = broadcast_tasks.join_next().await {
+                    if let Ok(Err(error)) = result {
+                        error!(%error, "broadcast_task");
+                    }
+                }
+                break;
+            }
+            Some(result) = broadcast_tasks.join_next(), if !broadcast_tasks.is_empty() => {
+                if let Ok(Err(error)) = result {
+                    error!(%error, "broadcast_task");
+                }
+            }
             msg = bundle_rx.recv() => {
                 let broadcast_msg : Result<MessageTxCompose, RecvError> = msg;
                 match broadcast_msg {
@@ -65,12 +85,12 @@ where
                             if smart_mode {
                                 if current_block < broadcast_request.next_block_number {
                                     current_block = broadcast_request.next_block_number;
-                                    best_request = BestTxCompose::new_with_pct( U256::from(8000));
+                                    best_request = BestTxCompose::new_with_pct(U256::from(tips_policy.broadcast_validity_pct()));
                                 }
 
                                 if best_request.check(&broadcast_request) {
                                     if allow_broadcast {
-                                         tokio::task::spawn(
+                                         broadcast_tasks.spawn(
                                             broadcast_task(
                                             broadcast_request,
                                             client.clone(),
@@ -81,7 +101,7 @@ where
                                     }
                                 }
                             } else if allow_broadcast {
-                                      tokio::task::spawn(
+                                      broadcast_tasks.spawn(
                                         broadcast_task(
                                             broadcast_request,
                                             client.clone(),
@@ -99,12 +119,15 @@ where
             }
         }
     }
+
+    Ok("Flashbots broadcaster stopped".to_string())
 }
 
 #[derive(Accessor, Consumer)]
 pub struct FlashbotsBroadcastActor<P, T> {
     client: Arc<Flashbots<P, T>>,
     smart: bool,
+    tips_policy: Arc<dyn TipsPolicy>,
     #[consumer]
     tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
     allow_broadcast: bool,
@@ -116,7 +139,17 @@ where
     P: Provider<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: Flashbots<P, T>, smart: bool, allow_broadcast: bool) -> FlashbotsBroadcastActor<P, T> {
-        FlashbotsBroadcastActor { client: Arc::new(client), smart, tx_compose_channel_rx: None, allow_broadcast }
+        FlashbotsBroadcastActor {
+            client: Arc::new(client),
+            smart,
+            tips_policy: Arc::new(CurveTipsPolicy::default()),
+            tx_compose_channel_rx: None,
+            allow_broadcast,
+        }
+    }
+
+    pub fn with_tips_policy(self, tips_policy: Arc<dyn TipsPolicy>) -> Self {
+        Self { tips_policy, ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
@@ -130,11 +163,17 @@ where
     P: Provider<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(flashbots_broadcaster_worker(
             self.client.clone(),
             self.smart,
+            self.tips_policy.clone(),
             self.tx_compose_channel_rx.clone().unwrap(),
             self.allow_broadcast,
+            token,
         ));
         Ok(vec![task])
     }
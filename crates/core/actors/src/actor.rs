@@ -1,13 +1,39 @@
+use crate::actors_registry::ActorsRegistry;
 use crate::channels::Broadcaster;
 use crate::shared_state::SharedState;
 use eyre::{eyre, Result};
-use tokio::task::JoinHandle;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
 pub type WorkerResult = Result<String>;
 
 pub type ActorResult = Result<Vec<JoinHandle<WorkerResult>>>;
 
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Wait for the worker, abort it when `token` is cancelled or the waiting task is aborted
+async fn abort_on_cancel(mut worker: JoinHandle<WorkerResult>, token: CancellationToken) -> WorkerResult {
+    let _abort_on_drop = AbortOnDrop(worker.abort_handle());
+    tokio::select! {
+        result = &mut worker => match result {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(eyre!("Worker aborted : {}", e)),
+        },
+        _ = token.cancelled() => {
+            worker.abort();
+            Ok("Worker aborted on cancellation".to_string())
+        }
+    }
+}
+
 pub trait Actor {
     fn wait(&self, handles: ActorResult) -> Result<()> {
         let handles = handles?;
@@ -33,6 +59,28 @@ pub trait Actor {
 
     fn start(&self) -> ActorResult;
 
+    /// Start workers and register them in [`ActorsRegistry`] to report liveness of actors started outside of actor managers
+    fn start_registered(&self) -> ActorResult {
+        let workers = self.start()?;
+        ActorsRegistry::register(self.name()).set_workers(workers.iter().map(|worker| worker.abort_handle()).collect());
+        Ok(workers)
+    }
+
+    /// Start workers stopping when `token` is cancelled and register them in [`ActorsRegistry`]
+    fn start_registered_with_token(&self, token: CancellationToken) -> ActorResult {
+        let workers = self.start_with_token(token)?;
+        ActorsRegistry::register(self.name()).set_workers(workers.iter().map(|worker| worker.abort_handle()).collect());
+        Ok(workers)
+    }
+
+    /// Start workers stopping when `token` is cancelled.
+    /// Long running actors pass the token into their workers to finish the message in progress before stopping,
+    /// workers of one-shot actors not observing the token are aborted on cancellation.
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let workers = self.start()?;
+        Ok(workers.into_iter().map(|worker| tokio::task::spawn(abort_on_cancel(worker, token.clone()))).collect())
+    }
+
     fn name(&self) -> &'static str;
 }
 
@@ -62,8 +110,10 @@ pub trait Accessor<T> {
 
 #[cfg(test)]
 mod test {
-    use crate::actor::{Consumer, Producer, SharedState};
+    use crate::actor::{Actor, ActorResult, Consumer, Producer, SharedState};
     use crate::channels::Broadcaster;
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
 
     //use crate::macros::*;
 
@@ -137,4 +187,38 @@ mod test {
         let mut test_actor: TestActor = TestActor::new();
         test_actor.produce(channel0).produce(channel1).consume(channel2).start().await;
     }
+
+    struct SleepingActor {
+        duration: Duration,
+    }
+
+    impl Actor for SleepingActor {
+        fn start(&self) -> ActorResult {
+            let duration = self.duration;
+            Ok(vec![tokio::task::spawn(async move {
+                tokio::time::sleep(duration).await;
+                Ok("SleepingActor finished".to_string())
+            })])
+        }
+
+        fn name(&self) -> &'static str {
+            "SleepingActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_with_token() {
+        // finished worker leaves no task waiting for the token
+        let token = CancellationToken::new();
+        let workers = SleepingActor { duration: Duration::ZERO }.start_with_token(token.clone()).unwrap();
+        for worker in workers {
+            assert_eq!(worker.await.unwrap().unwrap(), "SleepingActor finished");
+        }
+
+        let workers = SleepingActor { duration: Duration::from_secs(60) }.start_with_token(token.clone()).unwrap();
+        token.cancel();
+        for worker in workers {
+            assert_eq!(worker.await.unwrap().unwrap(), "Worker aborted on cancellation");
+        }
+    }
 }
@@ -1,12 +1,154 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
 use eyre::Result;
-use tokio::task::JoinHandle;
-use tracing::{error, info};
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
+use crate::actors_registry::{ActorEntry, ActorsRegistry};
 use crate::{Actor, WorkerResult};
 
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Worker is not restarted
+    #[default]
+    Never,
+    /// Restart when a worker returns an error or panics
+    OnFailure,
+    /// Restart whenever a worker finishes, only failures count toward the restart budget
+    Always,
+}
+
+/// Restart policy of a supervised actor with its restart budget and backoff
+#[derive(Clone, Debug)]
+pub struct RestartStrategy {
+    pub policy: RestartPolicy,
+    /// Restarts after failures allowed before the actor is given up
+    pub max_restarts: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Escalate when the restart budget is exceeded
+    pub critical: bool,
+}
+
+impl Default for RestartStrategy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+impl RestartStrategy {
+    pub fn never() -> Self {
+        Self {
+            policy: RestartPolicy::Never,
+            max_restarts: 0,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            critical: false,
+        }
+    }
+
+    pub fn on_failure(max_restarts: usize) -> Self {
+        Self { policy: RestartPolicy::OnFailure, max_restarts, ..Self::never() }
+    }
+
+    pub fn always(max_restarts: usize) -> Self {
+        Self { policy: RestartPolicy::Always, max_restarts, ..Self::never() }
+    }
+
+    pub fn with_backoff(self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self { initial_backoff, max_backoff, ..self }
+    }
+
+    pub fn critical(self) -> Self {
+        Self { critical: true, ..self }
+    }
+
+    fn should_restart(&self, failed: bool) -> bool {
+        match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => failed,
+            RestartPolicy::Always => true,
+        }
+    }
+
+    /// Exponential backoff, doubled with every restart
+    pub fn backoff(&self, restarts: usize) -> Duration {
+        let multiplier = 1u32.checked_shl(restarts.min(31) as u32).unwrap_or(u32::MAX);
+        self.initial_backoff.saturating_mul(multiplier).min(self.max_backoff)
+    }
+}
+
+/// Called with the actor name when a critical actor exceeds its restart budget
+pub type EscalationHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Restart counters by actor name
+pub type RestartCounters = Arc<RwLock<HashMap<String, usize>>>;
+
+/// Result of graceful shutdown
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownReport {
+    pub stopped: Vec<String>,
+    /// Actors with workers still running after the shutdown timeout, their workers are aborted
+    pub failed: Vec<String>,
+}
+
+impl ShutdownReport {
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+struct ManagedActor {
+    name: &'static str,
+    actor: Option<Arc<dyn Actor + Send + Sync>>,
+    strategy: RestartStrategy,
+    restarts: usize,
+    failures: usize,
+    generation: usize,
+    /// Generation of the workers replaced by the scheduled restart
+    restart_pending: Option<usize>,
+    workers: Vec<AbortHandle>,
+    entry: Arc<ActorEntry>,
+}
+
+enum SupervisorEvent {
+    WorkerFinished { actor_idx: usize, generation: usize, result: std::result::Result<WorkerResult, JoinError> },
+    Restart { actor_idx: usize, generation: usize },
+}
+
+type SupervisorFuture = Pin<Box<dyn Future<Output = SupervisorEvent> + Send>>;
+
 pub struct ActorsManager {
-    tasks: Vec<JoinHandle<WorkerResult>>,
+    actors: Vec<ManagedActor>,
+    tasks: Vec<(usize, JoinHandle<WorkerResult>)>,
+    restart_counters: RestartCounters,
+    escalation_hook: EscalationHook,
+    shutdown_token: CancellationToken,
+    shutdown_timeout: Duration,
+}
+
+impl Default for ActorsManager {
+    fn default() -> Self {
+        let shutdown_token = CancellationToken::new();
+        let escalation_token = shutdown_token.clone();
+        Self {
+            actors: Vec::new(),
+            tasks: Vec::new(),
+            restart_counters: Arc::new(RwLock::new(HashMap::new())),
+            escalation_hook: Arc::new(move |name: &str| {
+                error!("Critical actor {name} exceeded restart budget, shutting down");
+                escalation_token.cancel();
+            }),
+            shutdown_token,
+            shutdown_timeout: Duration::from_secs(10),
+        }
+    }
 }
 
 impl ActorsManager {
@@ -14,11 +156,81 @@ impl ActorsManager {
         Self::default()
     }
 
+    /// Replace default escalation hook which shuts down all actors
+    pub fn with_escalation_hook(&mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> &mut Self {
+        self.escalation_hook = Arc::new(hook);
+        self
+    }
+
+    /// Time given to workers to stop after cancellation before they are aborted
+    pub fn with_shutdown_timeout(&mut self, shutdown_timeout: Duration) -> &mut Self {
+        self.shutdown_timeout = shutdown_timeout;
+        self
+    }
+
+    /// Token cancelled on shutdown, cancelling it stops all actors
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown_token.clone()
+    }
+
+    pub fn restart_counters(&self) -> RestartCounters {
+        self.restart_counters.clone()
+    }
+
+    pub fn restart_count(&self, name: &str) -> usize {
+        self.restart_counters.read().map(|counters| counters.get(name).cloned().unwrap_or_default()).unwrap_or_default()
+    }
+
+    fn add_workers(&mut self, actor_idx: usize, workers: Vec<JoinHandle<WorkerResult>>) {
+        let managed = &mut self.actors[actor_idx];
+        managed.workers = workers.iter().map(|worker| worker.abort_handle()).collect();
+        managed.entry.set_workers(managed.workers.clone());
+        self.tasks.extend(workers.into_iter().map(|worker| (actor_idx, worker)));
+    }
+
     pub fn start(&mut self, actor: impl Actor + 'static) -> Result<()> {
-        match actor.start() {
+        match actor.start_with_token(self.shutdown_token.child_token()) {
             Ok(workers) => {
                 info!("{} started successfully", actor.name());
-                self.tasks.extend(workers);
+                self.actors.push(ManagedActor {
+                    name: actor.name(),
+                    actor: None,
+                    strategy: RestartStrategy::never(),
+                    restarts: 0,
+                    failures: 0,
+                    generation: 0,
+                    restart_pending: None,
+                    workers: Vec::new(),
+                    entry: ActorsRegistry::register(actor.name()),
+                });
+                self.add_workers(self.actors.len() - 1, workers);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Error starting {} : {}", actor.name(), e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Start actor which is restarted according to the strategy when its workers finish
+    pub fn start_supervised(&mut self, actor: impl Actor + Send + Sync + 'static, strategy: RestartStrategy) -> Result<()> {
+        match actor.start_with_token(self.shutdown_token.child_token()) {
+            Ok(workers) => {
+                info!("{} started successfully with {:?} restart policy", actor.name(), strategy.policy);
+                let entry = ActorsRegistry::register(actor.name());
+                self.actors.push(ManagedActor {
+                    name: actor.name(),
+                    actor: Some(Arc::new(actor)),
+                    strategy,
+                    restarts: 0,
+                    failures: 0,
+                    generation: 0,
+                    restart_pending: None,
+                    workers: Vec::new(),
+                    entry,
+                });
+                self.add_workers(self.actors.len() - 1, workers);
                 Ok(())
             }
             Err(e) => {
@@ -41,27 +253,428 @@ impl ActorsManager {
         }
     }
 
-    pub async fn wait(self) {
-        let mut f_remaining_futures = self.tasks;
-        let mut futures_counter = f_remaining_futures.len();
+    fn watch(actor_idx: usize, generation: usize, worker: JoinHandle<WorkerResult>) -> SupervisorFuture {
+        Box::pin(async move { SupervisorEvent::WorkerFinished { actor_idx, generation, result: worker.await } })
+    }
+
+    /// Schedules restart if policy and budget allow, escalates critical actors with exhausted budget.
+    /// Only failures count toward the budget, clean exits restarted by [`RestartPolicy::Always`] do not.
+    /// Workers finishing while a restart is pending are replaced by it and are not counted again.
+    fn schedule_restart(&mut self, actor_idx: usize, failed: bool) -> Option<SupervisorFuture> {
+        let managed = &mut self.actors[actor_idx];
+        managed.actor.as_ref()?;
+        if self.shutdown_token.is_cancelled() || !managed.strategy.should_restart(failed) {
+            return None;
+        }
+        if managed.restart_pending.is_some() {
+            return None;
+        }
+
+        if failed {
+            if managed.failures >= managed.strategy.max_restarts {
+                error!("{} exceeded restart budget of {}", managed.name, managed.strategy.max_restarts);
+                if managed.strategy.critical {
+                    (self.escalation_hook)(managed.name);
+                }
+                // given up, other workers of the instance finishing later are not restarted or escalated again
+                managed.actor = None;
+                return None;
+            }
+            managed.failures += 1;
+        }
+
+        let generation = managed.generation;
+        managed.restart_pending = Some(generation);
+        let backoff = managed.strategy.backoff(managed.failures.saturating_sub(1));
+        warn!("Restarting {} in {:?}, failures {} of {}", managed.name, backoff, managed.failures, managed.strategy.max_restarts);
+        Some(Box::pin(async move {
+            tokio::time::sleep(backoff).await;
+            SupervisorEvent::Restart { actor_idx, generation }
+        }))
+    }
+
+    fn restart(&mut self, actor_idx: usize) -> Result<Vec<SupervisorFuture>> {
+        let managed = &mut self.actors[actor_idx];
+        let Some(actor) = managed.actor.clone() else {
+            return Ok(Vec::new());
+        };
+
+        // stop remaining workers of the previous instance
+        for worker in managed.workers.drain(..) {
+            worker.abort();
+        }
+        managed.restarts += 1;
+        managed.generation += 1;
+        managed.entry.record_restart();
+        if let Ok(mut counters) = self.restart_counters.write() {
+            *counters.entry(managed.name.to_string()).or_default() += 1;
+        }
+
+        let generation = managed.generation;
+        let workers = actor.start_with_token(self.shutdown_token.child_token())?;
+        managed.workers = workers.iter().map(|worker| worker.abort_handle()).collect();
+        managed.entry.set_workers(managed.workers.clone());
+        info!("{} restarted", managed.name);
+        Ok(workers.into_iter().map(|worker| Self::watch(actor_idx, generation, worker)).collect())
+    }
 
-        while futures_counter > 0 {
-            let (result, _index, remaining_futures) = futures::future::select_all(f_remaining_futures).await;
-            match result {
-                Ok(work_result) => match work_result {
-                    Ok(s) => {
-                        info!("ActorWorker {_index} finished : {s}")
+    fn handle_event(&mut self, event: SupervisorEvent, events: &mut FuturesUnordered<SupervisorFuture>) {
+        match event {
+            SupervisorEvent::WorkerFinished { actor_idx, generation, result } => {
+                let name = self.actors[actor_idx].name;
+                if generation != self.actors[actor_idx].generation {
+                    // worker of replaced instance
+                    return;
+                }
+                let failed = match result {
+                    Ok(work_result) => match work_result {
+                        Ok(s) => {
+                            info!("ActorWorker {name} finished : {s}");
+                            false
+                        }
+                        Err(e) => {
+                            error!("ActorWorker {name} finished with error : {e}");
+                            true
+                        }
+                    },
+                    Err(e) if e.is_cancelled() && self.shutdown_token.is_cancelled() => {
+                        info!("ActorWorker {name} aborted on shutdown");
+                        false
                     }
                     Err(e) => {
-                        error!("ActorWorker {_index} finished with error : {e}")
+                        error!("ActorWorker join error {name} : {e}");
+                        true
+                    }
+                };
+                if let Some(restart) = self.schedule_restart(actor_idx, failed) {
+                    events.push(restart);
+                }
+            }
+            SupervisorEvent::Restart { actor_idx, generation } => {
+                if self.shutdown_token.is_cancelled() || self.actors[actor_idx].restart_pending != Some(generation) {
+                    // restart of replaced instance
+                    return;
+                }
+                self.actors[actor_idx].restart_pending = None;
+                match self.restart(actor_idx) {
+                    Ok(workers) => events.extend(workers),
+                    Err(e) => {
+                        error!("Error restarting {} : {}", self.actors[actor_idx].name, e);
+                        if let Some(restart) = self.schedule_restart(actor_idx, true) {
+                            events.push(restart);
+                        }
                     }
-                },
-                Err(e) => {
-                    error!("ActorWorker join error {_index} : {e}")
                 }
             }
-            f_remaining_futures = remaining_futures;
-            futures_counter = f_remaining_futures.len();
         }
     }
+
+    /// Wait for workers until all of them finish or shutdown token is cancelled.
+    /// On cancellation workers are given shutdown timeout to stop, remaining workers are aborted.
+    pub async fn wait(mut self) -> ShutdownReport {
+        let mut events: FuturesUnordered<SupervisorFuture> = FuturesUnordered::new();
+        for (actor_idx, worker) in std::mem::take(&mut self.tasks) {
+            events.push(Self::watch(actor_idx, 0, worker));
+        }
+
+        let shutdown_token = self.shutdown_token.clone();
+        loop {
+            let event = tokio::select! {
+                event = events.next() => event,
+                _ = shutdown_token.cancelled() => break,
+            };
+            match event {
+                Some(event) => self.handle_event(event, &mut events),
+                None => return ShutdownReport::default(),
+            }
+        }
+
+        info!("Shutting down actors, timeout {:?}", self.shutdown_timeout);
+        let deadline = tokio::time::sleep(self.shutdown_timeout);
+        tokio::pin!(deadline);
+        loop {
+            let event = tokio::select! {
+                event = events.next() => event,
+                _ = &mut deadline => break,
+            };
+            match event {
+                Some(event) => self.handle_event(event, &mut events),
+                None => break,
+            }
+        }
+
+        let mut report = ShutdownReport::default();
+        for managed in self.actors.iter() {
+            if managed.workers.iter().any(|worker| !worker.is_finished()) {
+                error!("{} failed to stop in {:?}", managed.name, self.shutdown_timeout);
+                for worker in managed.workers.iter() {
+                    worker.abort();
+                }
+                report.failed.push(managed.name.to_string());
+            } else {
+                report.stopped.push(managed.name.to_string());
+            }
+        }
+        info!("Actors stopped : {}, failed to stop : {:?}", report.stopped.len(), report.failed);
+        report
+    }
+
+    /// Cancel shutdown token and wait for workers to stop
+    pub async fn shutdown(self) -> ShutdownReport {
+        self.shutdown_token.cancel();
+        self.wait().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ActorResult, Broadcaster};
+    use eyre::eyre;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct FailingActor {
+        starts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for FailingActor {
+        fn start(&self) -> ActorResult {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![tokio::task::spawn(async { Err(eyre!("WORKER_FAILED")) })])
+        }
+
+        fn name(&self) -> &'static str {
+            "FailingActor"
+        }
+    }
+
+    struct LoopActor {
+        observe_token: bool,
+    }
+
+    impl Actor for LoopActor {
+        fn start(&self) -> ActorResult {
+            Ok(vec![tokio::task::spawn(async {
+                loop {
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            })])
+        }
+
+        fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+            if !self.observe_token {
+                // never stops
+                return self.start();
+            }
+            Ok(vec![tokio::task::spawn(async move {
+                token.cancelled().await;
+                Ok("LoopActor stopped".to_string())
+            })])
+        }
+
+        fn name(&self) -> &'static str {
+            if self.observe_token {
+                "StoppingActor"
+            } else {
+                "HangingActor"
+            }
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let strategy = RestartStrategy::on_failure(10).with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(strategy.backoff(0), Duration::from_millis(100));
+        assert_eq!(strategy.backoff(2), Duration::from_millis(400));
+        assert_eq!(strategy.backoff(5), Duration::from_secs(1));
+        assert_eq!(strategy.backoff(100), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_restart_on_failure() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let escalated = Arc::new(AtomicUsize::new(0));
+        let escalated_clone = escalated.clone();
+
+        let mut manager = ActorsManager::new();
+        manager.with_escalation_hook(move |_| {
+            escalated_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        manager
+            .start_supervised(
+                FailingActor { starts: starts.clone() },
+                RestartStrategy::on_failure(3).with_backoff(Duration::from_millis(1), Duration::from_millis(10)).critical(),
+            )
+            .unwrap();
+        let counters = manager.restart_counters();
+        manager.wait().await;
+
+        assert_eq!(starts.load(Ordering::SeqCst), 4);
+        assert_eq!(counters.read().unwrap().get("FailingActor"), Some(&3));
+        assert_eq!(escalated.load(Ordering::SeqCst), 1);
+    }
+
+    struct FailingPairActor {
+        starts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for FailingPairActor {
+        fn start(&self) -> ActorResult {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![tokio::task::spawn(async { Err(eyre!("WORKER_FAILED")) }), tokio::task::spawn(async { Err(eyre!("WORKER_FAILED")) })])
+        }
+
+        fn name(&self) -> &'static str {
+            "FailingPairActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restart_once_for_failed_workers() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let escalated = Arc::new(AtomicUsize::new(0));
+        let escalated_clone = escalated.clone();
+
+        let mut manager = ActorsManager::new();
+        manager.with_escalation_hook(move |_| {
+            escalated_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        manager
+            .start_supervised(
+                FailingPairActor { starts: starts.clone() },
+                RestartStrategy::on_failure(3).with_backoff(Duration::from_millis(1), Duration::from_millis(10)).critical(),
+            )
+            .unwrap();
+        let counters = manager.restart_counters();
+        manager.wait().await;
+
+        // both workers of an instance fail, each instance is restarted once
+        assert_eq!(starts.load(Ordering::SeqCst), 4);
+        assert_eq!(counters.read().unwrap().get("FailingPairActor"), Some(&3));
+        assert_eq!(escalated.load(Ordering::SeqCst), 1);
+    }
+
+    struct ExitingActor {
+        starts: Arc<AtomicUsize>,
+    }
+
+    impl Actor for ExitingActor {
+        fn start(&self) -> ActorResult {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![tokio::task::spawn(async { Ok("ExitingActor finished".to_string()) })])
+        }
+
+        fn name(&self) -> &'static str {
+            "ExitingActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_always_restart_clean_exit() {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let escalated = Arc::new(AtomicUsize::new(0));
+        let escalated_clone = escalated.clone();
+
+        let mut manager = ActorsManager::new();
+        manager.with_escalation_hook(move |_| {
+            escalated_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        manager
+            .start_supervised(
+                ExitingActor { starts: starts.clone() },
+                RestartStrategy::always(1).with_backoff(Duration::from_millis(1), Duration::from_millis(1)).critical(),
+            )
+            .unwrap();
+
+        let shutdown_token = manager.shutdown_token();
+        let starts_clone = starts.clone();
+        tokio::spawn(async move {
+            while starts_clone.load(Ordering::SeqCst) < 5 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            shutdown_token.cancel();
+        });
+        manager.wait().await;
+
+        // clean exits are restarted beyond the budget of failures
+        assert!(starts.load(Ordering::SeqCst) >= 5);
+        assert_eq!(escalated.load(Ordering::SeqCst), 0);
+    }
+
+    struct ReceivingActor {
+        rx: Broadcaster<u64>,
+    }
+
+    impl Actor for ReceivingActor {
+        fn start(&self) -> ActorResult {
+            let rx = self.rx.clone();
+            Ok(vec![tokio::task::spawn(async move {
+                let mut rx = rx.subscribe().await;
+                rx.recv().await?;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok("ReceivingActor finished".to_string())
+            })])
+        }
+
+        fn name(&self) -> &'static str {
+            "ReceivingActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_actors_registry() {
+        let broadcaster: Broadcaster<u64> = Broadcaster::new(10);
+        let mut manager = ActorsManager::new();
+        manager.start(ReceivingActor { rx: broadcaster.clone() }).unwrap();
+
+        while broadcaster.stats().await.subscribers == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        broadcaster.send(1).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let actor = ActorsRegistry::actors().into_iter().find(|actor| actor.name == "ReceivingActor").unwrap();
+        assert!(actor.is_alive());
+        assert_eq!(actor.workers, 1);
+        assert!(actor.last_activity.is_some());
+    }
+
+    struct OneShotActor;
+
+    impl Actor for OneShotActor {
+        fn start(&self) -> ActorResult {
+            Ok(vec![tokio::task::spawn(async { Ok("OneShotActor finished".to_string()) })])
+        }
+
+        fn name(&self) -> &'static str {
+            "OneShotActor"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_registered_and_prune() {
+        for worker in OneShotActor.start_registered().unwrap() {
+            worker.await.unwrap().unwrap();
+        }
+
+        // finished actor is reported until the retention passes
+        let actor = ActorsRegistry::actors().into_iter().find(|actor| actor.name == "OneShotActor").unwrap();
+        assert!(!actor.is_alive());
+
+        ActorsRegistry::prune(0);
+        assert!(!ActorsRegistry::actors().into_iter().any(|actor| actor.name == "OneShotActor"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        let mut manager = ActorsManager::new();
+        manager.with_shutdown_timeout(Duration::from_millis(50));
+        manager.start(LoopActor { observe_token: true }).unwrap();
+        manager.start(LoopActor { observe_token: false }).unwrap();
+
+        let report = manager.shutdown().await;
+        assert_eq!(report.stopped, vec!["StoppingActor".to_string()]);
+        assert_eq!(report.failed, vec!["HangingActor".to_string()]);
+        assert!(!report.is_clean());
+    }
 }
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use lazy_static::lazy_static;
+use tokio::task::{AbortHandle, Id};
+
+lazy_static! {
+    static ref ACTORS: RwLock<Vec<Arc<ActorEntry>>> = RwLock::new(Vec::new());
+    static ref WORKERS: RwLock<HashMap<Id, Arc<ActorEntry>>> = RwLock::new(HashMap::new());
+}
+
+/// Actors with all workers finished are kept in the registry this long to report them as not alive
+const FINISHED_RETENTION_MS: u64 = 300_000;
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_millis() as u64).unwrap_or_default()
+}
+
+/// Liveness of a started actor, timestamps are unix time in milliseconds
+#[derive(Clone, Debug)]
+pub struct ActorInfo {
+    pub name: String,
+    pub workers: usize,
+    pub alive_workers: usize,
+    pub restarts: usize,
+    pub started_at: u64,
+    /// Last time a worker received a message from a broadcaster
+    pub last_activity: Option<u64>,
+}
+
+impl ActorInfo {
+    pub fn is_alive(&self) -> bool {
+        self.alive_workers > 0
+    }
+}
+
+pub(crate) struct ActorEntry {
+    name: &'static str,
+    started_at: AtomicU64,
+    last_activity: AtomicU64,
+    restarts: AtomicUsize,
+    /// Time all workers were first seen finished, zero while any worker is alive
+    finished_at: AtomicU64,
+    registered: AtomicBool,
+    workers: RwLock<Vec<AbortHandle>>,
+}
+
+impl ActorEntry {
+    /// Replace workers of the actor, activity of the new workers is recorded to the entry
+    pub(crate) fn set_workers(self: &Arc<Self>, workers: Vec<AbortHandle>) {
+        self.started_at.store(now_millis(), Ordering::Relaxed);
+        self.finished_at.store(0, Ordering::Relaxed);
+        if let (Ok(mut worker_entries), Ok(mut entry_workers)) = (WORKERS.write(), self.workers.write()) {
+            for worker in entry_workers.iter() {
+                worker_entries.remove(&worker.id());
+            }
+            for worker in workers.iter() {
+                worker_entries.insert(worker.id(), self.clone());
+            }
+            *entry_workers = workers;
+        }
+        // restarted actor pruned while waiting for the restart
+        if !self.registered.swap(true, Ordering::Relaxed) {
+            if let Ok(mut actors) = ACTORS.write() {
+                actors.push(self.clone());
+            }
+        }
+    }
+
+    pub(crate) fn record_restart(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns true if all workers are finished for at least `retention_ms`
+    fn is_expired(&self, now: u64, retention_ms: u64) -> bool {
+        let alive = match self.workers.read() {
+            Ok(workers) => workers.iter().any(|worker| !worker.is_finished()),
+            Err(_) => false,
+        };
+        if alive {
+            self.finished_at.store(0, Ordering::Relaxed);
+            return false;
+        }
+        match self.finished_at.compare_exchange(0, now, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => retention_ms == 0,
+            Err(finished_at) => now.saturating_sub(finished_at) >= retention_ms,
+        }
+    }
+
+    fn info(&self) -> ActorInfo {
+        let (workers, alive_workers) = match self.workers.read() {
+            Ok(workers) => (workers.len(), workers.iter().filter(|worker| !worker.is_finished()).count()),
+            Err(_) => (0, 0),
+        };
+        let last_activity = self.last_activity.load(Ordering::Relaxed);
+        ActorInfo {
+            name: self.name.to_string(),
+            workers,
+            alive_workers,
+            restarts: self.restarts.load(Ordering::Relaxed),
+            started_at: self.started_at.load(Ordering::Relaxed),
+            last_activity: if last_activity == 0 { None } else { Some(last_activity) },
+        }
+    }
+}
+
+/// Actors started by actor managers
+pub struct ActorsRegistry;
+
+impl ActorsRegistry {
+    pub(crate) fn register(name: &'static str) -> Arc<ActorEntry> {
+        let entry = Arc::new(ActorEntry {
+            name,
+            started_at: AtomicU64::new(now_millis()),
+            last_activity: AtomicU64::new(0),
+            restarts: AtomicUsize::new(0),
+            finished_at: AtomicU64::new(0),
+            registered: AtomicBool::new(true),
+            workers: RwLock::new(Vec::new()),
+        });
+        Self::prune(FINISHED_RETENTION_MS);
+        if let Ok(mut actors) = ACTORS.write() {
+            actors.push(entry.clone());
+        }
+        entry
+    }
+
+    /// Remove actors finished for at least `retention_ms` together with their workers
+    pub(crate) fn prune(retention_ms: u64) {
+        let now = now_millis();
+        let (Ok(mut actors), Ok(mut worker_entries)) = (ACTORS.write(), WORKERS.write()) else {
+            return;
+        };
+        actors.retain(|entry| {
+            if !entry.is_expired(now, retention_ms) {
+                return true;
+            }
+            if let Ok(workers) = entry.workers.read() {
+                for worker in workers.iter() {
+                    worker_entries.remove(&worker.id());
+                }
+            }
+            entry.registered.store(false, Ordering::Relaxed);
+            false
+        });
+    }
+
+    /// Record activity of the actor running current task
+    pub(crate) fn record_activity() {
+        let Some(task_id) = tokio::task::try_id() else {
+            return;
+        };
+        if let Ok(workers) = WORKERS.read() {
+            if let Some(entry) = workers.get(&task_id) {
+                entry.last_activity.store(now_millis(), Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn actors() -> Vec<ActorInfo> {
+        Self::prune(FINISHED_RETENTION_MS);
+        match ACTORS.read() {
+            Ok(actors) => actors.iter().map(|entry| entry.info()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
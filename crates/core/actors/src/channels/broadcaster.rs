@@ -1,35 +1,78 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use eyre::{eyre, Result};
 use tokio::sync::broadcast;
-use tokio::sync::broadcast::error::SendError;
+use tokio::sync::broadcast::error::{RecvError, SendError, TryRecvError};
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::RwLock;
 use tracing::error;
 
+use crate::actors_registry::ActorsRegistry;
+
+#[derive(Default)]
+struct ChannelCounters {
+    sent: AtomicU64,
+    lag_events: AtomicU64,
+    lagged_messages: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Channel counters. `dropped` counts messages overwritten before all subscribers received them,
+/// `lagged_messages` counts messages skipped by lagging subscribers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BroadcasterStats {
+    pub capacity: usize,
+    pub subscribers: usize,
+    pub queued: usize,
+    pub sent: u64,
+    pub lag_events: u64,
+    pub lagged_messages: u64,
+    pub dropped: u64,
+}
+
 #[derive(Clone)]
 pub struct Broadcaster<T>
 where
     T: Clone + Send + Sync + 'static,
 {
     sender: Arc<RwLock<broadcast::Sender<T>>>,
+    capacity: usize,
+    counters: Arc<ChannelCounters>,
 }
 
 impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
     pub fn new(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
-        Self { sender: Arc::new(RwLock::new(sender)) }
+        // broadcast channel capacity is rounded up to power of two
+        Self {
+            sender: Arc::new(RwLock::new(sender)),
+            capacity: capacity.next_power_of_two(),
+            counters: Arc::new(ChannelCounters::default()),
+        }
+    }
+
+    fn send_counted(&self, sender: &broadcast::Sender<T>, value: T) -> Result<usize, SendError<T>> {
+        if sender.len() >= self.capacity {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        let result = sender.send(value);
+        if result.is_ok() {
+            self.counters.sent.fetch_add(1, Ordering::Relaxed);
+        }
+        result
     }
 
     pub async fn send(&self, value: T) -> Result<usize, SendError<T>> {
         let sender = self.sender.write().await;
-        sender.send(value)
+        self.send_counted(&sender, value)
     }
 
     pub fn try_send(&self, value: T) -> Result<usize> {
         //let sender = self.sender.write().await;
         match self.sender.try_write() {
-            Ok(guard) => match guard.send(value) {
+            Ok(guard) => match self.send_counted(&guard, value) {
                 Ok(size) => Ok(size),
                 Err(_) => Err(eyre!("ERROR_SEND")),
             },
@@ -40,13 +83,98 @@ impl<T: Clone + Send + Sync + 'static> Broadcaster<T> {
         }
     }
 
-    pub async fn subscribe(&self) -> Receiver<T> {
+    pub async fn subscribe(&self) -> BroadcasterReceiver<T> {
         let sender = self.sender.write().await;
-        sender.subscribe()
+        BroadcasterReceiver { receiver: sender.subscribe(), counters: self.counters.clone() }
     }
 
-    pub fn subscribe_sync(&self) -> Result<Receiver<T>> {
+    pub fn subscribe_sync(&self) -> Result<BroadcasterReceiver<T>> {
         let sender = self.sender.try_write()?;
-        Ok(sender.subscribe())
+        Ok(BroadcasterReceiver { receiver: sender.subscribe(), counters: self.counters.clone() })
+    }
+
+    pub async fn stats(&self) -> BroadcasterStats {
+        let sender = self.sender.read().await;
+        BroadcasterStats {
+            capacity: self.capacity,
+            subscribers: sender.receiver_count(),
+            queued: sender.len(),
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            lag_events: self.counters.lag_events.load(Ordering::Relaxed),
+            lagged_messages: self.counters.lagged_messages.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Broadcast receiver counting lag of the channel and recording activity of the receiving actor
+pub struct BroadcasterReceiver<T> {
+    receiver: Receiver<T>,
+    counters: Arc<ChannelCounters>,
+}
+
+impl<T: Clone> BroadcasterReceiver<T> {
+    fn record_lag(&self, lag: u64) {
+        self.counters.lag_events.fetch_add(1, Ordering::Relaxed);
+        self.counters.lagged_messages.fetch_add(lag, Ordering::Relaxed);
+    }
+
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        let result = self.receiver.recv().await;
+        match &result {
+            Ok(_) => ActorsRegistry::record_activity(),
+            Err(RecvError::Lagged(lag)) => self.record_lag(*lag),
+            Err(RecvError::Closed) => {}
+        }
+        result
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let result = self.receiver.try_recv();
+        match &result {
+            Ok(_) => ActorsRegistry::record_activity(),
+            Err(TryRecvError::Lagged(lag)) => self.record_lag(*lag),
+            Err(_) => {}
+        }
+        result
+    }
+}
+
+impl<T> Deref for BroadcasterReceiver<T> {
+    type Target = Receiver<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<T> DerefMut for BroadcasterReceiver<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stats() {
+        let broadcaster: Broadcaster<u64> = Broadcaster::new(3);
+        let mut fast_rx = broadcaster.subscribe().await;
+        let mut slow_rx = broadcaster.subscribe().await;
+
+        for i in 0..6 {
+            broadcaster.send(i).await.unwrap();
+            assert_eq!(fast_rx.recv().await.unwrap(), i);
+        }
+        assert!(matches!(slow_rx.recv().await, Err(RecvError::Lagged(2))));
+        assert_eq!(slow_rx.recv().await.unwrap(), 2);
+
+        let stats = broadcaster.stats().await;
+        assert_eq!(
+            stats,
+            BroadcasterStats { capacity: 4, subscribers: 2, queued: 3, sent: 6, lag_events: 1, lagged_messages: 2, dropped: 2 }
+        );
     }
 }
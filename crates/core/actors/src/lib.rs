@@ -1,10 +1,12 @@
 pub use actor::{Accessor, Actor, ActorResult, Consumer, Producer, WorkerResult};
-pub use actor_manager::ActorsManager;
-pub use channels::{Broadcaster, MultiProducer};
+pub use actor_manager::{ActorsManager, EscalationHook, RestartCounters, RestartPolicy, RestartStrategy, ShutdownReport};
+pub use actors_registry::{ActorInfo, ActorsRegistry};
+pub use channels::{Broadcaster, BroadcasterReceiver, BroadcasterStats, MultiProducer};
 pub use shared_state::SharedState;
 
 mod actor;
 mod actor_manager;
+mod actors_registry;
 mod channels;
 mod shared_state;
 
@@ -19,7 +21,7 @@ macro_rules! run_async {
 }
 
 #[inline]
-pub async fn subscribe_helper<A: Clone + Send + Sync>(broadcaster: &Broadcaster<A>) -> tokio::sync::broadcast::Receiver<A> {
+pub async fn subscribe_helper<A: Clone + Send + Sync>(broadcaster: &Broadcaster<A>) -> BroadcasterReceiver<A> {
     broadcaster.subscribe().await
 }
 
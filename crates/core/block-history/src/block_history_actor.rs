@@ -15,6 +15,7 @@ use std::borrow::BorrowMut;
 use std::marker::PhantomData;
 use std::ops::DerefMut;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 
 pub async fn set_chain_head<P, T>(
@@ -75,6 +76,7 @@ pub async fn new_block_history_worker<P, T>(
     log_update_rx: Broadcaster<MessageBlockLogs>,
     state_update_rx: Broadcaster<MessageBlockStateUpdate>,
     market_events_tx: Broadcaster<MarketEvents>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone + Send + Sync + 'static,
@@ -89,6 +91,9 @@ where
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Block history worker stopped".to_string())
+            }
             msg = block_header_update_rx.recv() => {
                 let block_update : Result<MessageBlockHeader, RecvError>  = msg;
                 match block_update {
@@ -388,6 +393,10 @@ where
     P: Provider<T, Ethereum> + DebugProviderExt<T, Ethereum> + Sync + Send + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(new_block_history_worker(
             self.client.clone(),
             self.chain_parameters.clone(),
@@ -399,6 +408,7 @@ where
             self.log_update_rx.clone().unwrap(),
             self.state_update_rx.clone().unwrap(),
             self.market_events_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -8,7 +8,7 @@ use loom_broadcast_accounts::{InitializeSignersOneShotBlockingActor, NonceAndBal
 use loom_broadcast_broadcaster::FlashbotsBroadcastActor;
 use loom_broadcast_flashbots::client::RelayConfig;
 use loom_broadcast_flashbots::Flashbots;
-use loom_core_actors::{Actor, ActorsManager, SharedState};
+use loom_core_actors::{Actor, ActorsManager, RestartStrategy, SharedState, ShutdownReport};
 use loom_core_block_history::BlockHistoryActor;
 use loom_core_blockchain::Blockchain;
 use loom_core_mempool::MempoolActor;
@@ -16,7 +16,8 @@ use loom_core_router::SwapRouterActor;
 use loom_defi_address_book::TokenAddress;
 use loom_defi_health_monitor::{PoolHealthMonitorActor, StateHealthMonitorActor, StuffingTxMonitorActor};
 use loom_defi_market::{
-    CurvePoolLoaderOneShotActor, HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor, RequiredPoolLoaderActor,
+    CurvePoolLoaderOneShotActor, FactoryPoolLoaderOneShotActor, HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor,
+    RequiredPoolLoaderActor,
 };
 use loom_defi_pools::PoolsConfig;
 use loom_defi_preloader::MarketStatePreloadedOneShotActor;
@@ -24,7 +25,7 @@ use loom_defi_price::PriceActor;
 use loom_evm_utils::NWETH;
 use loom_execution_estimator::{EvmEstimatorActor, GethEstimatorActor};
 use loom_execution_multicaller::MulticallerSwapEncoder;
-use loom_metrics::{BlockLatencyRecorderActor, InfluxDbWriterActor};
+use loom_metrics::{ActorsStatsRecorderActor, BlockLatencyRecorderActor, InfluxDbWriterActor};
 use loom_node_actor_config::NodeBlockActorConfig;
 #[cfg(feature = "db-access")]
 use loom_node_db_access::RethDbAccessBlockActor;
@@ -36,12 +37,14 @@ use loom_storage_db::DbPool;
 use loom_strategy_backrun::{
     BackrunConfig, BlockStateChangeProcessorActor, PendingTxStateChangeProcessorActor, StateChangeArbSearcherActor,
 };
-use loom_strategy_merger::{ArbSwapPathMergerActor, DiffPathMergerActor, SamePathMergerActor};
+use loom_strategy_core::{Strategy, StrategyActor};
+use loom_strategy_merger::{ArbSwapPathMergerActor, DiffPathMergerActor, PackingMergerActor, SamePathMergerActor};
 use loom_types_entities::required_state::RequiredState;
-use loom_types_entities::{PoolClass, TxSigners};
+use loom_types_entities::{CurveTipsPolicy, PoolClass, TipsPolicy, TxSigners};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
 pub struct BlockchainActors<P, T> {
@@ -52,9 +55,12 @@ pub struct BlockchainActors<P, T> {
     encoder: Option<MulticallerSwapEncoder>,
     has_mempool: bool,
     has_state_update: bool,
+    has_block_state_change: bool,
+    has_mempool_state_change: bool,
     has_signers: bool,
     mutlicaller_address: Option<Address>,
     relays: Vec<RelayConfig>,
+    tips_policy: Arc<dyn TipsPolicy>,
     _t: PhantomData<T>,
 }
 
@@ -72,23 +78,60 @@ where
             encoder: None,
             has_mempool: false,
             has_state_update: false,
+            has_block_state_change: false,
+            has_mempool_state_change: false,
             has_signers: false,
             mutlicaller_address: None,
             relays,
+            tips_policy: Arc::new(CurveTipsPolicy::default()),
             _t: PhantomData,
         }
     }
 
-    pub async fn wait(self) {
+    /// Wait for all actors to finish or for shutdown
+    pub async fn wait(self) -> ShutdownReport {
         self.actor_manager.wait().await
     }
 
+    /// Signal all actors to stop and wait for them, workers not stopped within the shutdown timeout are reported and aborted
+    pub async fn shutdown(self) -> ShutdownReport {
+        self.actor_manager.shutdown().await
+    }
+
+    /// Token to shutdown actors while waiting for them
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.actor_manager.shutdown_token()
+    }
+
+    pub fn with_shutdown_timeout(&mut self, shutdown_timeout: Duration) -> &mut Self {
+        self.actor_manager.with_shutdown_timeout(shutdown_timeout);
+        self
+    }
+
+    /// Set tips policy of the encoder, flashbots broadcaster, stuffing tx monitor and packing merger started later
+    pub fn with_tips_policy(&mut self, tips_policy: Arc<dyn TipsPolicy>) -> &mut Self {
+        self.tips_policy = tips_policy;
+        self
+    }
+
     /// Start a custom actor
     pub fn start(&mut self, actor: impl Actor + 'static) -> Result<&mut Self> {
         self.actor_manager.start(actor)?;
         Ok(self)
     }
 
+    /// Start a custom actor restarted according to the strategy
+    pub fn start_supervised(&mut self, actor: impl Actor + Send + Sync + 'static, strategy: RestartStrategy) -> Result<&mut Self> {
+        self.actor_manager.start_supervised(actor, strategy)?;
+        Ok(self)
+    }
+
+    /// Set hook called when a critical actor exceeds its restart budget, all actors are shut down by default
+    pub fn with_escalation_hook(&mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> &mut Self {
+        self.actor_manager.with_escalation_hook(hook);
+        self
+    }
+
     /// Start a custom actor and wait for it to finish
     pub fn start_and_wait(&mut self, actor: impl Actor + Send + Sync + 'static) -> Result<&mut Self> {
         self.actor_manager.start_and_wait(actor)?;
@@ -161,7 +204,11 @@ where
             },
         };
 
-        self.encoder = Some(MulticallerSwapEncoder::new(multicaller_address));
+        self.encoder = Some(
+            MulticallerSwapEncoder::new(multicaller_address)
+                .with_tips_policy(self.tips_policy.clone())
+                .for_chain(&self.bc.chain_parameters()),
+        );
         self.actor_manager.start(SwapRouterActor::new().with_signers(self.signers.clone()).on_bc(&self.bc))?;
         Ok(self)
     }
@@ -264,7 +311,7 @@ where
     pub fn mempool(&mut self) -> Result<&mut Self> {
         if !self.has_mempool {
             self.has_mempool = true;
-            self.actor_manager.start(MempoolActor::new().on_bc(&self.bc))?;
+            self.actor_manager.start_supervised(MempoolActor::new().on_bc(&self.bc), RestartStrategy::on_failure(10).critical())?;
         }
         Ok(self)
     }
@@ -320,7 +367,10 @@ where
             false => Flashbots::new(self.provider.clone(), "https://relay.flashbots.net", None).with_relays(self.relays.clone()),
         };
 
-        self.actor_manager.start(FlashbotsBroadcastActor::new(flashbots, smart, allow_broadcast).on_bc(&self.bc))?;
+        self.actor_manager.start_supervised(
+            FlashbotsBroadcastActor::new(flashbots, smart, allow_broadcast).with_tips_policy(self.tips_policy.clone()).on_bc(&self.bc),
+            RestartStrategy::on_failure(10).critical(),
+        )?;
         Ok(self)
     }
 
@@ -342,7 +392,8 @@ where
 
     /// Starts stuffing tx monitor
     pub fn with_health_monitor_stuffing_tx(&mut self) -> Result<&mut Self> {
-        self.actor_manager.start(StuffingTxMonitorActor::new(self.provider.clone()).on_bc(&self.bc))?;
+        self.actor_manager
+            .start(StuffingTxMonitorActor::new(self.provider.clone()).with_tips_policy(self.tips_policy.clone()).on_bc(&self.bc))?;
         Ok(self)
     }
 
@@ -358,6 +409,12 @@ where
         Ok(self)
     }
 
+    /// Start pool loader enumerating known factories, faster than the history loader on a cold start
+    pub fn with_pool_factory_loader(&mut self, pools_config: PoolsConfig) -> Result<&mut Self> {
+        self.actor_manager.start(FactoryPoolLoaderOneShotActor::new(self.provider.clone(), pools_config).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
     /// Start pool loader from new block events
     pub fn with_pool_loader(&mut self) -> Result<&mut Self> {
         self.actor_manager.start(PoolLoaderActor::new(self.provider.clone()).on_bc(&self.bc))?;
@@ -416,6 +473,12 @@ where
         Ok(self)
     }
 
+    /// Start packing merger, combines the non-conflicting backruns of a block with max total profit
+    pub fn with_packing_merger(&mut self) -> Result<&mut Self> {
+        self.actor_manager.start(PackingMergerActor::new().with_tips_policy(self.tips_policy.clone()).on_bc(&self.bc))?;
+        Ok(self)
+    }
+
     /// Start all mergers
     pub fn with_mergers(&mut self) -> Result<&mut Self> {
         self.with_swap_path_merger()?.with_same_path_merger()?.with_diff_path_merger()
@@ -427,8 +490,7 @@ where
             self.actor_manager.start(StateChangeArbSearcherActor::new(backrun_config).on_bc(&self.bc))?;
             self.has_state_update = true
         }
-        self.actor_manager.start(BlockStateChangeProcessorActor::new().on_bc(&self.bc))?;
-        Ok(self)
+        self.with_block_state_change_processor()
     }
 
     /// Start backrun for pending txs
@@ -437,8 +499,7 @@ where
             self.actor_manager.start(StateChangeArbSearcherActor::new(backrun_config).on_bc(&self.bc))?;
             self.has_state_update = true
         }
-        self.actor_manager.start(PendingTxStateChangeProcessorActor::new(self.provider.clone()).on_bc(&self.bc))?;
-        Ok(self)
+        self.with_mempool_state_change_processor()
     }
 
     /// Start backrun for blocks and pending txs
@@ -446,6 +507,34 @@ where
         self.with_backrun_block(backrun_config.clone())?.with_backrun_mempool(backrun_config)
     }
 
+    /// Start state updates for blocks
+    pub fn with_block_state_change_processor(&mut self) -> Result<&mut Self> {
+        if !self.has_block_state_change {
+            self.actor_manager.start(BlockStateChangeProcessorActor::new().on_bc(&self.bc))?;
+            self.has_block_state_change = true
+        }
+        Ok(self)
+    }
+
+    /// Start state updates for pending txs
+    pub fn with_mempool_state_change_processor(&mut self) -> Result<&mut Self> {
+        if !self.has_mempool_state_change {
+            self.actor_manager.start(PendingTxStateChangeProcessorActor::new(self.provider.clone()).on_bc(&self.bc))?;
+            self.has_mempool_state_change = true
+        }
+        Ok(self)
+    }
+
+    /// Start custom strategies for blocks and pending txs
+    pub fn with_strategies(&mut self, strategies: Vec<Arc<dyn Strategy>>, eoa: Option<Address>) -> Result<&mut Self> {
+        let mut strategy_actor = StrategyActor::new().with_strategies(strategies);
+        if let Some(eoa) = eoa {
+            strategy_actor = strategy_actor.with_eoa(eoa);
+        }
+        self.actor_manager.start(strategy_actor.on_bc(&self.bc))?;
+        self.with_block_state_change_processor()?.with_mempool_state_change_processor()
+    }
+
     /// Start influxdb writer
     pub fn with_influxdb_writer(&mut self, url: String, database: String, tags: HashMap<String, String>) -> Result<&mut Self> {
         self.actor_manager.start(InfluxDbWriterActor::new(url, database, tags).on_bc(&self.bc))?;
@@ -458,6 +547,12 @@ where
         Ok(self)
     }
 
+    /// Start recorder of actors liveness and channel stats
+    pub fn with_actors_stats_recorder(&mut self) -> Result<&mut Self> {
+        self.actor_manager.start(ActorsStatsRecorderActor::new().on_bc(&self.bc))?;
+        Ok(self)
+    }
+
     /// Start web server
     pub fn with_web_server<S>(&mut self, host: String, router: Router<S>, db_pool: DbPool) -> Result<&mut Self>
     where
@@ -1,8 +1,7 @@
 use alloy::primitives::BlockHash;
 use alloy::primitives::ChainId;
 use influxdb::WriteQuery;
-use loom_core_actors::{Broadcaster, SharedState};
-use loom_defi_address_book::TokenAddress;
+use loom_core_actors::{Broadcaster, BroadcasterStats, SharedState};
 use loom_types_blockchain::{ChainParameters, Mempool};
 use loom_types_entities::{AccountNonceAndBalanceState, BlockHistory, LatestBlock, Market, MarketState, Token};
 use loom_types_events::{
@@ -37,6 +36,10 @@ pub struct Blockchain {
 
 impl Blockchain {
     pub fn new(chain_id: ChainId) -> Blockchain {
+        Self::new_with_chain_parameters(ChainParameters::from(chain_id))
+    }
+
+    pub fn new_with_chain_parameters(chain_parameters: ChainParameters) -> Blockchain {
         let new_block_headers_channel: Broadcaster<MessageBlockHeader> = Broadcaster::new(10);
         let new_block_with_tx_channel: Broadcaster<MessageBlock> = Broadcaster::new(10);
         let new_block_state_update_channel: Broadcaster<MessageBlockStateUpdate> = Broadcaster::new(10);
@@ -54,23 +57,24 @@ impl Blockchain {
 
         let mut market_instance = Market::default();
 
-        let weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
-        let usdc_token = Token::new_with_data(TokenAddress::USDC, Some("USDC".to_string()), None, Some(6), true, false);
-        let usdt_token = Token::new_with_data(TokenAddress::USDT, Some("USDT".to_string()), None, Some(6), true, false);
-        let dai_token = Token::new_with_data(TokenAddress::DAI, Some("DAI".to_string()), None, Some(18), true, false);
-        let wbtc_token = Token::new_with_data(TokenAddress::WBTC, Some("WBTC".to_string()), None, Some(8), true, false);
-        let threecrv_token = Token::new_with_data(TokenAddress::THREECRV, Some("3Crv".to_string()), None, Some(18), false, true);
-
-        market_instance.add_token(weth_token).unwrap();
-        market_instance.add_token(usdc_token).unwrap();
-        market_instance.add_token(usdt_token).unwrap();
-        market_instance.add_token(dai_token).unwrap();
-        market_instance.add_token(wbtc_token).unwrap();
-        market_instance.add_token(threecrv_token).unwrap();
+        for chain_token in chain_parameters.tokens.iter() {
+            let mut token = Token::new_with_data(
+                chain_token.address,
+                Some(chain_token.symbol.clone()),
+                None,
+                Some(chain_token.decimals),
+                chain_token.basic,
+                chain_token.middle,
+            );
+            if chain_token.address == chain_parameters.weth {
+                token.set_wrapped_native();
+            }
+            market_instance.add_token(token).unwrap();
+        }
 
         Blockchain {
-            chain_id,
-            chain_parameters: ChainParameters::ethereum(),
+            chain_id: chain_parameters.chain_id,
+            chain_parameters,
             market: SharedState::new(market_instance),
             market_state: SharedState::new(MarketState::new(Default::default())),
             mempool: SharedState::new(Mempool::new()),
@@ -174,4 +178,22 @@ impl Blockchain {
     pub fn tasks_channel(&self) -> Broadcaster<Task> {
         self.tasks_channel.clone()
     }
+
+    /// Stats of all channels by channel name
+    pub async fn channels_stats(&self) -> Vec<(&'static str, BroadcasterStats)> {
+        vec![
+            ("new_block_headers", self.new_block_headers_channel.stats().await),
+            ("new_block_with_tx", self.new_block_with_tx_channel.stats().await),
+            ("new_block_state_update", self.new_block_state_update_channel.stats().await),
+            ("new_block_logs", self.new_block_logs_channel.stats().await),
+            ("new_mempool_tx", self.new_mempool_tx_channel.stats().await),
+            ("market_events", self.market_events_channel.stats().await),
+            ("mempool_events", self.mempool_events_channel.stats().await),
+            ("pool_health_monitor", self.pool_health_monitor_channel.stats().await),
+            ("compose", self.compose_channel.stats().await),
+            ("state_update", self.state_update_channel.stats().await),
+            ("influxdb_write", self.influxdb_write_channel.stats().await),
+            ("tasks", self.tasks_channel.stats().await),
+        ]
+    }
 }
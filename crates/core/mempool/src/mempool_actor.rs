@@ -3,6 +3,7 @@ use alloy_rpc_types::BlockTransactions;
 use chrono::{Duration, Utc};
 use eyre::eyre;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace};
 
 use loom_core_actors::{run_async, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
@@ -18,6 +19,7 @@ pub async fn new_mempool_worker(
     block_header_rx: Broadcaster<MessageBlockHeader>,
     block_with_txs_rx: Broadcaster<MessageBlock>,
     broadcaster: Broadcaster<MempoolEvents>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(mempool_update_rx);
     subscribe!(block_header_rx);
@@ -28,6 +30,9 @@ pub async fn new_mempool_worker(
 
     loop {
         tokio::select! {
+                _ = shutdown_token.cancelled() => {
+                    break Ok("Mempool worker stopped".to_string())
+                }
                 msg = mempool_update_rx.recv() => {
                     let mempool_update_msg = match msg {
                         Ok(mempool_update_msg) => mempool_update_msg,
@@ -201,6 +206,10 @@ impl MempoolActor {
 
 impl Actor for MempoolActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(new_mempool_worker(
             self.chain_parameters.clone(),
             self.mempool.clone().unwrap(),
@@ -208,6 +217,7 @@ impl Actor for MempoolActor {
             self.block_header_rx.clone().unwrap(),
             self.block_with_txs_rx.clone().unwrap(),
             self.mempool_events_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -1,11 +1,11 @@
 use eyre::{eyre, Result};
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_types_entities::{AccountNonceAndBalanceState, TxSigners};
 use loom_types_events::{MessageTxCompose, TxCompose, TxComposeData};
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 /// encoder task performs encode for request
@@ -49,13 +49,17 @@ async fn swap_router_worker(
     account_monitor: SharedState<AccountNonceAndBalanceState>,
     compose_channel_rx: Broadcaster<MessageTxCompose>,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    let mut compose_channel_rx: Receiver<MessageTxCompose> = compose_channel_rx.subscribe().await;
+    let mut compose_channel_rx: BroadcasterReceiver<MessageTxCompose> = compose_channel_rx.subscribe().await;
 
     info!("swap router worker started");
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Swap router worker stopped".to_string())
+            }
             msg = compose_channel_rx.recv() => {
                 let msg : Result<MessageTxCompose, RecvError> = msg;
                 match msg {
@@ -112,11 +116,16 @@ impl SwapRouterActor {
 
 impl Actor for SwapRouterActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(swap_router_worker(
             self.signers.clone().unwrap(),
             self.account_nonce_balance.clone().unwrap(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::topology_config::TransportType;
-use crate::topology_config::{BroadcasterConfig, ClientConfigParams, EncoderConfig, EstimatorConfig, SignersConfig, TopologyConfig};
+use crate::topology_config::{
+    BroadcasterConfig, ClientConfigParams, EncoderConfig, EstimatorConfig, HealthMonitorConfig, MergerConfig, SignersConfig, TopologyConfig,
+};
 use alloy_primitives::Address;
 use alloy_provider::{ProviderBuilder, RootProvider};
 use alloy_rpc_client::ClientBuilder;
 use alloy_transport::BoxTransport;
 use alloy_transport_ipc::IpcConnect;
 use alloy_transport_ws::WsConnect;
+use axum::Router;
 use eyre::{eyre, OptionExt, Result};
 use loom_broadcast_accounts::{InitializeSignersOneShotBlockingActor, NonceAndBalanceMonitorActor, TxSignersActor};
 use loom_broadcast_broadcaster::FlashbotsBroadcastActor;
@@ -17,20 +21,28 @@ use loom_core_actors::{Accessor, Actor, Consumer, Producer, SharedState, WorkerR
 use loom_core_block_history::BlockHistoryActor;
 use loom_core_blockchain::Blockchain;
 use loom_core_mempool::MempoolActor;
-use loom_defi_health_monitor::PoolHealthMonitorActor;
+use loom_core_router::SwapRouterActor;
+use loom_defi_address_book::AddressBook;
+use loom_defi_health_monitor::{PoolHealthMonitorActor, StateHealthMonitorActor, StuffingTxMonitorActor};
 use loom_defi_market::{CurvePoolLoaderOneShotActor, HistoryPoolLoaderOneShotActor, NewPoolLoaderActor, PoolLoaderActor};
 use loom_defi_pools::PoolsConfig;
 use loom_defi_preloader::MarketStatePreloadedOneShotActor;
 use loom_defi_price::PriceActor;
 use loom_execution_estimator::{EvmEstimatorActor, GethEstimatorActor};
 use loom_execution_multicaller::MulticallerSwapEncoder;
+use loom_metrics::{ActorsStatsRecorderActor, BlockLatencyRecorderActor, InfluxDbWriterActor};
 use loom_node_actor_config::NodeBlockActorConfig;
 #[cfg(feature = "db-access")]
 use loom_node_db_access::RethDbAccessBlockActor;
 use loom_node_grpc::NodeExExGrpcActor;
 use loom_node_json_rpc::{NodeBlockActor, NodeMempoolActor};
+use loom_rpc_handler::WebServerActor;
+use loom_storage_db::init_db_pool;
+use loom_strategy_backrun::{BlockStateChangeProcessorActor, PendingTxStateChangeProcessorActor, StateChangeArbSearcherActor};
+use loom_strategy_merger::{ArbSwapPathMergerActor, DiffPathMergerActor, PackingMergerActor, SamePathMergerActor};
 use loom_types_entities::TxSigners;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 pub struct Topology {
@@ -41,6 +53,7 @@ pub struct Topology {
     default_blockchain_name: Option<String>,
     default_multicaller_encoder_name: Option<String>,
     default_signer_name: Option<String>,
+    shutdown_token: CancellationToken,
 }
 
 impl Topology {
@@ -53,10 +66,14 @@ impl Topology {
             default_blockchain_name: None,
             default_multicaller_encoder_name: None,
             default_signer_name: None,
+            shutdown_token: CancellationToken::new(),
         };
+        let shutdown_token = topology.shutdown_token();
 
         let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
 
+        let tips_policy = config.tips.clone().unwrap_or_default().build()?;
+
         //let timeout_duration = Duration::from_secs(10);
 
         for (name, v) in config.clients.clone().iter() {
@@ -99,7 +116,7 @@ impl Topology {
             match v {
                 EncoderConfig::SwapStep(c) => {
                     let address: Address = c.address.parse()?;
-                    let encoder = MulticallerSwapEncoder::new(address);
+                    let encoder = MulticallerSwapEncoder::new(address).with_tips_policy(tips_policy.clone());
                     topology.multicaller_encoders.insert(k.clone(), encoder);
                     topology.default_multicaller_encoder_name = Some(k.clone());
                 }
@@ -107,21 +124,13 @@ impl Topology {
         }
 
         for (k, params) in config.blockchains.iter() {
-            let blockchain = Blockchain::new(params.chain_id.unwrap_or(1) as u64);
+            let chain_parameters = params.chain_parameters()?;
+            AddressBook::register(params.address_book(&chain_parameters)?);
+            let blockchain = Blockchain::new_with_chain_parameters(chain_parameters);
 
             info!("Starting block history actor {k}");
-            let mut block_history_actor = BlockHistoryActor::new(topology.get_client(None)?);
-            match block_history_actor
-                .access(blockchain.latest_block())
-                .access(blockchain.market_state())
-                .access(blockchain.block_history())
-                .consume(blockchain.new_block_headers_channel())
-                .consume(blockchain.new_block_with_tx_channel())
-                .consume(blockchain.new_block_logs_channel())
-                .consume(blockchain.new_block_state_update_channel())
-                .produce(blockchain.market_events_channel())
-                .start()
-            {
+            let block_history_actor = BlockHistoryActor::new(topology.get_client(None)?).on_bc(&blockchain);
+            match block_history_actor.start_registered_with_token(shutdown_token.clone()) {
                 Ok(r) => {
                     tasks.extend(r);
                     info!("Block history actor started successfully")
@@ -132,15 +141,8 @@ impl Topology {
             }
 
             info!("Starting mempool actor {k}");
-            let mut mempool_actor = MempoolActor::new();
-            match mempool_actor
-                .access(blockchain.mempool())
-                .consume(blockchain.new_mempool_tx_channel())
-                .consume(blockchain.new_block_headers_channel())
-                .consume(blockchain.new_block_with_tx_channel())
-                .produce(blockchain.mempool_events_channel())
-                .start()
-            {
+            let mempool_actor = MempoolActor::new().on_bc(&blockchain);
+            match mempool_actor.start_registered_with_token(shutdown_token.clone()) {
                 Ok(r) => {
                     tasks.extend(r);
                     info!("Mempool actor started successfully")
@@ -152,7 +154,12 @@ impl Topology {
 
             info!("Starting pool monitor monitor actor {k}");
             let mut new_pool_health_monior_actor = PoolHealthMonitorActor::new();
-            match new_pool_health_monior_actor.access(blockchain.market()).consume(blockchain.pool_health_monitor_channel()).start() {
+            match new_pool_health_monior_actor
+                .access(blockchain.market())
+                .consume(blockchain.pool_health_monitor_channel())
+                .produce(blockchain.market_events_channel())
+                .start_registered_with_token(shutdown_token.clone())
+            {
                 Ok(r) => {
                     tasks.extend(r);
                     info!("Pool monitor monitor actor started")
@@ -184,7 +191,11 @@ impl Topology {
                     }
 
                     let mut signers_actor = TxSignersActor::new();
-                    match signers_actor.consume(blockchain.compose_channel()).produce(blockchain.compose_channel()).start() {
+                    match signers_actor
+                        .consume(blockchain.compose_channel())
+                        .produce(blockchain.compose_channel())
+                        .start_registered_with_token(shutdown_token.clone())
+                    {
                         Ok(r) => {
                             tasks.extend(r);
                             info!("Signers actor has been started")
@@ -236,7 +247,7 @@ impl Topology {
                     .produce(blockchain.new_block_logs_channel())
                     .produce(blockchain.new_block_state_update_channel())
                     .produce(blockchain.new_mempool_tx_channel())
-                    .start()
+                    .start_registered_with_token(shutdown_token.clone())
                 {
                     Ok(r) => {
                         tasks.extend(r);
@@ -269,7 +280,7 @@ impl Topology {
                         .produce(blockchain.new_block_with_tx_channel())
                         .produce(blockchain.new_block_logs_channel())
                         .produce(blockchain.new_block_state_update_channel())
-                        .start()
+                        .start_registered_with_token(shutdown_token.clone())
                     {
                         Ok(r) => {
                             tasks.extend(r);
@@ -288,7 +299,7 @@ impl Topology {
                         .produce(blockchain.new_block_with_tx_channel())
                         .produce(blockchain.new_block_logs_channel())
                         .produce(blockchain.new_block_state_update_channel())
-                        .start()
+                        .start_registered_with_token(shutdown_token.clone())
                     {
                         Ok(r) => {
                             tasks.extend(r);
@@ -309,7 +320,10 @@ impl Topology {
                     Ok(client) => {
                         println!("Starting node mempool actor {name}");
                         let mut node_mempool_actor = NodeMempoolActor::new(client).with_name(name.clone());
-                        match node_mempool_actor.produce(blockchain.new_mempool_tx_channel()).start() {
+                        match node_mempool_actor
+                            .produce(blockchain.new_mempool_tx_channel())
+                            .start_registered_with_token(shutdown_token.clone())
+                        {
                             Ok(r) => {
                                 tasks.extend(r);
                                 info!("Node mempool actor started successfully {name}")
@@ -331,8 +345,8 @@ impl Topology {
                 let client = topology.get_client(c.client.as_ref())?;
                 let blockchain = topology.get_blockchain(c.blockchain.as_ref())?;
                 info!("Starting price actor");
-                let mut price_actor = PriceActor::new(client);
-                match price_actor.access(blockchain.market()).start() {
+                let price_actor = PriceActor::new(client).on_bc(blockchain);
+                match price_actor.start_registered_with_token(shutdown_token.clone()) {
                     Ok(r) => {
                         tasks.extend(r);
                         info!("Price actor has been initialized : {}", name)
@@ -357,7 +371,7 @@ impl Topology {
                     .access(blockchain.nonce_and_balance())
                     .access(blockchain.block_history())
                     .consume(blockchain.market_events_channel())
-                    .start()
+                    .start_registered_with_token(shutdown_token.clone())
                 {
                     Ok(r) => {
                         tasks.extend(r);
@@ -380,8 +394,9 @@ impl Topology {
                         let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
 
                         let flashbots_client = Flashbots::new(client, "https://relay.flashbots.net", None).with_default_relays();
-                        let mut flashbots_actor = FlashbotsBroadcastActor::new(flashbots_client, params.smart.unwrap_or(false), true);
-                        match flashbots_actor.consume(blockchain.compose_channel()).start() {
+                        let mut flashbots_actor = FlashbotsBroadcastActor::new(flashbots_client, params.smart.unwrap_or(false), true)
+                            .with_tips_policy(tips_policy.clone());
+                        match flashbots_actor.consume(blockchain.compose_channel()).start_registered_with_token(shutdown_token.clone()) {
                             Ok(r) => {
                                 tasks.extend(r);
                                 info!("Flashbots broadcaster actor {name} started successfully for {}", blockchain.chain_id())
@@ -407,7 +422,8 @@ impl Topology {
                     info!("Starting history pools loader {name}");
 
                     let mut history_pools_loader_actor = HistoryPoolLoaderOneShotActor::new(client.clone(), PoolsConfig::new());
-                    match history_pools_loader_actor.produce(blockchain.tasks_channel()).start() {
+                    match history_pools_loader_actor.produce(blockchain.tasks_channel()).start_registered_with_token(shutdown_token.clone())
+                    {
                         Ok(r) => {
                             tasks.extend(r);
                             info!("History pool loader actor started successfully {name}")
@@ -421,7 +437,11 @@ impl Topology {
                     info!("Starting curve pools loader {name}");
 
                     let mut curve_pools_loader_actor = CurvePoolLoaderOneShotActor::new(client.clone());
-                    match curve_pools_loader_actor.access(blockchain.market()).access(blockchain.market_state()).start() {
+                    match curve_pools_loader_actor
+                        .access(blockchain.market())
+                        .access(blockchain.market_state())
+                        .start_registered_with_token(shutdown_token.clone())
+                    {
                         Err(e) => {
                             panic!("CurvePoolLoaderOneShotActor : {}", e)
                         }
@@ -435,7 +455,11 @@ impl Topology {
                 if params.new {
                     info!("Starting new pool loader actor {name}");
                     let mut new_pool_actor = NewPoolLoaderActor::new(PoolsConfig::new());
-                    match new_pool_actor.consume(blockchain.new_block_logs_channel()).produce(blockchain.tasks_channel()).start() {
+                    match new_pool_actor
+                        .consume(blockchain.new_block_logs_channel())
+                        .produce(blockchain.tasks_channel())
+                        .start_registered_with_token(shutdown_token.clone())
+                    {
                         Ok(r) => {
                             tasks.extend(r);
                             info!("New pool actor started")
@@ -448,11 +472,14 @@ impl Topology {
 
                 info!("Starting pool loader actor {name}");
                 let mut pool_loader_actor = PoolLoaderActor::new(client.clone());
+                if let Some(token_cache) = params.token_cache.as_ref() {
+                    pool_loader_actor = pool_loader_actor.with_token_cache(token_cache);
+                }
                 match pool_loader_actor
                     .access(blockchain.market())
                     .access(blockchain.market_state())
                     .consume(blockchain.tasks_channel())
-                    .start()
+                    .start_registered_with_token(shutdown_token.clone())
                 {
                     Ok(r) => {
                         tasks.extend(r);
@@ -474,9 +501,9 @@ impl Topology {
                         let client = params.client.as_ref().map(|x| topology.get_client(Some(x))).transpose()?; //   topology.get_client(params.client.as_ref())?;
 
                         let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
-                        let encoder = topology.get_multicaller_encoder(params.encoder.as_ref())?;
-                        let mut evm_estimator_actor = EvmEstimatorActor::new_with_provider(encoder, client);
-                        match evm_estimator_actor.consume(blockchain.compose_channel()).produce(blockchain.compose_channel()).start() {
+                        let encoder = topology.get_multicaller_encoder(params.encoder.as_ref())?.for_chain(&blockchain.chain_parameters());
+                        let evm_estimator_actor = EvmEstimatorActor::new_with_provider(encoder, client).on_bc(&blockchain);
+                        match evm_estimator_actor.start_registered_with_token(shutdown_token.clone()) {
                             Ok(r) => {
                                 tasks.extend(r);
                                 info!("EVM estimator actor started successfully {name} @ {}", blockchain.chain_id())
@@ -489,12 +516,12 @@ impl Topology {
                     EstimatorConfig::Geth(params) => {
                         let client = topology.get_client(params.client.as_ref())?;
                         let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
-                        let encoder = topology.get_multicaller_encoder(params.encoder.as_ref())?;
+                        let encoder = topology.get_multicaller_encoder(params.encoder.as_ref())?.for_chain(&blockchain.chain_parameters());
 
                         let flashbots_client = Arc::new(Flashbots::new(client, "https://relay.flashbots.net", None).with_default_relays());
 
-                        let mut geth_estimator_actor = GethEstimatorActor::new(flashbots_client, encoder);
-                        match geth_estimator_actor.consume(blockchain.compose_channel()).produce(blockchain.compose_channel()).start() {
+                        let geth_estimator_actor = GethEstimatorActor::new(flashbots_client, encoder).on_bc(&blockchain);
+                        match geth_estimator_actor.start_registered_with_token(shutdown_token.clone()) {
                             Ok(r) => {
                                 tasks.extend(r);
                                 info!("Geth estimator actor started successfully {name} @ {}", blockchain.chain_id())
@@ -510,9 +537,248 @@ impl Topology {
             warn!("No estimator actors in config")
         }
 
+        if let Some(router_actors) = config.actors.router {
+            for (name, params) in router_actors {
+                let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                let signers = topology.get_signers(params.signers.as_ref())?;
+
+                info!("Starting swap router actor {name}");
+                let swap_router_actor = SwapRouterActor::new().with_signers(signers).on_bc(blockchain);
+                match swap_router_actor.start_registered_with_token(shutdown_token.clone()) {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Swap router actor started successfully {name} @ {}", blockchain.chain_id())
+                    }
+                    Err(e) => {
+                        panic!("Error starting swap router actor {name} @ {} : {}", blockchain.chain_id(), e)
+                    }
+                }
+            }
+        } else {
+            warn!("No router actors in config")
+        }
+
+        if let Some(merger_actors) = config.actors.merger {
+            for (name, params) in merger_actors {
+                let merger_actor_result = match params {
+                    MergerConfig::SwapPath(params) => {
+                        let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                        let multicaller_address = topology.get_multicaller_encoder(params.encoder.as_ref())?.get_contract_address();
+                        info!("Starting swap path merger actor {name}");
+                        ArbSwapPathMergerActor::new(multicaller_address)
+                            .on_bc(blockchain)
+                            .start_registered_with_token(shutdown_token.clone())
+                    }
+                    MergerConfig::SamePath(params) => {
+                        let client = topology.get_client(params.client.as_ref())?;
+                        let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                        info!("Starting same path merger actor {name}");
+                        SamePathMergerActor::new(client).on_bc(blockchain).start_registered_with_token(shutdown_token.clone())
+                    }
+                    MergerConfig::DiffPath(params) => {
+                        let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                        info!("Starting diff path merger actor {name}");
+                        DiffPathMergerActor::new().on_bc(blockchain).start_registered_with_token(shutdown_token.clone())
+                    }
+                    MergerConfig::Packing(params) => {
+                        let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                        info!("Starting packing merger actor {name}");
+                        let mut actor = PackingMergerActor::new().with_tips_policy(tips_policy.clone());
+                        if let Some(time_budget_ms) = params.time_budget_ms {
+                            actor = actor.with_time_budget(Duration::from_millis(time_budget_ms));
+                        }
+                        if let Some(max_candidates) = params.max_candidates {
+                            actor = actor.with_max_candidates(max_candidates);
+                        }
+                        actor.on_bc(blockchain).start_registered_with_token(shutdown_token.clone())
+                    }
+                };
+                match merger_actor_result {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Merger actor started successfully {name}")
+                    }
+                    Err(e) => {
+                        panic!("Error starting merger actor {name} : {}", e)
+                    }
+                }
+            }
+        } else {
+            warn!("No merger actors in config")
+        }
+
+        if let Some(backrun_actors) = config.actors.backrun {
+            // state change processors are shared by the searchers of a blockchain
+            let mut block_processor_chains: HashSet<u64> = HashSet::new();
+            let mut pending_tx_processor_chains: HashSet<u64> = HashSet::new();
+            for (name, params) in backrun_actors {
+                let client = topology.get_client(params.client.as_ref())?;
+                let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                let backrun_config = params.backrun_config()?;
+
+                info!("Starting state change arb searcher actor {name}");
+                match StateChangeArbSearcherActor::new(backrun_config).on_bc(blockchain).start_registered_with_token(shutdown_token.clone())
+                {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("State change arb searcher actor started successfully {name} @ {}", blockchain.chain_id())
+                    }
+                    Err(e) => {
+                        panic!("Error starting state change arb searcher actor {name} @ {} : {}", blockchain.chain_id(), e)
+                    }
+                }
+
+                if params.block.unwrap_or(true) && block_processor_chains.insert(blockchain.chain_id()) {
+                    info!("Starting block state change processor actor {name}");
+                    match BlockStateChangeProcessorActor::new().on_bc(blockchain).start_registered_with_token(shutdown_token.clone()) {
+                        Ok(r) => {
+                            tasks.extend(r);
+                            info!("Block state change processor actor started successfully {name}")
+                        }
+                        Err(e) => {
+                            panic!("Error starting block state change processor actor {name} : {}", e)
+                        }
+                    }
+                }
+
+                if params.mempool.unwrap_or(true) && pending_tx_processor_chains.insert(blockchain.chain_id()) {
+                    info!("Starting pending tx state change processor actor {name}");
+                    match PendingTxStateChangeProcessorActor::new(client)
+                        .on_bc(blockchain)
+                        .start_registered_with_token(shutdown_token.clone())
+                    {
+                        Ok(r) => {
+                            tasks.extend(r);
+                            info!("Pending tx state change processor actor started successfully {name}")
+                        }
+                        Err(e) => {
+                            panic!("Error starting pending tx state change processor actor {name} : {}", e)
+                        }
+                    }
+                }
+            }
+        } else {
+            warn!("No backrun actors in config")
+        }
+
+        if let Some(health_monitor_actors) = config.actors.health_monitor {
+            for (name, params) in health_monitor_actors {
+                let health_monitor_actor_result = match params {
+                    HealthMonitorConfig::State(params) => {
+                        let client = topology.get_client(params.client.as_ref())?;
+                        let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                        info!("Starting state health monitor actor {name}");
+                        StateHealthMonitorActor::new(client).on_bc(blockchain).start_registered_with_token(shutdown_token.clone())
+                    }
+                    HealthMonitorConfig::StuffingTx(params) => {
+                        let client = topology.get_client(params.client.as_ref())?;
+                        let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+                        info!("Starting stuffing tx monitor actor {name}");
+                        StuffingTxMonitorActor::new(client)
+                            .with_tips_policy(tips_policy.clone())
+                            .on_bc(blockchain)
+                            .start_registered_with_token(shutdown_token.clone())
+                    }
+                };
+                match health_monitor_actor_result {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Health monitor actor started successfully {name}")
+                    }
+                    Err(e) => {
+                        panic!("Error starting health monitor actor {name} : {}", e)
+                    }
+                }
+            }
+        } else {
+            warn!("No health monitor actors in config")
+        }
+
+        if let Some(metrics_actors) = config.actors.metrics {
+            for (name, params) in metrics_actors {
+                let blockchain = topology.get_blockchain(params.blockchain.as_ref())?;
+
+                let Some(influxdb_config) = config.influxdb.clone() else {
+                    error!("Skipping metrics actors {name} @ {} : influxdb is not configured", blockchain.chain_id());
+                    continue;
+                };
+
+                info!("Starting influxdb writer actor {name}");
+                match InfluxDbWriterActor::new(influxdb_config.url, influxdb_config.database, influxdb_config.tags)
+                    .on_bc(blockchain)
+                    .start_registered_with_token(shutdown_token.clone())
+                {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("InfluxDB writer actor started successfully {name} @ {}", blockchain.chain_id())
+                    }
+                    Err(e) => {
+                        panic!("Error starting influxdb writer actor {name} @ {} : {}", blockchain.chain_id(), e)
+                    }
+                }
+
+                if params.block_latency.unwrap_or(true) {
+                    match BlockLatencyRecorderActor::new().on_bc(blockchain).start_registered_with_token(shutdown_token.clone()) {
+                        Ok(r) => {
+                            tasks.extend(r);
+                            info!("Block latency recorder actor started successfully {name}")
+                        }
+                        Err(e) => {
+                            panic!("Error starting block latency recorder actor {name} : {}", e)
+                        }
+                    }
+                }
+
+                if params.actors_stats.unwrap_or(true) {
+                    match ActorsStatsRecorderActor::new().on_bc(blockchain).start_registered_with_token(shutdown_token.clone()) {
+                        Ok(r) => {
+                            tasks.extend(r);
+                            info!("Actors stats recorder actor started successfully {name}")
+                        }
+                        Err(e) => {
+                            panic!("Error starting actors stats recorder actor {name} : {}", e)
+                        }
+                    }
+                }
+            }
+        } else {
+            warn!("No metrics actors in config")
+        }
+
+        match (config.webserver, config.database) {
+            (Some(webserver), Some(database)) => {
+                let blockchain = topology.get_blockchain(webserver.blockchain.as_ref())?;
+                let db_pool = init_db_pool(database.url).await?;
+
+                info!("Starting web server actor on {}", webserver.host);
+                let web_server_actor =
+                    WebServerActor::new(webserver.host, Router::new(), db_pool, shutdown_token.clone()).on_bc(blockchain);
+                match web_server_actor.start_registered_with_token(shutdown_token.clone()) {
+                    Ok(r) => {
+                        tasks.extend(r);
+                        info!("Web server actor started successfully")
+                    }
+                    Err(e) => {
+                        panic!("Error starting web server actor : {}", e)
+                    }
+                }
+            }
+            (Some(_), None) => {
+                error!("Skipping web server : database is not configured")
+            }
+            _ => {
+                warn!("No web server in config")
+            }
+        }
+
         Ok((topology, tasks))
     }
 
+    /// Token stopping the actors started from the config when cancelled
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown_token.clone()
+    }
+
     pub fn get_client(&self, name: Option<&String>) -> Result<RootProvider<BoxTransport>> {
         match self.clients.get(name.unwrap_or(&"local".to_string())) {
             Some(a) => Ok(a.client().ok_or_eyre("CLIENT_NOT_SET")?.clone()),
@@ -1,16 +1,97 @@
 use std::collections::HashMap;
 use std::fs;
 
+use alloy_eips::eip1559::BaseFeeParams;
+use alloy_primitives::Address;
 use alloy_provider::RootProvider;
 use alloy_transport::BoxTransport;
 use eyre::Result;
 use loom_broadcast_flashbots::client::RelayConfig;
+use loom_defi_address_book::AddressBook;
+use loom_strategy_backrun::{BackrunConfig, BackrunLimits};
+use loom_types_blockchain::{ChainFactory, ChainParameters, ChainToken};
+use loom_types_entities::TipsConfig;
 use serde::Deserialize;
 use strum_macros::Display;
 
+#[derive(Clone, Debug, Deserialize)]
+pub struct BaseFeeParamsConfig {
+    pub max_change_denominator: u128,
+    pub elasticity_multiplier: u128,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChainTokenConfig {
+    pub address: String,
+    pub symbol: String,
+    pub decimals: Option<u8>,
+    pub basic: Option<bool>,
+    pub middle: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BlockchainConfig {
     pub chain_id: Option<i64>,
+    pub base_fee_params: Option<BaseFeeParamsConfig>,
+    pub weth: Option<String>,
+    pub tokens: Option<Vec<ChainTokenConfig>>,
+    // factory name -> factory address
+    pub factories: Option<HashMap<String, String>>,
+    // TOML or JSON address book file
+    pub address_book: Option<String>,
+}
+
+impl BlockchainConfig {
+    /// Built-in parameters of the chain overridden with values from config
+    pub fn chain_parameters(&self) -> Result<ChainParameters> {
+        let mut chain_parameters = ChainParameters::from(self.chain_id.unwrap_or(1) as u64);
+
+        if let Some(base_fee_params) = &self.base_fee_params {
+            chain_parameters.base_fee_params =
+                BaseFeeParams::new(base_fee_params.max_change_denominator, base_fee_params.elasticity_multiplier);
+        }
+
+        if let Some(weth) = &self.weth {
+            chain_parameters.weth = weth.parse()?;
+        }
+
+        if let Some(tokens) = &self.tokens {
+            for token in tokens.iter() {
+                let address: Address = token.address.parse()?;
+                let chain_token = ChainToken::new(
+                    address,
+                    &token.symbol,
+                    token.decimals.unwrap_or(18),
+                    token.basic.unwrap_or_default(),
+                    token.middle.unwrap_or_default(),
+                );
+                chain_parameters.tokens.retain(|t| t.address != address);
+                chain_parameters.tokens.push(chain_token);
+            }
+        }
+
+        if let Some(factories) = &self.factories {
+            for (name, address) in factories.iter() {
+                chain_parameters.factories.retain(|f| &f.name != name);
+                chain_parameters.factories.push(ChainFactory::new(name, address.parse()?));
+            }
+        }
+
+        Ok(chain_parameters)
+    }
+
+    /// Registered book of the chain extended with chain parameters and the address book file
+    pub fn address_book(&self, chain_parameters: &ChainParameters) -> Result<AddressBook> {
+        let mut address_book = match AddressBook::get(chain_parameters.chain_id) {
+            Some(address_book) => address_book.as_ref().clone(),
+            None => AddressBook::new(chain_parameters.chain_id),
+        };
+        address_book.merge(chain_parameters.to_address_book());
+        if let Some(file_name) = &self.address_book {
+            address_book.merge(AddressBook::load_from_file(file_name)?);
+        }
+        Ok(address_book)
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Display)]
@@ -198,16 +279,96 @@ pub struct PoolsConfig {
     pub history: bool,
     pub new: bool,
     pub protocol: bool,
+    // file of token classifications, loaded on start and saved on shutdown
+    pub token_cache: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RouterConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub signers: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SwapPathMergerConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub encoder: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PackingMergerConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub time_budget_ms: Option<u64>,
+    pub max_candidates: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum MergerConfig {
+    #[serde(rename = "swappath")]
+    SwapPath(SwapPathMergerConfig),
+    #[serde(rename = "samepath")]
+    SamePath(BlockchainClientConfig),
+    #[serde(rename = "diffpath")]
+    DiffPath(BlockchainClientConfig),
+    #[serde(rename = "packing")]
+    Packing(PackingMergerConfig),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackrunStrategyConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub client: Option<String>,
+    // backrun state changes of new blocks
+    pub block: Option<bool>,
+    // backrun state changes of pending txs
+    pub mempool: Option<bool>,
+    pub eoa: Option<String>,
+    pub smart: Option<bool>,
+    #[serde(flatten)]
+    pub limits: BackrunLimits,
+}
+
+impl BackrunStrategyConfig {
+    pub fn backrun_config(&self) -> Result<BackrunConfig> {
+        let eoa = self.eoa.as_ref().map(|eoa| eoa.parse()).transpose()?;
+        Ok(BackrunConfig::new(eoa, self.smart.unwrap_or(true))
+            .with_origins(self.block.unwrap_or(true), self.mempool.unwrap_or(true))
+            .with_limits(self.limits.clone()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum HealthMonitorConfig {
+    #[serde(rename = "state")]
+    State(BlockchainClientConfig),
+    #[serde(rename = "stuffing")]
+    StuffingTx(BlockchainClientConfig),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
+    pub block_latency: Option<bool>,
+    pub actors_stats: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct WebserverConfig {
     pub host: String,
+    #[serde(rename = "bc")]
+    pub blockchain: Option<String>,
 }
 
 impl Default for WebserverConfig {
     fn default() -> Self {
-        WebserverConfig { host: "127.0.0.1:3333".to_string() }
+        WebserverConfig { host: "127.0.0.1:3333".to_string(), blockchain: None }
     }
 }
 
@@ -226,6 +387,11 @@ pub struct ActorConfig {
     pub pools: Option<HashMap<String, PoolsConfig>>,
     pub noncebalance: Option<HashMap<String, BlockchainClientConfig>>,
     pub estimator: Option<HashMap<String, EstimatorConfig>>,
+    pub router: Option<HashMap<String, RouterConfig>>,
+    pub merger: Option<HashMap<String, MergerConfig>>,
+    pub backrun: Option<HashMap<String, BackrunStrategyConfig>>,
+    pub health_monitor: Option<HashMap<String, HealthMonitorConfig>>,
+    pub metrics: Option<HashMap<String, MetricsConfig>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -239,6 +405,7 @@ pub struct TopologyConfig {
     pub preloaders: Option<HashMap<String, PreloaderConfig>>,
     pub webserver: Option<WebserverConfig>,
     pub database: Option<DatabaseConfig>,
+    pub tips: Option<TipsConfig>,
 }
 
 impl TopologyConfig {
@@ -252,6 +419,82 @@ impl TopologyConfig {
 #[cfg(test)]
 mod test {
     use super::*;
+    use loom_types_entities::config::StrategyConfig;
+
+    #[test]
+    fn test_blockchain_config() -> Result<()> {
+        let config: BlockchainConfig = toml::from_str(
+            r#"
+            chain_id = 8453
+            weth = "0x4200000000000000000000000000000000000006"
+            base_fee_params = { max_change_denominator = 50, elasticity_multiplier = 6 }
+            tokens = [{ address = "0x50c5725949a6f0c72e6c4a641f24049a917db0cb", symbol = "DAI", basic = true }]
+            factories = { AERODROME = "0x420dd381b31aef6683db6b902084cb0ffece40da" }
+            "#,
+        )?;
+
+        let chain_parameters = config.chain_parameters()?;
+        assert_eq!(chain_parameters.chain_id, 8453);
+        assert_eq!(chain_parameters.base_fee_params, BaseFeeParams::new(50, 6));
+        assert!(chain_parameters.tokens.iter().any(|token| token.symbol == "DAI" && token.decimals == 18 && token.basic));
+        assert!(chain_parameters.get_factory("AERODROME").is_some());
+        assert!(chain_parameters.get_factory("UNISWAP_V3").is_some());
+
+        let address_book = config.address_book(&chain_parameters)?;
+        assert_eq!(address_book.token("DAI"), Some("0x50c5725949a6f0c72e6c4a641f24049a917db0cb".parse()?));
+        Ok(())
+    }
+
+    #[test]
+    fn test_actor_config() -> Result<()> {
+        let config: ActorConfig = toml::from_str(
+            r#"
+            [router]
+            mainnet = { bc = "mainnet", signers = "env_signer" }
+
+            [merger]
+            swappath = { type = "swappath", bc = "mainnet", encoder = "mainnet" }
+            samepath = { type = "samepath", bc = "mainnet", client = "local" }
+            diffpath = { type = "diffpath", bc = "mainnet" }
+            packing = { type = "packing", bc = "mainnet", time_budget_ms = 10 }
+
+            [backrun]
+            mainnet = { bc = "mainnet", client = "local", mempool = false, eoa = "0x0000000000000000000000000000000000000001", max_candidates = 2 }
+
+            [health_monitor]
+            state = { type = "state", bc = "mainnet", client = "local" }
+            stuffing = { type = "stuffing", bc = "mainnet", client = "local" }
+
+            [metrics]
+            mainnet = { bc = "mainnet", actors_stats = false }
+            "#,
+        )?;
+
+        assert!(config.node.is_none());
+        assert_eq!(config.router.unwrap()["mainnet"].signers.as_deref(), Some("env_signer"));
+
+        let merger = config.merger.unwrap();
+        assert!(matches!(&merger["swappath"], MergerConfig::SwapPath(c) if c.encoder.as_deref() == Some("mainnet")));
+        assert!(matches!(&merger["samepath"], MergerConfig::SamePath(c) if c.client.as_deref() == Some("local")));
+        assert!(matches!(&merger["diffpath"], MergerConfig::DiffPath(_)));
+        assert!(matches!(&merger["packing"], MergerConfig::Packing(c) if c.time_budget_ms == Some(10) && c.max_candidates.is_none()));
+
+        let backrun = &config.backrun.unwrap()["mainnet"];
+        assert_eq!((backrun.block, backrun.mempool), (None, Some(false)));
+        let backrun_config = backrun.backrun_config()?;
+        assert!(backrun_config.smart());
+        assert_eq!(backrun_config.eoa(), Some(Address::with_last_byte(1)));
+        assert!(!backrun_config.is_origin_enabled("pending_tx_searcher"));
+        assert_eq!(backrun_config.max_candidates(), Some(2));
+
+        let health_monitor = config.health_monitor.unwrap();
+        assert!(matches!(health_monitor["state"], HealthMonitorConfig::State(_)));
+        assert!(matches!(health_monitor["stuffing"], HealthMonitorConfig::StuffingTx(_)));
+
+        let metrics = &config.metrics.unwrap()["mainnet"];
+        assert_eq!((metrics.block_latency, metrics.actors_stats), (None, Some(false)));
+        Ok(())
+    }
 
     #[test]
     fn test_load() {
@@ -0,0 +1,53 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IAlgebraPool {
+        event Initialize(uint160 price, int24 tick);
+        event Mint(
+            address sender,
+            address indexed owner,
+            int24 indexed bottomTick,
+            int24 indexed topTick,
+            uint128 liquidityAmount,
+            uint256 amount0,
+            uint256 amount1
+        );
+        event Burn(address indexed owner, int24 indexed bottomTick, int24 indexed topTick, uint128 liquidityAmount, uint256 amount0, uint256 amount1);
+        event Swap(address indexed sender, address indexed recipient, int256 amount0, int256 amount1, uint160 price, uint128 liquidity, int24 tick);
+        event Fee(uint16 fee);
+
+        function factory() external view returns (address);
+        function token0() external view returns (address);
+        function token1() external view returns (address);
+        function tickSpacing() external view returns (int24);
+
+        // Algebra V1 layout, Camelot V3 has separate zeroToOne and oneToZero fees at the place of fee
+        function globalState() external view returns (
+            uint160 price,
+            int24 tick,
+            uint16 fee,
+            uint16 timepointIndex,
+            uint8 communityFeeToken0,
+            uint8 communityFeeToken1,
+            bool unlocked
+        );
+        function liquidity() external view returns (uint128);
+        function ticks(int24 tick) external view returns (
+            uint128 liquidityTotal,
+            int128 liquidityDelta,
+            uint256 outerFeeGrowth0Token,
+            uint256 outerFeeGrowth1Token,
+            int56 outerTickCumulative,
+            uint160 outerSecondsPerLiquidity,
+            uint32 outerSecondsSpent,
+            bool initialized
+        );
+        function tickTable(int16 wordPosition) external view returns (uint256);
+
+        function swap(address recipient, bool zeroToOne, int256 amountRequired, uint160 limitSqrtPrice, bytes calldata data)
+            external
+            returns (int256 amount0, int256 amount1);
+    }
+}
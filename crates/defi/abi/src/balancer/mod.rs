@@ -1,3 +1,5 @@
+pub use pool::{IBalancerPool, IBalancerStablePool, IBalancerWeightedPool};
 pub use vault::*;
 
+mod pool;
 mod vault;
@@ -0,0 +1,28 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IBalancerPool {
+        function getPoolId() external view returns (bytes32);
+        function getVault() external view returns (address);
+        function getSwapFeePercentage() external view returns (uint256);
+        function getScalingFactors() external view returns (uint256[] memory);
+        function totalSupply() external view returns (uint256);
+        function version() external view returns (string memory);
+    }
+
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IBalancerWeightedPool {
+        function getNormalizedWeights() external view returns (uint256[] memory);
+    }
+
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IBalancerStablePool {
+        function getAmplificationParameter() external view returns (uint256 value, bool isUpdating, uint256 precision);
+        function getBptIndex() external view returns (uint256);
+        function getRate() external view returns (uint256);
+    }
+}
@@ -35,3 +35,36 @@ sol! {
         function balances(int128) external view returns (uint256);
     }
 }
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ICurveStableSwapParams {
+        function A() external view returns (uint256);
+        function A_precise() external view returns (uint256);
+        function fee() external view returns (uint256);
+    }
+}
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ICurveCryptoSwapParams {
+        function A() external view returns (uint256);
+        function gamma() external view returns (uint256);
+        function D() external view returns (uint256);
+        function mid_fee() external view returns (uint256);
+        function out_fee() external view returns (uint256);
+        function fee_gamma() external view returns (uint256);
+        function future_A_gamma_time() external view returns (uint256);
+        function price_scale(uint256) external view returns (uint256);
+    }
+}
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ICurveCryptoSwap2Params {
+        function price_scale() external view returns (uint256);
+    }
+}
@@ -0,0 +1,3 @@
+pub use vault::IERC4626;
+
+mod vault;
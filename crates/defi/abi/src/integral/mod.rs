@@ -0,0 +1,5 @@
+pub use pair::ITwapPair;
+pub use relayer::ITwapRelayer;
+
+mod pair;
+mod relayer;
@@ -0,0 +1,36 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ITwapRelayer {
+        struct SellParams {
+            address tokenIn;
+            address tokenOut;
+            uint256 amountIn;
+            uint256 amountOutMin;
+            bool wrapUnwrap;
+            address to;
+            uint32 submitDeadline;
+        }
+
+        struct BuyParams {
+            address tokenIn;
+            address tokenOut;
+            uint256 amountInMax;
+            uint256 amountOut;
+            bool wrapUnwrap;
+            address to;
+            uint32 submitDeadline;
+        }
+
+        function factory() external view returns (address);
+        function isPairEnabled(address pair) external view returns (bool);
+
+        function quoteSell(address tokenIn, address tokenOut, uint256 amountIn) external view returns (uint256 amountOut);
+        function quoteBuy(address tokenIn, address tokenOut, uint256 amountOut) external view returns (uint256 amountIn);
+
+        function sell(SellParams calldata sellParams) external payable returns (uint256 orderId);
+        function buy(BuyParams calldata buyParams) external payable returns (uint256 orderId);
+    }
+}
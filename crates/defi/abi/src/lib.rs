@@ -1,13 +1,20 @@
 pub use erc20::IERC20;
+pub use multicall3::IMulticall3;
 pub use multicaller::IMultiCaller;
 pub use weth::IWETH;
 
+pub mod algebra;
 pub mod balancer;
 pub mod curve;
 mod erc20;
+pub mod erc4626;
+pub mod integral;
 pub mod lido;
 pub mod maverick;
+mod multicall3;
 pub mod multicaller;
+pub mod rocketpool;
+pub mod solidly;
 pub mod uniswap2;
 pub mod uniswap3;
 pub mod uniswap4;
@@ -1,6 +1,7 @@
 use alloy_sol_types::sol;
 
 sol! {
+    #[sol(abi = true, rpc)]
     #[derive(Debug, PartialEq, Eq)]
     interface IWStEth {
         function getWstETHByStETH(uint256 stETHAmount) external view returns (uint256);
@@ -19,6 +19,7 @@ sol! {
         function swapCallback(int256 , int256 , bytes calldata data) external;
         function callFunction(address, DyDxAccountInfo memory, bytes calldata data) external;
         function receiveFlashLoan(address[] memory,uint256[] memory ,uint256[] memory,bytes calldata) external;
+        function unlockCallback(bytes calldata data) external returns (bytes memory);
         function transferTipsMinBalance(address token, uint256 min_balance, uint256 tips, address owner) external payable;
         function transferTipsMinBalanceWETH(uint256 min_balance, uint256 tips,address owner) external payable;
         function transferTipsMinBalanceNoPayout(address token, uint256 min_balance, uint256 tips) external payable;
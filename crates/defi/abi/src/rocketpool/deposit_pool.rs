@@ -0,0 +1,15 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IRocketDepositPool {
+        event DepositReceived(address indexed from, uint256 amount, uint256 time);
+
+        function getBalance() external view returns (uint256);
+        function getExcessBalance() external view returns (uint256);
+        function getMaximumDepositAmount() external view returns (uint256);
+
+        function deposit() external payable;
+    }
+}
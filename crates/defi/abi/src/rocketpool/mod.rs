@@ -0,0 +1,7 @@
+pub use deposit_pool::IRocketDepositPool;
+pub use reth::IRocketTokenRETH;
+pub use storage::IRocketStorage;
+
+mod deposit_pool;
+mod reth;
+mod storage;
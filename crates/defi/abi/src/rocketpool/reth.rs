@@ -0,0 +1,17 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IRocketTokenRETH {
+        event TokensMinted(address indexed to, uint256 amount, uint256 ethAmount, uint256 time);
+        event TokensBurned(address indexed from, uint256 amount, uint256 ethAmount, uint256 time);
+
+        function getEthValue(uint256 _rethAmount) external view returns (uint256);
+        function getRethValue(uint256 _ethAmount) external view returns (uint256);
+        function getExchangeRate() external view returns (uint256);
+        function getTotalCollateral() external view returns (uint256);
+
+        function burn(uint256 _rethAmount) external;
+    }
+}
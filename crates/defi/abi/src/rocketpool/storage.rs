@@ -0,0 +1,11 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IRocketStorage {
+        function getAddress(bytes32 _key) external view returns (address);
+        function getUint(bytes32 _key) external view returns (uint256);
+        function getBool(bytes32 _key) external view returns (bool);
+    }
+}
@@ -0,0 +1,14 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ISolidlyPoolFactory {
+        event PoolCreated(address indexed token0, address indexed token1, bool indexed stable, address pool, uint256);
+
+        function allPoolsLength() external view returns (uint256);
+        function allPools(uint256 index) external view returns (address);
+        function getPool(address tokenA, address tokenB, bool stable) external view returns (address);
+        function getFee(address pool, bool stable) external view returns (uint256);
+    }
+}
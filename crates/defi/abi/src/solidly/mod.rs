@@ -0,0 +1,5 @@
+pub use factory::ISolidlyPoolFactory;
+pub use pool::ISolidlyPool;
+
+mod factory;
+mod pool;
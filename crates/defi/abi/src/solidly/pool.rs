@@ -0,0 +1,32 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface ISolidlyPool {
+        event Fees(address indexed sender, uint256 amount0, uint256 amount1);
+        event Mint(address indexed sender, uint256 amount0, uint256 amount1);
+        event Burn(address indexed sender, address indexed to, uint256 amount0, uint256 amount1);
+        event Swap(
+            address indexed sender,
+            address indexed to,
+            uint256 amount0In,
+            uint256 amount1In,
+            uint256 amount0Out,
+            uint256 amount1Out
+        );
+        event Sync(uint256 reserve0, uint256 reserve1);
+
+        function factory() external view returns (address);
+        function token0() external view returns (address);
+        function token1() external view returns (address);
+        function stable() external view returns (bool);
+        function metadata() external view returns (uint256 dec0, uint256 dec1, uint256 r0, uint256 r1, bool st, address t0, address t1);
+        function getReserves() external view returns (uint256 reserve0, uint256 reserve1, uint256 blockTimestampLast);
+        function getAmountOut(uint256 amountIn, address tokenIn) external view returns (uint256);
+
+        function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes calldata data) external;
+        function skim(address to) external;
+        function sync() external;
+    }
+}
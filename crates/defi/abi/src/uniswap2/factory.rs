@@ -0,0 +1,13 @@
+use alloy_sol_types::sol;
+
+sol! {
+    #[sol(abi=true,rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IUniswapV2Factory {
+        event PairCreated(address indexed token0, address indexed token1, address pair, uint);
+
+        function getPair(address tokenA, address tokenB) external view returns (address pair);
+        function allPairs(uint) external view returns (address pair);
+        function allPairsLength() external view returns (uint);
+    }
+}
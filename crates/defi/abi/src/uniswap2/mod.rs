@@ -1,5 +1,7 @@
+pub use factory::*;
 pub use pool::*;
 pub use router::*;
 
+mod factory;
 mod pool;
 mod router;
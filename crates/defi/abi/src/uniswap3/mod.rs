@@ -1,3 +1,5 @@
+pub use factory::*;
 pub use pool::*;
 
+mod factory;
 mod pool;
@@ -145,6 +145,15 @@ sol! {
 
 
 
+    #[derive(Debug, PartialEq, Eq)]
+    interface IUniswapV4UnlockCallback {
+        /// @notice Called by the pool manager on `msg.sender` when the manager is unlocked
+        /// @param data The data that was passed to the call to unlock
+        /// @return Any data that you want to be returned from the unlock call
+        function unlockCallback(bytes calldata data) external returns (bytes memory);
+    }
+
+    #[sol(abi = true, rpc)]
     #[derive(Debug, PartialEq, Eq)]
     interface IUniswapV4PoolManager {
     /// @notice Thrown when currencies touched has exceeded max of 256
@@ -282,6 +291,11 @@ sol! {
         /// @return The data returned by the call to `ILockCallback(msg.sender).lockAcquired(data)`
         function lock(address lockTarget, bytes calldata data) external payable returns (bytes memory);
 
+        /// @notice All interactions on the contract that account deltas require unlocking
+        /// @param data Any data to pass to the callback, via `IUnlockCallback(msg.sender).unlockCallback(data)`
+        /// @return The data returned by the call to `IUnlockCallback(msg.sender).unlockCallback(data)`
+        function unlock(bytes calldata data) external returns (bytes memory);
+
         struct ModifyLiquidityParams {
             // the lower and upper tick of the position
             int24 tickLower;
@@ -326,6 +340,10 @@ sol! {
         /// @notice Called by the user to move value from ERC6909 balance
         function burn(address from, uint256 id, uint256 amount) external;
 
+        /// @notice Writes the current ERC20 balance of the specified currency to transient storage
+        /// This is used to checkpoint balances for the manager and derive deltas for the caller.
+        function sync(Currency currency) external;
+
         /// @notice Called by the user to pay what is owed
         function settle(Currency token) external payable returns (uint256 paid);
 
@@ -346,6 +364,39 @@ sol! {
         /// @param nSlots Number of slots to load into return value
         /// @return value The value of the sload-ed slots concatenated as dynamic bytes
         function extsload(bytes32 slot, uint256 nSlots) external view returns (bytes memory value);
+
+        /// @notice Called by external contracts to access transient storage of the contract
+        /// @param slot Key of slot to tload
+        /// @return value The value of the slot as bytes32
+        function exttload(bytes32 slot) external view returns (bytes32 value);
+    }
+
+    /// Deployed singleton pool manager, input tokens are paid with sync and settle and pools are announced with the initial price
+    #[sol(abi = true, rpc)]
+    #[derive(Debug, PartialEq, Eq)]
+    interface IUniswapV4PoolManagerUnlock {
+        /// @notice Emitted when a new pool is initialized
+        /// @param id The abi encoded hash of the pool key struct for the new pool
+        /// @param currency0 The first currency of the pool by address sort order
+        /// @param currency1 The second currency of the pool by address sort order
+        /// @param fee The fee collected upon every swap in the pool, denominated in hundredths of a bip
+        /// @param tickSpacing The minimum number of ticks between initialized ticks
+        /// @param hooks The hooks contract address for the pool, or address(0) if none
+        /// @param sqrtPriceX96 The price of the pool on initialization
+        /// @param tick The initial tick of the pool corresponding to the initialized price
+        event Initialize(
+            PoolId indexed id,
+            Currency indexed currency0,
+            Currency indexed currency1,
+            uint24 fee,
+            int24 tickSpacing,
+            Hooks hooks,
+            uint160 sqrtPriceX96,
+            int24 tick
+        );
+
+        /// @notice Called by the user to pay what is owed, the synced currency is paid
+        function settle() external payable returns (uint256 paid);
     }
 
     #[derive(Debug, PartialEq, Eq)]
@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use alloy_primitives::Address;
+use eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BalancerPoolAddress, CurveMetapoolAddress, CurvePoolAddress, FactoryAddress, PancakeV2PoolAddress, PancakeV3PoolAddress,
+    PeripheryAddress, TokenAddress, UniswapV2PoolAddress, UniswapV3PoolAddress,
+};
+
+lazy_static! {
+    static ref ADDRESS_BOOKS: RwLock<HashMap<u64, Arc<AddressBook>>> = RwLock::new(HashMap::from([(1, Arc::new(AddressBook::mainnet()))]));
+}
+
+/// Named addresses of a chain. Names follow the constants of this crate, e.g. `WETH`, `UNISWAP_V2`, `UNISWAP_V2_ROUTER`.
+/// Pools are grouped by protocol, e.g. `uniswap_v3` -> `USDC_WETH_500`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBook {
+    #[serde(default)]
+    pub chain_id: u64,
+    #[serde(default)]
+    pub tokens: BTreeMap<String, Address>,
+    #[serde(default)]
+    pub factories: BTreeMap<String, Address>,
+    #[serde(default)]
+    pub periphery: BTreeMap<String, Address>,
+    #[serde(default)]
+    pub pools: BTreeMap<String, BTreeMap<String, Address>>,
+}
+
+impl AddressBook {
+    pub fn new(chain_id: u64) -> Self {
+        Self { chain_id, ..Self::default() }
+    }
+
+    /// Default mainnet book built from the constants
+    pub fn mainnet() -> Self {
+        let tokens = [
+            ("WETH", TokenAddress::WETH),
+            ("USDC", TokenAddress::USDC),
+            ("USDT", TokenAddress::USDT),
+            ("DAI", TokenAddress::DAI),
+            ("SDAI", TokenAddress::SDAI),
+            ("WBTC", TokenAddress::WBTC),
+            ("THREECRV", TokenAddress::THREECRV),
+            ("CRV", TokenAddress::CRV),
+            ("STETH", TokenAddress::STETH),
+            ("WSTETH", TokenAddress::WSTETH),
+            ("RETH", TokenAddress::RETH),
+            ("LUSD", TokenAddress::LUSD),
+        ];
+        let factories = [
+            ("UNISWAP_V2", FactoryAddress::UNISWAP_V2),
+            ("SUSHISWAP_V2", FactoryAddress::SUSHISWAP_V2),
+            ("NOMISWAP", FactoryAddress::NOMISWAP),
+            ("DOOARSWAP", FactoryAddress::DOOARSWAP),
+            ("SAFESWAP", FactoryAddress::SAFESWAP),
+            ("MINISWAP", FactoryAddress::MINISWAP),
+            ("SHIBASWAP", FactoryAddress::SHIBASWAP),
+            ("OG_PEPE", FactoryAddress::OG_PEPE),
+            ("UNISWAP_V3", FactoryAddress::UNISWAP_V3),
+            ("SUSHISWAP_V3", FactoryAddress::SUSHISWAP_V3),
+            ("PANCAKE_V3", FactoryAddress::PANCAKE_V3),
+            ("MAVERICK", FactoryAddress::MAVERICK),
+            ("INTEGRAL", FactoryAddress::INTEGRAL),
+        ];
+        let periphery = [
+            ("UNISWAP_V2_ROUTER", PeripheryAddress::UNISWAP_V2_ROUTER),
+            ("UNISWAP_V3_QUOTER_V2", PeripheryAddress::UNISWAP_V3_QUOTER_V2),
+            ("UNISWAP_V3_TICK_LENS", PeripheryAddress::UNISWAP_V3_TICK_LENS),
+            ("PANCAKE_V3_QUOTER", PeripheryAddress::PANCAKE_V3_QUOTER),
+            ("PANCAKE_V3_TICK_LENS", PeripheryAddress::PANCAKE_V3_TICK_LENS),
+            ("MAVERICK_QUOTER", PeripheryAddress::MAVERICK_QUOTER),
+            ("BALANCER_VAULT", PeripheryAddress::BALANCER_VAULT),
+            ("UNISWAP_V4_POOL_MANAGER", PeripheryAddress::UNISWAP_V4_POOL_MANAGER),
+            ("ROCKET_STORAGE", PeripheryAddress::ROCKET_STORAGE),
+            ("INTEGRAL_RELAYER", PeripheryAddress::INTEGRAL_RELAYER),
+            ("MULTICALL3", PeripheryAddress::MULTICALL3),
+        ];
+        let pools: [(&str, &[(&str, Address)]); 7] = [
+            ("uniswap_v2", &[("LUSD_WETH", UniswapV2PoolAddress::LUSD_WETH), ("WETH_USDT", UniswapV2PoolAddress::WETH_USDT)]),
+            (
+                "uniswap_v3",
+                &[
+                    ("USDC_USDT_100", UniswapV3PoolAddress::USDC_USDT_100),
+                    ("USDC_WETH_500", UniswapV3PoolAddress::USDC_WETH_500),
+                    ("USDC_WETH_3000", UniswapV3PoolAddress::USDC_WETH_3000),
+                    ("WBTC_USDT_3000", UniswapV3PoolAddress::WBTC_USDT_3000),
+                    ("WETH_USDT_3000", UniswapV3PoolAddress::WETH_USDT_3000),
+                ],
+            ),
+            ("pancake_v2", &[("WETH_USDT", PancakeV2PoolAddress::WETH_USDT)]),
+            ("pancake_v3", &[("USDC_USDT_100", PancakeV3PoolAddress::USDC_USDT_100)]),
+            (
+                "curve",
+                &[
+                    ("DAI_USDC_USDT", CurvePoolAddress::DAI_USDC_USDT),
+                    ("USDT_BTC_ETH", CurvePoolAddress::USDT_BTC_ETH),
+                    ("ETH_BTC_USD", CurvePoolAddress::ETH_BTC_USD),
+                    ("TRICRYPTO_USDT", CurvePoolAddress::TRICRYPTO_USDT),
+                    ("FRXETH_WETH", CurvePoolAddress::FRXETH_WETH),
+                    ("ETH", CurvePoolAddress::ETH),
+                ],
+            ),
+            ("curve_meta", &[("LUSD", CurveMetapoolAddress::LUSD)]),
+            (
+                "balancer",
+                &[("BAL_WETH_80_20", BalancerPoolAddress::BAL_WETH_80_20), ("WSTETH_WETH_STABLE", BalancerPoolAddress::WSTETH_WETH_STABLE)],
+            ),
+        ];
+
+        AddressBook {
+            chain_id: 1,
+            tokens: tokens.into_iter().map(|(name, address)| (name.to_string(), address)).collect(),
+            factories: factories.into_iter().map(|(name, address)| (name.to_string(), address)).collect(),
+            periphery: periphery.into_iter().map(|(name, address)| (name.to_string(), address)).collect(),
+            pools: pools
+                .into_iter()
+                .map(|(group, pools)| (group.to_string(), pools.iter().map(|(name, address)| (name.to_string(), *address)).collect()))
+                .collect(),
+        }
+    }
+
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    pub fn from_json_str(contents: &str) -> Result<Self> {
+        Ok(serde_json::from_str(contents)?)
+    }
+
+    /// Load book from `.json` or `.toml` file
+    pub fn load_from_file<P: AsRef<Path>>(file_name: P) -> Result<Self> {
+        let contents = fs::read_to_string(file_name.as_ref())?;
+        match file_name.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            _ => Self::from_toml_str(&contents),
+        }
+    }
+
+    /// Add entries of other book, existing names are overwritten
+    pub fn merge(&mut self, other: AddressBook) {
+        self.tokens.extend(other.tokens);
+        self.factories.extend(other.factories);
+        self.periphery.extend(other.periphery);
+        for (group, pools) in other.pools {
+            self.pools.entry(group).or_default().extend(pools);
+        }
+    }
+
+    pub fn token(&self, name: &str) -> Option<Address> {
+        self.tokens.get(name).cloned()
+    }
+
+    pub fn factory(&self, name: &str) -> Option<Address> {
+        self.factories.get(name).cloned()
+    }
+
+    pub fn periphery(&self, name: &str) -> Option<Address> {
+        self.periphery.get(name).cloned()
+    }
+
+    pub fn pool(&self, group: &str, name: &str) -> Option<Address> {
+        self.pools.get(group).and_then(|pools| pools.get(name)).cloned()
+    }
+
+    pub fn get_token(&self, name: &str) -> Result<Address> {
+        self.token(name).ok_or_else(|| eyre!("TOKEN_NOT_IN_ADDRESS_BOOK"))
+    }
+
+    pub fn get_periphery(&self, name: &str) -> Result<Address> {
+        self.periphery(name).ok_or_else(|| eyre!("PERIPHERY_NOT_IN_ADDRESS_BOOK"))
+    }
+
+    pub fn get_pool(&self, group: &str, name: &str) -> Result<Address> {
+        self.pool(group, name).ok_or_else(|| eyre!("POOL_NOT_IN_ADDRESS_BOOK"))
+    }
+
+    pub fn token_name(&self, address: &Address) -> Option<&str> {
+        self.tokens.iter().find(|(_, token_address)| *token_address == address).map(|(name, _)| name.as_str())
+    }
+
+    pub fn factory_name(&self, address: &Address) -> Option<&str> {
+        self.factories.iter().find(|(_, factory_address)| *factory_address == address).map(|(name, _)| name.as_str())
+    }
+
+    /// Register book for its chain, replacing the previous one
+    pub fn register(book: AddressBook) -> Arc<AddressBook> {
+        let book = Arc::new(book);
+        if let Ok(mut books) = ADDRESS_BOOKS.write() {
+            books.insert(book.chain_id, book.clone());
+        }
+        book
+    }
+
+    pub fn get(chain_id: u64) -> Option<Arc<AddressBook>> {
+        ADDRESS_BOOKS.read().ok()?.get(&chain_id).cloned()
+    }
+
+    /// Name of the factory in the registered book of the chain
+    pub fn find_factory_name(chain_id: u64, address: &Address) -> Option<String> {
+        Self::get(chain_id)?.factory_name(address).map(|name| name.to_string())
+    }
+
+    /// Periphery contract from the registered book of the chain
+    pub fn find_periphery(chain_id: u64, name: &str) -> Option<Address> {
+        Self::get(chain_id)?.periphery(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_mainnet() {
+        let book = AddressBook::get(1).unwrap();
+        assert_eq!(book.token("WETH"), Some(TokenAddress::WETH));
+        assert_eq!(book.factory_name(&FactoryAddress::PANCAKE_V3), Some("PANCAKE_V3"));
+        assert_eq!(book.pool("uniswap_v3", "USDC_WETH_500"), Some(UniswapV3PoolAddress::USDC_WETH_500));
+        assert_eq!(AddressBook::find_factory_name(1, &FactoryAddress::MAVERICK), Some("MAVERICK".to_string()));
+        assert_eq!(AddressBook::find_factory_name(8453, &FactoryAddress::MAVERICK), None);
+        assert_eq!(AddressBook::find_factory_name(1, &FactoryAddress::INTEGRAL), Some("INTEGRAL".to_string()));
+        assert_eq!(AddressBook::find_periphery(1, "INTEGRAL_RELAYER"), Some(PeripheryAddress::INTEGRAL_RELAYER));
+        assert_eq!(AddressBook::find_periphery(1, "MULTICALL3"), Some(PeripheryAddress::MULTICALL3));
+    }
+
+    #[test]
+    fn test_load() -> Result<()> {
+        let toml_book = AddressBook::from_toml_str(
+            r#"
+            chain_id = 8453
+
+            [tokens]
+            WETH = "0x4200000000000000000000000000000000000006"
+
+            [factories]
+            UNISWAP_V3 = "0x33128a8fc17869897dce68ed026d694621f6fdfd"
+
+            [pools.uniswap_v3]
+            USDC_WETH_500 = "0xd0b53d9277642d899df5c87a3966a349a798f224"
+            "#,
+        )?;
+        assert_eq!(toml_book.token("WETH"), Some(address!("4200000000000000000000000000000000000006")));
+        assert_eq!(toml_book.factory_name(&address!("33128a8fc17869897dce68ed026d694621f6fdfd")), Some("UNISWAP_V3"));
+
+        let json_book = AddressBook::from_json_str(&serde_json::to_string(&toml_book)?)?;
+        assert_eq!(json_book, toml_book);
+
+        let mut book = AddressBook::mainnet();
+        book.merge(json_book);
+        assert_eq!(book.token("WETH"), Some(address!("4200000000000000000000000000000000000006")));
+        assert_eq!(book.token("USDC"), Some(TokenAddress::USDC));
+        assert_eq!(book.pool("uniswap_v3", "USDC_WETH_500"), Some(address!("d0b53d9277642d899df5c87a3966a349a798f224")));
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 use alloy_primitives::{address, Address};
 
+pub use address_book::AddressBook;
+
+mod address_book;
+
 #[non_exhaustive]
 pub struct TokenAddress;
 
@@ -9,11 +13,13 @@ impl TokenAddress {
     pub const USDC: Address = address!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
     pub const USDT: Address = address!("dac17f958d2ee523a2206206994597c13d831ec7");
     pub const DAI: Address = address!("6b175474e89094c44da98b954eedeac495271d0f");
+    pub const SDAI: Address = address!("83f20f44975d03b1b09e64809b757c47f942beea");
     pub const WBTC: Address = address!("2260fac5e5542a773aa44fbcfedf7c193bc2c599");
     pub const THREECRV: Address = address!("6c3f90f043a72fa612cbac8115ee7e52bde6e490");
     pub const CRV: Address = address!("d533a949740bb3306d119cc777fa900ba034cd52");
     pub const STETH: Address = address!("ae7ab96520de3a18e5e111b5eaab095312d7fe84");
     pub const WSTETH: Address = address!("7f39c581f595b53c5cb19bd0b3f8da6c935e2ca0");
+    pub const RETH: Address = address!("ae78736cd615f374d3085123a210448e74fc6393");
     pub const LUSD: Address = address!("5f98805a4e8be255a32880fdec7f6728c6568ba0");
 }
 
@@ -38,6 +44,9 @@ impl FactoryAddress {
 
     // Maverick
     pub const MAVERICK: Address = address!("eb6625d65a0553c9dbc64449e56abfe519bd9c9b");
+
+    // Integral SIZE, pairs emit Uniswap V2 events
+    pub const INTEGRAL: Address = address!("c480b33ee5229de3fbdfad1d2dcd3f3bad0c56c6");
 }
 
 #[non_exhaustive]
@@ -50,6 +59,11 @@ impl PeripheryAddress {
     pub const PANCAKE_V3_QUOTER: Address = address!("b048bbc1ee6b733fffcfb9e9cef7375518e25997");
     pub const PANCAKE_V3_TICK_LENS: Address = address!("9a489505a00ce272eaa5e07dba6491314cae3796");
     pub const MAVERICK_QUOTER: Address = address!("9980ce3b5570e41324904f46a06ce7b466925e23");
+    pub const BALANCER_VAULT: Address = address!("ba12222222228d8ba445958a75a0704d566bf2c8");
+    pub const UNISWAP_V4_POOL_MANAGER: Address = address!("000000000004444c5dc75cb358380d2e3de08a90");
+    pub const ROCKET_STORAGE: Address = address!("1d8f8f00cfa6758d7be78336684788fb0ee0fa46");
+    pub const INTEGRAL_RELAYER: Address = address!("d17b3c9784510e33cd5b87b490e79253bcd81e2e");
+    pub const MULTICALL3: Address = address!("ca11bde05977b3631167028862be2a173976ca11");
 }
 
 #[non_exhaustive]
@@ -92,10 +106,19 @@ impl CurvePoolAddress {
     pub const DAI_USDC_USDT: Address = address!("bebc44782c7db0a1a60cb6fe97d0b483032ff1c7");
     pub const USDT_BTC_ETH: Address = address!("d51a44d3fae010294c616388b506acda1bfaae46");
     pub const ETH_BTC_USD: Address = address!("7f86bf177dd4f3494b841a37e810a34dd56c829b");
+    pub const TRICRYPTO_USDT: Address = address!("f5f5b97624542d72a9e06f04804bf81baa15e2b4");
     pub const FRXETH_WETH: Address = address!("9c3b46c0ceb5b9e304fcd6d88fc50f7dd24b31bc");
     pub const ETH: Address = address!("a1f8a6807c402e4a15ef4eba36528a3fed24e577");
 }
 
+#[non_exhaustive]
+pub struct BalancerPoolAddress;
+
+impl BalancerPoolAddress {
+    pub const BAL_WETH_80_20: Address = address!("5c6ee304399dbdb9c8ef030ab642b10820db8f56");
+    pub const WSTETH_WETH_STABLE: Address = address!("93d199263632a4ef4bb438f1feb99e57b4b5f0bd");
+}
+
 #[non_exhaustive]
 pub struct CurveMetapoolAddress;
 
@@ -3,17 +3,20 @@ use std::collections::HashMap;
 use alloy_primitives::Address;
 use eyre::Result;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
-use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
-use loom_core_actors_macros::{Accessor, Consumer};
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_types_entities::Market;
-use loom_types_events::{HealthEvent, MessageHealthEvent};
+use loom_types_events::{HealthEvent, MarketEvents, MessageHealthEvent};
 
 pub async fn pool_health_monitor_worker(
     market: SharedState<Market>,
     pool_health_monitor_rx: Broadcaster<MessageHealthEvent>,
+    market_events_tx: Broadcaster<MarketEvents>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(pool_health_monitor_rx);
 
@@ -21,6 +24,9 @@ pub async fn pool_health_monitor_worker(
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Pool health monitor worker stopped".to_string())
+            }
             msg = pool_health_monitor_rx.recv() => {
 
                 let pool_health_update : Result<MessageHealthEvent, RecvError>  = msg;
@@ -32,6 +38,7 @@ pub async fn pool_health_monitor_worker(
                             *entry += 1;
                             if *entry >= 10 {
                                 let mut market_guard = market.write().await;
+                                let was_ok = market_guard.is_pool_ok(&swap_error.pool);
                                 market_guard.set_pool_ok(swap_error.pool, false);
                                 match market_guard.get_pool(&swap_error.pool) {
                                     Some(pool)=>{
@@ -41,6 +48,13 @@ pub async fn pool_health_monitor_worker(
                                         error!("Disabled pool missing in market: address={:?}, msg={} amount={}", swap_error.pool, swap_error.msg, swap_error.amount);
                                     }
                                 }
+                                drop(market_guard);
+
+                                if was_ok {
+                                    if let Err(e) = market_events_tx.send(MarketEvents::PoolDisabled{ pool_address: swap_error.pool }).await {
+                                        error!("market_events_tx.send error : {}", e)
+                                    }
+                                }
                             }
                         }
                     }
@@ -54,12 +68,14 @@ pub async fn pool_health_monitor_worker(
     }
 }
 
-#[derive(Accessor, Consumer, Default)]
+#[derive(Accessor, Consumer, Producer, Default)]
 pub struct PoolHealthMonitorActor {
     #[accessor]
     market: Option<SharedState<Market>>,
     #[consumer]
     pool_health_update_rx: Option<Broadcaster<MessageHealthEvent>>,
+    #[producer]
+    market_events_tx: Option<Broadcaster<MarketEvents>>,
 }
 
 impl PoolHealthMonitorActor {
@@ -68,14 +84,26 @@ impl PoolHealthMonitorActor {
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { market: Some(bc.market()), pool_health_update_rx: Some(bc.pool_health_monitor_channel()) }
+        Self {
+            market: Some(bc.market()),
+            pool_health_update_rx: Some(bc.pool_health_monitor_channel()),
+            market_events_tx: Some(bc.market_events_channel()),
+        }
     }
 }
 
 impl Actor for PoolHealthMonitorActor {
     fn start(&self) -> ActorResult {
-        let task =
-            tokio::task::spawn(pool_health_monitor_worker(self.market.clone().unwrap(), self.pool_health_update_rx.clone().unwrap()));
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(pool_health_monitor_worker(
+            self.market.clone().unwrap(),
+            self.pool_health_update_rx.clone().unwrap(),
+            self.market_events_tx.clone().unwrap(),
+            token,
+        ));
         Ok(vec![task])
     }
 
@@ -9,10 +9,10 @@ use alloy_transport::Transport;
 use chrono::{DateTime, Duration, Local};
 use eyre::Result;
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
 use loom_types_entities::MarketState;
@@ -57,15 +57,19 @@ pub async fn state_health_monitor_worker<T: Transport + Clone, P: Provider<T, Et
     market_state: SharedState<MarketState>,
     tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
     market_events_rx: Broadcaster<MarketEvents>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    let mut tx_compose_channel_rx: Receiver<MessageTxCompose> = tx_compose_channel_rx.subscribe().await;
-    let mut market_events_rx: Receiver<MarketEvents> = market_events_rx.subscribe().await;
+    let mut tx_compose_channel_rx: BroadcasterReceiver<MessageTxCompose> = tx_compose_channel_rx.subscribe().await;
+    let mut market_events_rx: BroadcasterReceiver<MarketEvents> = market_events_rx.subscribe().await;
 
     let mut check_time_map: HashMap<Address, DateTime<Local>> = HashMap::new();
     let mut pool_address_to_verify_vec: Vec<Address> = Vec::new();
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("State health monitor worker stopped".to_string())
+            }
             msg = market_events_rx.recv() => {
                 let market_event_msg : Result<MarketEvents, RecvError> = msg;
                 match market_event_msg {
@@ -152,11 +156,16 @@ where
     P: Provider<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(state_health_monitor_worker(
             self.client.clone(),
             self.market_state.clone().unwrap(),
             self.tx_compose_channel_rx.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -7,14 +7,14 @@ use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
-use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 use loom_core_blockchain::Blockchain;
 use loom_evm_utils::NWETH;
-use loom_types_entities::{LatestBlock, Swap, Token};
+use loom_types_entities::{LandedBundle, LatestBlock, Swap, TipsPolicy, Token};
 
-use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, SharedState, WorkerResult};
+use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, BroadcasterReceiver, Consumer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_types_blockchain::debug_trace_transaction;
 use loom_types_events::{MarketEvents, MessageTxCompose, TxCompose};
@@ -32,6 +32,8 @@ async fn check_mf_tx<P: Provider<T, Ethereum> + 'static, T: Transport + Clone>(
     client: P,
     tx_hash: TxHash,
     coinbase: Address,
+    tx_to_check: TxToCheck,
+    tips_policy: Option<Arc<dyn TipsPolicy>>,
 ) -> Result<()> {
     let (pre, post) = debug_trace_transaction(client, tx_hash, true).await?;
 
@@ -41,6 +43,14 @@ async fn check_mf_tx<P: Provider<T, Ethereum> + 'static, T: Transport + Clone>(
     let balance_diff = coinbase_post.balance.unwrap_or_default().checked_sub(coinbase_pre.balance.unwrap_or_default()).unwrap_or_default();
     info!("Stuffing tx mined MF tx: {:?} sent to coinbase: {}", tx_hash, NWETH::to_float(balance_diff));
 
+    if let Some(tips_policy) = tips_policy {
+        tips_policy.on_landed_bundle(&LandedBundle {
+            token: tx_to_check.token_in.get_address(),
+            profit_eth: tx_to_check.profit,
+            tips: balance_diff,
+        });
+    }
+
     Ok(())
 }
 
@@ -49,14 +59,19 @@ pub async fn stuffing_tx_monitor_worker<P: Provider<T, Ethereum> + Clone + 'stat
     latest_block: SharedState<LatestBlock>,
     tx_compose_channel_rx: Broadcaster<MessageTxCompose>,
     market_events_rx: Broadcaster<MarketEvents>,
+    tips_policy: Option<Arc<dyn TipsPolicy>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    let mut tx_compose_channel_rx: Receiver<MessageTxCompose> = tx_compose_channel_rx.subscribe().await;
-    let mut market_events_rx: Receiver<MarketEvents> = market_events_rx.subscribe().await;
+    let mut tx_compose_channel_rx: BroadcasterReceiver<MessageTxCompose> = tx_compose_channel_rx.subscribe().await;
+    let mut market_events_rx: BroadcasterReceiver<MarketEvents> = market_events_rx.subscribe().await;
 
     let mut txs_to_check: HashMap<TxHash, TxToCheck> = HashMap::new();
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Stuffing tx monitor worker stopped".to_string())
+            }
             msg = market_events_rx.recv() => {
                 let market_event_msg : Result<MarketEvents, RecvError> = msg;
                 match market_event_msg {
@@ -72,7 +87,7 @@ pub async fn stuffing_tx_monitor_worker<P: Provider<T, Ethereum> + Clone + 'stat
                                             let mf_tx = &txs[idx+1];
                                             info!("Stuffing tx mined {:?} MF tx: {:?} to: {:?}", tx.hash, mf_tx.hash, mf_tx.to.unwrap_or_default() );
                                             tokio::task::spawn(
-                                                check_mf_tx(client.clone(), mf_tx.hash, coinbase)
+                                                check_mf_tx(client.clone(), mf_tx.hash, coinbase, tx_to_check, tips_policy.clone())
                                             );
                                         }
                                         txs_to_check.remove::<TxHash>(&tx.hash);
@@ -139,12 +154,25 @@ pub struct StuffingTxMonitorActor<P, T> {
     tx_compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
     #[consumer]
     market_events_rx: Option<Broadcaster<MarketEvents>>,
+    tips_policy: Option<Arc<dyn TipsPolicy>>,
     _t: PhantomData<T>,
 }
 
 impl<P: Provider<T, Ethereum> + Send + Sync + Clone + 'static, T: Transport + Clone> StuffingTxMonitorActor<P, T> {
     pub fn new(client: P) -> Self {
-        StuffingTxMonitorActor { client, latest_block: None, tx_compose_channel_rx: None, market_events_rx: None, _t: PhantomData }
+        StuffingTxMonitorActor {
+            client,
+            latest_block: None,
+            tx_compose_channel_rx: None,
+            market_events_rx: None,
+            tips_policy: None,
+            _t: PhantomData,
+        }
+    }
+
+    /// Landed bundles of stuffing txs are passed to the tips policy
+    pub fn with_tips_policy(self, tips_policy: Arc<dyn TipsPolicy>) -> Self {
+        Self { tips_policy: Some(tips_policy), ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
@@ -163,11 +191,17 @@ where
     P: Provider<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(stuffing_tx_monitor_worker(
             self.client.clone(),
             self.latest_block.clone().unwrap(),
             self.tx_compose_channel_rx.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
+            self.tips_policy.clone(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -11,7 +11,7 @@ use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
 use loom_defi_pools::protocols::CurveProtocol;
-use loom_defi_pools::CurvePool;
+use loom_defi_pools::{CurvePool, RocketEthPool, StEthPool, WstEthPool};
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_entities::{Market, MarketState, PoolWrapper};
 
@@ -21,6 +21,23 @@ where
     N: Network,
     P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
 {
+    // Staking pools are used together with curve steth pools
+    let mut staking_pools = vec![PoolWrapper::new(Arc::new(StEthPool::mainnet())), PoolWrapper::new(Arc::new(WstEthPool::mainnet()))];
+    match RocketEthPool::fetch_mainnet(client.clone()).await {
+        Ok(rocket_pool) => staking_pools.push(PoolWrapper::new(Arc::new(rocket_pool))),
+        Err(e) => error!("Rocket pool fetching error : {}", e),
+    }
+    for pool_wrapped in staking_pools {
+        match fetch_state_and_add_pool(client.clone(), market.clone(), market_state.clone(), pool_wrapped.clone()).await {
+            Err(e) => {
+                error!("Staking pool loading error {:?} : {}", pool_wrapped.get_address(), e)
+            }
+            Ok(_) => {
+                debug!("Staking pool loaded {:#20x}", pool_wrapped.get_address());
+            }
+        }
+    }
+
     let curve_contracts = CurveProtocol::get_contracts_vec(client.clone());
     for curve_contract in curve_contracts.into_iter() {
         if let Ok(curve_pool) = CurvePool::fetch_pool_data(client.clone(), curve_contract).await {
@@ -0,0 +1,438 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::time::Duration;
+
+use alloy_network::Network;
+use alloy_primitives::aliases::U24;
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, Result};
+use tracing::{error, info, warn};
+
+use loom_core_actors::{run_async, Accessor, Actor, ActorResult, Broadcaster, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_defi_abi::uniswap2::{IUniswapV2Factory, IUniswapV2Pair};
+use loom_defi_abi::uniswap3::{IUniswapV3Factory, IUniswapV3Pool};
+use loom_defi_abi::IMulticall3;
+use loom_defi_address_book::AddressBook;
+use loom_defi_pools::PoolsConfig;
+use loom_types_entities::{Market, PoolClass, Token};
+use loom_types_events::Task;
+
+// factory names in the address book of the chain
+const UNISWAP_V2_FACTORIES: [&str; 5] = ["UNISWAP_V2", "SUSHISWAP_V2", "SHIBASWAP", "DOOARSWAP", "OG_PEPE"];
+
+const UNISWAP_V3_FEES: [u32; 4] = [100, 500, 3000, 10000];
+const PANCAKE_V3_FEES: [u32; 4] = [100, 500, 2500, 10000];
+
+const UNISWAP_V3_FACTORIES: [(&str, &[u32]); 3] =
+    [("UNISWAP_V3", &UNISWAP_V3_FEES), ("SUSHISWAP_V3", &UNISWAP_V3_FEES), ("PANCAKE_V3", &PANCAKE_V3_FEES)];
+
+const MULTICALL_RETRIES: u64 = 3;
+
+/// Filters and batching of the factory pool loader
+#[derive(Clone, Debug)]
+pub struct FactoryPoolLoaderConfig {
+    /// Minimal reserve of both tokens of Uniswap V2 pairs
+    pub min_reserve: U256,
+    /// Minimal in range liquidity of Uniswap V3 pools
+    pub min_liquidity: u128,
+    /// Number of calls in one multicall
+    pub batch_size: usize,
+    /// Multicall3 contract, MULTICALL3 of the chain address book if not set
+    pub multicall: Option<Address>,
+    /// Tokens paired in Uniswap V3 pool lookups in addition to the basic and middle tokens of the market
+    pub tokens: Vec<Address>,
+}
+
+impl Default for FactoryPoolLoaderConfig {
+    fn default() -> Self {
+        Self { min_reserve: U256::from(1_000_000), min_liquidity: 1_000_000, batch_size: 500, multicall: None, tokens: Vec::new() }
+    }
+}
+
+// V3 factories can't be enumerated, pools are looked up for pairs of basic, middle and configured tokens only as
+// pairs of all market tokens would need millions of lookups
+fn uniswap_v3_lookup_tokens<'a>(market_tokens: impl Iterator<Item = &'a Token>, config_tokens: &[Address]) -> Vec<Address> {
+    let mut tokens: Vec<Address> = market_tokens
+        .filter(|token| token.is_basic() || token.is_middle())
+        .map(|token| token.get_address())
+        .chain(config_tokens.iter().cloned())
+        .collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+// getPool calls for all pairs of the tokens and fee tiers
+fn uniswap_v3_pool_calls(factory: Address, fees: &[u32], tokens: &[Address]) -> Vec<(Address, Vec<u8>)> {
+    let mut pool_calls = Vec::new();
+    for (idx, token_a) in tokens.iter().enumerate() {
+        for token_b in tokens.iter().skip(idx + 1) {
+            for fee in fees {
+                let call_data = IUniswapV3Factory::getPoolCall { tokenA: *token_a, tokenB: *token_b, fee: U24::from(*fee) }.abi_encode();
+                pool_calls.push((factory, call_data));
+            }
+        }
+    }
+    pool_calls
+}
+
+fn batch_ranges(len: usize, batch_size: usize) -> Vec<Range<usize>> {
+    (0..len).step_by(batch_size.max(1)).map(|batch_start| batch_start..(batch_start + batch_size.max(1)).min(len)).collect()
+}
+
+// The pool loader fetches pools slower than factories are enumerated, sending waits to not overrun the tasks channel
+async fn send_pools(tasks_tx: &Broadcaster<Task>, pools: Vec<(Address, PoolClass)>) {
+    loop {
+        let stats = tasks_tx.stats().await;
+        if stats.queued * 2 < stats.capacity {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    run_async!(tasks_tx.send(Task::FetchStateAndAddPools(pools)));
+}
+
+// Executes calls with Multicall3, failed calls return None. Failed multicalls are retried, a batch failing every retry
+// returns the error to be skipped by the caller.
+async fn multicall<P, T, N>(client: P, multicall: Address, calls: Vec<(Address, Vec<u8>)>) -> Result<Vec<Option<Bytes>>>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    let mut attempt = 0;
+    loop {
+        match try_multicall(client.clone(), multicall, calls.clone()).await {
+            Ok(results) => break Ok(results),
+            Err(e) if attempt < MULTICALL_RETRIES => {
+                attempt += 1;
+                warn!("Multicall of {} calls failed, retry {} of {} : {}", calls.len(), attempt, MULTICALL_RETRIES, e);
+                tokio::time::sleep(Duration::from_millis(500 * attempt)).await;
+            }
+            Err(e) => break Err(e),
+        }
+    }
+}
+
+async fn try_multicall<P, T, N>(client: P, multicall: Address, calls: Vec<(Address, Vec<u8>)>) -> Result<Vec<Option<Bytes>>>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    let calls: Vec<IMulticall3::Call3> = calls
+        .into_iter()
+        .map(|(target, call_data)| IMulticall3::Call3 { target, allowFailure: true, callData: call_data.into() })
+        .collect();
+
+    let results = IMulticall3::new(multicall, client).aggregate3(calls).call().await?.returnData;
+
+    Ok(results
+        .into_iter()
+        .map(|result| if result.success && !result.returnData.is_empty() { Some(result.returnData) } else { None })
+        .collect())
+}
+
+async fn load_uniswap_v2_factory<P, T, N>(
+    client: P,
+    config: &FactoryPoolLoaderConfig,
+    multicall_address: Address,
+    factory: Address,
+    tasks_tx: &Broadcaster<Task>,
+) -> Result<usize>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    let pairs_len: usize = IUniswapV2Factory::new(factory, client.clone()).allPairsLength().call().await?._0.to();
+    info!("Enumerating {} pairs of factory {:#20x}", pairs_len, factory);
+
+    let mut pools_found = 0;
+    for batch in batch_ranges(pairs_len, config.batch_size) {
+        let (batch_start, batch_end) = (batch.start, batch.end);
+
+        let pair_calls = batch.map(|idx| (factory, IUniswapV2Factory::allPairsCall { _0: U256::from(idx) }.abi_encode())).collect();
+        let pairs: Vec<Address> = match multicall(client.clone(), multicall_address, pair_calls).await {
+            Ok(results) => results
+                .into_iter()
+                .filter_map(|result| IUniswapV2Factory::allPairsCall::abi_decode_returns(&result?, false).ok().map(|ret| ret.pair))
+                .collect(),
+            Err(e) => {
+                error!("Factory {:#20x} pairs {}..{} skipped : {}", factory, batch_start, batch_end, e);
+                continue;
+            }
+        };
+
+        let reserves_calls = pairs.iter().map(|pair| (*pair, IUniswapV2Pair::getReservesCall {}.abi_encode())).collect();
+        let reserves = match multicall(client.clone(), multicall_address, reserves_calls).await {
+            Ok(reserves) => reserves,
+            Err(e) => {
+                error!("Factory {:#20x} reserves of pairs {}..{} skipped : {}", factory, batch_start, batch_end, e);
+                continue;
+            }
+        };
+
+        let pools: Vec<(Address, PoolClass)> = pairs
+            .into_iter()
+            .zip(reserves)
+            .filter(|(_, result)| {
+                result
+                    .as_ref()
+                    .and_then(|data| IUniswapV2Pair::getReservesCall::abi_decode_returns(data, false).ok())
+                    .map_or(false, |ret| U256::from(ret.reserve0) >= config.min_reserve && U256::from(ret.reserve1) >= config.min_reserve)
+            })
+            .map(|(pair, _)| (pair, PoolClass::UniswapV2))
+            .collect();
+
+        pools_found += pools.len();
+        info!("Factory {:#20x} pairs {}..{} of {} : {} pools found", factory, batch_start, batch_end, pairs_len, pools.len());
+        if !pools.is_empty() {
+            send_pools(tasks_tx, pools).await;
+        }
+    }
+
+    Ok(pools_found)
+}
+
+async fn load_uniswap_v3_factory<P, T, N>(
+    client: P,
+    config: &FactoryPoolLoaderConfig,
+    multicall_address: Address,
+    factory: Address,
+    fees: &[u32],
+    tokens: &[Address],
+    tasks_tx: &Broadcaster<Task>,
+) -> Result<usize>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    let pool_calls = uniswap_v3_pool_calls(factory, fees, tokens);
+    info!("Looking up {} pools of factory {:#20x}", pool_calls.len(), factory);
+
+    let mut pools_found = 0;
+    let calls_len = pool_calls.len();
+    for batch in batch_ranges(calls_len, config.batch_size) {
+        let batch_end = batch.end;
+        let pools: Vec<Address> = match multicall(client.clone(), multicall_address, pool_calls[batch].to_vec()).await {
+            Ok(results) => results
+                .into_iter()
+                .filter_map(|result| IUniswapV3Factory::getPoolCall::abi_decode_returns(&result?, false).ok().map(|ret| ret.pool))
+                .filter(|pool| !pool.is_zero())
+                .collect(),
+            Err(e) => {
+                error!("Factory {:#20x} lookups up to {} of {} skipped : {}", factory, batch_end, calls_len, e);
+                continue;
+            }
+        };
+        if pools.is_empty() {
+            continue;
+        }
+
+        let liquidity_calls = pools.iter().map(|pool| (*pool, IUniswapV3Pool::liquidityCall {}.abi_encode())).collect();
+        let liquidity = match multicall(client.clone(), multicall_address, liquidity_calls).await {
+            Ok(liquidity) => liquidity,
+            Err(e) => {
+                error!("Factory {:#20x} liquidity of lookups up to {} of {} skipped : {}", factory, batch_end, calls_len, e);
+                continue;
+            }
+        };
+
+        let pools: Vec<(Address, PoolClass)> = pools
+            .into_iter()
+            .zip(liquidity)
+            .filter(|(_, result)| {
+                result
+                    .as_ref()
+                    .and_then(|data| IUniswapV3Pool::liquidityCall::abi_decode_returns(data, false).ok())
+                    .map_or(false, |ret| ret._0 >= config.min_liquidity)
+            })
+            .map(|(pool, _)| (pool, PoolClass::UniswapV3))
+            .collect();
+
+        pools_found += pools.len();
+        info!("Factory {:#20x} lookups {} of {} : {} pools found", factory, batch_end, calls_len, pools.len());
+        if !pools.is_empty() {
+            send_pools(tasks_tx, pools).await;
+        }
+    }
+
+    Ok(pools_found)
+}
+
+async fn factory_pool_loader_one_shot_worker<P, T, N>(
+    client: P,
+    pools_config: PoolsConfig,
+    config: FactoryPoolLoaderConfig,
+    market: SharedState<Market>,
+    tasks_tx: Broadcaster<Task>,
+) -> WorkerResult
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    let chain_id = client.get_chain_id().await?;
+    let address_book = AddressBook::get(chain_id).ok_or(eyre!("ADDRESS_BOOK_NOT_FOUND"))?;
+    let multicall_address = config.multicall.or(address_book.periphery("MULTICALL3")).ok_or(eyre!("MULTICALL3_NOT_FOUND"))?;
+
+    let mut pools_found = 0;
+
+    if pools_config.is_enabled(PoolClass::UniswapV2) {
+        for (idx, factory_name) in UNISWAP_V2_FACTORIES.iter().enumerate() {
+            let Some(factory) = address_book.factory(factory_name) else {
+                info!("Factory {} not in the address book of chain {}", factory_name, chain_id);
+                continue;
+            };
+            match load_uniswap_v2_factory(client.clone(), &config, multicall_address, factory, &tasks_tx).await {
+                Ok(found) => {
+                    pools_found += found;
+                    info!("Factory {:#20x} loaded {} pools, factory {} of {}", factory, found, idx + 1, UNISWAP_V2_FACTORIES.len());
+                }
+                Err(e) => {
+                    error!("Error loading factory {:#20x} : {}", factory, e)
+                }
+            }
+        }
+    }
+
+    if pools_config.is_enabled(PoolClass::UniswapV3) {
+        let tokens = uniswap_v3_lookup_tokens(market.read().await.tokens().values().map(|token| token.as_ref()), &config.tokens);
+
+        for (idx, (factory_name, fees)) in UNISWAP_V3_FACTORIES.iter().enumerate() {
+            let Some(factory) = address_book.factory(factory_name) else {
+                info!("Factory {} not in the address book of chain {}", factory_name, chain_id);
+                continue;
+            };
+            match load_uniswap_v3_factory(client.clone(), &config, multicall_address, factory, fees, &tokens, &tasks_tx).await {
+                Ok(found) => {
+                    pools_found += found;
+                    info!("Factory {:#20x} loaded {} pools, factory {} of {}", factory, found, idx + 1, UNISWAP_V3_FACTORIES.len());
+                }
+                Err(e) => {
+                    error!("Error loading factory {:#20x} : {}", factory, e)
+                }
+            }
+        }
+    }
+
+    info!("factory_pool_loader_worker finished, {} pools found", pools_found);
+
+    Ok("factory_pool_loader_worker".to_string())
+}
+
+/// Loads pools on a cold start by enumerating the Uniswap V2 and V3 factories of the chain address book instead of scanning logs.
+/// Pools below the configured reserves or liquidity are skipped, found pools are sent to the pool loader in batches.
+#[derive(Accessor, Producer)]
+pub struct FactoryPoolLoaderOneShotActor<P, T, N> {
+    client: P,
+    pools_config: PoolsConfig,
+    config: FactoryPoolLoaderConfig,
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[producer]
+    tasks_tx: Option<Broadcaster<Task>>,
+    _t: PhantomData<T>,
+    _n: PhantomData<N>,
+}
+
+impl<P, T, N> FactoryPoolLoaderOneShotActor<P, T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    pub fn new(client: P, pools_config: PoolsConfig) -> Self {
+        Self {
+            client,
+            pools_config,
+            config: FactoryPoolLoaderConfig::default(),
+            market: None,
+            tasks_tx: None,
+            _t: PhantomData,
+            _n: PhantomData,
+        }
+    }
+
+    pub fn with_config(self, config: FactoryPoolLoaderConfig) -> Self {
+        Self { config, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self { market: Some(bc.market()), tasks_tx: Some(bc.tasks_channel()), ..self }
+    }
+}
+
+impl<P, T, N> Actor for FactoryPoolLoaderOneShotActor<P, T, N>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    fn start(&self) -> ActorResult {
+        let task = tokio::task::spawn(factory_pool_loader_one_shot_worker(
+            self.client.clone(),
+            self.pools_config.clone(),
+            self.config.clone(),
+            self.market.clone().unwrap(),
+            self.tasks_tx.clone().unwrap(),
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "FactoryPoolLoaderOneShotActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_batch_ranges() {
+        assert!(batch_ranges(0, 500).is_empty());
+        assert_eq!(batch_ranges(3, 500), vec![0..3]);
+        assert_eq!(batch_ranges(1000, 500), vec![0..500, 500..1000]);
+        assert_eq!(batch_ranges(1001, 500), vec![0..500, 500..1000, 1000..1001]);
+        assert_eq!(batch_ranges(2, 0), vec![0..1, 1..2]);
+    }
+
+    #[test]
+    fn test_uniswap_v3_lookup_tokens() {
+        let basic = Token::new_with_data(Address::repeat_byte(3), None, None, None, true, false);
+        let middle = Token::new_with_data(Address::repeat_byte(2), None, None, None, false, true);
+        let other = Token::new(Address::repeat_byte(1));
+        let configured = Address::repeat_byte(4);
+
+        let tokens = uniswap_v3_lookup_tokens([&basic, &middle, &other].into_iter(), &[configured, basic.get_address()]);
+
+        assert_eq!(tokens, vec![middle.get_address(), basic.get_address(), configured]);
+    }
+
+    #[test]
+    fn test_uniswap_v3_pool_calls() {
+        let factory = Address::repeat_byte(9);
+        let tokens = [Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3)];
+
+        let calls = uniswap_v3_pool_calls(factory, &UNISWAP_V3_FEES, &tokens);
+
+        // 3 pairs by 4 fee tiers
+        assert_eq!(calls.len(), 12);
+        assert!(calls.iter().all(|(target, _)| *target == factory));
+
+        let first = IUniswapV3Factory::getPoolCall::abi_decode(&calls[0].1, false).unwrap();
+        assert_eq!((first.tokenA, first.tokenB, first.fee), (tokens[0], tokens[1], U24::from(100)));
+        let last = IUniswapV3Factory::getPoolCall::abi_decode(&calls[11].1, false).unwrap();
+        assert_eq!((last.tokenA, last.tokenB, last.fee), (tokens[1], tokens[2], U24::from(10000)));
+
+        assert!(uniswap_v3_pool_calls(factory, &UNISWAP_V3_FEES, &tokens[..1]).is_empty());
+    }
+}
@@ -1,10 +1,12 @@
 pub use curve_protocol_pool_actor::CurvePoolLoaderOneShotActor;
+pub use factory_pool_actor::{FactoryPoolLoaderConfig, FactoryPoolLoaderOneShotActor};
 pub use history_pool_actor::HistoryPoolLoaderOneShotActor;
 pub use new_pool_actor::NewPoolLoaderActor;
 pub use pool_loader::{fetch_and_add_pool_by_address, fetch_state_and_add_pool, PoolLoaderActor};
 pub use required_pools_actor::RequiredPoolLoaderActor;
 
 mod curve_protocol_pool_actor;
+mod factory_pool_actor;
 mod history_pool_actor;
 mod logs_parser;
 mod new_pool_actor;
@@ -1,4 +1,4 @@
-use alloy_primitives::Log as EVMLog;
+use alloy_primitives::{Address, Log as EVMLog};
 use alloy_rpc_types::Log;
 use alloy_sol_types::SolEventInterface;
 use eyre::Result;
@@ -6,10 +6,15 @@ use std::collections::HashMap;
 use tracing::error;
 
 use loom_core_actors::{run_async, Broadcaster};
+use loom_defi_abi::balancer::IVault::IVaultEvents;
 use loom_defi_abi::maverick::IMaverickPool::IMaverickPoolEvents;
+use loom_defi_abi::solidly::ISolidlyPool::ISolidlyPoolEvents;
 use loom_defi_abi::uniswap2::IUniswapV2Pair::IUniswapV2PairEvents;
 use loom_defi_abi::uniswap3::IUniswapV3Pool::IUniswapV3PoolEvents;
-use loom_defi_pools::PoolsConfig;
+use loom_defi_abi::uniswap4::IUniswapV4PoolManager::IUniswapV4PoolManagerEvents;
+use loom_defi_abi::uniswap4::IUniswapV4PoolManagerUnlock::IUniswapV4PoolManagerUnlockEvents;
+use loom_defi_abi::uniswap4::PoolKey;
+use loom_defi_pools::{BalancerPool, PoolsConfig, UniswapV4Pool};
 use loom_types_entities::PoolClass;
 use loom_types_events::Task;
 
@@ -39,6 +44,17 @@ fn determine_pool_class(log_entry: Log) -> Option<PoolClass> {
                         Err(_) => None,
                     }
                 }
+                .or_else(|| match ISolidlyPoolEvents::decode_log(&log_entry, false) {
+                    // Mint has the same signature as in Uniswap V2, pools are distinguished by the factory when loaded
+                    Ok(event) => match event.data {
+                        ISolidlyPoolEvents::Swap(_)
+                        | ISolidlyPoolEvents::Burn(_)
+                        | ISolidlyPoolEvents::Sync(_)
+                        | ISolidlyPoolEvents::Fees(_) => Some(PoolClass::Solidly),
+                        _ => None,
+                    },
+                    Err(_) => None,
+                })
                 .or_else(|| match IUniswapV2PairEvents::decode_log(&log_entry, false) {
                     Ok(event) => match event.data {
                         IUniswapV2PairEvents::Swap(_)
@@ -55,25 +71,101 @@ fn determine_pool_class(log_entry: Log) -> Option<PoolClass> {
     }
 }
 
+// Balancer swaps are emitted by the vault, pool address is taken from the pool id
+fn determine_balancer_pool(log_entry: &Log) -> Option<(Address, PoolClass)> {
+    let log_entry: EVMLog = EVMLog::new(log_entry.address(), log_entry.topics().to_vec(), log_entry.data().data.clone())?;
+    match IVaultEvents::decode_log(&log_entry, false) {
+        Ok(event) => match event.data {
+            IVaultEvents::Swap(swap) => Some((BalancerPool::pool_address_from_id(swap.poolId), PoolClass::Balancer)),
+            _ => None,
+        },
+        Err(_) => None,
+    }
+}
+
+enum UniswapV4PoolEvent {
+    // New pools are announced with their key by the pool manager
+    Initialize(Address, PoolKey),
+    // Swaps may move the price of a known pool out of its loaded ticks
+    Swap(Address),
+}
+
+// Uniswap V4 events are emitted by the pool manager, the emitter is the pool manager of the pool
+fn determine_uniswap_v4_pool(log_entry: &Log) -> Option<UniswapV4PoolEvent> {
+    let log_entry: EVMLog = EVMLog::new(log_entry.address(), log_entry.topics().to_vec(), log_entry.data().data.clone())?;
+    match IUniswapV4PoolManagerUnlockEvents::decode_log(&log_entry, false) {
+        Ok(event) => match event.data {
+            IUniswapV4PoolManagerUnlockEvents::Initialize(initialize) => Some(UniswapV4PoolEvent::Initialize(
+                log_entry.address,
+                PoolKey {
+                    currency0: initialize.currency0,
+                    currency1: initialize.currency1,
+                    fee: initialize.fee,
+                    tickSpacing: initialize.tickSpacing,
+                    hooks: initialize.hooks,
+                },
+            )),
+        },
+        Err(_) => match IUniswapV4PoolManagerEvents::decode_log(&log_entry, false) {
+            Ok(event) => match event.data {
+                IUniswapV4PoolManagerEvents::Swap(swap) => Some(UniswapV4PoolEvent::Swap(UniswapV4Pool::pool_address_from_id(swap.id))),
+                _ => None,
+            },
+            Err(_) => None,
+        },
+    }
+}
+
 pub async fn process_log_entries(log_entries: Vec<Log>, pools_config: &PoolsConfig, tasks_tx: Broadcaster<Task>) -> Result<()> {
     let mut pool_to_fetch = Vec::new();
+    let mut uniswap_v4_pools_to_fetch = Vec::new();
+    let mut pools_to_update = Vec::new();
     let mut processed_pools = HashMap::new();
 
     for log_entry in log_entries.into_iter() {
-        if let Some(pool_class) = determine_pool_class(log_entry.clone()) {
+        if let Some(uniswap_v4_event) = determine_uniswap_v4_pool(&log_entry) {
+            if !pools_config.is_enabled(PoolClass::UniswapV4) {
+                continue;
+            }
+            match uniswap_v4_event {
+                UniswapV4PoolEvent::Initialize(pool_manager, pool_key) => {
+                    let pool_address = UniswapV4Pool::pool_address_from_id(UniswapV4Pool::calc_pool_id(&pool_key));
+                    if processed_pools.insert(pool_address, true).is_none() {
+                        uniswap_v4_pools_to_fetch.push((pool_manager, pool_key));
+                    }
+                }
+                UniswapV4PoolEvent::Swap(pool_address) => {
+                    if !pools_to_update.contains(&pool_address) {
+                        pools_to_update.push(pool_address);
+                    }
+                }
+            }
+            continue;
+        }
+
+        let pool = determine_balancer_pool(&log_entry)
+            .or_else(|| determine_pool_class(log_entry.clone()).map(|pool_class| (log_entry.address(), pool_class)));
+
+        if let Some((pool_address, pool_class)) = pool {
             if !pools_config.is_enabled(pool_class) {
                 continue;
             }
 
             // was this pool already processed?
-            if processed_pools.insert(log_entry.address(), true).is_some() {
+            if processed_pools.insert(pool_address, true).is_some() {
                 continue;
             }
 
-            pool_to_fetch.push((log_entry.address(), pool_class));
+            pool_to_fetch.push((pool_address, pool_class));
         }
     }
 
     run_async!(tasks_tx.send(Task::FetchAndAddPools(pool_to_fetch)));
+    if !uniswap_v4_pools_to_fetch.is_empty() {
+        run_async!(tasks_tx.send(Task::FetchAndAddUniswapV4Pools(uniswap_v4_pools_to_fetch)));
+    }
+    if !pools_to_update.is_empty() {
+        run_async!(tasks_tx.send(Task::FetchPoolsStateUpdate(pools_to_update)));
+    }
     Ok(())
 }
@@ -1,5 +1,6 @@
 use eyre::Result;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
 use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
@@ -14,11 +15,15 @@ pub async fn new_pool_worker(
     log_update_rx: Broadcaster<MessageBlockLogs>,
     pools_config: PoolsConfig,
     tasks_tx: Broadcaster<Task>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(log_update_rx);
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("New pool worker stopped".to_string())
+            }
             msg = log_update_rx.recv() => {
                 debug!("Log update");
 
@@ -62,10 +67,15 @@ impl NewPoolLoaderActor {
 
 impl Actor for NewPoolLoaderActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(new_pool_worker(
             self.log_update_rx.clone().unwrap(),
             self.pools_config.clone(),
             self.tasks_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
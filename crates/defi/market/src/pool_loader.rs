@@ -1,47 +1,107 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
 
 use alloy_network::Network;
 use alloy_primitives::Address;
 use alloy_provider::Provider;
 use alloy_transport::Transport;
 use eyre::{eyre, Result};
+use futures::future::join_all;
 use futures::stream::FuturesUnordered;
-use futures::StreamExt;
-use tracing::{debug, error};
+use futures::{FutureExt, StreamExt};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
 
 use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, SharedState, WorkerResult};
 use loom_core_actors::{Accessor, Consumer};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
+use loom_defi_abi::uniswap4::PoolKey;
+use loom_defi_address_book::{PeripheryAddress, TokenAddress};
 use loom_defi_pools::protocols::{fetch_uni2_factory, fetch_uni3_factory, CurveProtocol};
-use loom_defi_pools::{CurvePool, MaverickPool, PancakeV3Pool, UniswapV2Pool, UniswapV3Pool};
+use loom_defi_pools::{
+    AlgebraPool, BalancerPool, CurvePool, Erc4626Pool, IntegralPool, MaverickPool, PancakeV3Pool, RocketEthPool, SolidlyPool, StEthPool,
+    TokenClassificationCache, TokenClassifier, UniswapV2Pool, UniswapV3Pool, UniswapV4Pool, WstEthPool,
+};
 use loom_node_debug_provider::DebugProviderExt;
-use loom_types_entities::required_state::RequiredStateReader;
-use loom_types_entities::{get_protocol_by_factory, Market, MarketState, PoolClass, PoolProtocol, PoolWrapper};
+use loom_types_entities::required_state::BatchRequiredStateReader;
+use loom_types_entities::{get_protocol_by_factory, Market, MarketState, Pool, PoolClass, PoolProtocol, PoolWrapper, Token};
 use loom_types_events::Task;
 
+// Shared by all loaders, state of pools loaded at the same time is fetched together and only once per block
+pub(crate) static STATE_READER: LazyLock<BatchRequiredStateReader> = LazyLock::new(BatchRequiredStateReader::default);
+
+// Shared by all loaders, tokens are simulated only once per process or once per cache file
+static TOKEN_CLASSIFICATIONS: LazyLock<TokenClassificationCache> = LazyLock::new(TokenClassificationCache::default);
+
 pub async fn pool_loader_worker<P, T, N>(
     client: P,
     market: SharedState<Market>,
     market_state: SharedState<MarketState>,
     tasks_rx: Broadcaster<Task>,
+    token_cache_path: Option<PathBuf>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
     N: Network,
     P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
 {
+    if let Some(path) = token_cache_path.as_ref().filter(|path| path.exists()) {
+        match TOKEN_CLASSIFICATIONS.load(path) {
+            Ok(loaded) => info!("Loaded {} token classifications from {}", loaded, path.display()),
+            Err(e) => error!("Error loading token classifications from {} : {}", path.display(), e),
+        }
+    }
+
     let mut fetch_tasks = FuturesUnordered::new();
     let mut processed_pools = HashMap::new();
 
     subscribe!(tasks_rx);
     loop {
-        if let Ok(task) = tasks_rx.recv().await {
+        let msg = tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                // let pools being fetched finish adding to the market
+                while fetch_tasks.next().await.is_some() {}
+                if let Some(path) = token_cache_path.as_ref() {
+                    if let Err(e) = TOKEN_CLASSIFICATIONS.save(path) {
+                        error!("Error saving token classifications to {} : {}", path.display(), e)
+                    }
+                }
+                break Ok("Pool loader worker stopped".to_string())
+            }
+            msg = tasks_rx.recv() => msg,
+        };
+        if let Ok(task) = msg {
             let pools = match task {
-                Task::FetchAndAddPools(pools) => pools,
-                _ => continue,
+                Task::FetchAndAddPools(pools) | Task::FetchStateAndAddPools(pools) => pools,
+                Task::FetchAndAddUniswapV4Pools(pools) => {
+                    for (pool_manager, pool_key) in pools {
+                        let pool_address = UniswapV4Pool::pool_address_from_id(UniswapV4Pool::calc_pool_id(&pool_key));
+                        if processed_pools.insert(pool_address, true).is_some() {
+                            continue;
+                        }
+                        fetch_tasks.push(
+                            fetch_and_add_uniswap_v4_pool(client.clone(), market.clone(), market_state.clone(), pool_manager, pool_key)
+                                .boxed(),
+                        );
+
+                        if fetch_tasks.len() > 20 {
+                            fetch_tasks.next().await;
+                        }
+                    }
+                    continue;
+                }
+                Task::FetchPoolsStateUpdate(pools) => {
+                    for pool_address in pools {
+                        if let Err(e) = fetch_pool_state_update(client.clone(), market.clone(), market_state.clone(), pool_address).await {
+                            error!("fetch_pool_state_update error {:#20x} : {}", pool_address, e)
+                        }
+                    }
+                    continue;
+                }
             };
 
             for (pool_address, pool_class) in pools {
@@ -50,13 +110,9 @@ where
                     continue;
                 }
                 // Fetch and add pool
-                fetch_tasks.push(fetch_and_add_pool_by_address(
-                    client.clone(),
-                    market.clone(),
-                    market_state.clone(),
-                    pool_address,
-                    pool_class,
-                ));
+                fetch_tasks.push(
+                    fetch_and_add_pool_by_address(client.clone(), market.clone(), market_state.clone(), pool_address, pool_class).boxed(),
+                );
 
                 // Limit the number of concurrent fetch tasks
                 if fetch_tasks.len() > 20 {
@@ -85,9 +141,18 @@ where
     match pool_class {
         PoolClass::UniswapV2 => {
             let factory_address = fetch_uni2_factory(client.clone(), pool_address).await?;
-            let fetch_result = match get_protocol_by_factory(factory_address) {
-                PoolProtocol::NomiswapStable | PoolProtocol::Miniswap | PoolProtocol::Integral | PoolProtocol::Safeswap => {
-                    Err(eyre!("POOL_PROTOCOL_NOT_SUPPORTED"))
+            let chain_id = client.get_chain_id().await?;
+            let fetch_result = match get_protocol_by_factory(chain_id, factory_address) {
+                PoolProtocol::NomiswapStable | PoolProtocol::Miniswap | PoolProtocol::Safeswap => Err(eyre!("POOL_PROTOCOL_NOT_SUPPORTED")),
+                // Integral pairs emit Uniswap V2 swap events, but are traded through the relayer
+                PoolProtocol::Integral => {
+                    let pool = IntegralPool::fetch_pool_data(client.clone(), pool_address).await?;
+                    fetch_state_and_add_pool(client.clone(), market.clone(), market_state.clone(), PoolWrapper::new(Arc::new(pool))).await
+                }
+                // Solidly pools share events and the swap function with Uniswap V2
+                protocol if protocol.is_solidly() => {
+                    let pool = SolidlyPool::fetch_pool_data(client.clone(), pool_address).await?;
+                    fetch_state_and_add_pool(client.clone(), market.clone(), market_state.clone(), PoolWrapper::new(Arc::new(pool))).await
                 }
 
                 _ => {
@@ -104,13 +169,18 @@ where
             let factory_address_result = fetch_uni3_factory(client.clone(), pool_address).await;
             match factory_address_result {
                 Ok(factory_address) => {
-                    let pool_wrapped = match get_protocol_by_factory(factory_address) {
+                    let chain_id = client.get_chain_id().await?;
+                    let pool_wrapped = match get_protocol_by_factory(chain_id, factory_address) {
                         PoolProtocol::PancakeV3 => {
                             PoolWrapper::new(Arc::new(PancakeV3Pool::fetch_pool_data(client.clone(), pool_address).await?))
                         }
                         PoolProtocol::Maverick => {
                             PoolWrapper::new(Arc::new(MaverickPool::fetch_pool_data(client.clone(), pool_address).await?))
                         }
+                        // Algebra pools emit the same Swap event as Uniswap V3
+                        protocol if protocol.is_algebra() => {
+                            PoolWrapper::new(Arc::new(AlgebraPool::fetch_pool_data(client.clone(), pool_address).await?))
+                        }
                         _ => PoolWrapper::new(Arc::new(UniswapV3Pool::fetch_pool_data(client.clone(), pool_address).await?)),
                     };
 
@@ -142,6 +212,59 @@ where
                 error!("Error getting curve contract from code {} : {} ", pool_address, e)
             }
         },
+        PoolClass::Balancer => {
+            let pool_wrapped = PoolWrapper::new(Arc::new(BalancerPool::fetch_pool_data(client.clone(), pool_address).await?));
+
+            if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+                error!("fetch_and_add_pool balancer error {:#20x} : {}", pool_address, e)
+            }
+        }
+        PoolClass::Solidly => {
+            let pool_wrapped = PoolWrapper::new(Arc::new(SolidlyPool::fetch_pool_data(client.clone(), pool_address).await?));
+
+            if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+                error!("fetch_and_add_pool solidly error {:#20x} : {}", pool_address, e)
+            }
+        }
+        PoolClass::Integral => {
+            let pool_wrapped = PoolWrapper::new(Arc::new(IntegralPool::fetch_pool_data(client.clone(), pool_address).await?));
+
+            if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+                error!("fetch_and_add_pool integral error {:#20x} : {}", pool_address, e)
+            }
+        }
+        PoolClass::Algebra => {
+            let pool_wrapped = PoolWrapper::new(Arc::new(AlgebraPool::fetch_pool_data(client.clone(), pool_address).await?));
+
+            if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+                error!("fetch_and_add_pool algebra error {:#20x} : {}", pool_address, e)
+            }
+        }
+        PoolClass::LidoStEth | PoolClass::LidoWstEth | PoolClass::RocketPool => {
+            let pool_wrapped = match pool_class {
+                PoolClass::LidoStEth => PoolWrapper::new(Arc::new(StEthPool::new(pool_address, TokenAddress::WETH))),
+                PoolClass::LidoWstEth => PoolWrapper::new(Arc::new(WstEthPool::new(pool_address, TokenAddress::STETH, TokenAddress::WETH))),
+                // pool address is RocketDepositPool
+                _ => PoolWrapper::new(Arc::new(RocketEthPool::new(
+                    pool_address,
+                    TokenAddress::RETH,
+                    TokenAddress::WETH,
+                    PeripheryAddress::ROCKET_STORAGE,
+                ))),
+            };
+
+            if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+                error!("fetch_and_add_pool {} error {:#20x} : {}", pool_class, pool_address, e)
+            }
+        }
+        PoolClass::Erc4626 => {
+            // pool address is the vault, which is also the share token
+            let pool_wrapped = PoolWrapper::new(Arc::new(Erc4626Pool::fetch_pool_data(client.clone(), pool_address).await?));
+
+            if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+                error!("fetch_and_add_pool erc4626 error {:#20x} : {}", pool_address, e)
+            }
+        }
         _ => {
             error!("Error pool not supported at {:#20x}", pool_address);
             return Err(eyre!("POOL_CLASS_NOT_SUPPORTED"));
@@ -150,19 +273,151 @@ where
     Ok(())
 }
 
+/// Fetch a Uniswap V4 pool of the pool manager by its key, add it to the market and fetch the required state
+pub async fn fetch_and_add_uniswap_v4_pool<P, T, N>(
+    client: P,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState>,
+    pool_manager: Address,
+    pool_key: PoolKey,
+) -> Result<()>
+where
+    N: Network,
+    T: Transport + Clone,
+    P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
+{
+    let pool_wrapped = PoolWrapper::new(Arc::new(UniswapV4Pool::fetch_pool_data(client.clone(), pool_manager, pool_key).await?));
+    let pool_address = pool_wrapped.get_address();
+
+    if let Err(e) = fetch_state_and_add_pool(client, market, market_state, pool_wrapped).await {
+        error!("fetch_and_add_pool uni4 error {:#20x} : {}", pool_address, e)
+    }
+    Ok(())
+}
+
+/// Fetch the state a known pool is missing at its current position. Fetched state can require more state,
+/// e.g. ticks of newly loaded tick bitmap words, so it is repeated once.
+pub async fn fetch_pool_state_update<P, T, N>(
+    client: P,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState>,
+    pool_address: Address,
+) -> Result<()>
+where
+    N: Network,
+    T: Transport + Clone,
+    P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
+{
+    let Some(pool_wrapped) = market.read().await.get_pool(&pool_address).cloned() else {
+        return Ok(());
+    };
+
+    for _ in 0..2 {
+        let Some(required_state) = pool_wrapped.get_state_required_update(&market_state.read().await.state_db)? else {
+            break;
+        };
+        let state = STATE_READER.fetch_calls_and_slots(client.clone(), required_state, None).await?;
+        market_state.write().await.state_db.apply_geth_update(state);
+    }
+    Ok(())
+}
+
+/// Classify a token new to the market by simulating trades with the pool holding it and add it to the market.
+/// Tokens already in the market are not simulated again, taxes are applied by swap lines from the market tokens.
+/// Tokens failed to be classified are added without taxes and flags.
+async fn classify_token<P, T, N>(client: P, market: SharedState<Market>, token_address: Address, pool_address: Address)
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + Send + Sync + Clone + 'static,
+{
+    if market.read().await.get_token(&token_address).is_some() {
+        return;
+    }
+
+    let mut token = Token::new(token_address);
+    if let Some(classification) = TokenClassifier::classify_cached(&TOKEN_CLASSIFICATIONS, client, token_address, pool_address).await {
+        classification.apply(&mut token);
+        if token.is_honeypot() || token.is_taxed() || token.is_rebasing() {
+            debug!("Token {:#20x} classified : {:?}", token_address, classification);
+        }
+    }
+    if let Err(e) = market.write().await.add_token(token) {
+        error!("add_token {:#20x} error : {}", token_address, e)
+    }
+}
+
+/// Fetch the required state of the pool and add it to the market. Tokens new to the market are checked for being an
+/// ERC-4626 vault, found vaults are added as pools too. Tokens new to the market are classified first, pools with honeypot
+/// tokens are refused. Tokens of pools not holding them, e.g. of the Balancer vault, are left for the next pool holding them.
 pub async fn fetch_state_and_add_pool<P, T, N>(
     client: P,
     market: SharedState<Market>,
     market_state: SharedState<MarketState>,
     pool_wrapped: PoolWrapper,
 ) -> Result<()>
+where
+    T: Transport + Clone,
+    N: Network,
+    P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
+{
+    // the simulation buys from the pool, so the pool has to hold its tokens
+    if pool_wrapped.get_encoder().swap_contract_address().is_none() {
+        let pool_address = pool_wrapped.get_address();
+        join_all(
+            pool_wrapped
+                .get_tokens()
+                .into_iter()
+                .map(|token_address| classify_token(client.clone(), market.clone(), token_address, pool_address)),
+        )
+        .await;
+    }
+
+    let new_tokens: Vec<Address> = {
+        let market_guard = market.read().await;
+        if pool_wrapped.get_tokens().iter().any(|token| market_guard.is_honeypot_token(token)) {
+            return Err(eyre!("HONEYPOT_TOKEN"));
+        }
+        pool_wrapped.get_tokens().into_iter().filter(|token| market_guard.get_token_pools(token).is_none()).collect()
+    };
+    let is_vault = pool_wrapped.get_class() == PoolClass::Erc4626;
+
+    fetch_state_and_add_single_pool(client.clone(), market.clone(), market_state.clone(), pool_wrapped).await?;
+
+    if !is_vault {
+        for token_address in new_tokens {
+            if market.read().await.is_pool(&token_address) {
+                continue;
+            }
+            // most tokens are no vaults and revert on asset()
+            let Ok(vault_pool) = Erc4626Pool::fetch_pool_data(client.clone(), token_address).await else {
+                continue;
+            };
+            debug!("ERC-4626 vault discovered {:#20x} asset {:#20x}", token_address, vault_pool.get_asset());
+
+            let vault_wrapped = PoolWrapper::new(Arc::new(vault_pool));
+            if let Err(e) = fetch_state_and_add_single_pool(client.clone(), market.clone(), market_state.clone(), vault_wrapped).await {
+                error!("fetch_and_add_pool erc4626 error {:#20x} : {}", token_address, e)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_state_and_add_single_pool<P, T, N>(
+    client: P,
+    market: SharedState<Market>,
+    market_state: SharedState<MarketState>,
+    pool_wrapped: PoolWrapper,
+) -> Result<()>
 where
     T: Transport + Clone,
     N: Network,
     P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
 {
     match pool_wrapped.get_state_required() {
-        Ok(required_state) => match RequiredStateReader::fetch_calls_and_slots(client, required_state, None).await {
+        Ok(required_state) => match STATE_READER.fetch_calls_and_slots(client, required_state, None).await {
             Ok(state) => {
                 let pool_address = pool_wrapped.get_address();
                 {
@@ -174,16 +429,11 @@ where
                     drop(market_state_write_guard);
                 }
 
-                let directions_vec = pool_wrapped.get_swap_directions();
-                let mut directions_tree: BTreeMap<PoolWrapper, Vec<(Address, Address)>> = BTreeMap::new();
-
-                directions_tree.insert(pool_wrapped.clone(), directions_vec);
-
                 let mut market_write_guard = market.write().await;
                 // Ignore error if pool already exists because it was maybe already added by e.g. db pool loader
                 let _ = market_write_guard.add_pool(pool_wrapped);
 
-                let swap_paths = market_write_guard.build_swap_path_vec(&directions_tree)?;
+                let swap_paths = market_write_guard.build_pool_swap_paths(&pool_address)?;
                 market_write_guard.add_paths(swap_paths);
 
                 drop(market_write_guard)
@@ -211,6 +461,7 @@ pub struct PoolLoaderActor<P, T, N> {
     market_state: Option<SharedState<MarketState>>,
     #[consumer]
     tasks_rx: Option<Broadcaster<Task>>,
+    token_cache_path: Option<PathBuf>,
     _t: PhantomData<T>,
     _n: PhantomData<N>,
 }
@@ -222,7 +473,12 @@ where
     P: Provider<T, N> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: P) -> Self {
-        Self { client, market: None, market_state: None, tasks_rx: None, _t: PhantomData, _n: PhantomData }
+        Self { client, market: None, market_state: None, tasks_rx: None, token_cache_path: None, _t: PhantomData, _n: PhantomData }
+    }
+
+    /// Load token classifications from the file on start and save them on shutdown
+    pub fn with_token_cache<F: Into<PathBuf>>(self, path: F) -> Self {
+        Self { token_cache_path: Some(path.into()), ..self }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
@@ -237,11 +493,17 @@ where
     P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(pool_loader_worker(
             self.client.clone(),
             self.market.clone().unwrap(),
             self.market_state.clone().unwrap(),
             self.tasks_rx.clone().unwrap(),
+            self.token_cache_path.clone(),
+            token,
         ));
         Ok(vec![task])
     }
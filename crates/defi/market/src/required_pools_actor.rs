@@ -6,14 +6,14 @@ use alloy_provider::Provider;
 use alloy_transport::Transport;
 use tracing::{debug, error};
 
-use crate::pool_loader::{fetch_and_add_pool_by_address, fetch_state_and_add_pool};
+use crate::pool_loader::{fetch_and_add_pool_by_address, fetch_state_and_add_pool, STATE_READER};
 use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer};
 use loom_core_blockchain::Blockchain;
 use loom_defi_pools::protocols::CurveProtocol;
 use loom_defi_pools::CurvePool;
 use loom_node_debug_provider::DebugProviderExt;
-use loom_types_entities::required_state::{RequiredState, RequiredStateReader};
+use loom_types_entities::required_state::RequiredState;
 use loom_types_entities::{Market, MarketState, PoolClass};
 
 async fn required_pools_loader_worker<P, T, N>(
@@ -31,7 +31,16 @@ where
     for (pool_address, pool_class) in pools {
         debug!(class=%pool_class, address=%pool_address, "Loading pool");
         match pool_class {
-            PoolClass::UniswapV2 | PoolClass::UniswapV3 => {
+            PoolClass::UniswapV2
+            | PoolClass::UniswapV3
+            | PoolClass::Balancer
+            | PoolClass::LidoStEth
+            | PoolClass::LidoWstEth
+            | PoolClass::RocketPool
+            | PoolClass::Erc4626
+            | PoolClass::Solidly
+            | PoolClass::Algebra
+            | PoolClass::Integral => {
                 if let Err(error) =
                     fetch_and_add_pool_by_address(client.clone(), market.clone(), market_state.clone(), pool_address, pool_class).await
                 {
@@ -54,7 +63,7 @@ where
     }
 
     if let Some(required_state) = required_state {
-        let update = RequiredStateReader::fetch_calls_and_slots(client.clone(), required_state, None).await?;
+        let update = STATE_READER.fetch_calls_and_slots(client.clone(), required_state, None).await?;
         market_state.write().await.state_db.apply_geth_update(update);
     }
 
@@ -0,0 +1,302 @@
+use std::ops::Sub;
+
+use alloy_primitives::{Address, Bytes, I256, U160, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use loom_defi_abi::algebra::IAlgebraPool;
+use loom_defi_abi::uniswap_periphery::ITickLens;
+use loom_defi_abi::IERC20;
+use loom_defi_address_book::AddressBook;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{get_protocol_by_factory, AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::state_readers::{AlgebraGlobalState, AlgebraStateReader};
+use crate::virtual_impl::AlgebraPoolVirtual;
+use crate::UniswapV3Pool;
+
+/// Algebra concentrated liquidity pool (Algebra V1, QuickSwap V3, Camelot V3).
+/// The fee is dynamic and stored in the global state, Camelot has separate fees for both swap directions.
+/// Populated ticks are preloaded with the Algebra tick lens registered as `ALGEBRA_TICK_LENS` in the address book.
+#[derive(Clone)]
+pub struct AlgebraPool {
+    address: Address,
+    pub token0: Address,
+    pub token1: Address,
+    tick_spacing: u32,
+    global_state: Option<AlgebraGlobalState>,
+    factory: Address,
+    tick_lens: Option<Address>,
+    protocol: PoolProtocol,
+    encoder: AlgebraAbiSwapEncoder,
+}
+
+impl AlgebraPool {
+    pub fn new_with_data(
+        address: Address,
+        token0: Address,
+        token1: Address,
+        tick_spacing: u32,
+        global_state: Option<AlgebraGlobalState>,
+        factory: Address,
+        chain_id: u64,
+    ) -> Self {
+        AlgebraPool {
+            address,
+            token0,
+            token1,
+            tick_spacing,
+            global_state,
+            factory,
+            tick_lens: Self::tick_lens(chain_id),
+            protocol: Self::get_protocol_by_factory(chain_id, factory),
+            encoder: AlgebraAbiSwapEncoder {},
+        }
+    }
+
+    pub fn tick_spacing(&self) -> u32 {
+        self.tick_spacing
+    }
+
+    pub fn get_factory(&self) -> Address {
+        self.factory
+    }
+
+    fn get_protocol_by_factory(chain_id: u64, factory_address: Address) -> PoolProtocol {
+        match get_protocol_by_factory(chain_id, factory_address) {
+            protocol @ (PoolProtocol::QuickswapV3 | PoolProtocol::CamelotV3) => protocol,
+            _ => PoolProtocol::Algebra,
+        }
+    }
+
+    fn tick_lens(chain_id: u64) -> Option<Address> {
+        AddressBook::find_periphery(chain_id, "ALGEBRA_TICK_LENS")
+    }
+
+    pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
+        let token0 = AlgebraStateReader::token0(db, env.clone(), address)?;
+        let token1 = AlgebraStateReader::token1(db, env.clone(), address)?;
+        let factory = AlgebraStateReader::factory(db, env.clone(), address)?;
+        let tick_spacing = AlgebraStateReader::tick_spacing(db, env.clone(), address)?;
+        let global_state = AlgebraStateReader::global_state(db, env.clone(), address)?;
+
+        Ok(Self::new_with_data(address, token0, token1, tick_spacing, Some(global_state), factory, env.cfg.chain_id))
+    }
+
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        address: Address,
+    ) -> Result<Self> {
+        let pool_contract = IAlgebraPool::new(address, client.clone());
+
+        let token0 = pool_contract.token0().call().await?._0;
+        let token1 = pool_contract.token1().call().await?._0;
+        let factory = pool_contract.factory().call().await?._0;
+        let tick_spacing = match pool_contract.tickSpacing().call().await {
+            Ok(ret) => ret._0.try_into()?,
+            Err(_) => 60,
+        };
+
+        // globalState layout differs between forks, so it is decoded by the state reader from the raw return
+        let call_data_result = pool_contract.globalState().call_raw().await?;
+        let global_state = AlgebraStateReader::decode_global_state(&call_data_result)?;
+
+        let chain_id = client.get_chain_id().await?;
+
+        debug!("fetch_pool_data {:?} {:?} {:?} tick_spacing={} {:?}", address, token0, token1, tick_spacing, global_state);
+        Ok(Self::new_with_data(address, token0, token1, tick_spacing, Some(global_state), factory, chain_id))
+    }
+
+    pub fn get_price_limit(token_address_from: &Address, token_address_to: &Address) -> U160 {
+        UniswapV3Pool::get_price_limit(token_address_from, token_address_to)
+    }
+}
+
+impl Pool for AlgebraPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::Algebra
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        self.protocol
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_fee(&self) -> U256 {
+        U256::from(self.global_state.as_ref().map(|state| state.fee_zero_to_one).unwrap_or_default())
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token0, self.token1]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.token0, self.token1), (self.token1, self.token0)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let ret = AlgebraPoolVirtual::simulate_swap_in_amount(state_db, env, self, *token_address_from, in_amount)?;
+
+        if ret.is_zero() {
+            Err(eyre!("RETURN_RESULT_IS_ZERO"))
+        } else {
+            Ok((ret, 170000)) // value, gas_used
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let ret = AlgebraPoolVirtual::simulate_swap_out_amount(state_db, env, self, *token_address_from, out_amount)?;
+
+        if ret.is_zero() {
+            Err(eyre!("RETURN_RESULT_IS_ZERO"))
+        } else {
+            Ok((ret, 170000)) // value, gas_used
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let tick = self.global_state.as_ref().ok_or_eyre("GLOBAL_STATE_NOT_SET")?.tick;
+        let tick_lens = self.tick_lens.ok_or_eyre("ALGEBRA_TICK_LENS_NOT_SET")?;
+        let tick_table_index = UniswapV3Pool::get_tick_bitmap_index(tick, self.tick_spacing);
+
+        let pool_address = self.get_address();
+        let mut state_required = RequiredState::new();
+
+        state_required
+            .add_call(pool_address, IAlgebraPool::globalStateCall {}.abi_encode())
+            .add_call(pool_address, IAlgebraPool::liquidityCall {}.abi_encode())
+            .add_call(pool_address, IAlgebraPool::tickSpacingCall {}.abi_encode());
+
+        for i in -4..=3 {
+            state_required.add_call(
+                tick_lens,
+                ITickLens::getPopulatedTicksInWordCall { pool: pool_address, tickBitmapIndex: tick_table_index + i }.abi_encode(),
+            );
+        }
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: pool_address }.abi_encode());
+        }
+
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct AlgebraAbiSwapEncoder {}
+
+impl AbiSwapEncoder for AlgebraAbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        let swap_call = IAlgebraPool::swapCall {
+            recipient,
+            zeroToOne: token_from_address < token_to_address,
+            amountRequired: I256::from_raw(amount),
+            limitSqrtPrice: AlgebraPool::get_price_limit(&token_from_address, &token_to_address),
+            data: payload,
+        };
+
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        let swap_call = IAlgebraPool::swapCall {
+            recipient,
+            zeroToOne: token_from_address < token_to_address,
+            amountRequired: I256::ZERO.sub(I256::from_raw(amount)),
+            limitSqrtPrice: AlgebraPool::get_price_limit(&token_from_address, &token_to_address),
+            data: payload,
+        };
+
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+
+    // the pool calls algebraSwapCallback of the sender to get paid
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Callback
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x44)
+    }
+
+    fn swap_out_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x44)
+    }
+
+    fn swap_in_amount_return_offset(&self, token_from_address: Address, token_to_address: Address) -> Option<u32> {
+        if token_from_address < token_to_address {
+            Some(0x20)
+        } else {
+            Some(0x0)
+        }
+    }
+
+    fn swap_in_amount_return_script(&self, _token_from_address: Address, _token_to_address: Address) -> Option<Bytes> {
+        Some(Bytes::from(vec![0x8, 0x2A, 0x00]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_encode_swap() -> Result<()> {
+        let token0 = address!("0000000000000000000000000000000000000001");
+        let token1 = address!("0000000000000000000000000000000000000002");
+        let encoder = AlgebraAbiSwapEncoder {};
+
+        let data = encoder.encode_swap_in_amount_provided(token0, token1, U256::from(1000), Address::ZERO, Bytes::new())?;
+        let offset = encoder.swap_in_amount_offset(token0, token1).unwrap() as usize;
+        assert_eq!(U256::from_be_slice(&data[offset..offset + 0x20]), U256::from(1000));
+
+        let call = IAlgebraPool::swapCall::abi_decode(&data, true)?;
+        assert!(call.zeroToOne);
+        assert_eq!(call.limitSqrtPrice, AlgebraPool::get_price_limit(&token0, &token1));
+        Ok(())
+    }
+}
@@ -0,0 +1,528 @@
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use loom_defi_abi::balancer::{IBalancerPool, IBalancerStablePool, IBalancerWeightedPool, IVault};
+use loom_defi_abi::IERC20;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::state_readers::BalancerStateReader;
+use crate::virtual_impl::balancer::fixed_point::{div_down, div_up, mul_down, mul_up, ONE};
+use crate::virtual_impl::balancer::{BalancerStableMath, BalancerWeightedMath};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalancerPoolVariant {
+    Weighted,
+    Stable,
+    ComposableStable,
+}
+
+#[derive(Clone)]
+pub struct BalancerPool {
+    address: Address,
+    pool_id: B256,
+    vault: Address,
+    variant: BalancerPoolVariant,
+    // Tokens as registered in the vault, including BPT for composable stable pools
+    tokens: Vec<Address>,
+    weights: Vec<U256>,
+    scaling_factors: Vec<U256>,
+    bpt_index: Option<usize>,
+    fee: U256,
+    encoder: BalancerAbiSwapEncoder,
+}
+
+struct BalancerPoolState {
+    balances: Vec<U256>,
+    scaling_factors: Vec<U256>,
+    amp: U256,
+}
+
+impl BalancerPool {
+    pub fn get_pool_id(&self) -> B256 {
+        self.pool_id
+    }
+
+    pub fn get_vault(&self) -> Address {
+        self.vault
+    }
+
+    pub fn get_variant(&self) -> BalancerPoolVariant {
+        self.variant
+    }
+
+    /// Pool address is encoded in the first 20 bytes of the pool id
+    pub fn pool_address_from_id(pool_id: B256) -> Address {
+        Address::from_slice(&pool_id[0..20])
+    }
+
+    fn scaling_factor_for_decimals(decimals: U256) -> Result<U256> {
+        let decimals_difference = U256::from(18).checked_sub(decimals).ok_or_eyre("TOKEN_DECIMALS_NOT_SUPPORTED")?;
+        Ok(ONE * U256::from(10).pow(decimals_difference))
+    }
+
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        address: Address,
+    ) -> Result<Self> {
+        let pool = IBalancerPool::IBalancerPoolInstance::new(address, client.clone());
+
+        let pool_id: B256 = pool.getPoolId().call().await?._0;
+        let vault: Address = pool.getVault().call().await?._0;
+        let fee: U256 = pool.getSwapFeePercentage().call().await?._0;
+
+        let vault_contract = IVault::IVaultInstance::new(vault, client.clone());
+        let tokens: Vec<Address> = vault_contract.getPoolTokens(pool_id).call().await?.tokens;
+
+        let weighted_pool = IBalancerWeightedPool::IBalancerWeightedPoolInstance::new(address, client.clone());
+        let stable_pool = IBalancerStablePool::IBalancerStablePoolInstance::new(address, client.clone());
+
+        let (variant, weights, bpt_index) = match weighted_pool.getNormalizedWeights().call().await {
+            Ok(weights) => (BalancerPoolVariant::Weighted, weights._0, None),
+            Err(_) => {
+                stable_pool.getAmplificationParameter().call().await.map_err(|_| eyre!("BALANCER_POOL_TYPE_NOT_SUPPORTED"))?;
+                match stable_pool.getBptIndex().call().await {
+                    Ok(bpt_index) => (BalancerPoolVariant::ComposableStable, Vec::new(), Some(bpt_index._0.to::<usize>())),
+                    Err(_) => (BalancerPoolVariant::Stable, Vec::new(), None),
+                }
+            }
+        };
+
+        let scaling_factors = match pool.getScalingFactors().call().await {
+            Ok(scaling_factors) => scaling_factors._0,
+            Err(_) => {
+                let mut scaling_factors = Vec::new();
+                for token in tokens.iter() {
+                    let decimals = IERC20::IERC20Instance::new(*token, client.clone()).decimals().call().await?._0;
+                    scaling_factors.push(Self::scaling_factor_for_decimals(decimals)?);
+                }
+                scaling_factors
+            }
+        };
+
+        if scaling_factors.len() != tokens.len() || (variant == BalancerPoolVariant::Weighted && weights.len() != tokens.len()) {
+            return Err(eyre!("BALANCER_POOL_DATA_MISMATCH"));
+        }
+
+        debug!("fetch_pool_data {:?} {:?} {:?} {:?}", address, variant, tokens, fee);
+
+        Ok(BalancerPool {
+            address,
+            pool_id,
+            vault,
+            variant,
+            tokens,
+            weights,
+            scaling_factors,
+            bpt_index,
+            fee,
+            encoder: BalancerAbiSwapEncoder::new(address, pool_id, vault),
+        })
+    }
+
+    fn fetch_state(&self, state_db: &LoomDBType, env: Env) -> Result<BalancerPoolState> {
+        let (tokens, balances) = BalancerStateReader::get_pool_tokens(state_db, env.clone(), self.vault, self.pool_id)?;
+        if tokens != self.tokens {
+            return Err(eyre!("BALANCER_TOKENS_CHANGED"));
+        }
+
+        let (scaling_factors, amp) = match self.variant {
+            BalancerPoolVariant::Weighted => (self.scaling_factors.clone(), U256::ZERO),
+            BalancerPoolVariant::Stable => {
+                (self.scaling_factors.clone(), BalancerStateReader::get_amplification_parameter(state_db, env, self.address)?)
+            }
+            BalancerPoolVariant::ComposableStable => {
+                // Scaling factors include token rates which change over time
+                let scaling_factors = BalancerStateReader::get_scaling_factors(state_db, env.clone(), self.address)?;
+                (scaling_factors, BalancerStateReader::get_amplification_parameter(state_db, env, self.address)?)
+            }
+        };
+
+        Ok(BalancerPoolState { balances, scaling_factors, amp })
+    }
+
+    fn token_index(&self, token: &Address) -> Result<usize> {
+        self.tokens.iter().position(|t| t == token).ok_or_eyre("TOKEN_NOT_IN_POOL")
+    }
+
+    fn upscale_balances(state: &BalancerPoolState) -> Result<Vec<U256>> {
+        state
+            .balances
+            .iter()
+            .zip(state.scaling_factors.iter())
+            .map(|(balance, scaling_factor)| mul_down(*balance, *scaling_factor))
+            .collect()
+    }
+
+    // Composable stable pools do their math without the BPT entry
+    fn drop_bpt(&self, values: Vec<U256>, index_in: usize, index_out: usize) -> (Vec<U256>, usize, usize) {
+        match self.bpt_index {
+            Some(bpt_index) => {
+                let values = values.into_iter().enumerate().filter(|(i, _)| *i != bpt_index).map(|(_, v)| v).collect();
+                let skip = |i: usize| if i > bpt_index { i - 1 } else { i };
+                (values, skip(index_in), skip(index_out))
+            }
+            None => (values, index_in, index_out),
+        }
+    }
+
+    fn calc_out_given_in_upscaled(&self, state: &BalancerPoolState, index_in: usize, index_out: usize, amount_in: U256) -> Result<U256> {
+        let balances = Self::upscale_balances(state)?;
+        match self.variant {
+            BalancerPoolVariant::Weighted => BalancerWeightedMath::calc_out_given_in(
+                balances[index_in],
+                self.weights[index_in],
+                balances[index_out],
+                self.weights[index_out],
+                amount_in,
+            ),
+            BalancerPoolVariant::Stable | BalancerPoolVariant::ComposableStable => {
+                let (balances, index_in, index_out) = self.drop_bpt(balances, index_in, index_out);
+                let invariant = BalancerStableMath::calculate_invariant(state.amp, &balances)?;
+                BalancerStableMath::calc_out_given_in(state.amp, &balances, index_in, index_out, amount_in, invariant)
+            }
+        }
+    }
+
+    fn calc_in_given_out_upscaled(&self, state: &BalancerPoolState, index_in: usize, index_out: usize, amount_out: U256) -> Result<U256> {
+        let balances = Self::upscale_balances(state)?;
+        match self.variant {
+            BalancerPoolVariant::Weighted => BalancerWeightedMath::calc_in_given_out(
+                balances[index_in],
+                self.weights[index_in],
+                balances[index_out],
+                self.weights[index_out],
+                amount_out,
+            ),
+            BalancerPoolVariant::Stable | BalancerPoolVariant::ComposableStable => {
+                let (balances, index_in, index_out) = self.drop_bpt(balances, index_in, index_out);
+                let invariant = BalancerStableMath::calculate_invariant(state.amp, &balances)?;
+                BalancerStableMath::calc_in_given_out(state.amp, &balances, index_in, index_out, amount_out, invariant)
+            }
+        }
+    }
+
+    fn gas_used(&self) -> u64 {
+        match self.variant {
+            BalancerPoolVariant::Weighted => 120_000,
+            BalancerPoolVariant::Stable | BalancerPoolVariant::ComposableStable => 180_000,
+        }
+    }
+}
+
+impl Pool for BalancerPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::Balancer
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::Balancer
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_fee(&self) -> U256 {
+        self.fee
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        self.tokens.iter().enumerate().filter(|(i, _)| Some(*i) != self.bpt_index).map(|(_, t)| *t).collect()
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        let tokens = self.get_tokens();
+        let mut directions = Vec::new();
+        for token_from in tokens.iter() {
+            for token_to in tokens.iter() {
+                if token_from != token_to {
+                    directions.push((*token_from, *token_to));
+                }
+            }
+        }
+        directions
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let index_in = self.token_index(token_address_from)?;
+        let index_out = self.token_index(token_address_to)?;
+        let state = self.fetch_state(state_db, env)?;
+
+        let amount_in = in_amount.checked_sub(mul_up(in_amount, self.fee)?).ok_or_eyre("FEE_EXCEEDS_AMOUNT")?;
+        let amount_in = mul_down(amount_in, state.scaling_factors[index_in])?;
+
+        let amount_out = self.calc_out_given_in_upscaled(&state, index_in, index_out, amount_in)?;
+        let amount_out = div_down(amount_out, state.scaling_factors[index_out])?;
+
+        if amount_out.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((amount_out, self.gas_used()))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let index_in = self.token_index(token_address_from)?;
+        let index_out = self.token_index(token_address_to)?;
+        let state = self.fetch_state(state_db, env)?;
+
+        let amount_out = mul_down(out_amount, state.scaling_factors[index_out])?;
+
+        let amount_in = self.calc_in_given_out_upscaled(&state, index_in, index_out, amount_out)?;
+        let amount_in = div_up(amount_in, state.scaling_factors[index_in])?;
+        let amount_in = div_up(amount_in, ONE - self.fee)?;
+
+        if amount_in.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((amount_in, self.gas_used()))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+
+        state_required
+            .add_call(self.vault, IVault::getPoolTokensCall { poolId: self.pool_id }.abi_encode())
+            .add_call(self.address, IBalancerPool::getSwapFeePercentageCall {}.abi_encode());
+
+        match self.variant {
+            BalancerPoolVariant::Weighted => {
+                state_required.add_call(self.address, IBalancerWeightedPool::getNormalizedWeightsCall {}.abi_encode());
+            }
+            BalancerPoolVariant::Stable => {
+                state_required.add_call(self.address, IBalancerStablePool::getAmplificationParameterCall {}.abi_encode());
+            }
+            BalancerPoolVariant::ComposableStable => {
+                state_required
+                    .add_call(self.address, IBalancerStablePool::getAmplificationParameterCall {}.abi_encode())
+                    .add_call(self.address, IBalancerPool::getScalingFactorsCall {}.abi_encode());
+            }
+        }
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: self.vault }.abi_encode());
+        }
+
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BalancerAbiSwapEncoder {
+    pool_address: Address,
+    pool_id: B256,
+    vault: Address,
+}
+
+impl BalancerAbiSwapEncoder {
+    pub fn new(pool_address: Address, pool_id: B256, vault: Address) -> Self {
+        Self { pool_address, pool_id, vault }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn encode_swap(
+        &self,
+        kind: IVault::SwapKind,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        limit: U256,
+        sender: Address,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Bytes {
+        // Swap is executed by the vault, input tokens are pulled from the sender which has to be the caller of the vault
+        let swap_call = IVault::swapCall {
+            singleSwap: IVault::SingleSwap {
+                poolId: self.pool_id,
+                kind,
+                assetIn: token_from_address,
+                assetOut: token_to_address,
+                amount,
+                userData: payload,
+            },
+            funds: IVault::FundManagement { sender, fromInternalBalance: false, recipient, toInternalBalance: false },
+            limit,
+            deadline: U256::MAX,
+        };
+
+        Bytes::from(swap_call.abi_encode())
+    }
+}
+
+impl AbiSwapEncoder for BalancerAbiSwapEncoder {
+    // The vault takes the input tokens from the sender, which is not known without the caller
+    fn encode_swap_in_amount_provided(
+        &self,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount: U256,
+        _recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        Err(eyre!("BALANCER_SENDER_REQUIRED"))
+    }
+
+    fn encode_swap_in_amount_provided_from(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        sender: Address,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        Ok(self.encode_swap(
+            IVault::SwapKind::GIVEN_IN,
+            token_from_address,
+            token_to_address,
+            amount,
+            U256::ZERO,
+            sender,
+            recipient,
+            payload,
+        ))
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Allowance
+    }
+
+    fn swap_contract_address(&self) -> Option<Address> {
+        Some(self.vault)
+    }
+
+    // SingleSwap.amount is located after the struct offset, FundManagement, limit and deadline
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x164)
+    }
+
+    fn swap_in_amount_return_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x0)
+    }
+}
+
+// The test are using the deployed contracts for comparison to allow to adjust the test easily
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::BlockNumber;
+    use alloy_rpc_types::BlockId;
+    use loom_defi_address_book::{BalancerPoolAddress, PeripheryAddress};
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use std::env;
+
+    const POOL_ADDRESSES: [Address; 2] = [BalancerPoolAddress::BAL_WETH_80_20, BalancerPoolAddress::WSTETH_WETH_STABLE];
+    const BLOCK_NUMBER: u64 = 20935488u64;
+
+    #[test]
+    fn test_pool_address_from_id() {
+        let pool_id: B256 = "0x5c6ee304399dbdb9c8ef030ab642b10820db8f56000200000000000000000014".parse().unwrap();
+        assert_eq!(BalancerPool::pool_address_from_id(pool_id), BalancerPoolAddress::BAL_WETH_80_20);
+    }
+
+    #[test]
+    fn test_swap_amount_offset() {
+        let encoder = BalancerAbiSwapEncoder::new(Address::ZERO, B256::ZERO, PeripheryAddress::BALANCER_VAULT);
+        let amount = U256::from(0x1234567u64);
+        let call_data = encoder
+            .encode_swap_in_amount_provided_from(Address::ZERO, Address::ZERO, amount, Address::ZERO, Address::ZERO, Bytes::new())
+            .unwrap();
+        let offset = encoder.swap_in_amount_offset(Address::ZERO, Address::ZERO).unwrap() as usize;
+        assert_eq!(U256::from_be_slice(&call_data[offset..offset + 0x20]), amount);
+    }
+
+    #[test]
+    fn test_swap_funds() {
+        let encoder = BalancerAbiSwapEncoder::new(Address::ZERO, B256::ZERO, PeripheryAddress::BALANCER_VAULT);
+        let (sender, recipient) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let call_data = encoder
+            .encode_swap_in_amount_provided_from(Address::ZERO, Address::ZERO, U256::from(1), sender, recipient, Bytes::new())
+            .unwrap();
+
+        let swap_call = IVault::swapCall::abi_decode(&call_data, true).unwrap();
+        assert_eq!(swap_call.funds.sender, sender);
+        assert_eq!(swap_call.funds.recipient, recipient);
+        assert_eq!(encoder.swap_contract_address(), Some(PeripheryAddress::BALANCER_VAULT));
+        assert!(encoder.encode_swap_in_amount_provided(Address::ZERO, Address::ZERO, U256::from(1), recipient, Bytes::new()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_calculate_out_amount() -> Result<()> {
+        // Verify that the calculated out amount is the same as the vault query result
+        let node_url = env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(BLOCK_NUMBER)).await?;
+
+        for pool_address in POOL_ADDRESSES {
+            let pool = BalancerPool::fetch_pool_data(client.clone(), pool_address).await?;
+            let state_required = pool.get_state_required()?;
+            let state_update = RequiredStateReader::fetch_calls_and_slots(client.clone(), state_required, Some(BLOCK_NUMBER)).await?;
+
+            let mut state_db = LoomDBType::default();
+            state_db.apply_geth_update(state_update);
+
+            let tokens = pool.get_tokens();
+            let (token_in, token_out) = (tokens[0], tokens[1]);
+            let amount_in = U256::from(10u64).pow(U256::from(18));
+
+            let vault = IVault::new(pool.get_vault(), client.clone());
+            let funds = IVault::FundManagement {
+                sender: Address::ZERO,
+                fromInternalBalance: false,
+                recipient: Address::ZERO,
+                toInternalBalance: false,
+            };
+            let swap_step = IVault::BatchSwapStep {
+                poolId: pool.get_pool_id(),
+                assetInIndex: U256::ZERO,
+                assetOutIndex: U256::from(1),
+                amount: amount_in,
+                userData: Bytes::new(),
+            };
+            let deltas = vault
+                .queryBatchSwap(IVault::SwapKind::GIVEN_IN, vec![swap_step], vec![token_in, token_out], funds)
+                .call()
+                .block(BlockId::from(BLOCK_NUMBER))
+                .await?
+                .assetDeltas;
+            let contract_amount_out = deltas[1].unsigned_abs();
+
+            let (amount_out, gas_used) = pool.calculate_out_amount(&state_db, Env::default(), &token_in, &token_out, amount_in)?;
+
+            assert_eq!(amount_out, contract_amount_out, "{}", format!("Missmatch for pool={:?}, amount_in={}", pool_address, amount_in));
+            assert!(gas_used > 100_000);
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 
-use alloy_primitives::{address, Address, Bytes, U256};
+use alloy_primitives::{address, Address, Bytes, B256, U256};
 use alloy_provider::{Network, Provider};
 use alloy_sol_types::SolCall;
 use alloy_transport::Transport;
 use eyre::{eyre, Result};
+use loom_defi_abi::curve::{ICurveCryptoSwap2Params, ICurveCryptoSwapParams, ICurveStableSwapParams};
 use loom_defi_abi::IERC20;
 use loom_defi_address_book::TokenAddress;
 use loom_evm_db::LoomDBType;
@@ -12,9 +14,14 @@ use loom_evm_utils::evm::evm_call;
 use loom_types_entities::required_state::RequiredState;
 use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
 use revm::primitives::Env;
-use tracing::error;
+use revm::DatabaseRef;
+use tracing::{debug, error};
 
 use crate::protocols::{CurveCommonContract, CurveContract, CurveProtocol};
+use crate::virtual_impl::curve::{CurveCodeHashRegistry, CurveCodeHashVerdict, CurveNativeState, CurveNativeVariant, CurvePoolVirtual};
+
+// Native states kept per pool, the cache is cleared when full
+const MAX_NATIVE_STATES: usize = 16;
 
 pub struct CurvePool<P, T, N>
 where
@@ -31,6 +38,9 @@ where
     abi_encoder: Arc<CurveAbiSwapEncoder<P, T, N>>,
     is_meta: bool,
     is_native: bool,
+    native_variant: Option<CurveNativeVariant>,
+    precisions: Vec<U256>,
+    native_states: Arc<RwLock<HashMap<B256, Arc<CurveNativeState>>>>,
 }
 
 impl<P, T, N> Clone for CurvePool<P, T, N>
@@ -50,6 +60,9 @@ where
             abi_encoder: Arc::clone(&self.abi_encoder),
             is_meta: self.is_meta,
             is_native: self.is_native,
+            native_variant: self.native_variant,
+            precisions: self.precisions.clone(),
+            native_states: Arc::clone(&self.native_states),
         }
     }
 }
@@ -93,6 +106,124 @@ where
         self.pool_contract.get_dy(i, j, amount_in).await
     }
 
+    async fn fetch_native_variant(client: P, pool_contract: &CurveContract<P, T, N>) -> Option<CurveNativeVariant> {
+        match pool_contract {
+            CurveContract::I128_2(_) | CurveContract::I128_2To(_) | CurveContract::I128_3(_) | CurveContract::I128_4(_) => {
+                // Pools exposing A_precise keep A multiplied by A_PRECISION = 100
+                let a_precise = ICurveStableSwapParams::new(pool_contract.get_address(), client).A_precise().call().await;
+                Some(CurveNativeVariant::StableSwap { a_precision: if a_precise.is_ok() { 100 } else { 1 } })
+            }
+            CurveContract::I128_2ToMeta(_) => None,
+            _ => Some(CurveNativeVariant::CryptoSwap),
+        }
+    }
+
+    async fn fetch_precisions(client: P, tokens: &[Address]) -> Result<Vec<U256>> {
+        let mut precisions = Vec::new();
+        for token in tokens.iter() {
+            let decimals = IERC20::new(*token, client.clone()).decimals().call().await?._0;
+            let exp = U256::from(18).checked_sub(decimals).ok_or(eyre!("BAD_DECIMALS"))?;
+            precisions.push(U256::from(10).pow(exp));
+        }
+        Ok(precisions)
+    }
+
+    // Coin indexes of a plain exchange that can be evaluated natively
+    fn native_coin_indexes(&self, token_address_from: &Address, token_address_to: &Address) -> Option<(CurveNativeVariant, usize, usize)> {
+        if self.is_meta || self.precisions.len() != self.tokens.len() {
+            return None;
+        }
+        let variant = self.native_variant?;
+        let i = self.get_coin_idx(*token_address_from).ok()?;
+        let j = self.get_coin_idx(*token_address_to).ok()?;
+        Some((variant, i as usize, j as usize))
+    }
+
+    // Pool getters are called once per pool storage state and block, later quotes of the same state use the cached result
+    fn native_state(&self, state_db: &LoomDBType, env: Env, variant: CurveNativeVariant) -> Result<Arc<CurveNativeState>> {
+        let state_key = CurvePoolVirtual::state_key(state_db, &env, self.address)?;
+        if let Some(state) = self.native_states.read().ok().and_then(|native_states| native_states.get(&state_key).cloned()) {
+            return Ok(state);
+        }
+
+        let state = Arc::new(CurvePoolVirtual::fetch_state(state_db, env, self.address, variant, self.precisions.len())?);
+        if let Ok(mut native_states) = self.native_states.write() {
+            if native_states.len() >= MAX_NATIVE_STATES {
+                native_states.clear();
+            }
+            native_states.insert(state_key, state.clone());
+        }
+        Ok(state)
+    }
+
+    fn calculate_out_amount_native(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        variant: CurveNativeVariant,
+        i: usize,
+        j: usize,
+        in_amount: U256,
+    ) -> Result<U256> {
+        let state = self.native_state(state_db, env, variant)?;
+        let ret = CurvePoolVirtual::get_dy(&state, variant, &self.precisions, i, j, in_amount)?;
+        if ret.is_zero() {
+            Err(eyre!("ZERO_OUT_AMOUNT"))
+        } else {
+            Ok(ret - U256::from(1))
+        }
+    }
+
+    fn calculate_out_amount_evm(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64)> {
+        let mut env = env;
+        env.tx.gas_limit = 500_000;
+
+        let call_data = if self.is_meta {
+            let i: Result<u32> = self.get_coin_idx(*token_address_from);
+            let j: Result<u32> = self.get_coin_idx(*token_address_to);
+            if i.is_ok() && j.is_ok() {
+                self.pool_contract.get_dy_call_data(i.unwrap(), j.unwrap(), in_amount)?
+            } else {
+                let i: u32 = self.get_meta_coin_idx(*token_address_from)?;
+                let j: u32 = self.get_meta_coin_idx(*token_address_to)?;
+                self.pool_contract.get_dy_underlying_call_data(i, j, in_amount)?
+            }
+        } else if let Some(lp_token) = self.lp_token {
+            if *token_address_from == lp_token {
+                let i: u32 = self.get_coin_idx(*token_address_to)?;
+                self.pool_contract.calc_withdraw_one_coin_call_data(i, in_amount)?
+            } else if *token_address_to == lp_token {
+                let i: u32 = self.get_coin_idx(*token_address_from)?;
+                self.pool_contract.calc_token_amount_call_data(i, in_amount)?
+            } else {
+                let i: u32 = self.get_coin_idx(*token_address_from)?;
+                let j: u32 = self.get_coin_idx(*token_address_to)?;
+                self.pool_contract.get_dy_call_data(i, j, in_amount)?
+            }
+        } else {
+            let i: u32 = self.get_coin_idx(*token_address_from)?;
+            let j: u32 = self.get_coin_idx(*token_address_to)?;
+            self.pool_contract.get_dy_call_data(i, j, in_amount)?
+        };
+
+        let (value, gas_used) = evm_call(state_db, env, self.get_address(), call_data.to_vec())?;
+
+        let ret = if value.len() > 32 { U256::from_be_slice(&value[0..32]) } else { U256::from_be_slice(&value[0..]) };
+
+        if ret.is_zero() {
+            Err(eyre!("ZERO_OUT_AMOUNT"))
+        } else {
+            Ok((ret - U256::from(1), gas_used))
+        }
+    }
+
     pub async fn fetch_pool_data(client: P, pool_contract: CurveContract<P, T, N>) -> Result<Self> {
         let pool_contract = Arc::new(pool_contract);
 
@@ -119,6 +250,12 @@ where
 
         let balances = CurveCommonContract::balances(client.clone(), pool_contract.get_address()).await?;
 
+        let native_variant = Self::fetch_native_variant(client.clone(), pool_contract.as_ref()).await;
+        let precisions = match native_variant {
+            Some(_) => Self::fetch_precisions(client.clone(), &tokens).await.unwrap_or_default(),
+            None => vec![],
+        };
+
         let abi_encoder = Arc::new(CurveAbiSwapEncoder::new(
             pool_contract.get_address(),
             tokens.clone(),
@@ -139,6 +276,9 @@ where
             lp_token,
             is_meta,
             is_native,
+            native_variant,
+            precisions,
+            native_states: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 }
@@ -203,45 +343,38 @@ where
         token_address_to: &Address,
         in_amount: U256,
     ) -> Result<(U256, u64)> {
-        let mut env = env;
-        env.tx.gas_limit = 500_000;
-
-        let call_data = if self.is_meta {
-            let i: Result<u32> = self.get_coin_idx(*token_address_from);
-            let j: Result<u32> = self.get_coin_idx(*token_address_to);
-            if i.is_ok() && j.is_ok() {
-                self.pool_contract.get_dy_call_data(i.unwrap(), j.unwrap(), in_amount)?
-            } else {
-                let i: u32 = self.get_meta_coin_idx(*token_address_from)?;
-                let j: u32 = self.get_meta_coin_idx(*token_address_to)?;
-                self.pool_contract.get_dy_underlying_call_data(i, j, in_amount)?
-            }
-        } else if let Some(lp_token) = self.lp_token {
-            if *token_address_from == lp_token {
-                let i: u32 = self.get_coin_idx(*token_address_to)?;
-                self.pool_contract.calc_withdraw_one_coin_call_data(i, in_amount)?
-            } else if *token_address_to == lp_token {
-                let i: u32 = self.get_coin_idx(*token_address_from)?;
-                self.pool_contract.calc_token_amount_call_data(i, in_amount)?
-            } else {
-                let i: u32 = self.get_coin_idx(*token_address_from)?;
-                let j: u32 = self.get_coin_idx(*token_address_to)?;
-                self.pool_contract.get_dy_call_data(i, j, in_amount)?
-            }
-        } else {
-            let i: u32 = self.get_coin_idx(*token_address_from)?;
-            let j: u32 = self.get_coin_idx(*token_address_to)?;
-            self.pool_contract.get_dy_call_data(i, j, in_amount)?
+        let Some((variant, i, j)) = self.native_coin_indexes(token_address_from, token_address_to) else {
+            return self.calculate_out_amount_evm(state_db, env, token_address_from, token_address_to, in_amount);
         };
 
-        let (value, gas_used) = evm_call(state_db, env, self.get_address(), call_data.to_vec())?;
+        let code_hash = state_db.basic_ref(self.address)?.map(|account| account.code_hash);
 
-        let ret = if value.len() > 32 { U256::from_be_slice(&value[0..32]) } else { U256::from_be_slice(&value[0..]) };
+        let Some(code_hash) = code_hash else {
+            return self.calculate_out_amount_evm(state_db, env, token_address_from, token_address_to, in_amount);
+        };
 
-        if ret.is_zero() {
-            Err(eyre!("ZERO_OUT_AMOUNT"))
-        } else {
-            Ok((ret - U256::from(1), gas_used))
+        match CurveCodeHashRegistry::verdict(&code_hash) {
+            CurveCodeHashVerdict::Native => match self.calculate_out_amount_native(state_db, env.clone(), variant, i, j, in_amount) {
+                Ok(out_amount) => Ok((out_amount, variant.gas_estimate())),
+                Err(e) => {
+                    debug!("Native curve calculation failed {} : {}", self.address, e);
+                    self.calculate_out_amount_evm(state_db, env, token_address_from, token_address_to, in_amount)
+                }
+            },
+            CurveCodeHashVerdict::Evm => self.calculate_out_amount_evm(state_db, env, token_address_from, token_address_to, in_amount),
+            CurveCodeHashVerdict::Verify => {
+                // Keep EVM result and sample native math against it
+                let evm_result = self.calculate_out_amount_evm(state_db, env.clone(), token_address_from, token_address_to, in_amount);
+                if let Ok((evm_out_amount, _)) = &evm_result {
+                    let native_result = self.calculate_out_amount_native(state_db, env, variant, i, j, in_amount);
+                    let matched = matches!(native_result, Ok(native_out_amount) if native_out_amount == *evm_out_amount);
+                    if !matched {
+                        debug!("Native curve calculation mismatch {} {} : {:?} {}", self.address, code_hash, native_result, evm_out_amount);
+                    }
+                    CurveCodeHashRegistry::record(&code_hash, matched);
+                }
+                evm_result
+            }
         }
     }
 
@@ -346,6 +479,34 @@ where
                 }
             }
         }
+        match self.native_variant {
+            Some(CurveNativeVariant::StableSwap { a_precision }) => {
+                state_reader.add_call(self.get_address(), ICurveStableSwapParams::ACall {}.abi_encode());
+                if a_precision > 1 {
+                    state_reader.add_call(self.get_address(), ICurveStableSwapParams::A_preciseCall {}.abi_encode());
+                }
+                state_reader.add_call(self.get_address(), ICurveStableSwapParams::feeCall {}.abi_encode());
+            }
+            Some(CurveNativeVariant::CryptoSwap) => {
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::ACall {}.abi_encode());
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::gammaCall {}.abi_encode());
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::DCall {}.abi_encode());
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::mid_feeCall {}.abi_encode());
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::out_feeCall {}.abi_encode());
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::fee_gammaCall {}.abi_encode());
+                state_reader.add_call(self.get_address(), ICurveCryptoSwapParams::future_A_gamma_timeCall {}.abi_encode());
+                if self.tokens.len() == 2 {
+                    state_reader.add_call(self.get_address(), ICurveCryptoSwap2Params::price_scaleCall {}.abi_encode());
+                } else {
+                    for k in 0..self.tokens.len() - 1 {
+                        state_reader
+                            .add_call(self.get_address(), ICurveCryptoSwapParams::price_scaleCall { _0: U256::from(k) }.abi_encode());
+                    }
+                }
+            }
+            None => {}
+        }
+
         state_reader.add_slot_range(self.get_address(), U256::from(0), 0x20);
 
         for token_address in self.get_tokens() {
@@ -520,9 +681,11 @@ mod tests {
     use loom_node_debug_provider::AnvilDebugProviderFactory;
     use loom_types_entities::required_state::RequiredStateReader;
     use loom_types_entities::{MarketState, Pool};
+    use rand::Rng;
     use tracing::debug;
 
     use crate::protocols::CurveProtocol;
+    use crate::virtual_impl::curve::CurvePoolVirtual;
     use crate::CurvePool;
 
     #[tokio::test]
@@ -653,4 +816,70 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_native_calculation() -> Result<()> {
+        let _ = env_logger::try_init_from_env(EnvLog::default().default_filter_or("info,alloy_rpc_client=off"));
+
+        let node_url = std::env::var("MAINNET_WS")?;
+
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, 20045799).await?;
+
+        let mut market_state = MarketState::new(LoomDBType::new());
+
+        let curve_contracts = CurveProtocol::get_contracts_vec(client.clone());
+
+        for curve_contract in curve_contracts.into_iter() {
+            let pool = CurvePool::fetch_pool_data(client.clone(), curve_contract).await.unwrap();
+            let Some(variant) = pool.native_variant else {
+                continue;
+            };
+            let state_required = pool.get_state_required().unwrap();
+            let state_required = RequiredStateReader::fetch_calls_and_slots(client.clone(), state_required, None).await.unwrap();
+            market_state.state_db.apply_geth_update(state_required);
+
+            let block_header = client.get_block_by_number(BlockNumberOrTag::Latest, false).await.unwrap().unwrap().header;
+            let mut evm_env = revm::primitives::Env::default();
+            evm_env.block.number = U256::from(block_header.number);
+            evm_env.block.timestamp = U256::from(block_header.timestamp);
+
+            let tokens = pool.tokens.clone();
+            for i in 0..tokens.len() {
+                for j in 0..tokens.len() {
+                    if i == j {
+                        continue;
+                    }
+                    for _ in 0..5 {
+                        // Random amount up to 5% of the pool balance
+                        let in_amount = pool.balances[i] * U256::from(rand::thread_rng().gen_range(1..500u64)) / U256::from(10000);
+                        if in_amount.is_zero() {
+                            continue;
+                        }
+
+                        let native_out_amount = CurvePoolVirtual::fetch_state(
+                            &market_state.state_db,
+                            evm_env.clone(),
+                            pool.get_address(),
+                            variant,
+                            pool.precisions.len(),
+                        )
+                        .and_then(|state| CurvePoolVirtual::get_dy(&state, variant, &pool.precisions, i, j, in_amount));
+                        let out_amount_fetched = pool.fetch_out_amount(tokens[i], tokens[j], in_amount).await.unwrap();
+                        debug!(
+                            "Native {:?} {:?} {} -> {} : {} -> {:?} fetched {}",
+                            pool.get_address(),
+                            variant,
+                            tokens[i],
+                            tokens[j],
+                            in_amount,
+                            native_out_amount,
+                            out_amount_fetched
+                        );
+                        assert_eq!(native_out_amount.unwrap(), out_amount_fetched);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,180 @@
+use std::ops::{BitAnd, Shl, Shr};
+
+use alloy_primitives::{Address, I256, U256};
+use eyre::Result;
+use lazy_static::lazy_static;
+use tracing::trace;
+
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::remv_db_direct_access::{calc_hashmap_cell, try_read_cell};
+
+pub struct MaverickDBReader {}
+
+lazy_static! {
+    static ref BITS128MASK: U256 = U256::from(1).shl(128) - U256::from(1);
+    static ref BITS32MASK: U256 = U256::from(1).shl(32) - U256::from(1);
+    static ref BITS8MASK: U256 = U256::from(0xFF);
+}
+
+// Maverick V1 pool storage layout
+// slot 0 : binBalanceA, binBalanceB
+// slot 1 : twa
+// slot 2 : mapping(uint256 => mapping(uint128 => uint256)) balances
+// slot 3 : mapping(uint128 => BinState) bins
+// slot 4 : mapping(int32 => uint256) binMap
+// slot 5 : mapping(int32 => mapping(uint256 => uint128)) binPositions
+// slot 6 : State state
+const BINS_SLOT: u64 = 3;
+const BIN_MAP_SLOT: u64 = 4;
+const BIN_POSITIONS_SLOT: u64 = 5;
+const STATE_SLOT: u64 = 6;
+
+pub const NUMBER_OF_KINDS: u8 = 4;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MaverickBin {
+    pub reserve_a: U256,
+    pub reserve_b: U256,
+    pub merge_id: u128,
+    pub total_supply: U256,
+    pub kind: u8,
+    pub lower_tick: i32,
+}
+
+impl MaverickDBReader {
+    fn int32_key(value: i32) -> Result<U256> {
+        Ok(U256::from_be_bytes(I256::try_from(value)?.to_be_bytes::<32>()))
+    }
+
+    pub fn state_slot() -> U256 {
+        U256::from(STATE_SLOT)
+    }
+
+    pub fn bin_slot(bin_id: u128) -> U256 {
+        calc_hashmap_cell(U256::from(BINS_SLOT), U256::from(bin_id))
+    }
+
+    /// Every tick has a bit for each bin kind, so one map word covers 64 ticks.
+    pub fn bin_map_pointer(tick: i32, kind: u8) -> (i32, usize) {
+        let position = tick * NUMBER_OF_KINDS as i32 + kind as i32;
+        (position >> 8, (position & 0xFF) as usize)
+    }
+
+    pub fn bin_map_slot(map_index: i32) -> Result<U256> {
+        Ok(calc_hashmap_cell(U256::from(BIN_MAP_SLOT), Self::int32_key(map_index)?))
+    }
+
+    pub fn bin_position_slot(tick: i32, kind: u8) -> Result<U256> {
+        let tick_slot = calc_hashmap_cell(U256::from(BIN_POSITIONS_SLOT), Self::int32_key(tick)?);
+        Ok(calc_hashmap_cell(tick_slot, U256::from(kind)))
+    }
+
+    pub fn parse_active_tick(cell: U256) -> i32 {
+        let tick: u32 = cell.bitand(*BITS32MASK).to();
+        tick as i32
+    }
+
+    pub fn parse_bin(cells: [U256; 3]) -> MaverickBin {
+        let lower_tick: u32 = Shr::<U256>::shr(cells[2], U256::from(136)).bitand(*BITS32MASK).to();
+        MaverickBin {
+            reserve_a: cells[0].bitand(*BITS128MASK),
+            reserve_b: Shr::<U256>::shr(cells[0], U256::from(128)),
+            merge_id: Shr::<U256>::shr(cells[1], U256::from(128)).to(),
+            total_supply: cells[2].bitand(*BITS128MASK),
+            kind: Shr::<U256>::shr(cells[2], U256::from(128)).bitand(*BITS8MASK).to(),
+            lower_tick: lower_tick as i32,
+        }
+    }
+
+    pub fn active_tick(db: &LoomDBType, address: Address) -> Result<i32> {
+        let cell = try_read_cell(db, &address, &Self::state_slot())?;
+        Ok(Self::parse_active_tick(cell))
+    }
+
+    pub fn bin_map(db: &LoomDBType, address: Address, map_index: i32) -> Result<U256> {
+        let cell = try_read_cell(db, &address, &Self::bin_map_slot(map_index)?)?;
+        trace!("binMap {address} {map_index} {cell}");
+        Ok(cell)
+    }
+
+    /// Bitmask of bin kinds present at the tick
+    pub fn tick_kinds(db: &LoomDBType, address: Address, tick: i32) -> Result<u8> {
+        let (map_index, bit) = Self::bin_map_pointer(tick, 0);
+        let word = Self::bin_map(db, address, map_index)?;
+        let kinds: u8 = Shr::<U256>::shr(word, U256::from(bit)).bitand(U256::from(0xF)).to();
+        Ok(kinds)
+    }
+
+    pub fn bin_position(db: &LoomDBType, address: Address, tick: i32, kind: u8) -> Result<u128> {
+        let cell = try_read_cell(db, &address, &Self::bin_position_slot(tick, kind)?)?;
+        Ok(cell.bitand(*BITS128MASK).to())
+    }
+
+    pub fn bin(db: &LoomDBType, address: Address, bin_id: u128) -> Result<MaverickBin> {
+        let bin_slot = Self::bin_slot(bin_id);
+        let cells = [
+            try_read_cell(db, &address, &bin_slot)?,
+            try_read_cell(db, &address, &(bin_slot + U256::from(1)))?,
+            try_read_cell(db, &address, &(bin_slot + U256::from(2)))?,
+        ];
+        let bin = Self::parse_bin(cells);
+        trace!("bin {address} {bin_id} {:?}", bin);
+        Ok(bin)
+    }
+
+    /// Sum of reserves of all active bins at the tick and the number of the bins
+    pub fn tick_reserves(db: &LoomDBType, address: Address, tick: i32) -> Result<(U256, U256, u64)> {
+        let kinds = Self::tick_kinds(db, address, tick)?;
+        let mut reserve_a = U256::ZERO;
+        let mut reserve_b = U256::ZERO;
+        let mut bins = 0u64;
+
+        for kind in 0..NUMBER_OF_KINDS {
+            if kinds & (1 << kind) == 0 {
+                continue;
+            }
+            let bin_id = Self::bin_position(db, address, tick, kind)?;
+            if bin_id == 0 {
+                continue;
+            }
+            let bin = Self::bin(db, address, bin_id)?;
+            // Merged bins keep no reserves of their own, they are accounted in the merge target
+            if bin.merge_id != 0 {
+                continue;
+            }
+            reserve_a += bin.reserve_a;
+            reserve_b += bin.reserve_b;
+            bins += 1;
+        }
+
+        Ok((reserve_a, reserve_b, bins))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bin_map_pointer() {
+        assert_eq!(MaverickDBReader::bin_map_pointer(0, 0), (0, 0));
+        assert_eq!(MaverickDBReader::bin_map_pointer(1, 3), (0, 7));
+        assert_eq!(MaverickDBReader::bin_map_pointer(64, 0), (1, 0));
+        assert_eq!(MaverickDBReader::bin_map_pointer(-1, 0), (-1, 252));
+    }
+
+    #[test]
+    fn test_parse_bin() {
+        let reserve_a = U256::from(1000u64);
+        let reserve_b = U256::from(2000u64);
+        let lower_tick = U256::from(0xFFFFFFFEu32); // -2
+        let cells = [
+            reserve_a | reserve_b.shl(128),
+            U256::from(5u64).shl(128),
+            U256::from(3000u64) | U256::from(2u8).shl(128) | lower_tick.shl(136),
+        ];
+
+        let bin = MaverickDBReader::parse_bin(cells);
+        assert_eq!(bin, MaverickBin { reserve_a, reserve_b, merge_id: 5, total_supply: U256::from(3000u64), kind: 2, lower_tick: -2 });
+    }
+}
@@ -1,3 +1,7 @@
+pub use maverick::{MaverickBin, MaverickDBReader, NUMBER_OF_KINDS};
 pub use uniswapv3::UniswapV3DBReader;
+pub use uniswapv4::{UniswapV4DBReader, UniswapV4Slot0};
 
+mod maverick;
 mod uniswapv3;
+mod uniswapv4;
@@ -0,0 +1,107 @@
+use std::ops::{BitAnd, Shl, Shr};
+
+use alloy_primitives::{Address, Signed, Uint, B256, I256, U256};
+use eyre::Result;
+use lazy_static::lazy_static;
+use tracing::trace;
+
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::remv_db_direct_access::{calc_hashmap_cell, try_read_cell};
+
+pub struct UniswapV4DBReader {}
+
+lazy_static! {
+    static ref BITS160MASK: U256 = U256::from(1).shl(160) - U256::from(1);
+    static ref BITS24MASK: U256 = U256::from(1).shl(24) - U256::from(1);
+}
+
+// PoolManager keeps `mapping(PoolId => Pool.State) _pools` at slot 6
+const POOLS_SLOT: u64 = 6;
+const LIQUIDITY_OFFSET: u64 = 3;
+const TICKS_OFFSET: u64 = 4;
+const TICK_BITMAP_OFFSET: u64 = 5;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UniswapV4Slot0 {
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+    pub protocol_fee: u32,
+    pub lp_fee: u32,
+}
+
+impl UniswapV4DBReader {
+    pub fn pool_state_slot(pool_id: B256) -> U256 {
+        calc_hashmap_cell(U256::from(POOLS_SLOT), U256::from_be_bytes(pool_id.0))
+    }
+
+    pub fn liquidity_slot(pool_id: B256) -> U256 {
+        Self::pool_state_slot(pool_id) + U256::from(LIQUIDITY_OFFSET)
+    }
+
+    pub fn tick_info_slot(pool_id: B256, tick: i32) -> Result<U256> {
+        let ticks_slot = Self::pool_state_slot(pool_id) + U256::from(TICKS_OFFSET);
+        Ok(calc_hashmap_cell(ticks_slot, U256::from_be_bytes(I256::try_from(tick)?.to_be_bytes::<32>())))
+    }
+
+    pub fn tick_bitmap_slot(pool_id: B256, word: i16) -> Result<U256> {
+        let tick_bitmap_slot = Self::pool_state_slot(pool_id) + U256::from(TICK_BITMAP_OFFSET);
+        Ok(calc_hashmap_cell(tick_bitmap_slot, U256::from_be_bytes(I256::try_from(word)?.to_be_bytes::<32>())))
+    }
+
+    pub fn parse_slot0(cell: U256) -> Result<UniswapV4Slot0> {
+        let tick: Uint<24, 1> = ((Shr::<U256>::shr(cell, U256::from(160))) & *BITS24MASK).to();
+        let tick: Signed<24, 1> = Signed::<24, 1>::from_raw(tick);
+
+        Ok(UniswapV4Slot0 {
+            sqrt_price_x96: cell.bitand(*BITS160MASK),
+            tick: tick.as_i32(),
+            protocol_fee: ((Shr::<U256>::shr(cell, U256::from(160 + 24))) & *BITS24MASK).to(),
+            lp_fee: ((Shr::<U256>::shr(cell, U256::from(160 + 24 + 24))) & *BITS24MASK).to(),
+        })
+    }
+
+    pub fn slot0(db: &LoomDBType, pool_manager: Address, pool_id: B256) -> Result<UniswapV4Slot0> {
+        let cell = try_read_cell(db, &pool_manager, &Self::pool_state_slot(pool_id))?;
+        Self::parse_slot0(cell)
+    }
+
+    pub fn liquidity(db: &LoomDBType, pool_manager: Address, pool_id: B256) -> Result<u128> {
+        let cell = try_read_cell(db, &pool_manager, &Self::liquidity_slot(pool_id))?;
+        let cell: u128 = cell.saturating_to();
+        Ok(cell)
+    }
+
+    pub fn ticks_liquidity_net(db: &LoomDBType, pool_manager: Address, pool_id: B256, tick: i32) -> Result<i128> {
+        let cell = try_read_cell(db, &pool_manager, &Self::tick_info_slot(pool_id, tick)?)?;
+        let unsigned_liqudity: Uint<128, 2> = cell.shr(U256::from(128)).to();
+        let lu128: u128 = unsigned_liqudity.to();
+        let li128: i128 = lu128 as i128;
+        trace!("ticks_liquidity_net {pool_id} {tick} {cell} -> {li128}");
+
+        Ok(li128)
+    }
+
+    pub fn tick_bitmap(db: &LoomDBType, pool_manager: Address, pool_id: B256, word: i16) -> Result<U256> {
+        let cell = try_read_cell(db, &pool_manager, &Self::tick_bitmap_slot(pool_id, word)?)?;
+        trace!("tickBitmap {pool_id} {word} {cell}");
+        Ok(cell)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_slot0() -> Result<()> {
+        let sqrt_price_x96 = U256::from(79228162514264337593543950336u128);
+        let tick = U256::from(0xFFFFFFu32); // -1
+        let protocol_fee = U256::from(0x001001u32);
+        let lp_fee = U256::from(3000u32);
+        let cell = sqrt_price_x96 | tick.shl(160) | protocol_fee.shl(184) | lp_fee.shl(208);
+
+        let slot0 = UniswapV4DBReader::parse_slot0(cell)?;
+        assert_eq!(slot0, UniswapV4Slot0 { sqrt_price_x96, tick: -1, protocol_fee: 0x001001, lp_fee: 3000 });
+        Ok(())
+    }
+}
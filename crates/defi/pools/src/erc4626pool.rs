@@ -0,0 +1,250 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, Result};
+use lazy_static::lazy_static;
+use loom_defi_abi::erc4626::IERC4626;
+use loom_defi_abi::IERC20;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::state_readers::Erc4626StateReader;
+
+lazy_static! {
+    static ref PREVIEW_AMOUNT: U256 = U256::from(1_000_000_000_000_000_000u128);
+}
+
+/// ERC-4626 vault. Depositing the asset mints vault shares and redeeming shares returns the asset,
+/// amounts are previewed by the vault itself so fees and rounding of the vault are included.
+#[derive(Clone)]
+pub struct Erc4626Pool {
+    address: Address,
+    asset: Address,
+    encoder: Erc4626AbiSwapEncoder,
+}
+
+impl Erc4626Pool {
+    pub fn new(address: Address, asset: Address) -> Self {
+        Self { address, asset, encoder: Erc4626AbiSwapEncoder::new(address, asset) }
+    }
+
+    pub fn get_asset(&self) -> Address {
+        self.asset
+    }
+
+    pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
+        let asset = Erc4626StateReader::asset(db, env, address)?;
+        if asset.is_zero() {
+            return Err(eyre!("ZERO_ASSET"));
+        }
+        Ok(Self::new(address, asset))
+    }
+
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        address: Address,
+    ) -> Result<Self> {
+        let vault = IERC4626::new(address, client);
+        let asset = vault.asset().call().await?._0;
+        if asset.is_zero() {
+            return Err(eyre!("ZERO_ASSET"));
+        }
+        // vaults must support previews, tokens having only asset() are no vaults
+        vault.previewRedeem(*PREVIEW_AMOUNT).call().await?;
+
+        debug!("fetch_pool_data {:?} {:?}", address, asset);
+        Ok(Self::new(address, asset))
+    }
+
+    fn is_deposit(&self, token_address_from: &Address, token_address_to: &Address) -> Result<bool> {
+        if *token_address_from == self.asset && *token_address_to == self.address {
+            Ok(true)
+        } else if *token_address_from == self.address && *token_address_to == self.asset {
+            Ok(false)
+        } else {
+            Err(eyre!("DIRECTION_NOT_SUPPORTED"))
+        }
+    }
+}
+
+impl Pool for Erc4626Pool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::Erc4626
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::Erc4626
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.asset, self.address]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.asset, self.address), (self.address, self.asset)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let (out_amount, gas_used) = if self.is_deposit(token_address_from, token_address_to)? {
+            (Erc4626StateReader::preview_deposit(state_db, env, self.address, in_amount)?, 120_000)
+        } else {
+            (Erc4626StateReader::preview_redeem(state_db, env, self.address, in_amount)?, 100_000)
+        };
+
+        if out_amount.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((out_amount, gas_used))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let (in_amount, gas_used) = if self.is_deposit(token_address_from, token_address_to)? {
+            (Erc4626StateReader::preview_mint(state_db, env, self.address, out_amount)?, 120_000)
+        } else {
+            (Erc4626StateReader::preview_withdraw(state_db, env, self.address, out_amount)?, 100_000)
+        };
+
+        if in_amount.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((in_amount, gas_used))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+
+        state_required
+            .add_call(self.address, IERC4626::assetCall {}.abi_encode())
+            .add_call(self.address, IERC4626::totalAssetsCall {}.abi_encode())
+            .add_call(self.address, IERC4626::previewDepositCall { assets: *PREVIEW_AMOUNT }.abi_encode())
+            .add_call(self.address, IERC4626::previewMintCall { shares: *PREVIEW_AMOUNT }.abi_encode())
+            .add_call(self.address, IERC4626::previewWithdrawCall { assets: *PREVIEW_AMOUNT }.abi_encode())
+            .add_call(self.address, IERC4626::previewRedeemCall { shares: *PREVIEW_AMOUNT }.abi_encode())
+            .add_call(self.asset, IERC20::balanceOfCall { account: self.address }.abi_encode());
+
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Erc4626AbiSwapEncoder {
+    pool_address: Address,
+    asset: Address,
+}
+
+impl Erc4626AbiSwapEncoder {
+    pub fn new(pool_address: Address, asset: Address) -> Self {
+        Self { pool_address, asset }
+    }
+}
+
+impl AbiSwapEncoder for Erc4626AbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        if token_from_address == self.asset && token_to_address == self.pool_address {
+            Ok(Bytes::from(IERC4626::depositCall { assets: amount, receiver: recipient }.abi_encode()))
+        } else if token_from_address == self.pool_address && token_to_address == self.asset {
+            // shares are redeemed from the caller, which is expected to be the recipient
+            Ok(Bytes::from(IERC4626::redeemCall { shares: amount, receiver: recipient, owner: recipient }.abi_encode()))
+        } else {
+            Err(eyre!("DIRECTION_NOT_SUPPORTED"))
+        }
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Allowance
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x04)
+    }
+
+    fn swap_in_amount_return_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x00)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::BlockNumber;
+    use alloy_rpc_types::BlockId;
+    use loom_defi_address_book::TokenAddress;
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use std::env;
+
+    #[tokio::test]
+    async fn test_calculate_amounts() -> Result<()> {
+        let block_number = 20935488u64;
+
+        let node_url = env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(block_number)).await?;
+
+        let pool = Erc4626Pool::fetch_pool_data(client.clone(), TokenAddress::SDAI).await?;
+        assert_eq!(pool.get_asset(), TokenAddress::DAI);
+
+        let state_update =
+            RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, Some(block_number)).await?;
+        let mut state_db = LoomDBType::default();
+        state_db.apply_geth_update(state_update);
+
+        let vault = IERC4626::new(TokenAddress::SDAI, client.clone());
+        let amount = U256::from(1_234_567_890_000_000_000_000u128);
+        let mut evm_env = Env::default();
+        evm_env.block.number = U256::from(block_number);
+        evm_env.block.timestamp = U256::from(client.get_block_by_number(block_number.into(), false).await?.unwrap().header.timestamp);
+
+        let contract_deposit = vault.previewDeposit(amount).call().block(BlockId::from(block_number)).await?._0;
+        let (deposit_out, _) = pool.calculate_out_amount(&state_db, evm_env.clone(), &TokenAddress::DAI, &TokenAddress::SDAI, amount)?;
+        assert_eq!(deposit_out, contract_deposit);
+
+        let contract_redeem = vault.previewRedeem(amount).call().block(BlockId::from(block_number)).await?._0;
+        let (redeem_out, _) = pool.calculate_out_amount(&state_db, evm_env.clone(), &TokenAddress::SDAI, &TokenAddress::DAI, amount)?;
+        assert_eq!(redeem_out, contract_redeem);
+
+        let contract_mint = vault.previewMint(amount).call().block(BlockId::from(block_number)).await?._0;
+        let (mint_in, _) = pool.calculate_in_amount(&state_db, evm_env.clone(), &TokenAddress::DAI, &TokenAddress::SDAI, amount)?;
+        assert_eq!(mint_in, contract_mint);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, OptionExt, Result};
+use loom_defi_abi::integral::{ITwapPair, ITwapRelayer};
+use loom_defi_abi::IERC20;
+use loom_defi_address_book::AddressBook;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::state_readers::IntegralStateReader;
+
+/// Integral SIZE pair. Swaps are executed by the TWAP relayer at the oracle price from its own inventory,
+/// so amounts are quoted by the relayer and the pair address only identifies the market.
+/// Only sells are encoded, therefore in amounts are not calculated for swap paths.
+#[derive(Clone)]
+pub struct IntegralPool {
+    address: Address,
+    token0: Address,
+    token1: Address,
+    factory: Address,
+    relayer: Address,
+    liquidity0: U256,
+    liquidity1: U256,
+    encoder: IntegralAbiSwapEncoder,
+}
+
+impl IntegralPool {
+    pub fn new_with_data(
+        address: Address,
+        token0: Address,
+        token1: Address,
+        factory: Address,
+        relayer: Address,
+        liquidity0: U256,
+        liquidity1: U256,
+    ) -> Self {
+        IntegralPool { address, token0, token1, factory, relayer, liquidity0, liquidity1, encoder: IntegralAbiSwapEncoder { relayer } }
+    }
+
+    pub fn get_factory(&self) -> Address {
+        self.factory
+    }
+
+    pub fn get_relayer(&self) -> Address {
+        self.relayer
+    }
+
+    // TWAP relayer from the address book of the chain
+    fn relayer(chain_id: u64) -> Result<Address> {
+        AddressBook::find_periphery(chain_id, "INTEGRAL_RELAYER").ok_or_eyre("INTEGRAL_RELAYER_NOT_IN_ADDRESS_BOOK")
+    }
+
+    pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
+        let token0 = IntegralStateReader::token0(db, env.clone(), address)?;
+        let token1 = IntegralStateReader::token1(db, env.clone(), address)?;
+        let factory = IntegralStateReader::factory(db, env.clone(), address)?;
+        let relayer = Self::relayer(env.cfg.chain_id)?;
+        if !IntegralStateReader::is_pair_enabled(db, env, relayer, address)? {
+            return Err(eyre!("PAIR_NOT_ENABLED"));
+        }
+
+        Ok(Self::new_with_data(address, token0, token1, factory, relayer, U256::ZERO, U256::ZERO))
+    }
+
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        address: Address,
+    ) -> Result<Self> {
+        let relayer = Self::relayer(client.get_chain_id().await?)?;
+        let pair_contract = ITwapPair::new(address, client.clone());
+        let relayer_contract = ITwapRelayer::new(relayer, client.clone());
+
+        let token0 = pair_contract.token0().call().await?._0;
+        let token1 = pair_contract.token1().call().await?._0;
+        let factory = pair_contract.factory().call().await?._0;
+        if !relayer_contract.isPairEnabled(address).call().await?._0 {
+            return Err(eyre!("PAIR_NOT_ENABLED"));
+        }
+
+        // the relayer trades from its own balances
+        let liquidity0 = IERC20::new(token0, client.clone()).balanceOf(relayer).call().await?._0;
+        let liquidity1 = IERC20::new(token1, client.clone()).balanceOf(relayer).call().await?._0;
+
+        debug!("fetch_pool_data {:?} {:?} {:?} {} {}", address, token0, token1, liquidity0, liquidity1);
+        Ok(Self::new_with_data(address, token0, token1, factory, relayer, liquidity0, liquidity1))
+    }
+}
+
+impl Pool for IntegralPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::Integral
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::Integral
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token0, self.token1]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.token0, self.token1), (self.token1, self.token0)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let (out_amount, _) =
+            IntegralStateReader::quote_sell(state_db, env, self.relayer, *token_address_from, *token_address_to, in_amount)?;
+
+        if out_amount.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((out_amount, 250_000))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let (in_amount, _) =
+            IntegralStateReader::quote_buy(state_db, env, self.relayer, *token_address_from, *token_address_to, out_amount)?;
+
+        if in_amount.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((in_amount, 250_000))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn can_calculate_in_amount(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+
+        state_required
+            .add_call(self.relayer, ITwapRelayer::isPairEnabledCall { pair: self.address }.abi_encode())
+            .add_call(
+                self.relayer,
+                ITwapRelayer::quoteSellCall { tokenIn: self.token0, tokenOut: self.token1, amountIn: self.liquidity0 / U256::from(100) }
+                    .abi_encode(),
+            )
+            .add_call(
+                self.relayer,
+                ITwapRelayer::quoteSellCall { tokenIn: self.token1, tokenOut: self.token0, amountIn: self.liquidity1 / U256::from(100) }
+                    .abi_encode(),
+            )
+            .add_call(self.address, ITwapPair::getReservesCall {}.abi_encode());
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: self.relayer }.abi_encode());
+        }
+
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct IntegralAbiSwapEncoder {
+    relayer: Address,
+}
+
+impl AbiSwapEncoder for IntegralAbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        let sell_call = ITwapRelayer::sellCall {
+            sellParams: ITwapRelayer::SellParams {
+                tokenIn: token_from_address,
+                tokenOut: token_to_address,
+                amountIn: amount,
+                amountOutMin: U256::ZERO,
+                wrapUnwrap: false,
+                to: recipient,
+                submitDeadline: u32::MAX,
+            },
+        };
+
+        Ok(Bytes::from(sell_call.abi_encode()))
+    }
+
+    // the relayer pulls the tokens in with transferFrom
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Allowance
+    }
+
+    fn swap_contract_address(&self) -> Option<Address> {
+        Some(self.relayer)
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x44)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use loom_defi_address_book::PeripheryAddress;
+
+    #[test]
+    fn test_encode_sell() -> Result<()> {
+        let token_from = address!("0000000000000000000000000000000000000001");
+        let token_to = address!("0000000000000000000000000000000000000002");
+        let encoder = IntegralAbiSwapEncoder { relayer: PeripheryAddress::INTEGRAL_RELAYER };
+
+        let data = encoder.encode_swap_in_amount_provided(token_from, token_to, U256::from(1000), Address::ZERO, Bytes::new())?;
+        let offset = encoder.swap_in_amount_offset(token_from, token_to).unwrap() as usize;
+        assert_eq!(U256::from_be_slice(&data[offset..offset + 0x20]), U256::from(1000));
+
+        let call = ITwapRelayer::sellCall::abi_decode(&data, true)?;
+        assert_eq!(call.sellParams.tokenIn, token_from);
+        assert_eq!(call.sellParams.tokenOut, token_to);
+        Ok(())
+    }
+}
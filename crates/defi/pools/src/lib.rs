@@ -1,21 +1,42 @@
 extern crate core;
 
+pub use algebrapool::AlgebraPool;
+pub use balancerpool::{BalancerPool, BalancerPoolVariant};
 pub use config::PoolsConfig;
 pub use curvepool::CurvePool;
+pub use erc4626pool::Erc4626Pool;
+pub use integralpool::IntegralPool;
 pub use maverickpool::MaverickPool;
 pub use pancakev3pool::PancakeV3Pool;
+pub use rocketpool::RocketEthPool;
+pub use solidlypool::SolidlyPool;
+pub use stethpool::StEthPool;
+pub use token_classifier::{TokenClassification, TokenClassificationCache, TokenClassifier};
 pub use uniswapv2pool::UniswapV2Pool;
 pub use uniswapv3pool::{Slot0, UniswapV3Pool};
+pub use uniswapv4pool::UniswapV4Pool;
+pub use virtual_impl::curve::{CurveCodeHashRegistry, CurveCodeHashVerdict};
+pub use wstethpool::WstEthPool;
 
+mod algebrapool;
+mod balancerpool;
 pub mod db_reader;
 mod maverickpool;
 pub mod state_readers;
 mod uniswapv2pool;
 mod uniswapv3pool;
+mod uniswapv4pool;
 
 mod curvepool;
+mod erc4626pool;
+mod integralpool;
 pub mod protocols;
 
 mod config;
 mod pancakev3pool;
+mod rocketpool;
+mod solidlypool;
+mod stethpool;
+mod token_classifier;
 mod virtual_impl;
+mod wstethpool;
@@ -4,18 +4,27 @@ use alloy_sol_types::{SolCall, SolInterface};
 use alloy_transport::Transport;
 use eyre::{eyre, ErrReport, OptionExt, Result};
 use loom_defi_abi::maverick::IMaverickPool::{getStateCall, IMaverickPoolCalls, IMaverickPoolInstance};
+#[cfg(feature = "debug-calculation")]
 use loom_defi_abi::maverick::IMaverickQuoter::{calculateSwapCall, IMaverickQuoterCalls};
-use loom_defi_abi::maverick::{IMaverickPool, IMaverickQuoter, State};
+use loom_defi_abi::maverick::{IMaverickPool, State};
 use loom_defi_abi::IERC20;
+#[cfg(feature = "debug-calculation")]
 use loom_defi_address_book::PeripheryAddress;
 use loom_evm_db::LoomDBType;
+#[cfg(feature = "debug-calculation")]
 use loom_evm_utils::evm::evm_call;
 use loom_types_entities::required_state::RequiredState;
 use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
 use revm::primitives::Env;
 use tracing::error;
 
-use crate::state_readers::UniswapV3StateReader;
+use crate::db_reader::{MaverickDBReader, NUMBER_OF_KINDS};
+use crate::state_readers::MaverickStateReader;
+use crate::virtual_impl::MaverickPoolVirtual;
+
+// Swap gas without bins and gas per swapped bin
+const SWAP_BASE_GAS: u64 = 100000;
+const SWAP_BIN_GAS: u64 = 25000;
 
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -28,7 +37,10 @@ pub struct MaverickPool {
     liquidity1: U256,
     fee: U256,
     spacing: u32,
+    token_a_scale: U256,
+    token_b_scale: U256,
     slot0: Option<State>,
+    bins: Vec<(i32, u8, u128)>,
     factory: Address,
     protocol: PoolProtocol,
     encoder: MaverickAbiSwapEncoder,
@@ -44,7 +56,10 @@ impl MaverickPool {
             liquidity1: U256::ZERO,
             fee: U256::ZERO,
             spacing: 0,
+            token_a_scale: U256::from(1),
+            token_b_scale: U256::from(1),
             slot0: None,
+            bins: Vec::new(),
             factory: Address::ZERO,
             protocol: PoolProtocol::Maverick,
             encoder: MaverickAbiSwapEncoder::new(address),
@@ -77,6 +92,31 @@ impl MaverickPool {
         PoolProtocol::Maverick
     }
 
+    pub fn tick_spacing(&self) -> u32 {
+        self.spacing
+    }
+
+    /// Amounts are scaled to 18 decimals inside the pool, returns (scale_in, scale_out)
+    pub fn token_scales(&self, token_a_in: bool) -> (U256, U256) {
+        if token_a_in {
+            (self.token_a_scale, self.token_b_scale)
+        } else {
+            (self.token_b_scale, self.token_a_scale)
+        }
+    }
+
+    // Bin map words of all ticks a swap searches around the active tick, every word covers 64 ticks
+    fn bin_map_indexes(active_tick: i32) -> std::ops::RangeInclusive<i32> {
+        let (first_map_index, _) = MaverickDBReader::bin_map_pointer(active_tick - MaverickPoolVirtual::MAX_TICK_SEARCH, 0);
+        let (last_map_index, _) =
+            MaverickDBReader::bin_map_pointer(active_tick + MaverickPoolVirtual::MAX_TICK_SEARCH, NUMBER_OF_KINDS - 1);
+        first_map_index..=last_map_index
+    }
+
+    fn swap_gas(bins: u64) -> u64 {
+        SWAP_BASE_GAS + SWAP_BIN_GAS * bins
+    }
+
     pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
         client: P,
         address: Address,
@@ -90,6 +130,23 @@ impl MaverickPool {
         let factory: Address = pool.factory().call().await?._0;
         let spacing: u32 = pool.tickSpacing().call().await?._0.to();
 
+        let token_a_scale: U256 = pool.tokenAScale().call().await?._0;
+        let token_b_scale: U256 = pool.tokenBScale().call().await?._0;
+
+        let mut bins = Vec::new();
+        for map_index in Self::bin_map_indexes(slot0.activeTick) {
+            let word: U256 = pool.binMap(map_index).call().await?._0;
+            for bit in 0..256usize {
+                if word.bit(bit) {
+                    let position = map_index * 256 + bit as i32;
+                    let tick = position.div_euclid(NUMBER_OF_KINDS as i32);
+                    let kind = position.rem_euclid(NUMBER_OF_KINDS as i32) as u8;
+                    let bin_id: u128 = pool.binPositions(tick, U256::from(kind)).call().await?._0;
+                    bins.push((tick, kind, bin_id));
+                }
+            }
+        }
+
         let token0_erc20 = IERC20::IERC20Instance::new(token0, client.clone());
         let token1_erc20 = IERC20::IERC20Instance::new(token1, client.clone());
 
@@ -103,7 +160,10 @@ impl MaverickPool {
             token0,
             token1,
             fee,
+            token_a_scale,
+            token_b_scale,
             slot0: Some(slot0),
+            bins,
             liquidity0,
             liquidity1,
             factory,
@@ -115,11 +175,13 @@ impl MaverickPool {
         Ok(ret)
     }
     pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
-        let token0: Address = UniswapV3StateReader::token0(db, env.clone(), address)?;
-        let token1: Address = UniswapV3StateReader::token1(db, env.clone(), address)?;
-        let fee = UniswapV3StateReader::fee(db, env.clone(), address)?;
-        let factory: Address = UniswapV3StateReader::factory(db, env.clone(), address)?;
-        let spacing: u32 = UniswapV3StateReader::tick_spacing(db, env.clone(), address)?;
+        let token0: Address = MaverickStateReader::token_a(db, env.clone(), address)?;
+        let token1: Address = MaverickStateReader::token_b(db, env.clone(), address)?;
+        let fee = MaverickStateReader::fee(db, env.clone(), address)?;
+        let factory: Address = MaverickStateReader::factory(db, env.clone(), address)?;
+        let spacing: u32 = MaverickStateReader::tick_spacing(db, env.clone(), address)?;
+        let token_a_scale = MaverickStateReader::token_a_scale(db, env.clone(), address)?;
+        let token_b_scale = MaverickStateReader::token_b_scale(db, env.clone(), address)?;
 
         let protocol = Self::get_protocol_by_factory(factory);
 
@@ -129,9 +191,12 @@ impl MaverickPool {
             token1,
             liquidity0: Default::default(),
             liquidity1: Default::default(),
-            fee: U256::from(fee),
+            fee,
             spacing,
+            token_a_scale,
+            token_b_scale,
             slot0: None,
+            bins: Vec::new(),
             factory,
             protocol,
             encoder: MaverickAbiSwapEncoder { pool_address: address },
@@ -162,12 +227,16 @@ impl Pool for MaverickPool {
         vec![(self.token0, self.token1), (self.token1, self.token0)]
     }
 
+    fn get_fee(&self) -> U256 {
+        self.fee
+    }
+
     fn calculate_out_amount(
         &self,
         state_db: &LoomDBType,
-        env: Env,
+        _env: Env,
         token_address_from: &Address,
-        token_address_to: &Address,
+        _token_address_to: &Address,
         in_amount: U256,
     ) -> Result<(U256, u64), ErrReport> {
         if in_amount >= U256::from(U128::MAX) {
@@ -175,68 +244,80 @@ impl Pool for MaverickPool {
             return Err(eyre!("IN_AMOUNT_EXCEEDS_MAX"));
         }
 
-        let token_a_in = MaverickPool::get_zero_for_one(token_address_from, token_address_to);
-        //let sqrt_price_limit = MaverickPool::get_price_limit(token_address_from, token_address_to);
-
-        let mut env = env;
-        env.tx.gas_limit = 1_500_000;
-
-        let call_data_vec = IMaverickQuoterCalls::calculateSwap(calculateSwapCall {
-            pool: self.address,
-            amount: in_amount.to(),
-            tokenAIn: token_a_in,
-            exactOutput: false,
-            sqrtPriceLimit: U256::ZERO,
-        })
-        .abi_encode();
-
-        let (value, gas_used) = evm_call(state_db, env, PeripheryAddress::MAVERICK_QUOTER, call_data_vec)?;
-
-        let ret = calculateSwapCall::abi_decode_returns(&value, false)?.returnAmount;
+        let (ret, bins) = MaverickPoolVirtual::simulate_swap_in_amount(state_db, self, *token_address_from, in_amount)?;
+
+        #[cfg(feature = "debug-calculation")]
+        {
+            let mut env = _env;
+            env.tx.gas_limit = 1_500_000;
+
+            let call_data_vec = IMaverickQuoterCalls::calculateSwap(calculateSwapCall {
+                pool: self.address,
+                amount: in_amount.to(),
+                tokenAIn: MaverickPool::get_zero_for_one(token_address_from, _token_address_to),
+                exactOutput: false,
+                sqrtPriceLimit: U256::ZERO,
+            })
+            .abi_encode();
+
+            let (value, _gas_used) = evm_call(state_db, env, PeripheryAddress::MAVERICK_QUOTER, call_data_vec)?;
+            let ret_evm = calculateSwapCall::abi_decode_returns(&value, false)?.returnAmount;
+
+            if ret != ret_evm {
+                error!("calculate_out_amount RETURN_RESULT_IS_INCORRECT : {ret} need {ret_evm}");
+                return Err(eyre!("RETURN_RESULT_IS_INCORRECT"));
+            }
+        }
 
         if ret.is_zero() {
             Err(eyre!("ZERO_OUT_AMOUNT"))
         } else {
-            Ok((ret.checked_sub(U256::from(1)).ok_or_eyre("SUBTRACTION_OVERFLOWN")?, gas_used))
+            Ok((ret.checked_sub(U256::from(1)).ok_or_eyre("SUBTRACTION_OVERFLOWN")?, Self::swap_gas(bins)))
         }
     }
 
     fn calculate_in_amount(
         &self,
         state_db: &LoomDBType,
-        env: Env,
+        _env: Env,
         token_address_from: &Address,
-        token_address_to: &Address,
+        _token_address_to: &Address,
         out_amount: U256,
     ) -> Result<(U256, u64), ErrReport> {
-        let mut env = env;
-        env.tx.gas_limit = 500_000;
-
         if out_amount >= U256::from(U128::MAX) {
             error!("OUT_AMOUNT_EXCEEDS_MAX {} ", self.get_address().to_checksum(None));
             return Err(eyre!("OUT_AMOUNT_EXCEEDS_MAX"));
         }
 
-        let token_a_in = MaverickPool::get_zero_for_one(token_address_from, token_address_to);
-        //let sqrt_price_limit = MaverickPool::get_price_limit(token_address_from, token_address_to);
-
-        let call_data_vec = IMaverickQuoterCalls::calculateSwap(calculateSwapCall {
-            pool: self.address,
-            amount: out_amount.to(),
-            tokenAIn: token_a_in,
-            exactOutput: true,
-            sqrtPriceLimit: U256::ZERO,
-        })
-        .abi_encode();
-
-        let (value, gas_used) = evm_call(state_db, env, PeripheryAddress::MAVERICK_QUOTER, call_data_vec)?;
-
-        let ret = calculateSwapCall::abi_decode_returns(&value, false)?.returnAmount;
+        let (ret, bins) = MaverickPoolVirtual::simulate_swap_out_amount(state_db, self, *token_address_from, out_amount)?;
+
+        #[cfg(feature = "debug-calculation")]
+        {
+            let mut env = _env;
+            env.tx.gas_limit = 1_500_000;
+
+            let call_data_vec = IMaverickQuoterCalls::calculateSwap(calculateSwapCall {
+                pool: self.address,
+                amount: out_amount.to(),
+                tokenAIn: MaverickPool::get_zero_for_one(token_address_from, _token_address_to),
+                exactOutput: true,
+                sqrtPriceLimit: U256::ZERO,
+            })
+            .abi_encode();
+
+            let (value, _gas_used) = evm_call(state_db, env, PeripheryAddress::MAVERICK_QUOTER, call_data_vec)?;
+            let ret_evm = calculateSwapCall::abi_decode_returns(&value, false)?.returnAmount;
+
+            if ret != ret_evm {
+                error!("calculate_in_amount RETURN_RESULT_IS_INCORRECT : {ret} need {ret_evm}");
+                return Err(eyre!("RETURN_RESULT_IS_INCORRECT"));
+            }
+        }
 
         if ret.is_zero() {
             Err(eyre!("ZERO_IN_AMOUNT"))
         } else {
-            Ok((ret + U256::from(1), gas_used))
+            Ok((ret + U256::from(1), Self::swap_gas(bins)))
         }
     }
 
@@ -249,84 +330,28 @@ impl Pool for MaverickPool {
     }
 
     fn get_state_required(&self) -> Result<RequiredState> {
-        let tick = self.slot0.clone().unwrap().activeTick;
-
-        let quoter_swap_0_1_call = IMaverickQuoterCalls::calculateSwap(calculateSwapCall {
-            pool: self.address,
-            amount: (self.liquidity0 / U256::from(100)).to(),
-            tokenAIn: true,
-            exactOutput: false,
-            sqrtPriceLimit: U256::ZERO,
-        })
-        .abi_encode();
-
-        //let sqrt_price_limit = MaverickPool::get_price_limit(&self.token1, &self.token0);
-
-        let quoter_swap_1_0_call = IMaverickQuoterCalls::calculateSwap(calculateSwapCall {
-            pool: self.address,
-            amount: (self.liquidity1 / U256::from(100)).to(),
-            tokenAIn: false,
-            exactOutput: false,
-            sqrtPriceLimit: U256::ZERO,
-        })
-        .abi_encode();
-
-        //let tick_bitmap_index = MaverickPool::get_tick_bitmap_index(tick, self.spacing.as_u32());
-        let tick_bitmap_index = tick;
-
+        let tick = self.slot0.as_ref().ok_or_eyre("SLOT0_NOT_SET")?.activeTick;
         let pool_address = self.get_address();
 
         let mut state_required = RequiredState::new();
-        state_required
-            .add_call(self.get_address(), IMaverickPoolCalls::getState(getStateCall {}).abi_encode())
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index - 4 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index - 3 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index - 2 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index - 1 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index + 1 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index + 2 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index + 3 })
-                    .abi_encode(),
-            )
-            .add_call(
-                PeripheryAddress::MAVERICK_QUOTER,
-                IMaverickQuoterCalls::getBinsAtTick(IMaverickQuoter::getBinsAtTickCall { pool: pool_address, tick: tick_bitmap_index + 4 })
-                    .abi_encode(),
-            )
-            .add_call(PeripheryAddress::MAVERICK_QUOTER, quoter_swap_0_1_call)
-            .add_call(PeripheryAddress::MAVERICK_QUOTER, quoter_swap_1_0_call)
-            .add_slot_range(self.get_address(), U256::from(0), 0x20);
+        state_required.add_call(pool_address, IMaverickPoolCalls::getState(getStateCall {}).abi_encode()).add_slot_range(
+            pool_address,
+            U256::from(0),
+            0x20,
+        );
+
+        // Bins read by the virtual implementation
+        for map_index in Self::bin_map_indexes(tick) {
+            state_required.add_slot(pool_address, MaverickDBReader::bin_map_slot(map_index)?);
+        }
+        for (bin_tick, kind, bin_id) in self.bins.iter() {
+            let bin_slot = MaverickDBReader::bin_slot(*bin_id);
+            state_required.add_slot(pool_address, MaverickDBReader::bin_position_slot(*bin_tick, *kind)?).add_slot_range(
+                pool_address,
+                bin_slot,
+                3,
+            );
+        }
 
         for token_address in self.get_tokens() {
             state_required.add_call(token_address, IERC20::balanceOfCall { account: pool_address }.abi_encode());
@@ -419,13 +444,90 @@ mod tests {
     use super::*;
     use alloy_rpc_types::BlockNumberOrTag;
     use loom_defi_abi::maverick::IMaverickQuoter::IMaverickQuoterInstance;
+    use loom_defi_address_book::PeripheryAddress;
     use loom_evm_utils::evm_env::env_for_block;
     use loom_node_debug_provider::AnvilDebugProviderFactory;
     use loom_types_entities::required_state::RequiredStateReader;
     use loom_types_entities::MarketState;
     use std::env;
+    use std::ops::Shl;
     use tracing::debug;
 
+    const ONE: u128 = 1_000_000_000_000_000_000;
+
+    fn test_pool(address: Address) -> MaverickPool {
+        let mut pool = MaverickPool::new(address);
+        pool.token0 = Address::repeat_byte(1);
+        pool.token1 = Address::repeat_byte(2);
+        pool.fee = U256::from(ONE / 1000);
+        pool.spacing = 10;
+        pool
+    }
+
+    // Ticks from 0 get a bin of kind 0 with equal reserves, only the bin map word of the active tick is loaded
+    fn test_state_db(address: Address, ticks: i32, reserve: u128) -> Result<LoomDBType> {
+        let mut state_db = LoomDBType::new();
+        state_db.insert_account_storage(address, MaverickDBReader::state_slot(), U256::ZERO)?;
+
+        let mut bin_map = U256::ZERO;
+        for tick in 0..ticks {
+            let (_, bit) = MaverickDBReader::bin_map_pointer(tick, 0);
+            bin_map.set_bit(bit, true);
+
+            let bin_id = (tick + 1) as u128;
+            let bin_slot = MaverickDBReader::bin_slot(bin_id);
+            state_db.insert_account_storage(address, MaverickDBReader::bin_position_slot(tick, 0)?, U256::from(bin_id))?;
+            state_db.insert_account_storage(address, bin_slot, U256::from(reserve) | U256::from(reserve).shl(128))?;
+            state_db.insert_account_storage(address, bin_slot + U256::from(1), U256::ZERO)?;
+            state_db.insert_account_storage(address, bin_slot + U256::from(2), U256::from(reserve))?;
+        }
+        state_db.insert_account_storage(address, MaverickDBReader::bin_map_slot(0)?, bin_map)?;
+
+        Ok(state_db)
+    }
+
+    #[test]
+    fn test_bin_map_indexes_cover_search_range() {
+        let map_indexes = MaverickPool::bin_map_indexes(0);
+        let (first_map_index, _) = MaverickDBReader::bin_map_pointer(-MaverickPoolVirtual::MAX_TICK_SEARCH, 0);
+        let (last_map_index, _) = MaverickDBReader::bin_map_pointer(MaverickPoolVirtual::MAX_TICK_SEARCH, NUMBER_OF_KINDS - 1);
+        assert!(map_indexes.contains(&first_map_index));
+        assert!(map_indexes.contains(&last_map_index));
+    }
+
+    #[test]
+    fn test_gas_per_bin_and_margins() -> Result<()> {
+        let address = Address::repeat_byte(0x33);
+        let pool = test_pool(address);
+        let state_db = test_state_db(address, 4, 1000 * ONE)?;
+
+        let (small_out, small_gas) = pool.calculate_out_amount(&state_db, Env::default(), &pool.token0, &pool.token1, U256::from(ONE))?;
+        let (virtual_out, bins) = MaverickPoolVirtual::simulate_swap_in_amount(&state_db, &pool, pool.token0, U256::from(ONE))?;
+        assert_eq!(small_out + U256::from(1), virtual_out);
+        assert_eq!(small_gas, SWAP_BASE_GAS + SWAP_BIN_GAS * bins);
+
+        // Crossing into the next ticks swaps more bins
+        let (_, large_gas) = pool.calculate_out_amount(&state_db, Env::default(), &pool.token0, &pool.token1, U256::from(1500 * ONE))?;
+        assert!(large_gas > small_gas);
+
+        let (in_amount, _) = pool.calculate_in_amount(&state_db, Env::default(), &pool.token0, &pool.token1, small_out)?;
+        let (virtual_in, _) = MaverickPoolVirtual::simulate_swap_out_amount(&state_db, &pool, pool.token0, small_out)?;
+        assert_eq!(in_amount, virtual_in + U256::from(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_swap_stops_at_loaded_boundary() -> Result<()> {
+        let address = Address::repeat_byte(0x33);
+        let pool = test_pool(address);
+        let state_db = test_state_db(address, 4, 1000 * ONE)?;
+
+        // Loaded ticks have less than the requested amount, the next bin map word is not loaded
+        let result = pool.calculate_out_amount(&state_db, Env::default(), &pool.token0, &pool.token1, U256::from(100000 * ONE));
+        assert_eq!(result.unwrap_err().to_string(), "MAVERICK_BINS_NOT_LOADED");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_pool() -> Result<()> {
         let _ = env_logger::try_init_from_env(env_logger::Env::default().default_filter_or("info,defi_pools=off"));
@@ -459,32 +561,62 @@ mod tests {
         debug!("Router call : {:?}", resp.returnAmount);
         assert_ne!(resp.returnAmount, U256::ZERO);
 
-        let (out_amount, gas_used) = pool
-            .calculate_out_amount(
-                &market_state.state_db,
-                evm_env.clone(),
-                &pool.token1,
-                &pool.token0,
-                U256::from(pool.liquidity1 / U256::from(1000)),
-            )
-            .unwrap();
+        let (out_amount, gas_used) =
+            pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &pool.token1, &pool.token0, amount).unwrap();
         debug!("{} {} {}", pool.get_protocol(), out_amount, gas_used);
-        assert_ne!(out_amount, U256::ZERO);
+        assert_eq!(out_amount + U256::from(1), resp.returnAmount);
         assert!(gas_used > 100000);
 
-        let (out_amount, gas_used) = pool
-            .calculate_out_amount(
-                &market_state.state_db,
-                evm_env.clone(),
-                &pool.token0,
-                &pool.token1,
-                U256::from(pool.liquidity0 / U256::from(1000)),
-            )
-            .unwrap();
+        let amount = U256::from(pool.liquidity0 / U256::from(1000));
+        let resp = quoter.calculateSwap(pool_address, amount.to(), true, false, U256::ZERO).call().await?;
+
+        let (out_amount, gas_used) =
+            pool.calculate_out_amount(&market_state.state_db, evm_env.clone(), &pool.token0, &pool.token1, amount).unwrap();
         debug!("{} {} {}", pool.get_protocol(), out_amount, gas_used);
-        assert_ne!(out_amount, U256::ZERO);
+        assert_eq!(out_amount + U256::from(1), resp.returnAmount);
         assert!(gas_used > 100000);
 
+        let resp = quoter.calculateSwap(pool_address, out_amount.to(), true, true, U256::ZERO).call().await?;
+
+        let (in_amount, _) =
+            pool.calculate_in_amount(&market_state.state_db, evm_env.clone(), &pool.token0, &pool.token1, out_amount).unwrap();
+        debug!("{} {}", pool.get_protocol(), in_amount);
+        assert_eq!(in_amount, resp.returnAmount + U256::from(1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_db_reader() -> Result<()> {
+        let node_url = env::var("MAINNET_WS")?;
+
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, 20045799).await?;
+
+        let pool_address: Address = "0x352B186090068Eb35d532428676cE510E17AB581".parse().unwrap();
+
+        let pool = MaverickPool::fetch_pool_data(client.clone(), pool_address).await.unwrap();
+
+        let state_required = RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, None).await?;
+
+        let mut market_state = MarketState::new(LoomDBType::default());
+        market_state.state_db.apply_geth_update(state_required);
+
+        let pool_contract = IMaverickPoolInstance::new(pool_address, client.clone());
+        let state = pool_contract.getState().call().await?._0;
+        assert_eq!(MaverickDBReader::active_tick(&market_state.state_db, pool_address)?, state.activeTick);
+
+        assert!(!pool.bins.is_empty());
+        for (tick, kind, bin_id) in pool.bins.iter() {
+            assert_eq!(MaverickDBReader::bin_position(&market_state.state_db, pool_address, *tick, *kind)?, *bin_id);
+
+            let bin_evm = pool_contract.getBin(*bin_id).call().await?.bin;
+            let bin = MaverickDBReader::bin(&market_state.state_db, pool_address, *bin_id)?;
+            assert_eq!(bin.reserve_a, U256::from(bin_evm.reserveA));
+            assert_eq!(bin.reserve_b, U256::from(bin_evm.reserveB));
+            assert_eq!(bin.kind, bin_evm.kind);
+            assert_eq!(bin.lower_tick, bin_evm.lowerTick);
+        }
+
         Ok(())
     }
 }
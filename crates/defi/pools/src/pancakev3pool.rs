@@ -14,11 +14,11 @@ use loom_defi_abi::pancake::{IPancakeQuoterV2, IPancakeV3Pool};
 use loom_defi_abi::uniswap3::IUniswapV3Pool;
 use loom_defi_abi::uniswap_periphery::ITickLens;
 use loom_defi_abi::IERC20;
-use loom_defi_address_book::PeripheryAddress;
+use loom_defi_address_book::{AddressBook, PeripheryAddress};
 use loom_evm_db::LoomDBType;
 use loom_evm_utils::evm::evm_call;
 use loom_types_entities::required_state::RequiredState;
-use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use loom_types_entities::{get_protocol_by_factory, AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
 use revm::primitives::Env;
 
 #[allow(dead_code)]
@@ -61,6 +61,9 @@ pub struct PancakeV3Pool {
     slot0: Option<Slot0>,
     factory: Address,
     protocol: PoolProtocol,
+    // Periphery of the pool chain, quoter is used to calculate amounts
+    tick_lens: Option<Address>,
+    quoter: Option<Address>,
     encoder: PancakeV3AbiSwapEncoder,
 }
 
@@ -77,6 +80,8 @@ impl PancakeV3Pool {
             slot0: None,
             factory: Address::ZERO,
             protocol: PoolProtocol::PancakeV3,
+            tick_lens: Some(PeripheryAddress::PANCAKE_V3_TICK_LENS),
+            quoter: Some(PeripheryAddress::PANCAKE_V3_QUOTER),
             encoder: PancakeV3AbiSwapEncoder::new(address),
         }
     }
@@ -113,21 +118,26 @@ impl PancakeV3Pool {
         *token_address_from < *token_address_to
     }
 
-    fn get_protocol_by_factory(factory_address: Address) -> PoolProtocol {
-        let pancake3_factory: Address = "0x0BFbCF9fa4f9C56B0F40a671Ad40E0805A091865".parse().unwrap();
-        if factory_address == pancake3_factory {
-            PoolProtocol::PancakeV3
-        } else {
-            PoolProtocol::UniswapV3Like
+    fn get_protocol_by_factory(chain_id: u64, factory_address: Address) -> PoolProtocol {
+        match get_protocol_by_factory(chain_id, factory_address) {
+            PoolProtocol::PancakeV3 => PoolProtocol::PancakeV3,
+            _ => PoolProtocol::UniswapV3Like,
         }
     }
+
+    // Tick lens and quoter from the address book of the chain
+    fn periphery(chain_id: u64) -> (Option<Address>, Option<Address>) {
+        (AddressBook::find_periphery(chain_id, "PANCAKE_V3_TICK_LENS"), AddressBook::find_periphery(chain_id, "PANCAKE_V3_QUOTER"))
+    }
+
     pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
         let token0: Address = UniswapV3StateReader::token0(db, env.clone(), address)?;
         let token1: Address = UniswapV3StateReader::token1(db, env.clone(), address)?;
         let fee = UniswapV3StateReader::fee(db, env.clone(), address)?;
         let fee_u32: u32 = fee.to();
         let factory = UniswapV3StateReader::factory(db, env.clone(), address)?;
-        let protocol = Self::get_protocol_by_factory(factory);
+        let protocol = Self::get_protocol_by_factory(env.cfg.chain_id, factory);
+        let (tick_lens, quoter) = Self::periphery(env.cfg.chain_id);
 
         let ret = PancakeV3Pool {
             address,
@@ -140,6 +150,8 @@ impl PancakeV3Pool {
             slot0: None,
             factory,
             protocol,
+            tick_lens,
+            quoter,
             encoder: PancakeV3AbiSwapEncoder::new(address),
         };
 
@@ -165,7 +177,9 @@ impl PancakeV3Pool {
         let liquidity0: U256 = token0_erc20.balanceOf(address).call().await?._0;
         let liquidity1: U256 = token1_erc20.balanceOf(address).call().await?._0;
 
-        let protocol = PancakeV3Pool::get_protocol_by_factory(factory);
+        let chain_id = client.get_chain_id().await?;
+        let protocol = PancakeV3Pool::get_protocol_by_factory(chain_id, factory);
+        let (tick_lens, quoter) = Self::periphery(chain_id);
 
         let ret = PancakeV3Pool {
             address,
@@ -178,6 +192,8 @@ impl PancakeV3Pool {
             liquidity1,
             factory,
             protocol,
+            tick_lens,
+            quoter,
             encoder: PancakeV3AbiSwapEncoder::new(address),
         };
 
@@ -228,7 +244,7 @@ impl Pool for PancakeV3Pool {
         })
         .abi_encode();
 
-        let (value, gas_used) = evm_call(state_db, env, PeripheryAddress::PANCAKE_V3_QUOTER, call_data)?;
+        let (value, gas_used) = evm_call(state_db, env, self.quoter.ok_or_eyre("PANCAKE_V3_QUOTER_NOT_SET")?, call_data)?;
 
         let ret = IPancakeQuoterV2::quoteExactInputSingleCall::abi_decode_returns(&value, false)?;
 
@@ -261,7 +277,7 @@ impl Pool for PancakeV3Pool {
         })
         .abi_encode();
 
-        let (value, gas_used) = evm_call(state_db, env, PeripheryAddress::PANCAKE_V3_QUOTER, call_data)?;
+        let (value, gas_used) = evm_call(state_db, env, self.quoter.ok_or_eyre("PANCAKE_V3_QUOTER_NOT_SET")?, call_data)?;
 
         let ret = IPancakeQuoterV2::quoteExactOutputSingleCall::abi_decode_returns(&value, false)?;
 
@@ -315,13 +331,15 @@ impl Pool for PancakeV3Pool {
         .abi_encode();
 
         let pool_address = self.get_address();
+        let tick_lens = self.tick_lens.ok_or_eyre("PANCAKE_V3_TICK_LENS_NOT_SET")?;
+        let quoter = self.quoter.ok_or_eyre("PANCAKE_V3_QUOTER_NOT_SET")?;
 
         let mut state_required = RequiredState::new();
         state_required
             .add_call(self.get_address(), IUniswapV3Pool::IUniswapV3PoolCalls::slot0(IUniswapV3Pool::slot0Call {}).abi_encode())
             .add_call(self.get_address(), IUniswapV3Pool::IUniswapV3PoolCalls::liquidity(IUniswapV3Pool::liquidityCall {}).abi_encode())
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index - 4,
@@ -329,7 +347,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index - 3,
@@ -337,7 +355,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index - 2,
@@ -345,7 +363,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index - 1,
@@ -353,7 +371,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index,
@@ -361,7 +379,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index + 1,
@@ -369,7 +387,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index + 2,
@@ -377,7 +395,7 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index + 3,
@@ -385,15 +403,15 @@ impl Pool for PancakeV3Pool {
                 .abi_encode(),
             )
             .add_call(
-                PeripheryAddress::PANCAKE_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index + 4,
                 })
                 .abi_encode(),
             )
-            .add_call(PeripheryAddress::PANCAKE_V3_QUOTER, quoter_swap_0_1_call)
-            .add_call(PeripheryAddress::PANCAKE_V3_QUOTER, quoter_swap_1_0_call)
+            .add_call(quoter, quoter_swap_0_1_call)
+            .add_call(quoter, quoter_swap_1_0_call)
             .add_slot_range(self.get_address(), U256::from(0), 0x20)
             .add_empty_slot_range(self.get_address(), U256::from(0x10000), 0x20);
 
@@ -0,0 +1,211 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolInterface;
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, Result};
+use loom_defi_abi::rocketpool::{IRocketDepositPool, IRocketStorage};
+use loom_defi_address_book::{PeripheryAddress, TokenAddress};
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+
+use crate::state_readers::RocketPoolStateReader;
+
+/// Rocket Pool deposit pool. ETH deposits mint rETH at network balance rate minus deposit fee, swaps are one way WETH -> rETH
+#[derive(Clone)]
+pub struct RocketEthPool {
+    address: Address,
+    reth: Address,
+    weth: Address,
+    rocket_storage: Address,
+    encoder: RocketEthAbiSwapEncoder,
+}
+
+impl RocketEthPool {
+    pub fn new(address: Address, reth: Address, weth: Address, rocket_storage: Address) -> Self {
+        Self { address, reth, weth, rocket_storage, encoder: RocketEthAbiSwapEncoder::new(address) }
+    }
+
+    /// Fetch current deposit pool address from RocketStorage
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        rocket_storage: Address,
+    ) -> Result<Self> {
+        let storage_contract = IRocketStorage::new(rocket_storage, client);
+        let deposit_pool = storage_contract.getAddress(RocketPoolStateReader::deposit_pool_key()).call().await?._0;
+        if deposit_pool.is_zero() {
+            return Err(eyre!("DEPOSIT_POOL_NOT_FOUND"));
+        }
+        Ok(Self::new(deposit_pool, TokenAddress::RETH, TokenAddress::WETH, rocket_storage))
+    }
+
+    pub async fn fetch_mainnet<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+    ) -> Result<Self> {
+        Self::fetch_pool_data(client, PeripheryAddress::ROCKET_STORAGE).await
+    }
+
+    fn check_direction(&self, token_address_from: &Address, token_address_to: &Address) -> Result<()> {
+        if *token_address_from == self.weth && *token_address_to == self.reth {
+            Ok(())
+        } else {
+            Err(eyre!("DIRECTION_NOT_SUPPORTED"))
+        }
+    }
+}
+
+impl Pool for RocketEthPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::RocketPool
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::RocketEth
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.weth, self.reth]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.weth, self.reth)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        self.check_direction(token_address_from, token_address_to)?;
+
+        let state = RocketPoolStateReader::rocket_pool_state(state_db, self.rocket_storage)?;
+        let out_amount = state.deposit(in_amount)?;
+        if out_amount.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((out_amount, 200_000))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        self.check_direction(token_address_from, token_address_to)?;
+
+        let state = RocketPoolStateReader::rocket_pool_state(state_db, self.rocket_storage)?;
+        let in_amount = state.deposit_for(out_amount)?;
+        if in_amount.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((in_amount, 200_000))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+        for slot in RocketPoolStateReader::storage_slots() {
+            state_required.add_slot(self.rocket_storage, slot);
+        }
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct RocketEthAbiSwapEncoder {
+    #[allow(dead_code)]
+    pool_address: Address,
+}
+
+impl RocketEthAbiSwapEncoder {
+    pub fn new(pool_address: Address) -> Self {
+        Self { pool_address }
+    }
+}
+
+impl AbiSwapEncoder for RocketEthAbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount: U256,
+        _recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        Ok(Bytes::from(IRocketDepositPool::IRocketDepositPoolCalls::deposit(IRocketDepositPool::depositCall {}).abi_encode()))
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Base
+    }
+
+    fn is_native(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::BlockNumber;
+    use alloy_rpc_types::BlockId;
+    use loom_defi_abi::rocketpool::IRocketTokenRETH;
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use std::env;
+
+    #[tokio::test]
+    async fn test_calculate_out_amount() -> Result<()> {
+        let block_number = 20935488u64;
+
+        let node_url = env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(block_number)).await?;
+
+        let pool = RocketEthPool::fetch_mainnet(client.clone()).await?;
+        let state_update =
+            RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, Some(block_number)).await?;
+        let mut state_db = LoomDBType::default();
+        state_db.apply_geth_update(state_update);
+
+        let state = RocketPoolStateReader::rocket_pool_state(&state_db, PeripheryAddress::ROCKET_STORAGE)?;
+        let reth_contract = IRocketTokenRETH::new(TokenAddress::RETH, client.clone());
+        let amount_in = U256::from(10).pow(U256::from(18));
+
+        let contract_reth_value = reth_contract.getRethValue(amount_in).call().block(BlockId::from(block_number)).await?._0;
+        assert_eq!(state.reth_value(amount_in)?, contract_reth_value);
+
+        if state.deposit_enabled {
+            let (amount_out, gas_used) =
+                pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::RETH, amount_in)?;
+            assert!(amount_out <= contract_reth_value);
+            assert_eq!(gas_used, 200_000);
+
+            let (calculated_in, _) =
+                pool.calculate_in_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::RETH, amount_out)?;
+            let (calculated_out, _) =
+                pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::RETH, calculated_in)?;
+            assert!(calculated_out >= amount_out);
+        }
+        Ok(())
+    }
+}
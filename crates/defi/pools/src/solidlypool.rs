@@ -0,0 +1,282 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, Result};
+use loom_defi_abi::solidly::{ISolidlyPool, ISolidlyPoolFactory};
+use loom_defi_abi::IERC20;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{get_protocol_by_factory, AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::state_readers::SolidlyStateReader;
+use crate::virtual_impl::{SolidlyPoolState, SolidlyPoolVirtual};
+
+/// Solidly fork pool (Velodrome V2, Aerodrome) with a volatile `x*y=k` or a stable `x³y+y³x=k` invariant.
+/// The fee is set per pool by the factory in basis points.
+#[derive(Clone)]
+pub struct SolidlyPool {
+    address: Address,
+    token0: Address,
+    token1: Address,
+    factory: Address,
+    protocol: PoolProtocol,
+    stable: bool,
+    decimals0: U256,
+    decimals1: U256,
+    fee: U256,
+    encoder: SolidlyAbiSwapEncoder,
+}
+
+impl SolidlyPool {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_data(
+        address: Address,
+        token0: Address,
+        token1: Address,
+        factory: Address,
+        chain_id: u64,
+        stable: bool,
+        decimals0: U256,
+        decimals1: U256,
+        fee: U256,
+    ) -> Self {
+        SolidlyPool {
+            address,
+            token0,
+            token1,
+            factory,
+            protocol: Self::get_protocol_by_factory(chain_id, factory),
+            stable,
+            decimals0,
+            decimals1,
+            fee,
+            encoder: SolidlyAbiSwapEncoder {},
+        }
+    }
+
+    pub fn get_factory(&self) -> Address {
+        self.factory
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.stable
+    }
+
+    fn get_protocol_by_factory(chain_id: u64, factory_address: Address) -> PoolProtocol {
+        match get_protocol_by_factory(chain_id, factory_address) {
+            protocol @ (PoolProtocol::Velodrome | PoolProtocol::Aerodrome) => protocol,
+            _ => PoolProtocol::Solidly,
+        }
+    }
+
+    pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
+        let metadata = SolidlyStateReader::metadata(db, env.clone(), address)?;
+        let factory = SolidlyStateReader::factory(db, env.clone(), address)?;
+        let chain_id = env.cfg.chain_id;
+        let fee = SolidlyStateReader::get_fee(db, env, factory, address, metadata.st)?;
+
+        debug!("fetch_pool_data_evm {:?} {:?} {:?} stable={} fee={}", address, metadata.t0, metadata.t1, metadata.st, fee);
+        Ok(Self::new_with_data(address, metadata.t0, metadata.t1, factory, chain_id, metadata.st, metadata.dec0, metadata.dec1, fee))
+    }
+
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        address: Address,
+    ) -> Result<Self> {
+        let pool_contract = ISolidlyPool::new(address, client.clone());
+
+        let metadata = pool_contract.metadata().call().await?;
+        let factory = pool_contract.factory().call().await?._0;
+        let fee = ISolidlyPoolFactory::new(factory, client.clone()).getFee(address, metadata.st).call().await?._0;
+        let chain_id = client.get_chain_id().await?;
+
+        debug!("fetch_pool_data {:?} {:?} {:?} stable={} fee={}", address, metadata.t0, metadata.t1, metadata.st, fee);
+        Ok(Self::new_with_data(address, metadata.t0, metadata.t1, factory, chain_id, metadata.st, metadata.dec0, metadata.dec1, fee))
+    }
+
+    pub fn fetch_pool_state(&self, state_db: &LoomDBType, env: Env) -> Result<SolidlyPoolState> {
+        let (reserve0, reserve1) = SolidlyStateReader::get_reserves(state_db, env, self.address)?;
+        Ok(SolidlyPoolState {
+            reserve0,
+            reserve1,
+            decimals0: self.decimals0,
+            decimals1: self.decimals1,
+            stable: self.stable,
+            fee: self.fee,
+        })
+    }
+
+    fn gas_used(&self) -> u64 {
+        if self.stable {
+            180_000
+        } else {
+            120_000
+        }
+    }
+}
+
+impl Pool for SolidlyPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::Solidly
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        self.protocol
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_fee(&self) -> U256 {
+        self.fee
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token0, self.token1]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.token0, self.token1), (self.token1, self.token0)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let state = self.fetch_pool_state(state_db, env)?;
+        let out_amount = SolidlyPoolVirtual::calculate_out_amount(&state, *token_address_from == self.token0, in_amount)?;
+
+        if out_amount.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((out_amount, self.gas_used()))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let state = self.fetch_pool_state(state_db, env)?;
+        let in_amount = SolidlyPoolVirtual::calculate_in_amount(&state, *token_address_from == self.token0, out_amount)?;
+
+        if in_amount.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((in_amount, self.gas_used()))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+
+        state_required
+            .add_call(self.address, ISolidlyPool::getReservesCall {}.abi_encode())
+            .add_call(self.address, ISolidlyPool::getAmountOutCall { amountIn: U256::from(1), tokenIn: self.token0 }.abi_encode());
+
+        for token_address in self.get_tokens() {
+            state_required.add_call(token_address, IERC20::balanceOfCall { account: self.address }.abi_encode());
+        }
+
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct SolidlyAbiSwapEncoder {}
+
+impl AbiSwapEncoder for SolidlyAbiSwapEncoder {
+    fn encode_swap_out_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        let swap_call = if token_from_address < token_to_address {
+            ISolidlyPool::swapCall { amount0Out: U256::ZERO, amount1Out: amount, to: recipient, data: payload }
+        } else {
+            ISolidlyPool::swapCall { amount0Out: amount, amount1Out: U256::ZERO, to: recipient, data: payload }
+        };
+
+        Ok(Bytes::from(swap_call.abi_encode()))
+    }
+
+    // tokens are transferred to the pool by the swap encoder after the out amount was requested from the pool
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Base
+    }
+
+    fn swap_out_amount_offset(&self, token_from_address: Address, token_to_address: Address) -> Option<u32> {
+        if token_from_address < token_to_address {
+            Some(0x24)
+        } else {
+            Some(0x04)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{address, BlockNumber};
+    use alloy_rpc_types::BlockId;
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use std::env;
+
+    const POOL_ADDRESSES: [Address; 1] = [
+        address!("cdac0d6c6c59727a65f871236188350531885c43"), // Aerodrome WETH/USDC volatile pool
+    ];
+
+    #[tokio::test]
+    async fn test_calculate_out_amount() -> Result<()> {
+        let block_number = 21_000_000u64;
+
+        let node_url = env::var("BASE_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(block_number)).await?;
+
+        for pool_address in POOL_ADDRESSES {
+            let pool = SolidlyPool::fetch_pool_data(client.clone(), pool_address).await?;
+            let state_update =
+                RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, Some(block_number)).await?;
+            let mut state_db = LoomDBType::default();
+            state_db.apply_geth_update(state_update);
+
+            let pool_contract = ISolidlyPool::new(pool_address, client.clone());
+            let (reserve0, _) = SolidlyStateReader::get_reserves(&state_db, Env::default(), pool_address)?;
+            let amount_in = reserve0 / U256::from(1000);
+
+            let contract_amount_out =
+                pool_contract.getAmountOut(amount_in, pool.token0).call().block(BlockId::from(block_number)).await?._0;
+            let (amount_out, _) = pool.calculate_out_amount(&state_db, Env::default(), &pool.token0, &pool.token1, amount_in)?;
+            assert_eq!(amount_out, contract_amount_out, "{}", format!("Missmatch for pool={:?}, amount_in={}", pool_address, amount_in));
+
+            let (calculated_in, _) = pool.calculate_in_amount(&state_db, Env::default(), &pool.token0, &pool.token1, amount_out)?;
+            let (calculated_out, _) = pool.calculate_out_amount(&state_db, Env::default(), &pool.token0, &pool.token1, calculated_in)?;
+            assert!(calculated_out >= amount_out);
+        }
+        Ok(())
+    }
+}
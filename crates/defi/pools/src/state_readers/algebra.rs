@@ -0,0 +1,101 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use eyre::{eyre, Result};
+use revm::primitives::Env;
+
+use loom_defi_abi::algebra::IAlgebraPool;
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+// Algebra V1 pools have a constant tick spacing and no getter for it
+const DEFAULT_TICK_SPACING: u32 = 60;
+
+/// Price, tick and the direction dependent fees of an Algebra pool
+#[derive(Clone, Debug, Default)]
+pub struct AlgebraGlobalState {
+    pub price: U256,
+    pub tick: i32,
+    pub fee_zero_to_one: u32,
+    pub fee_one_to_zero: u32,
+}
+
+impl AlgebraGlobalState {
+    pub fn fee(&self, zero_for_one: bool) -> u32 {
+        if zero_for_one {
+            self.fee_zero_to_one
+        } else {
+            self.fee_one_to_zero
+        }
+    }
+}
+
+pub struct AlgebraStateReader {}
+
+impl AlgebraStateReader {
+    pub fn factory(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::factoryCall {}.abi_encode())?.0;
+        let call_return = IAlgebraPool::factoryCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token0(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::token0Call {}.abi_encode())?.0;
+        let call_return = IAlgebraPool::token0Call::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token1(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::token1Call {}.abi_encode())?.0;
+        let call_return = IAlgebraPool::token1Call::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn global_state(db: &LoomDBType, env: Env, pool: Address) -> Result<AlgebraGlobalState> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::globalStateCall {}.abi_encode())?.0;
+        Self::decode_global_state(&call_data_result)
+    }
+
+    /// Decodes `globalState` of Algebra V1 (one fee) and Camelot V3 (`feeZto`, `feeOtz`) pools from raw words
+    pub fn decode_global_state(call_data_result: &[u8]) -> Result<AlgebraGlobalState> {
+        if call_data_result.len() < 7 * 32 {
+            return Err(eyre!("BAD_GLOBAL_STATE"));
+        }
+        let word = |i: usize| &call_data_result[i * 32..(i + 1) * 32];
+
+        let price = U256::from_be_slice(word(0));
+        let tick = i32::from_be_bytes(word(1)[28..32].try_into()?);
+        let fee_zero_to_one = u16::from_be_bytes(word(2)[30..32].try_into()?) as u32;
+        let fee_one_to_zero =
+            if call_data_result.len() >= 8 * 32 { u16::from_be_bytes(word(3)[30..32].try_into()?) as u32 } else { fee_zero_to_one };
+
+        Ok(AlgebraGlobalState { price, tick, fee_zero_to_one, fee_one_to_zero })
+    }
+
+    pub fn liquidity(db: &LoomDBType, env: Env, pool: Address) -> Result<u128> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::liquidityCall {}.abi_encode())?.0;
+        let call_return = IAlgebraPool::liquidityCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn tick_spacing(db: &LoomDBType, env: Env, pool: Address) -> Result<u32> {
+        match evm_call(db, env, pool, IAlgebraPool::tickSpacingCall {}.abi_encode()) {
+            Ok((call_data_result, _)) => {
+                let call_return = IAlgebraPool::tickSpacingCall::abi_decode_returns(&call_data_result, false)?;
+                Ok(call_return._0.try_into()?)
+            }
+            Err(_) => Ok(DEFAULT_TICK_SPACING),
+        }
+    }
+
+    pub fn tick_table(db: &LoomDBType, env: Env, pool: Address, word_position: i16) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::tickTableCall { wordPosition: word_position }.abi_encode())?.0;
+        let call_return = IAlgebraPool::tickTableCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn ticks_liquidity_delta(db: &LoomDBType, env: Env, pool: Address, tick: i32) -> Result<i128> {
+        let call_data_result = evm_call(db, env, pool, IAlgebraPool::ticksCall { tick: tick.try_into()? }.abi_encode())?.0;
+        let call_return = IAlgebraPool::ticksCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return.liquidityDelta)
+    }
+}
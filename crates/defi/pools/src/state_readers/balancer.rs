@@ -0,0 +1,36 @@
+use alloy_primitives::{Address, B256, U256};
+use alloy_sol_types::SolCall;
+use eyre::Result;
+use revm::primitives::Env;
+
+use loom_defi_abi::balancer::{IBalancerPool, IBalancerStablePool, IVault};
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+pub struct BalancerStateReader {}
+
+impl BalancerStateReader {
+    pub fn get_pool_tokens(db: &LoomDBType, env: Env, vault: Address, pool_id: B256) -> Result<(Vec<Address>, Vec<U256>)> {
+        let call_data_result = evm_call(db, env, vault, IVault::getPoolTokensCall { poolId: pool_id }.abi_encode())?.0;
+        let call_return = IVault::getPoolTokensCall::abi_decode_returns(&call_data_result, false)?;
+        Ok((call_return.tokens, call_return.balances))
+    }
+
+    pub fn get_swap_fee_percentage(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, IBalancerPool::getSwapFeePercentageCall {}.abi_encode())?.0;
+        let call_return = IBalancerPool::getSwapFeePercentageCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn get_scaling_factors(db: &LoomDBType, env: Env, pool: Address) -> Result<Vec<U256>> {
+        let call_data_result = evm_call(db, env, pool, IBalancerPool::getScalingFactorsCall {}.abi_encode())?.0;
+        let call_return = IBalancerPool::getScalingFactorsCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn get_amplification_parameter(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, IBalancerStablePool::getAmplificationParameterCall {}.abi_encode())?.0;
+        let call_return = IBalancerStablePool::getAmplificationParameterCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return.value)
+    }
+}
@@ -0,0 +1,97 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use eyre::Result;
+use revm::primitives::Env;
+
+use loom_defi_abi::curve::{ICurveCommon, ICurveCommonI128, ICurveCryptoSwap2Params, ICurveCryptoSwapParams, ICurveStableSwapParams};
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+pub struct CurveStateReader {}
+
+impl CurveStateReader {
+    pub fn balance(db: &LoomDBType, env: Env, pool: Address, coin_id: usize) -> Result<U256> {
+        match evm_call(db, env.clone(), pool, ICurveCommon::balancesCall { _0: U256::from(coin_id) }.abi_encode()) {
+            Ok((call_data_result, _)) => Ok(ICurveCommon::balancesCall::abi_decode_returns(&call_data_result, false)?._0),
+            Err(_) => {
+                let call_data_result = evm_call(db, env, pool, ICurveCommonI128::balancesCall { _0: coin_id as i128 }.abi_encode())?.0;
+                Ok(ICurveCommonI128::balancesCall::abi_decode_returns(&call_data_result, false)?._0)
+            }
+        }
+    }
+
+    pub fn balances(db: &LoomDBType, env: Env, pool: Address, n_coins: usize) -> Result<Vec<U256>> {
+        (0..n_coins).map(|coin_id| Self::balance(db, env.clone(), pool, coin_id)).collect()
+    }
+
+    pub fn a(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveStableSwapParams::ACall {}.abi_encode())?.0;
+        let call_return = ICurveStableSwapParams::ACall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn a_precise(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveStableSwapParams::A_preciseCall {}.abi_encode())?.0;
+        let call_return = ICurveStableSwapParams::A_preciseCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn fee(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveStableSwapParams::feeCall {}.abi_encode())?.0;
+        let call_return = ICurveStableSwapParams::feeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn gamma(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveCryptoSwapParams::gammaCall {}.abi_encode())?.0;
+        let call_return = ICurveCryptoSwapParams::gammaCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn d(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveCryptoSwapParams::DCall {}.abi_encode())?.0;
+        let call_return = ICurveCryptoSwapParams::DCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn mid_fee(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveCryptoSwapParams::mid_feeCall {}.abi_encode())?.0;
+        let call_return = ICurveCryptoSwapParams::mid_feeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn out_fee(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveCryptoSwapParams::out_feeCall {}.abi_encode())?.0;
+        let call_return = ICurveCryptoSwapParams::out_feeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn fee_gamma(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveCryptoSwapParams::fee_gammaCall {}.abi_encode())?.0;
+        let call_return = ICurveCryptoSwapParams::fee_gammaCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn future_a_gamma_time(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, ICurveCryptoSwapParams::future_A_gamma_timeCall {}.abi_encode())?.0;
+        let call_return = ICurveCryptoSwapParams::future_A_gamma_timeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    /// Price scale of coins 1..N, two coin pools keep a single value
+    pub fn price_scale(db: &LoomDBType, env: Env, pool: Address, n_coins: usize) -> Result<Vec<U256>> {
+        if n_coins == 2 {
+            let call_data_result = evm_call(db, env, pool, ICurveCryptoSwap2Params::price_scaleCall {}.abi_encode())?.0;
+            let call_return = ICurveCryptoSwap2Params::price_scaleCall::abi_decode_returns(&call_data_result, false)?;
+            Ok(vec![call_return._0])
+        } else {
+            let mut ret = Vec::new();
+            for k in 0..n_coins - 1 {
+                let call_data_result =
+                    evm_call(db, env.clone(), pool, ICurveCryptoSwapParams::price_scaleCall { _0: U256::from(k) }.abi_encode())?.0;
+                ret.push(ICurveCryptoSwapParams::price_scaleCall::abi_decode_returns(&call_data_result, false)?._0);
+            }
+            Ok(ret)
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use eyre::Result;
+use revm::primitives::Env;
+
+use loom_defi_abi::erc4626::IERC4626;
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+pub struct Erc4626StateReader {}
+
+impl Erc4626StateReader {
+    pub fn asset(db: &LoomDBType, env: Env, vault: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::assetCall {}.abi_encode())?.0;
+        let call_return = IERC4626::assetCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn convert_to_shares(db: &LoomDBType, env: Env, vault: Address, assets: U256) -> Result<U256> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::convertToSharesCall { assets }.abi_encode())?.0;
+        let call_return = IERC4626::convertToSharesCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn convert_to_assets(db: &LoomDBType, env: Env, vault: Address, shares: U256) -> Result<U256> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::convertToAssetsCall { shares }.abi_encode())?.0;
+        let call_return = IERC4626::convertToAssetsCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn preview_deposit(db: &LoomDBType, env: Env, vault: Address, assets: U256) -> Result<U256> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::previewDepositCall { assets }.abi_encode())?.0;
+        let call_return = IERC4626::previewDepositCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn preview_mint(db: &LoomDBType, env: Env, vault: Address, shares: U256) -> Result<U256> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::previewMintCall { shares }.abi_encode())?.0;
+        let call_return = IERC4626::previewMintCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn preview_withdraw(db: &LoomDBType, env: Env, vault: Address, assets: U256) -> Result<U256> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::previewWithdrawCall { assets }.abi_encode())?.0;
+        let call_return = IERC4626::previewWithdrawCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn preview_redeem(db: &LoomDBType, env: Env, vault: Address, shares: U256) -> Result<U256> {
+        let call_data_result = evm_call(db, env, vault, IERC4626::previewRedeemCall { shares }.abi_encode())?.0;
+        let call_return = IERC4626::previewRedeemCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+}
@@ -0,0 +1,72 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use eyre::Result;
+use revm::primitives::Env;
+
+use loom_defi_abi::integral::{ITwapPair, ITwapRelayer};
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+pub struct IntegralStateReader {}
+
+impl IntegralStateReader {
+    pub fn factory(db: &LoomDBType, env: Env, pair: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pair, ITwapPair::factoryCall {}.abi_encode())?.0;
+        let call_return = ITwapPair::factoryCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token0(db: &LoomDBType, env: Env, pair: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pair, ITwapPair::token0Call {}.abi_encode())?.0;
+        let call_return = ITwapPair::token0Call::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token1(db: &LoomDBType, env: Env, pair: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pair, ITwapPair::token1Call {}.abi_encode())?.0;
+        let call_return = ITwapPair::token1Call::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn is_pair_enabled(db: &LoomDBType, env: Env, relayer: Address, pair: Address) -> Result<bool> {
+        let call_data_result = evm_call(db, env, relayer, ITwapRelayer::isPairEnabledCall { pair }.abi_encode())?.0;
+        let call_return = ITwapRelayer::isPairEnabledCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn quote_sell(
+        db: &LoomDBType,
+        env: Env,
+        relayer: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_in: U256,
+    ) -> Result<(U256, u64)> {
+        let (call_data_result, gas_used) = evm_call(
+            db,
+            env,
+            relayer,
+            ITwapRelayer::quoteSellCall { tokenIn: token_in, tokenOut: token_out, amountIn: amount_in }.abi_encode(),
+        )?;
+        let call_return = ITwapRelayer::quoteSellCall::abi_decode_returns(&call_data_result, false)?;
+        Ok((call_return.amountOut, gas_used))
+    }
+
+    pub fn quote_buy(
+        db: &LoomDBType,
+        env: Env,
+        relayer: Address,
+        token_in: Address,
+        token_out: Address,
+        amount_out: U256,
+    ) -> Result<(U256, u64)> {
+        let (call_data_result, gas_used) = evm_call(
+            db,
+            env,
+            relayer,
+            ITwapRelayer::quoteBuyCall { tokenIn: token_in, tokenOut: token_out, amountOut: amount_out }.abi_encode(),
+        )?;
+        let call_return = ITwapRelayer::quoteBuyCall::abi_decode_returns(&call_data_result, false)?;
+        Ok((call_return.amountIn, gas_used))
+    }
+}
@@ -0,0 +1,106 @@
+use alloy_primitives::{keccak256, Address, U256};
+use eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use loom_evm_db::LoomDBType;
+use revm::DatabaseRef;
+
+lazy_static! {
+    // Lido keeps its state in unstructured storage positions
+    static ref TOTAL_SHARES_POSITION: U256 = U256::from_be_bytes(keccak256("lido.StETH.totalShares").0);
+    static ref BUFFERED_ETHER_POSITION: U256 = U256::from_be_bytes(keccak256("lido.Lido.bufferedEther").0);
+    static ref CL_BALANCE_POSITION: U256 = U256::from_be_bytes(keccak256("lido.Lido.beaconBalance").0);
+    static ref CL_VALIDATORS_POSITION: U256 = U256::from_be_bytes(keccak256("lido.Lido.beaconValidators").0);
+    static ref DEPOSITED_VALIDATORS_POSITION: U256 = U256::from_be_bytes(keccak256("lido.Lido.depositedValidators").0);
+    static ref DEPOSIT_SIZE: U256 = U256::from(32_000_000_000_000_000_000u128);
+}
+
+/// Total pooled ether and total shares of stETH, all stETH and wstETH amounts are derived from them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LidoState {
+    pub total_pooled_ether: U256,
+    pub total_shares: U256,
+}
+
+impl LidoState {
+    pub fn new(total_pooled_ether: U256, total_shares: U256) -> Self {
+        Self { total_pooled_ether, total_shares }
+    }
+
+    pub fn shares_by_pooled_eth(&self, eth_amount: U256) -> Result<U256> {
+        if self.total_pooled_ether.is_zero() {
+            return Err(eyre!("ZERO_TOTAL_POOLED_ETHER"));
+        }
+        Ok(eth_amount.checked_mul(self.total_shares).ok_or(eyre!("SHARES_OVERFLOW"))? / self.total_pooled_ether)
+    }
+
+    pub fn pooled_eth_by_shares(&self, shares_amount: U256) -> Result<U256> {
+        if self.total_shares.is_zero() {
+            return Err(eyre!("ZERO_TOTAL_SHARES"));
+        }
+        Ok(shares_amount.checked_mul(self.total_pooled_ether).ok_or(eyre!("POOLED_ETH_OVERFLOW"))? / self.total_shares)
+    }
+
+    /// Smallest eth amount worth at least `shares_amount` shares
+    pub fn pooled_eth_by_shares_up(&self, shares_amount: U256) -> Result<U256> {
+        if self.total_shares.is_zero() {
+            return Err(eyre!("ZERO_TOTAL_SHARES"));
+        }
+        Ok(shares_amount.checked_mul(self.total_pooled_ether).ok_or(eyre!("POOLED_ETH_OVERFLOW"))?.div_ceil(self.total_shares))
+    }
+
+    /// Smallest shares amount worth at least `eth_amount`
+    pub fn shares_by_pooled_eth_up(&self, eth_amount: U256) -> Result<U256> {
+        if self.total_pooled_ether.is_zero() {
+            return Err(eyre!("ZERO_TOTAL_POOLED_ETHER"));
+        }
+        Ok(eth_amount.checked_mul(self.total_shares).ok_or(eyre!("SHARES_OVERFLOW"))?.div_ceil(self.total_pooled_ether))
+    }
+
+    /// State after submitting `eth_amount`, returns minted shares
+    pub fn submit(&self, eth_amount: U256) -> Result<(LidoState, U256)> {
+        let shares = self.shares_by_pooled_eth(eth_amount)?;
+        if shares.is_zero() {
+            return Err(eyre!("ZERO_SHARES_MINTED"));
+        }
+        Ok((LidoState::new(self.total_pooled_ether + eth_amount, self.total_shares + shares), shares))
+    }
+}
+
+pub struct LidoStateReader {}
+
+impl LidoStateReader {
+    /// Storage slots of stETH contract used to calculate total pooled ether and shares
+    pub fn storage_slots() -> Vec<U256> {
+        vec![
+            *TOTAL_SHARES_POSITION,
+            *BUFFERED_ETHER_POSITION,
+            *CL_BALANCE_POSITION,
+            *CL_VALIDATORS_POSITION,
+            *DEPOSITED_VALIDATORS_POSITION,
+        ]
+    }
+
+    pub fn total_shares(db: &LoomDBType, steth: Address) -> Result<U256> {
+        Ok(db.storage_ref(steth, *TOTAL_SHARES_POSITION)?)
+    }
+
+    /// Buffered ether + consensus layer balance + ether of deposited validators not yet seen on consensus layer
+    pub fn total_pooled_ether(db: &LoomDBType, steth: Address) -> Result<U256> {
+        let buffered_ether = db.storage_ref(steth, *BUFFERED_ETHER_POSITION)?;
+        let cl_balance = db.storage_ref(steth, *CL_BALANCE_POSITION)?;
+        let cl_validators = db.storage_ref(steth, *CL_VALIDATORS_POSITION)?;
+        let deposited_validators = db.storage_ref(steth, *DEPOSITED_VALIDATORS_POSITION)?;
+
+        let transient_validators = deposited_validators.checked_sub(cl_validators).ok_or(eyre!("CL_VALIDATORS_EXCEEDED"))?;
+        let transient_balance = transient_validators.checked_mul(*DEPOSIT_SIZE).ok_or(eyre!("TRANSIENT_BALANCE_OVERFLOW"))?;
+
+        buffered_ether
+            .checked_add(cl_balance)
+            .and_then(|value| value.checked_add(transient_balance))
+            .ok_or(eyre!("TOTAL_POOLED_ETHER_OVERFLOW"))
+    }
+
+    pub fn lido_state(db: &LoomDBType, steth: Address) -> Result<LidoState> {
+        Ok(LidoState::new(Self::total_pooled_ether(db, steth)?, Self::total_shares(db, steth)?))
+    }
+}
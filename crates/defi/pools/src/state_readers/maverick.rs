@@ -0,0 +1,54 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use eyre::Result;
+use revm::primitives::Env;
+
+use loom_defi_abi::maverick::IMaverickPool;
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+pub struct MaverickStateReader {}
+
+impl MaverickStateReader {
+    pub fn token_a(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::tokenACall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::tokenACall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token_b(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::tokenBCall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::tokenBCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn fee(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::feeCall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::feeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn tick_spacing(db: &LoomDBType, env: Env, pool: Address) -> Result<u32> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::tickSpacingCall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::tickSpacingCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0.try_into()?)
+    }
+
+    pub fn factory(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::factoryCall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::factoryCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token_a_scale(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::tokenAScaleCall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::tokenAScaleCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn token_b_scale(db: &LoomDBType, env: Env, pool: Address) -> Result<U256> {
+        let call_data_result = evm_call(db, env, pool, IMaverickPool::tokenBScaleCall {}.abi_encode())?.0;
+        let call_return = IMaverickPool::tokenBScaleCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+}
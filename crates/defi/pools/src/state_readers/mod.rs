@@ -1,10 +1,28 @@
+pub use algebra::{AlgebraGlobalState, AlgebraStateReader};
+pub use balancer::BalancerStateReader;
+pub use curve::CurveStateReader;
 pub use erc20::ERC20StateReader;
+pub use erc4626::Erc4626StateReader;
+pub use integral::IntegralStateReader;
+pub use lido::{LidoState, LidoStateReader};
+pub use maverick::MaverickStateReader;
+pub use rocketpool::{RocketPoolState, RocketPoolStateReader};
+pub use solidly::SolidlyStateReader;
 pub use uniswapv2::UniswapV2StateReader;
 pub use uniswapv3::UniswapV3StateReader;
 pub use uniswapv3_quoter::{UniswapV3QuoterV2Encoder, UniswapV3QuoterV2StateReader};
 
+mod algebra;
+mod balancer;
+mod curve;
+mod integral;
+mod lido;
+mod maverick;
+mod rocketpool;
+mod solidly;
 mod uniswapv2;
 mod uniswapv3;
 
 mod erc20;
+mod erc4626;
 pub mod uniswapv3_quoter;
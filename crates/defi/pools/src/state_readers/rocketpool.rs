@@ -0,0 +1,118 @@
+use alloy_primitives::{keccak256, Address, B256, U256};
+use eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use loom_evm_db::LoomDBType;
+use revm::DatabaseRef;
+
+// Storage slots of RocketStorage mappings
+const UINT_STORAGE_SLOT: u64 = 2;
+const BOOLEAN_STORAGE_SLOT: u64 = 5;
+
+lazy_static! {
+    static ref TOTAL_ETH_BALANCE_KEY: B256 = keccak256("network.balance.total");
+    static ref TOTAL_RETH_SUPPLY_KEY: B256 = keccak256("network.balance.reth.supply");
+    static ref DEPOSIT_SETTINGS_NAMESPACE: B256 = keccak256("dao.protocol.setting.deposit");
+    static ref DEPOSIT_ENABLED_KEY: B256 = RocketPoolStateReader::setting_key("deposit.enabled");
+    static ref DEPOSIT_MINIMUM_KEY: B256 = RocketPoolStateReader::setting_key("deposit.minimum");
+    static ref DEPOSIT_FEE_KEY: B256 = RocketPoolStateReader::setting_key("deposit.fee");
+    static ref DEPOSIT_POOL_KEY: B256 = keccak256([b"contract.address".as_slice(), b"rocketDepositPool".as_slice()].concat());
+    static ref CALC_BASE: U256 = U256::from(1_000_000_000_000_000_000u128);
+}
+
+/// Network balances and deposit settings used by RocketDepositPool.deposit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RocketPoolState {
+    pub total_eth_balance: U256,
+    pub total_reth_supply: U256,
+    pub deposit_enabled: bool,
+    pub deposit_minimum: U256,
+    pub deposit_fee: U256,
+}
+
+impl RocketPoolState {
+    pub fn reth_value(&self, eth_amount: U256) -> Result<U256> {
+        if self.total_eth_balance.is_zero() || self.total_reth_supply.is_zero() {
+            return Ok(eth_amount);
+        }
+        Ok(eth_amount.checked_mul(self.total_reth_supply).ok_or(eyre!("RETH_VALUE_OVERFLOW"))? / self.total_eth_balance)
+    }
+
+    pub fn eth_value(&self, reth_amount: U256) -> Result<U256> {
+        if self.total_reth_supply.is_zero() {
+            return Ok(reth_amount);
+        }
+        Ok(reth_amount.checked_mul(self.total_eth_balance).ok_or(eyre!("ETH_VALUE_OVERFLOW"))? / self.total_reth_supply)
+    }
+
+    /// rETH minted for deposit of `eth_amount`
+    pub fn deposit(&self, eth_amount: U256) -> Result<U256> {
+        if !self.deposit_enabled {
+            return Err(eyre!("DEPOSITS_DISABLED"));
+        }
+        if eth_amount < self.deposit_minimum {
+            return Err(eyre!("DEPOSIT_BELOW_MINIMUM"));
+        }
+        let fee = eth_amount.checked_mul(self.deposit_fee).ok_or(eyre!("DEPOSIT_FEE_OVERFLOW"))? / *CALC_BASE;
+        self.reth_value(eth_amount - fee)
+    }
+
+    /// Smallest deposit minting at least `reth_amount`
+    pub fn deposit_for(&self, reth_amount: U256) -> Result<U256> {
+        let net_amount = if self.total_eth_balance.is_zero() || self.total_reth_supply.is_zero() {
+            reth_amount
+        } else {
+            reth_amount.checked_mul(self.total_eth_balance).ok_or(eyre!("ETH_VALUE_OVERFLOW"))?.div_ceil(self.total_reth_supply)
+        };
+        if self.deposit_fee >= *CALC_BASE {
+            return Err(eyre!("DEPOSIT_FEE_EXCEEDED"));
+        }
+        let eth_amount = net_amount.checked_mul(*CALC_BASE).ok_or(eyre!("DEPOSIT_OVERFLOW"))?.div_ceil(*CALC_BASE - self.deposit_fee);
+        Ok(eth_amount.max(self.deposit_minimum))
+    }
+}
+
+pub struct RocketPoolStateReader {}
+
+impl RocketPoolStateReader {
+    fn setting_key(path: &str) -> B256 {
+        keccak256([DEPOSIT_SETTINGS_NAMESPACE.as_slice(), path.as_bytes()].concat())
+    }
+
+    fn mapping_slot(key: B256, slot: u64) -> U256 {
+        U256::from_be_bytes(keccak256([key.as_slice(), U256::from(slot).to_be_bytes::<32>().as_slice()].concat()).0)
+    }
+
+    /// Key of RocketDepositPool address in RocketStorage
+    pub fn deposit_pool_key() -> B256 {
+        *DEPOSIT_POOL_KEY
+    }
+
+    /// RocketStorage slots used to calculate deposits
+    pub fn storage_slots() -> Vec<U256> {
+        vec![
+            Self::mapping_slot(*TOTAL_ETH_BALANCE_KEY, UINT_STORAGE_SLOT),
+            Self::mapping_slot(*TOTAL_RETH_SUPPLY_KEY, UINT_STORAGE_SLOT),
+            Self::mapping_slot(*DEPOSIT_MINIMUM_KEY, UINT_STORAGE_SLOT),
+            Self::mapping_slot(*DEPOSIT_FEE_KEY, UINT_STORAGE_SLOT),
+            Self::mapping_slot(*DEPOSIT_ENABLED_KEY, BOOLEAN_STORAGE_SLOT),
+        ]
+    }
+
+    fn get_uint(db: &LoomDBType, rocket_storage: Address, key: B256) -> Result<U256> {
+        Ok(db.storage_ref(rocket_storage, Self::mapping_slot(key, UINT_STORAGE_SLOT))?)
+    }
+
+    fn get_bool(db: &LoomDBType, rocket_storage: Address, key: B256) -> Result<bool> {
+        Ok(!db.storage_ref(rocket_storage, Self::mapping_slot(key, BOOLEAN_STORAGE_SLOT))?.is_zero())
+    }
+
+    pub fn rocket_pool_state(db: &LoomDBType, rocket_storage: Address) -> Result<RocketPoolState> {
+        Ok(RocketPoolState {
+            total_eth_balance: Self::get_uint(db, rocket_storage, *TOTAL_ETH_BALANCE_KEY)?,
+            total_reth_supply: Self::get_uint(db, rocket_storage, *TOTAL_RETH_SUPPLY_KEY)?,
+            deposit_enabled: Self::get_bool(db, rocket_storage, *DEPOSIT_ENABLED_KEY)?,
+            deposit_minimum: Self::get_uint(db, rocket_storage, *DEPOSIT_MINIMUM_KEY)?,
+            deposit_fee: Self::get_uint(db, rocket_storage, *DEPOSIT_FEE_KEY)?,
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::SolCall;
+use eyre::Result;
+use revm::primitives::Env;
+
+use loom_defi_abi::solidly::{ISolidlyPool, ISolidlyPoolFactory};
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_call;
+
+pub struct SolidlyStateReader {}
+
+impl SolidlyStateReader {
+    pub fn factory(db: &LoomDBType, env: Env, pool: Address) -> Result<Address> {
+        let call_data_result = evm_call(db, env, pool, ISolidlyPool::factoryCall {}.abi_encode())?.0;
+        let call_return = ISolidlyPool::factoryCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    pub fn metadata(db: &LoomDBType, env: Env, pool: Address) -> Result<ISolidlyPool::metadataReturn> {
+        let call_data_result = evm_call(db, env, pool, ISolidlyPool::metadataCall {}.abi_encode())?.0;
+        Ok(ISolidlyPool::metadataCall::abi_decode_returns(&call_data_result, false)?)
+    }
+
+    pub fn get_reserves(db: &LoomDBType, env: Env, pool: Address) -> Result<(U256, U256)> {
+        let call_data_result = evm_call(db, env, pool, ISolidlyPool::getReservesCall {}.abi_encode())?.0;
+        let call_return = ISolidlyPool::getReservesCall::abi_decode_returns(&call_data_result, false)?;
+        Ok((call_return.reserve0, call_return.reserve1))
+    }
+
+    pub fn get_fee(db: &LoomDBType, env: Env, factory: Address, pool: Address, stable: bool) -> Result<U256> {
+        let call_data_result = evm_call(db, env, factory, ISolidlyPoolFactory::getFeeCall { pool, stable }.abi_encode())?.0;
+        let call_return = ISolidlyPoolFactory::getFeeCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+}
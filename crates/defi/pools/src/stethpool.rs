@@ -0,0 +1,196 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolInterface;
+use eyre::{eyre, ErrReport, Result};
+use loom_defi_abi::lido::IStEth;
+use loom_defi_address_book::TokenAddress;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+
+use crate::state_readers::LidoStateReader;
+
+/// Lido stETH submit. ETH is staked with the stETH contract which mints shares, so swaps are one way WETH -> stETH
+#[derive(Clone)]
+pub struct StEthPool {
+    address: Address,
+    weth: Address,
+    encoder: StEthAbiSwapEncoder,
+}
+
+impl StEthPool {
+    pub fn new(address: Address, weth: Address) -> Self {
+        Self { address, weth, encoder: StEthAbiSwapEncoder::new(address) }
+    }
+
+    pub fn mainnet() -> Self {
+        Self::new(TokenAddress::STETH, TokenAddress::WETH)
+    }
+
+    fn check_direction(&self, token_address_from: &Address, token_address_to: &Address) -> Result<()> {
+        if *token_address_from == self.weth && *token_address_to == self.address {
+            Ok(())
+        } else {
+            Err(eyre!("DIRECTION_NOT_SUPPORTED"))
+        }
+    }
+}
+
+impl Pool for StEthPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::LidoStEth
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::LidoStEth
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.weth, self.address]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.weth, self.address)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        self.check_direction(token_address_from, token_address_to)?;
+
+        let state = LidoStateReader::lido_state(state_db, self.address)?;
+        let (state_after, shares) = state.submit(in_amount)?;
+        let out_amount = state_after.pooled_eth_by_shares(shares)?;
+        if out_amount.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((out_amount, 90_000))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        self.check_direction(token_address_from, token_address_to)?;
+
+        let state = LidoStateReader::lido_state(state_db, self.address)?;
+        // submit keeps share rate, one extra share covers rounding of balanceOf
+        let shares = state.shares_by_pooled_eth_up(out_amount)? + U256::from(1);
+        let in_amount = state.pooled_eth_by_shares_up(shares)?;
+        if in_amount.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((in_amount, 90_000))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+        for slot in LidoStateReader::storage_slots() {
+            state_required.add_slot(self.address, slot);
+        }
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct StEthAbiSwapEncoder {
+    pool_address: Address,
+}
+
+impl StEthAbiSwapEncoder {
+    pub fn new(pool_address: Address) -> Self {
+        Self { pool_address }
+    }
+}
+
+impl AbiSwapEncoder for StEthAbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        _token_from_address: Address,
+        _token_to_address: Address,
+        _amount: U256,
+        _recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        Ok(Bytes::from(IStEth::IStEthCalls::submit(IStEth::submitCall { _referral: Address::ZERO }).abi_encode()))
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Base
+    }
+
+    fn is_native(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::BlockNumber;
+    use alloy_rpc_types::BlockId;
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use std::env;
+
+    #[tokio::test]
+    async fn test_calculate_out_amount() -> Result<()> {
+        let block_number = 20935488u64;
+
+        let node_url = env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(block_number)).await?;
+
+        let pool = StEthPool::mainnet();
+        let state_update =
+            RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, Some(block_number)).await?;
+        let mut state_db = LoomDBType::default();
+        state_db.apply_geth_update(state_update);
+
+        let steth_contract = IStEth::new(TokenAddress::STETH, client.clone());
+        let total_pooled_ether = steth_contract.getTotalPooledEther().call().block(BlockId::from(block_number)).await?._0;
+        let total_shares = steth_contract.getTotalShares().call().block(BlockId::from(block_number)).await?._0;
+        assert_eq!(LidoStateReader::total_pooled_ether(&state_db, TokenAddress::STETH)?, total_pooled_ether);
+        assert_eq!(LidoStateReader::total_shares(&state_db, TokenAddress::STETH)?, total_shares);
+
+        let amount_in = U256::from(10).pow(U256::from(18));
+        let shares = steth_contract.getSharesByPooledEth(amount_in).call().block(BlockId::from(block_number)).await?._0;
+        let expected_out = shares * (total_pooled_ether + amount_in) / (total_shares + shares);
+
+        let (amount_out, gas_used) =
+            pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::STETH, amount_in)?;
+        assert_eq!(amount_out, expected_out);
+        assert_eq!(gas_used, 90_000);
+
+        let (calculated_in, _) =
+            pool.calculate_in_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::STETH, amount_out)?;
+        let (calculated_out, _) =
+            pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::STETH, calculated_in)?;
+        assert!(calculated_out >= amount_out);
+
+        assert!(pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::STETH, &TokenAddress::WETH, amount_in).is_err());
+        Ok(())
+    }
+}
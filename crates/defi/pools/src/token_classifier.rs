@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::{address, Address, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::SolCall;
+use alloy_transport::Transport;
+use eyre::{eyre, Result};
+use loom_defi_abi::lido::IStEth;
+use loom_defi_abi::IERC20;
+use loom_evm_db::{LoomDB, LoomDBType};
+use loom_evm_utils::evm::evm_transact;
+use loom_evm_utils::evm_env::env_for_block;
+use loom_types_entities::{Token, TransferTax, TAX_DENOMINATOR};
+use revm::primitives::{AccountInfo, Bytecode, Env, TransactTo, CANCUN, KECCAK_EMPTY};
+use revm::{Database, Evm};
+use tracing::debug;
+
+// accounts without code that receive the bought tokens
+const BUYER_ADDRESS: Address = address!("9e2a6a4b42e0b1c9d6e6a2b0e1e3b5a8c4d7f101");
+const HOLDER_ADDRESS: Address = address!("4c3b7a1e0d2f6e8b9a5c1d3e7f2b4a6c8e0d9f02");
+
+// share of the pair balance that is bought, small enough to stay below max transaction limits
+const BUY_SHARE_DIVIDER: u64 = 1000;
+
+const REBASE_CHECK_SECONDS: u64 = 86400;
+const REBASE_CHECK_BLOCKS: u64 = 7200;
+
+/// Transfer behavior of a token found by the [`TokenClassifier`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenClassification {
+    pub tax: TransferTax,
+    pub rebasing: bool,
+    pub honeypot: bool,
+}
+
+impl TokenClassification {
+    /// Set the taxes and flags of the classification on the token
+    pub fn apply(&self, token: &mut Token) {
+        token.set_tax(self.tax);
+        if self.rebasing {
+            token.set_rebasing();
+        }
+        if self.honeypot {
+            token.set_honeypot();
+        }
+    }
+
+    // A token is a honeypot if any transfer failed, taxes are the share lost by selling to and buying from the pair
+    fn from_transfers(transfers: &TransferAmounts) -> Self {
+        let (Some(bought), Some(held), Some(sold)) = (transfers.bought, transfers.held, transfers.sold) else {
+            return Self { honeypot: true, rebasing: transfers.rebasing, ..Self::default() };
+        };
+        Self { tax: TransferTax::new(tax(held, sold), tax(transfers.sent, bought)), rebasing: transfers.rebasing, honeypot: false }
+    }
+}
+
+// Amounts received by the simulated transfers, None if the transfer failed or was not simulated
+#[derive(Clone, Copy, Debug, Default)]
+struct TransferAmounts {
+    sent: U256,
+    bought: Option<U256>,
+    held: Option<U256>,
+    sold: Option<U256>,
+    rebasing: bool,
+}
+
+/// Classifications of tokens by address. Verdicts can be saved to and loaded from a file to skip the simulations on a cold start.
+/// Tokens failed to be classified are kept as unknown for the process lifetime and are not saved.
+#[derive(Debug, Default)]
+pub struct TokenClassificationCache {
+    verdicts: RwLock<HashMap<Address, TokenClassification>>,
+    unknown: RwLock<HashSet<Address>>,
+}
+
+impl TokenClassificationCache {
+    pub fn get(&self, token: &Address) -> Option<TokenClassification> {
+        self.verdicts.read().ok()?.get(token).copied()
+    }
+
+    pub fn insert(&self, token: Address, classification: TokenClassification) {
+        if let Ok(mut verdicts) = self.verdicts.write() {
+            verdicts.insert(token, classification);
+        }
+    }
+
+    pub fn is_unknown(&self, token: &Address) -> bool {
+        self.unknown.read().is_ok_and(|unknown| unknown.contains(token))
+    }
+
+    pub fn insert_unknown(&self, token: Address) {
+        if let Ok(mut unknown) = self.unknown.write() {
+            unknown.insert(token);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.verdicts.read().map_or(0, |verdicts| verdicts.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Load verdicts from a file written by [`TokenClassificationCache::save`], returns the number of loaded verdicts
+    pub fn load<F: AsRef<Path>>(&self, path: F) -> Result<usize> {
+        let content = fs::read_to_string(path)?;
+        let verdicts: Vec<(Address, TokenClassification)> = content.lines().filter_map(parse_verdict).collect();
+        let loaded = verdicts.len();
+        self.verdicts.write().map_err(|_| eyre!("CACHE_LOCK_POISONED"))?.extend(verdicts);
+        Ok(loaded)
+    }
+
+    /// Save the verdicts to a file, one token per line
+    pub fn save<F: AsRef<Path>>(&self, path: F) -> Result<()> {
+        let content: String = self
+            .verdicts
+            .read()
+            .map_err(|_| eyre!("CACHE_LOCK_POISONED"))?
+            .iter()
+            .map(|(token, classification)| format_verdict(token, classification) + "\n")
+            .collect();
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+// token,tax_in,tax_out,rebasing,honeypot
+fn format_verdict(token: &Address, classification: &TokenClassification) -> String {
+    format!(
+        "{:#x},{},{},{},{}",
+        token, classification.tax.tax_in, classification.tax.tax_out, classification.rebasing, classification.honeypot
+    )
+}
+
+fn parse_verdict(line: &str) -> Option<(Address, TokenClassification)> {
+    let mut fields = line.trim().split(',');
+    let token = Address::from_str(fields.next()?).ok()?;
+    let tax_in = fields.next()?.parse().ok()?;
+    let tax_out = fields.next()?.parse().ok()?;
+    let rebasing = fields.next()?.parse().ok()?;
+    let honeypot = fields.next()?.parse().ok()?;
+    Some((token, TokenClassification { tax: TransferTax::new(tax_in, tax_out), rebasing, honeypot }))
+}
+
+fn tax(sent: U256, received: U256) -> u32 {
+    if sent.is_zero() {
+        return 0;
+    }
+    let tax = sent.saturating_sub(received) * U256::from(TAX_DENOMINATOR) / sent;
+    tax.to::<u32>()
+}
+
+/// Simulates buying a token from a pair, a wallet transfer and selling it back to the pair to find transfer taxes,
+/// rebasing balances and tokens that can't be sold. The pair is impersonated for the buy by removing its code in the simulation.
+pub struct TokenClassifier {}
+
+impl TokenClassifier {
+    pub async fn classify<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        token: Address,
+        pair: Address,
+    ) -> Result<TokenClassification> {
+        let block_number = client.get_block_number().await?;
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        let db = LoomDB::new_with_ro_db_and_provider(None, client)?;
+
+        Self::classify_evm(db, env_for_block(block_number, timestamp), token, pair)
+    }
+
+    /// Classify the token if it has no verdict in the cache yet and store the verdict. Tokens failed to be classified are
+    /// stored as unknown and not simulated again, None is returned for them.
+    pub async fn classify_cached<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        cache: &TokenClassificationCache,
+        client: P,
+        token: Address,
+        pair: Address,
+    ) -> Option<TokenClassification> {
+        if let Some(classification) = cache.get(&token) {
+            return Some(classification);
+        }
+        if cache.is_unknown(&token) {
+            return None;
+        }
+        match Self::classify(client, token, pair).await {
+            Ok(classification) => {
+                cache.insert(token, classification);
+                Some(classification)
+            }
+            Err(e) => {
+                debug!("Token {:#20x} not classified with pair {:#20x} : {}", token, pair, e);
+                cache.insert_unknown(token);
+                None
+            }
+        }
+    }
+
+    pub fn classify_evm(mut db: LoomDBType, env: Env, token: Address, pair: Address) -> Result<TokenClassification> {
+        let pair_info = db.basic(pair)?.ok_or(eyre!("PAIR_NOT_FOUND"))?;
+        db.insert_account_info(pair, AccountInfo { code: Some(Bytecode::default()), code_hash: KECCAK_EMPTY, ..pair_info.clone() });
+
+        let mut evm = Evm::builder().with_spec_id(CANCUN).with_db(db).with_env(Box::new(env)).build();
+
+        let amount = Self::balance_of(&mut evm, token, pair)? / U256::from(BUY_SHARE_DIVIDER);
+        if amount.is_zero() {
+            return Err(eyre!("PAIR_BALANCE_TOO_LOW"));
+        }
+
+        let transfers = Self::simulate_transfers(&mut evm, token, pair, pair_info, amount)?;
+        let classification = TokenClassification::from_transfers(&transfers);
+
+        debug!("Token {:#20x} classified with pair {:#20x} : {:?}", token, pair, classification);
+
+        Ok(classification)
+    }
+
+    // Buys from the pair, transfers between wallets and sells back to the pair, stops at the first failed transfer
+    fn simulate_transfers(
+        evm: &mut Evm<(), LoomDBType>,
+        token: Address,
+        pair: Address,
+        pair_info: AccountInfo,
+        amount: U256,
+    ) -> Result<TransferAmounts> {
+        let mut transfers = TransferAmounts { sent: amount, ..TransferAmounts::default() };
+
+        transfers.bought = Self::transfer(evm, token, pair, BUYER_ADDRESS, amount)?;
+        let Some(bought) = transfers.bought else {
+            return Ok(transfers);
+        };
+
+        // taxed tokens may swap collected taxes through the pair on sells, so the pair code is restored
+        evm.context.evm.db.insert_account_info(pair, pair_info);
+
+        transfers.rebasing = Self::is_rebasing(evm, token, BUYER_ADDRESS)?;
+
+        transfers.held = Self::transfer(evm, token, BUYER_ADDRESS, HOLDER_ADDRESS, bought)?;
+        let Some(held) = transfers.held else {
+            return Ok(transfers);
+        };
+
+        transfers.sold = Self::transfer(evm, token, HOLDER_ADDRESS, pair, held)?;
+        Ok(transfers)
+    }
+
+    // Transfers tokens and returns the amount received, None if the transfer failed or nothing was received
+    fn transfer(evm: &mut Evm<(), LoomDBType>, token: Address, from: Address, to: Address, amount: U256) -> Result<Option<U256>> {
+        let balance_before = Self::balance_of(evm, token, to)?;
+
+        let call_data = IERC20::transferCall { to, amount }.abi_encode();
+        if let Err(e) = Self::transact(evm, from, token, call_data) {
+            debug!("Token {:#20x} transfer from {:#20x} failed : {}", token, from, e);
+            return Ok(None);
+        }
+
+        let received = Self::balance_of(evm, token, to)?.saturating_sub(balance_before);
+        Ok(if received.is_zero() { None } else { Some(received) })
+    }
+
+    fn is_rebasing(evm: &mut Evm<(), LoomDBType>, token: Address, account: Address) -> Result<bool> {
+        let balance = Self::balance_of(evm, token, account)?;
+
+        let block = evm.context.evm.env.block.clone();
+        evm.context.evm.env.block.timestamp += U256::from(REBASE_CHECK_SECONDS);
+        evm.context.evm.env.block.number += U256::from(REBASE_CHECK_BLOCKS);
+        let later_balance = Self::balance_of(evm, token, account);
+        evm.context.evm.env.block = block;
+
+        // share based tokens like stETH rebase on oracle reports, their balances differ from the held shares
+        let shares = Self::transact(evm, Address::ZERO, token, IStEth::sharesOfCall { _account: account }.abi_encode())
+            .ok()
+            .and_then(|output| IStEth::sharesOfCall::abi_decode_returns(&output, false).ok())
+            .map(|shares| shares._0);
+
+        Ok(is_rebasing_balance(balance, later_balance?, shares))
+    }
+
+    fn balance_of(evm: &mut Evm<(), LoomDBType>, token: Address, account: Address) -> Result<U256> {
+        let call_data_result = Self::transact(evm, Address::ZERO, token, IERC20::balanceOfCall { account }.abi_encode())?;
+        let call_return = IERC20::balanceOfCall::abi_decode_returns(&call_data_result, false)?;
+        Ok(call_return._0)
+    }
+
+    fn transact(evm: &mut Evm<(), LoomDBType>, caller: Address, to: Address, call_data: Vec<u8>) -> Result<Vec<u8>> {
+        let tx = &mut evm.context.evm.env.tx;
+        tx.caller = caller;
+        tx.transact_to = TransactTo::Call(to);
+        tx.data = call_data.into();
+        tx.gas_limit = 1_000_000;
+        tx.gas_price = U256::ZERO;
+
+        Ok(evm_transact(evm)?.0)
+    }
+}
+
+// Balances growing with time or differing from the held shares are rebasing
+fn is_rebasing_balance(balance: U256, later_balance: U256, shares: Option<U256>) -> bool {
+    later_balance != balance || shares.is_some_and(|shares| !shares.is_zero() && shares != balance)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn transfers(sent: u64, bought: Option<u64>, held: Option<u64>, sold: Option<u64>) -> TransferAmounts {
+        TransferAmounts {
+            sent: U256::from(sent),
+            bought: bought.map(U256::from),
+            held: held.map(U256::from),
+            sold: sold.map(U256::from),
+            rebasing: false,
+        }
+    }
+
+    #[test]
+    fn test_untaxed_token() {
+        let classification = TokenClassification::from_transfers(&transfers(1000, Some(1000), Some(1000), Some(1000)));
+
+        assert_eq!(classification, TokenClassification::default());
+    }
+
+    #[test]
+    fn test_taxed_token() {
+        // 5% buy tax and 10% sell tax
+        let classification = TokenClassification::from_transfers(&transfers(1000, Some(950), Some(950), Some(855)));
+
+        assert!(!classification.honeypot);
+        assert_eq!(classification.tax, TransferTax::new(1000, 500));
+    }
+
+    #[test]
+    fn test_honeypot_token() {
+        assert!(TokenClassification::from_transfers(&transfers(1000, None, None, None)).honeypot);
+        assert!(TokenClassification::from_transfers(&transfers(1000, Some(1000), None, None)).honeypot);
+        assert!(TokenClassification::from_transfers(&transfers(1000, Some(1000), Some(1000), None)).honeypot);
+    }
+
+    #[test]
+    fn test_rebasing_balance() {
+        let balance = U256::from(1000);
+
+        assert!(!is_rebasing_balance(balance, balance, None));
+        assert!(is_rebasing_balance(balance, U256::from(1001), None));
+        // stETH like token holding fewer shares than its balance
+        assert!(is_rebasing_balance(balance, balance, Some(U256::from(870))));
+        assert!(!is_rebasing_balance(balance, balance, Some(balance)));
+    }
+
+    #[test]
+    fn test_tax() {
+        assert_eq!(tax(U256::from(1000), U256::from(1000)), 0);
+        assert_eq!(tax(U256::from(1000), U256::from(990)), 100);
+        assert_eq!(tax(U256::from(1000), U256::ZERO), TAX_DENOMINATOR);
+        assert_eq!(tax(U256::ZERO, U256::ZERO), 0);
+    }
+
+    #[test]
+    fn test_cache_save_and_load() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("token_classification_{}.csv", Address::random()));
+        let taxed_token = Address::random();
+        let honeypot_token = Address::random();
+
+        let cache = TokenClassificationCache::default();
+        let taxed = TokenClassification { tax: TransferTax::new(300, 200), rebasing: true, honeypot: false };
+        let honeypot = TokenClassification { honeypot: true, ..TokenClassification::default() };
+        cache.insert(taxed_token, taxed);
+        cache.insert(honeypot_token, honeypot);
+        let unknown_token = Address::random();
+        cache.insert_unknown(unknown_token);
+        assert!(cache.is_unknown(&unknown_token));
+        cache.save(&path)?;
+
+        let loaded_cache = TokenClassificationCache::default();
+        let loaded = loaded_cache.load(&path);
+        fs::remove_file(&path)?;
+
+        assert_eq!(loaded?, 2);
+        assert_eq!(loaded_cache.get(&taxed_token), Some(taxed));
+        assert_eq!(loaded_cache.get(&honeypot_token), Some(honeypot));
+        assert_eq!(loaded_cache.get(&Address::random()), None);
+        // unknown tokens are simulated again after a restart
+        assert!(!loaded_cache.is_unknown(&unknown_token));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_verdict_rejects_malformed_lines() {
+        assert!(parse_verdict("").is_none());
+        assert!(parse_verdict("0x1234,0,0,false,false").is_none());
+        assert!(parse_verdict(&format!("{:#x},0,0,false", Address::ZERO)).is_none());
+        assert!(parse_verdict(&format!("{:#x},0,0,false,false", Address::ZERO)).is_some());
+    }
+}
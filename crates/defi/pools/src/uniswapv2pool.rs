@@ -7,10 +7,9 @@ use eyre::{eyre, ErrReport, Result};
 use lazy_static::lazy_static;
 use loom_defi_abi::uniswap2::IUniswapV2Pair;
 use loom_defi_abi::IERC20;
-use loom_defi_address_book::FactoryAddress;
 use loom_evm_db::LoomDBType;
 use loom_types_entities::required_state::RequiredState;
-use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use loom_types_entities::{get_protocol_by_factory, AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
 use revm::primitives::Env;
 use revm::DatabaseRef;
 use std::ops::Div;
@@ -82,25 +81,10 @@ impl UniswapV2Pool {
         token_address_from < token_address_to
     }
 
-    fn get_protocol_by_factory(factory_address: Address) -> PoolProtocol {
-        if factory_address == FactoryAddress::UNISWAP_V2 {
-            PoolProtocol::UniswapV2
-        } else if factory_address == FactoryAddress::SUSHISWAP_V2 {
-            PoolProtocol::Sushiswap
-        } else if factory_address == FactoryAddress::NOMISWAP {
-            PoolProtocol::NomiswapStable
-        } else if factory_address == FactoryAddress::DOOARSWAP {
-            PoolProtocol::DooarSwap
-        } else if factory_address == FactoryAddress::SAFESWAP {
-            PoolProtocol::Safeswap
-        } else if factory_address == FactoryAddress::MINISWAP {
-            PoolProtocol::Miniswap
-        } else if factory_address == FactoryAddress::SHIBASWAP {
-            PoolProtocol::Shibaswap
-        } else if factory_address == FactoryAddress::OG_PEPE {
-            PoolProtocol::OgPepe
-        } else {
-            PoolProtocol::UniswapV2Like
+    fn get_protocol_by_factory(chain_id: u64, factory_address: Address) -> PoolProtocol {
+        match get_protocol_by_factory(chain_id, factory_address) {
+            PoolProtocol::Unknown => PoolProtocol::UniswapV2Like,
+            protocol => protocol,
         }
     }
 
@@ -120,7 +104,7 @@ impl UniswapV2Pool {
         let token0 = UniswapV2StateReader::token0(db, env.clone(), address)?;
         let token1 = UniswapV2StateReader::token1(db, env.clone(), address)?;
         let factory = UniswapV2StateReader::factory(db, env.clone(), address)?;
-        let protocol = Self::get_protocol_by_factory(factory);
+        let protocol = Self::get_protocol_by_factory(env.cfg.chain_id, factory);
 
         let fee = Self::get_fee_by_protocol(protocol);
 
@@ -164,7 +148,8 @@ impl UniswapV2Pool {
                 None
             };
 
-        let protocol = UniswapV2Pool::get_protocol_by_factory(factory);
+        let chain_id = client.get_chain_id().await?;
+        let protocol = UniswapV2Pool::get_protocol_by_factory(chain_id, factory);
 
         let fee = Self::get_fee_by_protocol(protocol);
 
@@ -288,6 +273,21 @@ impl Pool for UniswapV2Pool {
         }
     }
 
+    fn get_constant_product_reserves(
+        &self,
+        state_db: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+    ) -> Option<(U256, U256, U256)> {
+        let (reserves_0, reserves_1) = self.fetch_reserves(state_db, env).ok()?;
+
+        match token_address_from < token_address_to {
+            true => Some((reserves_0, reserves_1, self.fee)),
+            false => Some((reserves_1, reserves_0, self.fee)),
+        }
+    }
+
     fn can_flash_swap(&self) -> bool {
         true
     }
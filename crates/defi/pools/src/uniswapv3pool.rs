@@ -13,10 +13,10 @@ use loom_defi_abi::uniswap3::IUniswapV3Pool;
 use loom_defi_abi::uniswap3::IUniswapV3Pool::slot0Return;
 use loom_defi_abi::uniswap_periphery::ITickLens;
 use loom_defi_abi::IERC20;
-use loom_defi_address_book::{FactoryAddress, PeripheryAddress};
+use loom_defi_address_book::{AddressBook, PeripheryAddress};
 use loom_evm_db::LoomDBType;
 use loom_types_entities::required_state::RequiredState;
-use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use loom_types_entities::{get_protocol_by_factory, AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
 use revm::primitives::Env;
 use tracing::debug;
 #[cfg(feature = "debug-calculation")]
@@ -63,6 +63,9 @@ pub struct UniswapV3Pool {
     liquidity1: U256,
     factory: Address,
     protocol: PoolProtocol,
+    // Periphery of the pool chain, tick lens is required to fetch the state
+    tick_lens: Option<Address>,
+    quoter: Option<Address>,
     encoder: UniswapV3AbiSwapEncoder,
 }
 
@@ -79,6 +82,8 @@ impl UniswapV3Pool {
             slot0: None,
             factory: Address::ZERO,
             protocol: PoolProtocol::UniswapV3Like,
+            tick_lens: Some(PeripheryAddress::UNISWAP_V3_TICK_LENS),
+            quoter: Some(PeripheryAddress::UNISWAP_V3_QUOTER_V2),
             encoder: UniswapV3AbiSwapEncoder::new(address),
         }
     }
@@ -103,6 +108,8 @@ impl UniswapV3Pool {
             slot0,
             factory,
             protocol: PoolProtocol::UniswapV3Like,
+            tick_lens: Some(PeripheryAddress::UNISWAP_V3_TICK_LENS),
+            quoter: Some(PeripheryAddress::UNISWAP_V3_QUOTER_V2),
             encoder: UniswapV3AbiSwapEncoder::new(address),
         }
     }
@@ -143,23 +150,26 @@ impl UniswapV3Pool {
         *token_address_from < *token_address_to
     }
 
-    fn get_protocol_by_factory(factory_address: Address) -> PoolProtocol {
-        if factory_address == FactoryAddress::UNISWAP_V3 {
-            PoolProtocol::UniswapV3
-        } else if factory_address == FactoryAddress::SUSHISWAP_V3 {
-            PoolProtocol::SushiswapV3
-        } else {
-            PoolProtocol::UniswapV3Like
+    fn get_protocol_by_factory(chain_id: u64, factory_address: Address) -> PoolProtocol {
+        match get_protocol_by_factory(chain_id, factory_address) {
+            protocol @ (PoolProtocol::UniswapV3 | PoolProtocol::SushiswapV3) => protocol,
+            _ => PoolProtocol::UniswapV3Like,
         }
     }
 
+    // Tick lens and quoter from the address book of the chain
+    fn periphery(chain_id: u64) -> (Option<Address>, Option<Address>) {
+        (AddressBook::find_periphery(chain_id, "UNISWAP_V3_TICK_LENS"), AddressBook::find_periphery(chain_id, "UNISWAP_V3_QUOTER_V2"))
+    }
+
     pub fn fetch_pool_data_evm(db: &LoomDBType, env: Env, address: Address) -> Result<Self> {
         let token0 = UniswapV3StateReader::token0(db, env.clone(), address)?;
         let token1 = UniswapV3StateReader::token1(db, env.clone(), address)?;
         let fee: u32 = UniswapV3StateReader::fee(db, env.clone(), address)?.to();
         let liquidity = UniswapV3StateReader::liquidity(db, env.clone(), address)?;
         let factory = UniswapV3StateReader::factory(db, env.clone(), address).unwrap_or_default();
-        let protocol = UniswapV3Pool::get_protocol_by_factory(factory);
+        let protocol = UniswapV3Pool::get_protocol_by_factory(env.cfg.chain_id, factory);
+        let (tick_lens, quoter) = Self::periphery(env.cfg.chain_id);
 
         let ret = UniswapV3Pool {
             address,
@@ -172,6 +182,8 @@ impl UniswapV3Pool {
             slot0: None,
             factory,
             protocol,
+            tick_lens,
+            quoter,
             encoder: UniswapV3AbiSwapEncoder { pool_address: address },
         };
         debug!("fetch_pool_data_evm {:?} {:?} {} {:?} {}", token0, token1, fee, factory, protocol);
@@ -198,7 +210,9 @@ impl UniswapV3Pool {
         let liquidity0: U256 = token0_erc20.balanceOf(address).call().await?._0;
         let liquidity1: U256 = token1_erc20.balanceOf(address).call().await?._0;
 
-        let protocol = UniswapV3Pool::get_protocol_by_factory(factory);
+        let chain_id = client.get_chain_id().await?;
+        let protocol = UniswapV3Pool::get_protocol_by_factory(chain_id, factory);
+        let (tick_lens, quoter) = Self::periphery(chain_id);
 
         let ret = UniswapV3Pool {
             address,
@@ -211,6 +225,8 @@ impl UniswapV3Pool {
             liquidity1,
             factory,
             protocol,
+            tick_lens,
+            quoter,
             encoder: UniswapV3AbiSwapEncoder::new(address),
         };
 
@@ -256,7 +272,7 @@ impl Pool for UniswapV3Pool {
             let (ret_evm, _gas_used) = UniswapV3QuoterV2StateReader::quote_exact_input(
                 state_db,
                 env,
-                PeripheryAddress::UNISWAP_V3_QUOTER_V2,
+                self.quoter.ok_or_eyre("UNISWAP_V3_QUOTER_NOT_SET")?,
                 *token_address_from,
                 *_token_address_to,
                 self.fee.try_into()?,
@@ -293,7 +309,7 @@ impl Pool for UniswapV3Pool {
             let (ret_evm, _gas_used) = UniswapV3QuoterV2StateReader::quote_exact_output(
                 state_db,
                 env,
-                PeripheryAddress::UNISWAP_V3_QUOTER_V2,
+                self.quoter.ok_or_eyre("UNISWAP_V3_QUOTER_NOT_SET")?,
                 *token_address_from,
                 *_token_address_to,
                 self.fee.try_into()?,
@@ -323,6 +339,7 @@ impl Pool for UniswapV3Pool {
 
     fn get_state_required(&self) -> Result<RequiredState> {
         let tick = self.slot0.as_ref().ok_or_eyre("SLOT0_NOT_SET")?.tick;
+        let tick_lens = self.tick_lens.ok_or_eyre("UNISWAP_V3_TICK_LENS_NOT_SET")?;
         let price_step = UniswapV3Pool::get_price_step(self.fee);
         let mut state_required = RequiredState::new();
         if price_step == 0 {
@@ -342,7 +359,7 @@ impl Pool for UniswapV3Pool {
 
         for i in -4..=3 {
             state_required.add_call(
-                PeripheryAddress::UNISWAP_V3_TICK_LENS,
+                tick_lens,
                 ITickLens::ITickLensCalls::getPopulatedTicksInWord(ITickLens::getPopulatedTicksInWordCall {
                     pool: pool_address,
                     tickBitmapIndex: tick_bitmap_index + i,
@@ -360,7 +377,7 @@ impl Pool for UniswapV3Pool {
             state_required.add_call(token_address, IERC20::balanceOfCall { account: pool_address }.abi_encode());
         }
 
-        if self.protocol == PoolProtocol::UniswapV3 {
+        if let (PoolProtocol::UniswapV3, Some(quoter)) = (self.protocol, self.quoter) {
             let amount = self.liquidity0 / U256::from(100);
             let price_limit = UniswapV3Pool::get_price_limit(&self.token0, &self.token1);
             let quoter_swap_0_1_call =
@@ -373,9 +390,7 @@ impl Pool for UniswapV3Pool {
                 UniswapV3QuoterV2Encoder::quote_exact_input_encode(self.token1, self.token0, self.fee.try_into()?, price_limit, amount);
 
             // TODO: How about Sushiswap?
-            state_required
-                .add_call(PeripheryAddress::UNISWAP_V3_QUOTER_V2, quoter_swap_0_1_call)
-                .add_call(PeripheryAddress::UNISWAP_V3_QUOTER_V2, quoter_swap_1_0_call);
+            state_required.add_call(quoter, quoter_swap_0_1_call).add_call(quoter, quoter_swap_1_0_call);
         }
 
         Ok(state_required)
@@ -0,0 +1,395 @@
+use alloy_primitives::{keccak256, Address, Bytes, B256, I256, U160, U256};
+use alloy_provider::{Network, Provider};
+use alloy_sol_types::{SolCall, SolValue};
+use alloy_transport::Transport;
+use eyre::{eyre, ErrReport, Result};
+use loom_defi_abi::uniswap4::{IUniswapV4PoolManager, PoolKey};
+use loom_defi_uniswap_v3_math::tick_math::{MAX_SQRT_RATIO, MIN_SQRT_RATIO};
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::db_reader::UniswapV4DBReader;
+use crate::virtual_impl::UniswapV4PoolVirtual;
+
+// Pools with this fee flag have their LP fee set by the hook
+const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+// Tick bitmap words loaded below and above the current tick
+const TICK_BITMAP_WORDS: i16 = 4;
+
+#[derive(Clone)]
+pub struct UniswapV4Pool {
+    // V4 pools have no own contract, the address is derived from the pool id and used as a market key only
+    address: Address,
+    pool_manager: Address,
+    pool_id: B256,
+    pool_key: PoolKey,
+    pub token0: Address,
+    pub token1: Address,
+    pub fee: u32,
+    tick_spacing: i32,
+    tick: i32,
+    initialized_ticks: Vec<i32>,
+    encoder: UniswapV4AbiSwapEncoder,
+}
+
+impl UniswapV4Pool {
+    pub fn new(pool_manager: Address, pool_key: PoolKey) -> Result<Self> {
+        if pool_key.hooks != Address::ZERO {
+            return Err(eyre!("UNISWAP_V4_HOOKS_NOT_SUPPORTED"));
+        }
+
+        let fee: u32 = pool_key.fee.to();
+        if fee & DYNAMIC_FEE_FLAG != 0 {
+            return Err(eyre!("UNISWAP_V4_DYNAMIC_FEE_NOT_SUPPORTED"));
+        }
+
+        if pool_key.currency0 == Address::ZERO {
+            return Err(eyre!("UNISWAP_V4_NATIVE_CURRENCY_NOT_SUPPORTED"));
+        }
+
+        let pool_id = Self::calc_pool_id(&pool_key);
+
+        Ok(UniswapV4Pool {
+            address: Self::pool_address_from_id(pool_id),
+            pool_manager,
+            pool_id,
+            token0: pool_key.currency0,
+            token1: pool_key.currency1,
+            fee,
+            tick_spacing: pool_key.tickSpacing.as_i32(),
+            tick: 0,
+            initialized_ticks: Vec::new(),
+            encoder: UniswapV4AbiSwapEncoder::new(pool_manager, pool_key.clone()),
+            pool_key,
+        })
+    }
+
+    pub fn calc_pool_id(pool_key: &PoolKey) -> B256 {
+        keccak256(pool_key.abi_encode())
+    }
+
+    pub fn pool_address_from_id(pool_id: B256) -> Address {
+        Address::from_word(pool_id)
+    }
+
+    pub fn get_pool_manager(&self) -> Address {
+        self.pool_manager
+    }
+
+    pub fn get_pool_id(&self) -> B256 {
+        self.pool_id
+    }
+
+    pub fn get_pool_key(&self) -> &PoolKey {
+        &self.pool_key
+    }
+
+    pub fn tick_spacing(&self) -> i32 {
+        self.tick_spacing
+    }
+
+    pub fn get_tick_bitmap_index(tick: i32, tick_spacing: i32) -> i16 {
+        (tick.div_euclid(tick_spacing) >> 8) as i16
+    }
+
+    fn get_tick_bitmap_words(tick: i32, tick_spacing: i32) -> std::ops::RangeInclusive<i16> {
+        let tick_bitmap_index = Self::get_tick_bitmap_index(tick, tick_spacing);
+        tick_bitmap_index.saturating_sub(TICK_BITMAP_WORDS)..=tick_bitmap_index.saturating_add(TICK_BITMAP_WORDS - 1)
+    }
+
+    fn get_initialized_ticks(word: i16, bitmap: U256, tick_spacing: i32) -> impl Iterator<Item = i32> {
+        (0..256usize).filter(move |bit| bitmap.bit(*bit)).map(move |bit| ((word as i32) * 256 + bit as i32) * tick_spacing)
+    }
+
+    pub fn get_zero_for_one(token_address_from: &Address, token_address_to: &Address) -> bool {
+        token_address_from < token_address_to
+    }
+
+    pub fn get_price_limit(token_address_from: &Address, token_address_to: &Address) -> U160 {
+        if Self::get_zero_for_one(token_address_from, token_address_to) {
+            (MIN_SQRT_RATIO + U256::from(1)).to()
+        } else {
+            (MAX_SQRT_RATIO - U256::from(1)).to()
+        }
+    }
+
+    pub async fn fetch_pool_data<T: Transport + Clone, N: Network, P: Provider<T, N> + Send + Sync + Clone + 'static>(
+        client: P,
+        pool_manager: Address,
+        pool_key: PoolKey,
+    ) -> Result<Self> {
+        let mut pool = Self::new(pool_manager, pool_key)?;
+
+        let slot0_cell = client.get_storage_at(pool.pool_manager, UniswapV4DBReader::pool_state_slot(pool.pool_id)).await?;
+        let slot0 = UniswapV4DBReader::parse_slot0(slot0_cell)?;
+        if slot0.sqrt_price_x96.is_zero() {
+            return Err(eyre!("POOL_NOT_INITIALIZED"));
+        }
+        pool.tick = slot0.tick;
+
+        // There is no tick lens for the singleton, so initialized ticks around the current price are collected from the bitmap
+        for word in Self::get_tick_bitmap_words(pool.tick, pool.tick_spacing) {
+            let bitmap = client.get_storage_at(pool.pool_manager, UniswapV4DBReader::tick_bitmap_slot(pool.pool_id, word)?).await?;
+            pool.initialized_ticks.extend(Self::get_initialized_ticks(word, bitmap, pool.tick_spacing));
+        }
+
+        debug!("fetch_pool_data {:?} {:?} {:?} {} {}", pool.pool_id, pool.token0, pool.token1, pool.fee, pool.initialized_ticks.len());
+
+        Ok(pool)
+    }
+}
+
+impl Pool for UniswapV4Pool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::UniswapV4
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::UniswapV4
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_fee(&self) -> U256 {
+        U256::from(self.fee)
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token0, self.token1]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.token0, self.token1), (self.token1, self.token0)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let ret = UniswapV4PoolVirtual::simulate_swap_in_amount(state_db, self, *token_address_from, in_amount)?;
+
+        if ret.is_zero() {
+            Err(eyre!("RETURN_RESULT_IS_ZERO"))
+        } else {
+            Ok((ret, 150000)) // value, gas_used
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        _token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let ret = UniswapV4PoolVirtual::simulate_swap_out_amount(state_db, self, *token_address_from, out_amount)?;
+
+        if ret.is_zero() {
+            Err(eyre!("RETURN_RESULT_IS_ZERO"))
+        } else {
+            Ok((ret, 150000))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+
+        // slot0, fee growth and liquidity
+        state_required.add_slot_range(self.pool_manager, UniswapV4DBReader::pool_state_slot(self.pool_id), 4);
+
+        for word in Self::get_tick_bitmap_words(self.tick, self.tick_spacing) {
+            state_required.add_slot(self.pool_manager, UniswapV4DBReader::tick_bitmap_slot(self.pool_id, word)?);
+        }
+
+        for tick in self.initialized_ticks.iter() {
+            state_required.add_slot(self.pool_manager, UniswapV4DBReader::tick_info_slot(self.pool_id, *tick)?);
+        }
+
+        Ok(state_required)
+    }
+
+    // Bitmap words around the current tick and ticks in the loaded words that are not in the state db yet.
+    // Ticks of new words are returned on the next call after the words were fetched.
+    fn get_state_required_update(&self, state_db: &LoomDBType) -> Result<Option<RequiredState>> {
+        let tick = UniswapV4DBReader::slot0(state_db, self.pool_manager, self.pool_id)?.tick;
+
+        let mut state_required = RequiredState::new();
+
+        for word in Self::get_tick_bitmap_words(tick, self.tick_spacing) {
+            let bitmap_slot = UniswapV4DBReader::tick_bitmap_slot(self.pool_id, word)?;
+            if !state_db.is_slot(&self.pool_manager, &bitmap_slot) {
+                state_required.add_slot(self.pool_manager, bitmap_slot);
+                continue;
+            }
+
+            let bitmap = UniswapV4DBReader::tick_bitmap(state_db, self.pool_manager, self.pool_id, word)?;
+            for initialized_tick in Self::get_initialized_ticks(word, bitmap, self.tick_spacing) {
+                let tick_info_slot = UniswapV4DBReader::tick_info_slot(self.pool_id, initialized_tick)?;
+                if !state_db.is_slot(&self.pool_manager, &tick_info_slot) {
+                    state_required.add_slot(self.pool_manager, tick_info_slot);
+                }
+            }
+        }
+
+        Ok((!state_required.is_empty()).then_some(state_required))
+    }
+}
+
+#[derive(Clone)]
+struct UniswapV4AbiSwapEncoder {
+    pool_manager: Address,
+    pool_key: PoolKey,
+}
+
+impl UniswapV4AbiSwapEncoder {
+    pub fn new(pool_manager: Address, pool_key: PoolKey) -> Self {
+        Self { pool_manager, pool_key }
+    }
+
+    fn encode_swap(&self, token_from_address: Address, token_to_address: Address, amount_specified: I256, payload: Bytes) -> Bytes {
+        let swap_call = IUniswapV4PoolManager::swapCall {
+            key: self.pool_key.clone(),
+            params: IUniswapV4PoolManager::SwapParams {
+                zeroForOne: UniswapV4Pool::get_zero_for_one(&token_from_address, &token_to_address),
+                amountSpecified: amount_specified,
+                sqrtPriceLimitX96: UniswapV4Pool::get_price_limit(&token_from_address, &token_to_address),
+            },
+            hookData: payload,
+        };
+
+        Bytes::from(swap_call.abi_encode())
+    }
+}
+
+impl AbiSwapEncoder for UniswapV4AbiSwapEncoder {
+    // Negative amount specified is exact input for the pool manager
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        _recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        Ok(self.encode_swap(token_from_address, token_to_address, -I256::from_raw(amount), payload))
+    }
+
+    fn encode_swap_out_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        _recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        Ok(self.encode_swap(token_from_address, token_to_address, I256::from_raw(amount), payload))
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Callback
+    }
+
+    fn swap_contract_address(&self) -> Option<Address> {
+        Some(self.pool_manager)
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0xC4)
+    }
+
+    fn swap_out_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0xC4)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::aliases::{I24, U24};
+    use loom_defi_address_book::{PeripheryAddress, TokenAddress};
+    use loom_evm_db::LoomDB;
+    use std::ops::Shl;
+
+    const POOL_MANAGER: Address = PeripheryAddress::UNISWAP_V4_POOL_MANAGER;
+
+    fn pool_key(hooks: Address, fee: u32) -> PoolKey {
+        PoolKey {
+            currency0: TokenAddress::USDC,
+            currency1: TokenAddress::WETH,
+            fee: U24::from(fee),
+            tickSpacing: I24::try_from(60i32).unwrap(),
+            hooks,
+        }
+    }
+
+    #[test]
+    fn test_hooked_pools_are_not_supported() {
+        assert!(UniswapV4Pool::new(POOL_MANAGER, pool_key(Address::ZERO, 3000)).is_ok());
+        assert!(UniswapV4Pool::new(POOL_MANAGER, pool_key(Address::repeat_byte(1), 3000)).is_err());
+        assert!(UniswapV4Pool::new(POOL_MANAGER, pool_key(Address::ZERO, DYNAMIC_FEE_FLAG)).is_err());
+    }
+
+    #[test]
+    fn test_swap_amount_offset() -> Result<()> {
+        let pool = UniswapV4Pool::new(POOL_MANAGER, pool_key(Address::ZERO, 3000))?;
+        let encoder = pool.get_encoder();
+        let amount = U256::from(0x1234567u64);
+
+        let call_data = encoder.encode_swap_out_amount_provided(pool.token0, pool.token1, amount, Address::ZERO, Bytes::new())?;
+        let offset = encoder.swap_out_amount_offset(pool.token0, pool.token1).unwrap() as usize;
+        assert_eq!(U256::from_be_slice(&call_data[offset..offset + 0x20]), amount);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tick_bitmap_index() {
+        assert_eq!(UniswapV4Pool::get_tick_bitmap_index(0, 60), 0);
+        assert_eq!(UniswapV4Pool::get_tick_bitmap_index(-1, 60), -1);
+        assert_eq!(UniswapV4Pool::get_tick_bitmap_index(256 * 60, 60), 1);
+    }
+
+    fn slot0_cell(tick: i32) -> U256 {
+        let sqrt_price_x96 = U256::from(79228162514264337593543950336u128);
+        sqrt_price_x96 | U256::from(tick as u32 & 0xFFFFFF).shl(160)
+    }
+
+    #[test]
+    fn test_state_required_update() -> Result<()> {
+        let pool = UniswapV4Pool::new(POOL_MANAGER, pool_key(Address::ZERO, 3000))?;
+        let mut state_db = LoomDB::new();
+        state_db.insert_account_storage(POOL_MANAGER, UniswapV4DBReader::pool_state_slot(pool.pool_id), slot0_cell(0))?;
+
+        // one initialized tick in the word of the current tick
+        for word in UniswapV4Pool::get_tick_bitmap_words(0, pool.tick_spacing) {
+            let bitmap = if word == 0 { U256::from(2) } else { U256::ZERO };
+            state_db.insert_account_storage(POOL_MANAGER, UniswapV4DBReader::tick_bitmap_slot(pool.pool_id, word)?, bitmap)?;
+        }
+        assert!(pool.get_state_required_update(&state_db)?.is_some());
+
+        state_db.insert_account_storage(POOL_MANAGER, UniswapV4DBReader::tick_info_slot(pool.pool_id, 60)?, U256::from(1))?;
+        assert!(pool.get_state_required_update(&state_db)?.is_none());
+
+        // price moved by more than the loaded words
+        state_db.insert_account_storage(POOL_MANAGER, UniswapV4DBReader::pool_state_slot(pool.pool_id), slot0_cell(60 * 256 * 10))?;
+        assert!(pool.get_state_required_update(&state_db)?.is_some());
+        Ok(())
+    }
+}
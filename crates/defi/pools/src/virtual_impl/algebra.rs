@@ -0,0 +1,149 @@
+use alloy_primitives::{Address, I256, U256};
+use eyre::eyre;
+use loom_defi_uniswap_v3_math::tick_math::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK};
+use revm::primitives::Env;
+
+use super::uniswapv3::{CurrentState, StepComputations, U256_1};
+use crate::state_readers::AlgebraStateReader;
+use crate::virtual_impl::tick_provider::TickProviderAlgebraEVM;
+use crate::AlgebraPool;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::Pool;
+
+/// Swap simulation of Algebra pools. The tick table is a bitmap of compressed ticks like the Uniswap V3 one, so the
+/// Uniswap V3 math is reused. The fee is the one of the current global state and depends on the swap direction.
+pub struct AlgebraPoolVirtual;
+
+impl AlgebraPoolVirtual {
+    pub fn simulate_swap_in_amount(
+        db: &LoomDBType,
+        env: Env,
+        pool: &AlgebraPool,
+        token_in: Address,
+        amount_in: U256,
+    ) -> eyre::Result<U256> {
+        if amount_in.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        let zero_for_one = token_in == pool.get_tokens()[0];
+
+        let (amount_remaining, amount_calculated) = Self::simulate_swap(db, env, pool, zero_for_one, I256::from_raw(amount_in))?;
+        if amount_remaining.is_zero() {
+            Ok((-amount_calculated).into_raw())
+        } else {
+            Err(eyre!("NOT_ENOUGH_LIQUIDITY"))
+        }
+    }
+
+    pub fn simulate_swap_out_amount(
+        db: &LoomDBType,
+        env: Env,
+        pool: &AlgebraPool,
+        token_in: Address,
+        amount_out: U256,
+    ) -> eyre::Result<U256> {
+        if amount_out.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        let zero_for_one = token_in == pool.get_tokens()[0];
+
+        let (amount_remaining, amount_calculated) = Self::simulate_swap(db, env, pool, zero_for_one, -I256::from_raw(amount_out))?;
+        if amount_remaining.is_zero() {
+            Ok(amount_calculated.into_raw())
+        } else {
+            Err(eyre!("NOT_ENOUGH_LIQUIDITY"))
+        }
+    }
+
+    // positive amount_specified is exact input, negative is exact output. Returns remaining and calculated amounts.
+    fn simulate_swap(
+        db: &LoomDBType,
+        env: Env,
+        pool: &AlgebraPool,
+        zero_for_one: bool,
+        amount_specified: I256,
+    ) -> eyre::Result<(I256, I256)> {
+        let exact_input = amount_specified.is_positive();
+
+        // Set sqrt_price_limit_x_96 to the max or min sqrt price in the pool depending on zero_for_one
+        let sqrt_price_limit_x_96 = if zero_for_one { MIN_SQRT_RATIO + U256_1 } else { MAX_SQRT_RATIO - U256_1 };
+
+        let pool_address = pool.get_address();
+
+        let global_state = AlgebraStateReader::global_state(db, env.clone(), pool_address)?;
+        let liquidity = AlgebraStateReader::liquidity(db, env.clone(), pool_address)?;
+        let tick_spacing = pool.tick_spacing();
+        let fee = global_state.fee(zero_for_one);
+
+        let mut current_state = CurrentState {
+            sqrt_price_x_96: global_state.price,
+            amount_calculated: I256::ZERO,
+            amount_specified_remaining: amount_specified,
+            tick: global_state.tick,
+            liquidity,
+        };
+
+        let tick_provider = TickProviderAlgebraEVM::new(db, env.clone(), pool_address);
+
+        while current_state.amount_specified_remaining != I256::ZERO && current_state.sqrt_price_x_96 != sqrt_price_limit_x_96 {
+            let mut step = StepComputations { sqrt_price_start_x_96: current_state.sqrt_price_x_96, ..Default::default() };
+
+            (step.tick_next, step.initialized) = loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
+                &tick_provider,
+                current_state.tick,
+                tick_spacing as i32,
+                zero_for_one,
+            )?;
+
+            step.tick_next = step.tick_next.clamp(MIN_TICK, MAX_TICK);
+            step.sqrt_price_next_x96 = loom_defi_uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick(step.tick_next)?;
+
+            let swap_target_sqrt_ratio = if zero_for_one {
+                step.sqrt_price_next_x96.max(sqrt_price_limit_x_96)
+            } else {
+                step.sqrt_price_next_x96.min(sqrt_price_limit_x_96)
+            };
+
+            (current_state.sqrt_price_x_96, step.amount_in, step.amount_out, step.fee_amount) =
+                loom_defi_uniswap_v3_math::swap_math::compute_swap_step(
+                    current_state.sqrt_price_x_96,
+                    swap_target_sqrt_ratio,
+                    current_state.liquidity,
+                    current_state.amount_specified_remaining,
+                    fee,
+                )?;
+
+            let step_amount_in = I256::from_raw(step.amount_in.overflowing_add(step.fee_amount).0);
+            let step_amount_out = I256::from_raw(step.amount_out);
+            if exact_input {
+                current_state.amount_specified_remaining = current_state.amount_specified_remaining.overflowing_sub(step_amount_in).0;
+                current_state.amount_calculated = current_state.amount_calculated.overflowing_sub(step_amount_out).0;
+            } else {
+                current_state.amount_specified_remaining = current_state.amount_specified_remaining.overflowing_add(step_amount_out).0;
+                current_state.amount_calculated = current_state.amount_calculated.overflowing_add(step_amount_in).0;
+            }
+
+            if current_state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
+                if step.initialized {
+                    let mut liquidity_delta =
+                        AlgebraStateReader::ticks_liquidity_delta(db, env.clone(), pool_address, step.tick_next).unwrap_or_default();
+
+                    if zero_for_one {
+                        liquidity_delta = -liquidity_delta;
+                    }
+
+                    current_state.liquidity = if liquidity_delta < 0 {
+                        current_state.liquidity.checked_sub(liquidity_delta.unsigned_abs()).ok_or_else(|| eyre!("LIQUIDITY_UNDERFLOW"))?
+                    } else {
+                        current_state.liquidity + (liquidity_delta as u128)
+                    };
+                }
+                current_state.tick = if zero_for_one { step.tick_next.wrapping_sub(1) } else { step.tick_next }
+            } else if current_state.sqrt_price_x_96 != step.sqrt_price_start_x_96 {
+                current_state.tick = loom_defi_uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio(current_state.sqrt_price_x_96)?;
+            }
+        }
+
+        Ok((current_state.amount_specified_remaining, current_state.amount_calculated))
+    }
+}
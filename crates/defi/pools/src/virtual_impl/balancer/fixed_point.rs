@@ -0,0 +1,97 @@
+use alloy_primitives::U256;
+use eyre::{eyre, Result};
+
+pub const ONE: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+pub const TWO: U256 = U256::from_limbs([2_000_000_000_000_000_000, 0, 0, 0]);
+pub const FOUR: U256 = U256::from_limbs([4_000_000_000_000_000_000, 0, 0, 0]);
+
+// 1e-14 relative error allowed by FixedPoint.powUp
+pub const MAX_POW_RELATIVE_ERROR: U256 = U256::from_limbs([10_000, 0, 0, 0]);
+
+pub fn mul_down(a: U256, b: U256) -> Result<U256> {
+    let product = a.checked_mul(b).ok_or_else(|| eyre!("MUL_OVERFLOW"))?;
+    Ok(product / ONE)
+}
+
+pub fn mul_up(a: U256, b: U256) -> Result<U256> {
+    let product = a.checked_mul(b).ok_or_else(|| eyre!("MUL_OVERFLOW"))?;
+    if product.is_zero() {
+        Ok(U256::ZERO)
+    } else {
+        Ok((product - U256::from(1)) / ONE + U256::from(1))
+    }
+}
+
+pub fn div_down(a: U256, b: U256) -> Result<U256> {
+    if b.is_zero() {
+        return Err(eyre!("ZERO_DIVISION"));
+    }
+    let a_inflated = a.checked_mul(ONE).ok_or_else(|| eyre!("DIV_INTERNAL"))?;
+    Ok(a_inflated / b)
+}
+
+pub fn div_up(a: U256, b: U256) -> Result<U256> {
+    if b.is_zero() {
+        return Err(eyre!("ZERO_DIVISION"));
+    }
+    if a.is_zero() {
+        return Ok(U256::ZERO);
+    }
+    let a_inflated = a.checked_mul(ONE).ok_or_else(|| eyre!("DIV_INTERNAL"))?;
+    Ok((a_inflated - U256::from(1)) / b + U256::from(1))
+}
+
+pub fn complement(x: U256) -> U256 {
+    if x < ONE {
+        ONE - x
+    } else {
+        U256::ZERO
+    }
+}
+
+pub fn pow_up(x: U256, y: U256) -> Result<U256> {
+    if y == ONE {
+        Ok(x)
+    } else if y == TWO {
+        mul_up(x, x)
+    } else if y == FOUR {
+        let square = mul_up(x, x)?;
+        mul_up(square, square)
+    } else {
+        let raw = super::log_exp_math::pow(x, y)?;
+        let max_error = mul_up(raw, MAX_POW_RELATIVE_ERROR)? + U256::from(1);
+        Ok(raw + max_error)
+    }
+}
+
+/// Checked integer addition, `Math.add` in Balancer contracts
+pub fn raw_add(a: U256, b: U256) -> Result<U256> {
+    a.checked_add(b).ok_or_else(|| eyre!("ADD_OVERFLOW"))
+}
+
+/// Checked integer subtraction, `Math.sub` in Balancer contracts
+pub fn raw_sub(a: U256, b: U256) -> Result<U256> {
+    a.checked_sub(b).ok_or_else(|| eyre!("SUB_OVERFLOW"))
+}
+
+/// Checked integer multiplication, `Math.mul` in Balancer contracts
+pub fn raw_mul(a: U256, b: U256) -> Result<U256> {
+    a.checked_mul(b).ok_or_else(|| eyre!("MUL_OVERFLOW"))
+}
+
+/// Integer division rounding down, `Math.divDown` in Balancer contracts
+pub fn raw_div_down(a: U256, b: U256) -> Result<U256> {
+    a.checked_div(b).ok_or_else(|| eyre!("ZERO_DIVISION"))
+}
+
+/// Integer division rounding up, `Math.divUp` in Balancer contracts
+pub fn raw_div_up(a: U256, b: U256) -> Result<U256> {
+    if b.is_zero() {
+        return Err(eyre!("ZERO_DIVISION"));
+    }
+    if a.is_zero() {
+        Ok(U256::ZERO)
+    } else {
+        Ok((a - U256::from(1)) / b + U256::from(1))
+    }
+}
@@ -0,0 +1,215 @@
+//! Port of Balancer's `LogExpMath` library. All intermediate values are signed 18 or 20 decimal fixed point
+//! numbers, divisions truncate towards zero exactly as in Solidity.
+use alloy_primitives::{I256, U256};
+use eyre::{eyre, Result};
+use lazy_static::lazy_static;
+
+fn i(value: i128) -> I256 {
+    I256::try_from(value).unwrap()
+}
+
+lazy_static! {
+    static ref ONE_18: I256 = i(1_000_000_000_000_000_000);
+    static ref ONE_20: I256 = i(100_000_000_000_000_000_000);
+    static ref ONE_36: I256 = i(1_000_000_000_000_000_000_000_000_000_000_000_000);
+    static ref MAX_NATURAL_EXPONENT: I256 = i(130_000_000_000_000_000_000);
+    static ref MIN_NATURAL_EXPONENT: I256 = i(-41_000_000_000_000_000_000);
+    static ref LN_36_LOWER_BOUND: I256 = i(900_000_000_000_000_000);
+    static ref LN_36_UPPER_BOUND: I256 = i(1_100_000_000_000_000_000);
+    static ref MILD_EXPONENT_BOUND: U256 = (U256::from(1) << 254) / U256::from(100_000_000_000_000_000_000u128);
+
+    // 18 decimal constants
+    static ref X0: I256 = i(128_000_000_000_000_000_000);
+    static ref A0: I256 = I256::from_dec_str("38877084059945950922200000000000000000000000000000000000").unwrap();
+    static ref X1: I256 = i(64_000_000_000_000_000_000);
+    static ref A1: I256 = i(6_235_149_080_811_616_882_910_000_000);
+
+    // 20 decimal constants
+    static ref X2: I256 = i(3_200_000_000_000_000_000_000);
+    static ref A2: I256 = i(7_896_296_018_268_069_516_100_000_000_000_000);
+    static ref X3: I256 = i(1_600_000_000_000_000_000_000);
+    static ref A3: I256 = i(888_611_052_050_787_263_676_000_000);
+    static ref X4: I256 = i(800_000_000_000_000_000_000);
+    static ref A4: I256 = i(298_095_798_704_172_827_474_000);
+    static ref X5: I256 = i(400_000_000_000_000_000_000);
+    static ref A5: I256 = i(5_459_815_003_314_423_907_810);
+    static ref X6: I256 = i(200_000_000_000_000_000_000);
+    static ref A6: I256 = i(738_905_609_893_065_022_723);
+    static ref X7: I256 = i(100_000_000_000_000_000_000);
+    static ref A7: I256 = i(271_828_182_845_904_523_536);
+    static ref X8: I256 = i(50_000_000_000_000_000_000);
+    static ref A8: I256 = i(164_872_127_070_012_814_685);
+    static ref X9: I256 = i(25_000_000_000_000_000_000);
+    static ref A9: I256 = i(128_402_541_668_774_148_407);
+    static ref X10: I256 = i(12_500_000_000_000_000_000);
+    static ref A10: I256 = i(113_314_845_306_682_631_683);
+    static ref X11: I256 = i(6_250_000_000_000_000_000);
+    static ref A11: I256 = i(106_449_445_891_785_942_956);
+}
+
+/// x^y with both x and y 18 decimal fixed point numbers
+pub fn pow(x: U256, y: U256) -> Result<U256> {
+    if y.is_zero() {
+        return Ok(U256::from(1_000_000_000_000_000_000u64));
+    }
+    if x.is_zero() {
+        return Ok(U256::ZERO);
+    }
+    if x.bit(255) {
+        return Err(eyre!("X_OUT_OF_BOUNDS"));
+    }
+    if y >= *MILD_EXPONENT_BOUND {
+        return Err(eyre!("Y_OUT_OF_BOUNDS"));
+    }
+
+    let x_int = I256::from_raw(x);
+    let y_int = I256::from_raw(y);
+
+    let mut logx_times_y = if *LN_36_LOWER_BOUND < x_int && x_int < *LN_36_UPPER_BOUND {
+        let ln_36_x = ln_36(x_int);
+        (ln_36_x / *ONE_18) * y_int + ((ln_36_x % *ONE_18) * y_int) / *ONE_18
+    } else {
+        ln(x_int) * y_int
+    };
+    logx_times_y /= *ONE_18;
+
+    if logx_times_y < *MIN_NATURAL_EXPONENT || logx_times_y > *MAX_NATURAL_EXPONENT {
+        return Err(eyre!("PRODUCT_OUT_OF_BOUNDS"));
+    }
+
+    Ok(exp(logx_times_y)?.into_raw())
+}
+
+/// e^x with x an 18 decimal fixed point number
+pub fn exp(x: I256) -> Result<I256> {
+    if x < *MIN_NATURAL_EXPONENT || x > *MAX_NATURAL_EXPONENT {
+        return Err(eyre!("INVALID_EXPONENT"));
+    }
+
+    if x.is_negative() {
+        return Ok((*ONE_18 * *ONE_18) / exp(-x)?);
+    }
+
+    let mut x = x;
+    let first_an = if x >= *X0 {
+        x -= *X0;
+        *A0
+    } else if x >= *X1 {
+        x -= *X1;
+        *A1
+    } else {
+        i(1)
+    };
+
+    // Switch to 20 decimals for higher precision
+    x *= i(100);
+
+    let mut product = *ONE_20;
+    for (xn, an) in [(*X2, *A2), (*X3, *A3), (*X4, *A4), (*X5, *A5), (*X6, *A6), (*X7, *A7), (*X8, *A8), (*X9, *A9)] {
+        if x >= xn {
+            x -= xn;
+            product = (product * an) / *ONE_20;
+        }
+    }
+
+    // Taylor series up to the 12th term
+    let mut series_sum = *ONE_20;
+    let mut term = x;
+    series_sum += term;
+    for n in 2..=12 {
+        term = ((term * x) / *ONE_20) / i(n);
+        series_sum += term;
+    }
+
+    Ok((((product * series_sum) / *ONE_20) * first_an) / i(100))
+}
+
+fn ln(a: I256) -> I256 {
+    if a < *ONE_18 {
+        return -ln((*ONE_18 * *ONE_18) / a);
+    }
+
+    let mut a = a;
+    let mut sum = I256::ZERO;
+    if a >= *A0 * *ONE_18 {
+        a /= *A0;
+        sum += *X0;
+    }
+    if a >= *A1 * *ONE_18 {
+        a /= *A1;
+        sum += *X1;
+    }
+
+    // Switch to 20 decimals for higher precision
+    sum *= i(100);
+    a *= i(100);
+
+    for (xn, an) in
+        [(*X2, *A2), (*X3, *A3), (*X4, *A4), (*X5, *A5), (*X6, *A6), (*X7, *A7), (*X8, *A8), (*X9, *A9), (*X10, *A10), (*X11, *A11)]
+    {
+        if a >= an {
+            a = (a * *ONE_20) / an;
+            sum += xn;
+        }
+    }
+
+    // ln(a) = 2 * atanh(z) with z = (a - 1) / (a + 1)
+    let z = ((a - *ONE_20) * *ONE_20) / (a + *ONE_20);
+    let z_squared = (z * z) / *ONE_20;
+
+    let mut num = z;
+    let mut series_sum = num;
+    for n in [3, 5, 7, 9, 11] {
+        num = (num * z_squared) / *ONE_20;
+        series_sum += num / i(n);
+    }
+    series_sum *= i(2);
+
+    (sum + series_sum) / i(100)
+}
+
+/// High precision ln for x close to one, returns a 36 decimal fixed point number
+fn ln_36(x: I256) -> I256 {
+    let x = x * *ONE_18;
+
+    let z = ((x - *ONE_36) * *ONE_36) / (x + *ONE_36);
+    let z_squared = (z * z) / *ONE_36;
+
+    let mut num = z;
+    let mut series_sum = num;
+    for n in [3, 5, 7, 9, 11, 13, 15] {
+        num = (num * z_squared) / *ONE_36;
+        series_sum += num / i(n);
+    }
+
+    series_sum * i(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e18(value: f64) -> U256 {
+        U256::from((value * 1e18) as u128)
+    }
+
+    fn assert_close(value: U256, expected: f64) {
+        let value = value.to::<u128>() as f64 / 1e18;
+        assert!((value - expected).abs() / expected < 1e-12, "{value} != {expected}");
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_close(pow(e18(2.0), e18(0.5)).unwrap(), std::f64::consts::SQRT_2);
+        assert_close(pow(e18(0.5), e18(0.25)).unwrap(), 0.5f64.powf(0.25));
+        assert_close(pow(e18(1.05), e18(3.0)).unwrap(), 1.05f64.powi(3));
+        assert_close(pow(e18(0.999), e18(4.0)).unwrap(), 0.999f64.powi(4));
+        assert_eq!(pow(e18(3.0), U256::ZERO).unwrap(), e18(1.0));
+    }
+
+    #[test]
+    fn test_exp() {
+        assert_close(exp(I256::from_raw(e18(1.0))).unwrap().into_raw(), std::f64::consts::E);
+        assert_close(exp(-I256::from_raw(e18(2.0))).unwrap().into_raw(), (-2.0f64).exp());
+    }
+}
@@ -0,0 +1,7 @@
+pub use stable_math::{BalancerStableMath, AMP_PRECISION};
+pub use weighted_math::BalancerWeightedMath;
+
+pub mod fixed_point;
+mod log_exp_math;
+mod stable_math;
+mod weighted_math;
@@ -0,0 +1,175 @@
+use alloy_primitives::U256;
+use eyre::{eyre, Result};
+
+use super::fixed_point::{raw_add, raw_div_down, raw_div_up, raw_mul, raw_sub};
+
+pub const AMP_PRECISION: U256 = U256::from_limbs([1_000, 0, 0, 0]);
+
+const MAX_ITERATIONS: usize = 255;
+
+pub struct BalancerStableMath;
+
+impl BalancerStableMath {
+    /// StableSwap invariant `D`. `amp` already includes `AMP_PRECISION`, balances are upscaled.
+    pub fn calculate_invariant(amp: U256, balances: &[U256]) -> Result<U256> {
+        let num_tokens = U256::from(balances.len());
+        let sum = balances.iter().try_fold(U256::ZERO, |acc, b| raw_add(acc, *b))?;
+        if sum.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let amp_times_total = raw_mul(amp, num_tokens)?;
+        let mut invariant = sum;
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut d_p = invariant;
+            for balance in balances.iter() {
+                let denominator = raw_mul(*balance, num_tokens)?;
+                if denominator.is_zero() {
+                    return Err(eyre!("ZERO_BALANCE"));
+                }
+                d_p = raw_div_down(raw_mul(d_p, invariant)?, denominator)?;
+            }
+
+            let prev_invariant = invariant;
+            let numerator =
+                raw_mul(raw_add(raw_div_down(raw_mul(amp_times_total, sum)?, AMP_PRECISION)?, raw_mul(d_p, num_tokens)?)?, invariant)?;
+            let denominator = raw_add(
+                raw_div_down(raw_mul(raw_sub(amp_times_total, AMP_PRECISION)?, invariant)?, AMP_PRECISION)?,
+                raw_mul(raw_add(num_tokens, U256::from(1))?, d_p)?,
+            )?;
+            invariant = raw_div_down(numerator, denominator)?;
+
+            if invariant > prev_invariant {
+                if invariant - prev_invariant <= U256::from(1) {
+                    return Ok(invariant);
+                }
+            } else if prev_invariant - invariant <= U256::from(1) {
+                return Ok(invariant);
+            }
+        }
+
+        Err(eyre!("STABLE_INVARIANT_DIDNT_CONVERGE"))
+    }
+
+    pub fn calc_out_given_in(
+        amp: U256,
+        balances: &[U256],
+        token_index_in: usize,
+        token_index_out: usize,
+        amount_in: U256,
+        invariant: U256,
+    ) -> Result<U256> {
+        let mut balances = balances.to_vec();
+        balances[token_index_in] = raw_add(balances[token_index_in], amount_in)?;
+
+        let final_balance_out = Self::get_token_balance_given_invariant_and_all_other_balances(amp, &balances, invariant, token_index_out)?;
+
+        raw_sub(raw_sub(balances[token_index_out], final_balance_out)?, U256::from(1))
+    }
+
+    pub fn calc_in_given_out(
+        amp: U256,
+        balances: &[U256],
+        token_index_in: usize,
+        token_index_out: usize,
+        amount_out: U256,
+        invariant: U256,
+    ) -> Result<U256> {
+        let mut balances = balances.to_vec();
+        balances[token_index_out] = raw_sub(balances[token_index_out], amount_out)?;
+
+        let final_balance_in = Self::get_token_balance_given_invariant_and_all_other_balances(amp, &balances, invariant, token_index_in)?;
+
+        raw_add(raw_sub(final_balance_in, balances[token_index_in])?, U256::from(1))
+    }
+
+    fn get_token_balance_given_invariant_and_all_other_balances(
+        amp: U256,
+        balances: &[U256],
+        invariant: U256,
+        token_index: usize,
+    ) -> Result<U256> {
+        let num_tokens = U256::from(balances.len());
+        let amp_times_total = raw_mul(amp, num_tokens)?;
+
+        let mut sum = balances[0];
+        let mut p_d = raw_mul(balances[0], num_tokens)?;
+        for balance in balances.iter().skip(1) {
+            p_d = raw_div_down(raw_mul(raw_mul(p_d, *balance)?, num_tokens)?, invariant)?;
+            sum = raw_add(sum, *balance)?;
+        }
+        sum = raw_sub(sum, balances[token_index])?;
+
+        let inv2 = raw_mul(invariant, invariant)?;
+        let c = raw_mul(raw_mul(raw_div_up(inv2, raw_mul(amp_times_total, p_d)?)?, AMP_PRECISION)?, balances[token_index])?;
+        let b = raw_add(sum, raw_mul(raw_div_down(invariant, amp_times_total)?, AMP_PRECISION)?)?;
+
+        let mut token_balance = raw_div_up(raw_add(inv2, c)?, raw_add(invariant, b)?)?;
+
+        for _ in 0..MAX_ITERATIONS {
+            let prev_token_balance = token_balance;
+            let denominator = raw_sub(raw_add(raw_mul(token_balance, U256::from(2))?, b)?, invariant)?;
+            token_balance = raw_div_up(raw_add(raw_mul(token_balance, token_balance)?, c)?, denominator)?;
+
+            if token_balance > prev_token_balance {
+                if token_balance - prev_token_balance <= U256::from(1) {
+                    return Ok(token_balance);
+                }
+            } else if prev_token_balance - token_balance <= U256::from(1) {
+                return Ok(token_balance);
+            }
+        }
+
+        Err(eyre!("STABLE_GET_BALANCE_DIDNT_CONVERGE"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn test_balanced_pool_is_close_to_one_to_one() {
+        let amp = U256::from(200) * AMP_PRECISION;
+        let balances = vec![U256::from(1_000_000u128 * E18), U256::from(1_000_000u128 * E18)];
+        let invariant = BalancerStableMath::calculate_invariant(amp, &balances).unwrap();
+        assert!(invariant.abs_diff(U256::from(2_000_000u128 * E18)) <= U256::from(2));
+
+        let amount_in = U256::from(1_000u128 * E18);
+        let out = BalancerStableMath::calc_out_given_in(amp, &balances, 0, 1, amount_in, invariant).unwrap();
+        assert!(out < amount_in);
+        assert!(out > amount_in * U256::from(9999) / U256::from(10000));
+
+        let back = BalancerStableMath::calc_in_given_out(amp, &balances, 0, 1, out, invariant).unwrap();
+        // rounding is always in favour of the pool
+        assert!(back >= amount_in && back - amount_in < amount_in / U256::from(1_000_000_000_000u64));
+    }
+
+    #[test]
+    fn test_imbalanced_pool() {
+        let amp = U256::from(50) * AMP_PRECISION;
+        let balances = vec![U256::from(900_000u128 * E18), U256::from(100_000u128 * E18), U256::from(500_000u128 * E18)];
+        let invariant = BalancerStableMath::calculate_invariant(amp, &balances).unwrap();
+
+        // token with the smaller balance is worth more
+        let amount_in = U256::from(100u128 * E18);
+        let out = BalancerStableMath::calc_out_given_in(amp, &balances, 0, 1, amount_in, invariant).unwrap();
+        assert!(out < amount_in);
+        let out_reverse = BalancerStableMath::calc_out_given_in(amp, &balances, 1, 0, amount_in, invariant).unwrap();
+        assert!(out_reverse > amount_in);
+    }
+
+    #[test]
+    fn test_overflow_is_error() {
+        let amp = U256::from(200) * AMP_PRECISION;
+        let balances = vec![U256::MAX / U256::from(2), U256::MAX / U256::from(2)];
+        assert!(BalancerStableMath::calculate_invariant(amp, &balances).is_err());
+
+        let balances = vec![U256::from(1_000_000u128 * E18), U256::from(1_000_000u128 * E18)];
+        let invariant = BalancerStableMath::calculate_invariant(amp, &balances).unwrap();
+        assert!(BalancerStableMath::calc_out_given_in(amp, &balances, 0, 1, U256::MAX, invariant).is_err());
+    }
+}
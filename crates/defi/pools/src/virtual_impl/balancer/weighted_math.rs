@@ -0,0 +1,94 @@
+use alloy_primitives::U256;
+use eyre::{eyre, Result};
+
+use super::fixed_point::{complement, div_down, div_up, mul_down, mul_up, pow_up, ONE};
+
+// Swap limits: amounts swapped may not be larger than this percentage of total balance.
+const MAX_IN_RATIO: U256 = U256::from_limbs([300_000_000_000_000_000, 0, 0, 0]);
+const MAX_OUT_RATIO: U256 = U256::from_limbs([300_000_000_000_000_000, 0, 0, 0]);
+
+pub struct BalancerWeightedMath;
+
+impl BalancerWeightedMath {
+    /// Computes how many tokens can be taken out of a pool if `amount_in` are sent, given the current balances and weights.
+    /// All values are upscaled 18 decimal fixed point numbers.
+    pub fn calc_out_given_in(balance_in: U256, weight_in: U256, balance_out: U256, weight_out: U256, amount_in: U256) -> Result<U256> {
+        if amount_in > mul_down(balance_in, MAX_IN_RATIO)? {
+            return Err(eyre!("MAX_IN_RATIO"));
+        }
+
+        let denominator = balance_in.checked_add(amount_in).ok_or_else(|| eyre!("ADD_OVERFLOW"))?;
+        let base = div_up(balance_in, denominator)?;
+        let exponent = div_down(weight_in, weight_out)?;
+        let power = pow_up(base, exponent)?;
+
+        mul_down(balance_out, complement(power))
+    }
+
+    /// Computes how many tokens must be sent to a pool in order to take `amount_out`, given the current balances and weights.
+    pub fn calc_in_given_out(balance_in: U256, weight_in: U256, balance_out: U256, weight_out: U256, amount_out: U256) -> Result<U256> {
+        if amount_out > mul_down(balance_out, MAX_OUT_RATIO)? {
+            return Err(eyre!("MAX_OUT_RATIO"));
+        }
+
+        let base = div_up(balance_out, balance_out.checked_sub(amount_out).ok_or_else(|| eyre!("SUB_OVERFLOW"))?)?;
+        let exponent = div_up(weight_out, weight_in)?;
+        let power = pow_up(base, exponent)?;
+
+        let ratio = power.checked_sub(ONE).ok_or_else(|| eyre!("SUB_OVERFLOW"))?;
+        mul_up(balance_in, ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn test_equal_weights_match_constant_product() {
+        let balance_in = U256::from(1_000u128 * E18);
+        let balance_out = U256::from(2_000u128 * E18);
+        let weight = U256::from(E18 / 2);
+        let amount_in = U256::from(10u128 * E18);
+
+        let out = BalancerWeightedMath::calc_out_given_in(balance_in, weight, balance_out, weight, amount_in).unwrap();
+        let expected = balance_out * amount_in / (balance_in + amount_in);
+        // rounding is always in favour of the pool
+        assert!(out <= expected && expected - out < expected / U256::from(1_000_000_000_000u64));
+
+        let back = BalancerWeightedMath::calc_in_given_out(balance_in, weight, balance_out, weight, out).unwrap();
+        assert!(back.abs_diff(amount_in) < amount_in / U256::from(1_000_000_000_000u64));
+    }
+
+    #[test]
+    fn test_unequal_weights() {
+        let balance_in = U256::from(1_000u128 * E18);
+        let balance_out = U256::from(4_000u128 * E18);
+        let amount_in = U256::from(E18);
+
+        // 80/20 pool, out = 4000 * (1 - (1000/1001)^4)
+        let out =
+            BalancerWeightedMath::calc_out_given_in(balance_in, U256::from(E18 * 8 / 10), balance_out, U256::from(E18 * 2 / 10), amount_in)
+                .unwrap();
+        let expected = 4000.0 * (1.0 - (1000.0f64 / 1001.0).powi(4));
+        let out_f = out.to::<u128>() as f64 / E18 as f64;
+        assert!((out_f - expected).abs() / expected < 1e-9, "{out_f} {expected}");
+
+        // 20/80 pool exercises LogExpMath.pow
+        let out =
+            BalancerWeightedMath::calc_out_given_in(balance_in, U256::from(E18 * 2 / 10), balance_out, U256::from(E18 * 8 / 10), amount_in)
+                .unwrap();
+        let expected = 4000.0 * (1.0 - (1000.0f64 / 1001.0).powf(0.25));
+        let out_f = out.to::<u128>() as f64 / E18 as f64;
+        assert!((out_f - expected).abs() / expected < 1e-9, "{out_f} {expected}");
+    }
+
+    #[test]
+    fn test_max_in_ratio() {
+        let balance = U256::from(1_000u128 * E18);
+        let weight = U256::from(E18 / 2);
+        assert!(BalancerWeightedMath::calc_out_given_in(balance, weight, balance, weight, U256::from(301u128 * E18)).is_err());
+    }
+}
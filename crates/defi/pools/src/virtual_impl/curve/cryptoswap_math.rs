@@ -0,0 +1,315 @@
+use alloy_primitives::U256;
+use eyre::{eyre, Result};
+
+use super::stableswap_math::{FEE_DENOMINATOR, PRECISION};
+
+const A_MULTIPLIER: U256 = U256::from_limbs([10_000, 0, 0, 0]);
+const MAX_ITERATIONS: usize = 255;
+
+const E14: U256 = U256::from_limbs([100_000_000_000_000, 0, 0, 0]);
+const E16: U256 = U256::from_limbs([10_000_000_000_000_000, 0, 0, 0]);
+const E20: U256 = U256::from_limbs([7766279631452241920, 5, 0, 0]);
+
+/// State of a CryptoSwap pool as seen by `get_dy`
+#[derive(Clone, Debug, Default)]
+pub struct CurveCryptoSwapState {
+    pub balances: Vec<U256>,
+    /// `10^(18 - decimals)` multipliers
+    pub precisions: Vec<U256>,
+    /// Prices of coins 1..N in coin 0
+    pub price_scale: Vec<U256>,
+    pub ann: U256,
+    pub gamma: U256,
+    pub d: U256,
+    /// D is recalculated from balances while A and gamma are ramped
+    pub ramping: bool,
+    pub mid_fee: U256,
+    pub out_fee: U256,
+    pub fee_gamma: U256,
+}
+
+pub struct CurveCryptoSwapMath;
+
+impl CurveCryptoSwapMath {
+    fn abs_diff(a: U256, b: U256) -> U256 {
+        if a > b {
+            a - b
+        } else {
+            b - a
+        }
+    }
+
+    // Insertion sort from high to low
+    fn sort(x: &[U256]) -> Vec<U256> {
+        let mut ret = x.to_vec();
+        ret.sort_by(|a, b| b.cmp(a));
+        ret
+    }
+
+    fn check_frac(value: U256, d: U256) -> Result<()> {
+        let frac = value * PRECISION / d;
+        if frac < E16 - U256::from(1) || frac > E20 + U256::from(1) {
+            return Err(eyre!("CURVE_UNSAFE_VALUES"));
+        }
+        Ok(())
+    }
+
+    pub fn geometric_mean(x: &[U256]) -> Result<U256> {
+        let n_coins = U256::from(x.len());
+        let mut d = x[0];
+
+        for _ in 0..MAX_ITERATIONS {
+            let d_prev = d;
+            if x.len() == 2 {
+                d = (d + x[0] * x[1] / d) / n_coins;
+            } else {
+                let mut tmp = PRECISION;
+                for x_k in x.iter() {
+                    tmp = tmp * *x_k / d;
+                }
+                d = d * ((n_coins - U256::from(1)) * PRECISION + tmp) / (n_coins * PRECISION);
+            }
+
+            let diff = Self::abs_diff(d, d_prev);
+            if diff <= U256::from(1) || diff * PRECISION < d {
+                return Ok(d);
+            }
+        }
+
+        Err(eyre!("CURVE_GEOMETRIC_MEAN_DIDNT_CONVERGE"))
+    }
+
+    pub fn reduction_coefficient(x: &[U256], fee_gamma: U256) -> U256 {
+        let n_coins = U256::from(x.len());
+        let s: U256 = x.iter().fold(U256::ZERO, |acc, x_k| acc + *x_k);
+
+        let mut k = PRECISION;
+        for x_k in x.iter() {
+            k = k * n_coins * *x_k / s;
+        }
+        if !fee_gamma.is_zero() {
+            k = fee_gamma * PRECISION / (fee_gamma + PRECISION - k);
+        }
+        k
+    }
+
+    fn g1k0(gamma: U256, k0: U256) -> U256 {
+        let g1k0 = gamma + PRECISION;
+        if g1k0 > k0 {
+            g1k0 - k0 + U256::from(1)
+        } else {
+            k0 - g1k0 + U256::from(1)
+        }
+    }
+
+    pub fn newton_d(ann: U256, gamma: U256, x_unsorted: &[U256]) -> Result<U256> {
+        let n_coins = U256::from(x_unsorted.len());
+        let x = Self::sort(x_unsorted);
+        if x.iter().any(|x_k| x_k.is_zero()) {
+            return Err(eyre!("ZERO_BALANCE"));
+        }
+
+        let mut d = n_coins * Self::geometric_mean(&x)?;
+        let s: U256 = x.iter().fold(U256::ZERO, |acc, x_k| acc + *x_k);
+
+        for _ in 0..MAX_ITERATIONS {
+            let d_prev = d;
+
+            let k0 = if x.len() == 2 {
+                (PRECISION * n_coins * n_coins) * x[0] / d * x[1] / d
+            } else {
+                let mut k0 = PRECISION;
+                for x_k in x.iter() {
+                    k0 = k0 * *x_k * n_coins / d;
+                }
+                k0
+            };
+
+            let g1k0 = Self::g1k0(gamma, k0);
+
+            let mul1 = PRECISION * d / gamma * g1k0 / gamma * g1k0 * A_MULTIPLIER / ann;
+            let mul2 = U256::from(2) * PRECISION * n_coins * k0 / g1k0;
+
+            let neg_fprime = (s + s * mul2 / PRECISION) + mul1 * n_coins / k0 - mul2 * d / PRECISION;
+
+            let d_plus = d * (neg_fprime + s) / neg_fprime;
+            let mut d_minus = d * d / neg_fprime;
+            if PRECISION > k0 {
+                d_minus += d * (mul1 / neg_fprime) / PRECISION * (PRECISION - k0) / k0;
+            } else {
+                d_minus -= d * (mul1 / neg_fprime) / PRECISION * (k0 - PRECISION) / k0;
+            }
+
+            d = if d_plus > d_minus { d_plus - d_minus } else { (d_minus - d_plus) / U256::from(2) };
+
+            let diff = Self::abs_diff(d, d_prev);
+            if diff * E14 < d.max(E16) {
+                for x_k in x.iter() {
+                    Self::check_frac(*x_k, d)?;
+                }
+                return Ok(d);
+            }
+        }
+
+        Err(eyre!("CURVE_D_DIDNT_CONVERGE"))
+    }
+
+    /// Balance of coin `i` keeping the invariant `d` with other balances in `x`
+    pub fn newton_y(ann: U256, gamma: U256, x: &[U256], d: U256, i: usize) -> Result<U256> {
+        let n = x.len();
+        let n_coins = U256::from(n);
+
+        let mut x_sorted = x.to_vec();
+        x_sorted[i] = U256::ZERO;
+        let x_sorted = Self::sort(&x_sorted);
+        if x_sorted[..n - 1].iter().any(|x_k| x_k.is_zero()) {
+            return Err(eyre!("ZERO_BALANCE"));
+        }
+
+        let convergence_limit = (x_sorted[0] / E14).max(d / E14).max(U256::from(100));
+
+        let mut y;
+        let mut s_i = U256::ZERO;
+        let mut k0_i = PRECISION;
+
+        if n == 2 {
+            y = d * d / (x_sorted[0] * n_coins * n_coins);
+            s_i = x_sorted[0];
+            k0_i = (PRECISION * n_coins) * x_sorted[0] / d;
+        } else {
+            y = d / n_coins;
+            for j in 2..=n {
+                let x_k = x_sorted[n - j];
+                y = y * d / (x_k * n_coins);
+                s_i += x_k;
+            }
+            for x_k in x_sorted[..n - 1].iter() {
+                k0_i = k0_i * *x_k * n_coins / d;
+            }
+        }
+
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+
+            let k0 = k0_i * y * n_coins / d;
+            let s = s_i + y;
+
+            let g1k0 = Self::g1k0(gamma, k0);
+
+            let mul1 = PRECISION * d / gamma * g1k0 / gamma * g1k0 * A_MULTIPLIER / ann;
+            let mul2 = PRECISION + U256::from(2) * PRECISION * k0 / g1k0;
+
+            let mut yfprime = PRECISION * y + s * mul2 + mul1;
+            let dyfprime = d * mul2;
+            if yfprime < dyfprime {
+                y = y_prev / U256::from(2);
+                continue;
+            } else {
+                yfprime -= dyfprime;
+            }
+            let fprime = yfprime / y;
+
+            let mut y_minus = mul1 / fprime;
+            let y_plus = (yfprime + PRECISION * d) / fprime + y_minus * PRECISION / k0;
+            y_minus += PRECISION * s / fprime;
+
+            y = if y_plus < y_minus { y_prev / U256::from(2) } else { y_plus - y_minus };
+
+            let diff = Self::abs_diff(y, y_prev);
+            if diff < convergence_limit.max(y / E14) {
+                Self::check_frac(y, d)?;
+                return Ok(y);
+            }
+        }
+
+        Err(eyre!("CURVE_Y_DIDNT_CONVERGE"))
+    }
+
+    /// Dynamic fee in `FEE_DENOMINATOR` units, grows from `mid_fee` to `out_fee` as the pool gets imbalanced
+    pub fn fee(state: &CurveCryptoSwapState, xp: &[U256]) -> U256 {
+        let f = Self::reduction_coefficient(xp, state.fee_gamma);
+        (state.mid_fee * f + state.out_fee * (PRECISION - f)) / PRECISION
+    }
+
+    fn scale_balances(state: &CurveCryptoSwapState, balances: &[U256]) -> Vec<U256> {
+        let mut xp = balances.to_vec();
+        xp[0] *= state.precisions[0];
+        for k in 1..xp.len() {
+            xp[k] = xp[k] * state.price_scale[k - 1] * state.precisions[k] / PRECISION;
+        }
+        xp
+    }
+
+    pub fn get_dy(state: &CurveCryptoSwapState, i: usize, j: usize, dx: U256) -> Result<U256> {
+        let n = state.balances.len();
+        if i == j || i >= n || j >= n {
+            return Err(eyre!("BAD_COIN_INDEX"));
+        }
+        if state.precisions.len() != n || state.price_scale.len() != n - 1 {
+            return Err(eyre!("BAD_CRYPTO_SWAP_STATE"));
+        }
+
+        let d =
+            if state.ramping { Self::newton_d(state.ann, state.gamma, &Self::scale_balances(state, &state.balances))? } else { state.d };
+
+        let mut balances = state.balances.clone();
+        balances[i] += dx;
+        let mut xp = Self::scale_balances(state, &balances);
+
+        let y = Self::newton_y(state.ann, state.gamma, &xp, d, j)?;
+        let mut dy = xp[j].checked_sub(y + U256::from(1)).ok_or(eyre!("CURVE_NOT_ENOUGH_BALANCE"))?;
+        xp[j] = y;
+
+        if j > 0 {
+            dy = dy * PRECISION / state.price_scale[j - 1];
+        }
+        dy /= state.precisions[j];
+
+        Ok(dy - Self::fee(state, &xp) * dy / FEE_DENOMINATOR)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // tricrypto like pool : USDT, WBTC, WETH
+    fn state() -> CurveCryptoSwapState {
+        CurveCryptoSwapState {
+            balances: vec![U256::from(30_000_000_000_000u64), U256::from(50_000_000_000u64), PRECISION * U256::from(10_000)],
+            precisions: vec![U256::from(1_000_000_000_000u64), U256::from(10_000_000_000u64), U256::from(1)],
+            price_scale: vec![PRECISION * U256::from(60_000), PRECISION * U256::from(3_000)],
+            ann: U256::from(1_707_629),
+            gamma: U256::from(11_809_167_828_997u64),
+            d: U256::ZERO,
+            ramping: true,
+            mid_fee: U256::from(3_000_000),
+            out_fee: U256::from(30_000_000),
+            fee_gamma: U256::from(500_000_000_000_000u64),
+        }
+    }
+
+    #[test]
+    fn test_newton_d_balanced_pool() -> Result<()> {
+        let xp = vec![PRECISION * U256::from(30_000_000); 3];
+        let d = CurveCryptoSwapMath::newton_d(U256::from(1_707_629), U256::from(11_809_167_828_997u64), &xp)?;
+        assert!(CurveCryptoSwapMath::abs_diff(d, PRECISION * U256::from(90_000_000)) < PRECISION);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_dy() -> Result<()> {
+        let state = state();
+
+        // 3000 USDT -> WETH at price 3000 is close to 1 WETH less fee
+        let dy = CurveCryptoSwapMath::get_dy(&state, 0, 2, U256::from(3_000_000_000u64))?;
+        assert!(dy < PRECISION);
+        assert!(dy > PRECISION * U256::from(99) / U256::from(100));
+
+        // 1 WETH -> WBTC at price 1/20
+        let dy = CurveCryptoSwapMath::get_dy(&state, 2, 1, PRECISION)?;
+        assert!(dy < U256::from(5_000_000u64));
+        assert!(dy > U256::from(4_950_000u64));
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use eyre::{eyre, Result};
+use lazy_static::lazy_static;
+use revm::primitives::Env;
+use revm::DatabaseRef;
+
+use loom_evm_db::LoomDBType;
+
+use crate::state_readers::CurveStateReader;
+pub use cryptoswap_math::{CurveCryptoSwapMath, CurveCryptoSwapState};
+pub use stableswap_math::{CurveStableSwapMath, FEE_DENOMINATOR, PRECISION};
+
+mod cryptoswap_math;
+mod stableswap_math;
+
+/// Invariant implementation used to evaluate pool natively
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveNativeVariant {
+    /// `a_precision` is 1 for legacy pools without `A_precise`
+    StableSwap {
+        a_precision: u64,
+    },
+    CryptoSwap,
+}
+
+impl CurveNativeVariant {
+    pub fn gas_estimate(&self) -> u64 {
+        match self {
+            CurveNativeVariant::StableSwap { .. } => 150_000,
+            CurveNativeVariant::CryptoSwap => 300_000,
+        }
+    }
+}
+
+lazy_static! {
+    static ref CODE_HASHES: RwLock<HashMap<B256, CodeHashSamples>> = RwLock::new(HashMap::new());
+}
+
+// Matching samples required to trust native math for a code hash
+const REQUIRED_MATCHES: u32 = 5;
+// Mismatching samples after which a code hash is evaluated in EVM only
+const MAX_MISMATCHES: u32 = 3;
+// Every n-th quote of a decided code hash is sampled again
+const VERIFY_INTERVAL: u64 = 100;
+
+/// How a quote of a pool with the code hash is calculated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveCodeHashVerdict {
+    Native,
+    Evm,
+    /// Calculate in EVM and compare native result with it
+    Verify,
+}
+
+#[derive(Clone, Debug, Default)]
+struct CodeHashSamples {
+    matches: u32,
+    mismatches: u32,
+    quotes: u64,
+}
+
+impl CodeHashSamples {
+    fn verdict(&mut self) -> CurveCodeHashVerdict {
+        self.quotes += 1;
+        let is_decided = self.matches >= REQUIRED_MATCHES || self.mismatches >= MAX_MISMATCHES;
+        if !is_decided || self.quotes % VERIFY_INTERVAL == 0 {
+            CurveCodeHashVerdict::Verify
+        } else if self.matches >= REQUIRED_MATCHES {
+            CurveCodeHashVerdict::Native
+        } else {
+            CurveCodeHashVerdict::Evm
+        }
+    }
+
+    // A mismatch resets the matches, so a trusted code hash has to be verified again
+    fn record(&mut self, matched: bool) {
+        if matched {
+            self.matches += 1;
+            self.mismatches = 0;
+        } else {
+            self.matches = 0;
+            self.mismatches += 1;
+        }
+    }
+}
+
+/// Code hashes of pool implementations checked against native math.
+/// Pools are evaluated in EVM and sampled against native math until enough samples agree, decided code hashes are sampled again
+/// periodically.
+pub struct CurveCodeHashRegistry;
+
+impl CurveCodeHashRegistry {
+    /// Trust native math for a known implementation without sampling
+    pub fn register(code_hash: B256) {
+        if let Ok(mut code_hashes) = CODE_HASHES.write() {
+            code_hashes.insert(code_hash, CodeHashSamples { matches: REQUIRED_MATCHES, ..Default::default() });
+        }
+    }
+
+    pub fn verdict(code_hash: &B256) -> CurveCodeHashVerdict {
+        match CODE_HASHES.write() {
+            Ok(mut code_hashes) => code_hashes.entry(*code_hash).or_default().verdict(),
+            Err(_) => CurveCodeHashVerdict::Evm,
+        }
+    }
+
+    pub fn record(code_hash: &B256, matched: bool) {
+        if let Ok(mut code_hashes) = CODE_HASHES.write() {
+            code_hashes.entry(*code_hash).or_default().record(matched);
+        }
+    }
+}
+
+// Storage slots of pool variables read by getters, together with the ETH balance and the block timestamp they key the native state
+const STATE_KEY_SLOTS: u64 = 0x20;
+
+/// Pool state used by native `get_dy`
+#[derive(Clone, Debug)]
+pub enum CurveNativeState {
+    StableSwap { balances: Vec<U256>, amp: U256, fee: U256 },
+    CryptoSwap(CurveCryptoSwapState),
+}
+
+pub struct CurvePoolVirtual;
+
+impl CurvePoolVirtual {
+    /// Key of the pool state, the native state read with getters stays valid while the key is the same
+    pub fn state_key(db: &LoomDBType, env: &Env, pool: Address) -> Result<B256> {
+        let mut data = Vec::with_capacity((STATE_KEY_SLOTS as usize + 2) * 32);
+        data.extend_from_slice(&env.block.timestamp.to_be_bytes::<32>());
+        data.extend_from_slice(&db.basic_ref(pool)?.map(|account| account.balance).unwrap_or_default().to_be_bytes::<32>());
+        for slot in 0..STATE_KEY_SLOTS {
+            data.extend_from_slice(&db.storage_ref(pool, U256::from(slot))?.to_be_bytes::<32>());
+        }
+        Ok(keccak256(data))
+    }
+
+    /// Read the state used by `get_dy` with pool getters
+    pub fn fetch_state(db: &LoomDBType, env: Env, pool: Address, variant: CurveNativeVariant, n_coins: usize) -> Result<CurveNativeState> {
+        let balances = CurveStateReader::balances(db, env.clone(), pool, n_coins)?;
+
+        match variant {
+            CurveNativeVariant::StableSwap { a_precision } => {
+                let amp = if a_precision > 1 {
+                    CurveStateReader::a_precise(db, env.clone(), pool)?
+                } else {
+                    CurveStateReader::a(db, env.clone(), pool)?
+                };
+                let fee = CurveStateReader::fee(db, env, pool)?;
+                Ok(CurveNativeState::StableSwap { balances, amp, fee })
+            }
+            CurveNativeVariant::CryptoSwap => Ok(CurveNativeState::CryptoSwap(CurveCryptoSwapState {
+                balances,
+                precisions: vec![],
+                price_scale: CurveStateReader::price_scale(db, env.clone(), pool, n_coins)?,
+                ann: CurveStateReader::a(db, env.clone(), pool)?,
+                gamma: CurveStateReader::gamma(db, env.clone(), pool)?,
+                d: CurveStateReader::d(db, env.clone(), pool)?,
+                ramping: !CurveStateReader::future_a_gamma_time(db, env.clone(), pool)?.is_zero(),
+                mid_fee: CurveStateReader::mid_fee(db, env.clone(), pool)?,
+                out_fee: CurveStateReader::out_fee(db, env.clone(), pool)?,
+                fee_gamma: CurveStateReader::fee_gamma(db, env, pool)?,
+            })),
+        }
+    }
+
+    /// Native `get_dy`. `precisions` are `10^(18 - decimals)` of pool coins.
+    pub fn get_dy(
+        state: &CurveNativeState,
+        variant: CurveNativeVariant,
+        precisions: &[U256],
+        i: usize,
+        j: usize,
+        dx: U256,
+    ) -> Result<U256> {
+        match (state, variant) {
+            (CurveNativeState::StableSwap { balances, amp, fee }, CurveNativeVariant::StableSwap { a_precision }) => {
+                let rates: Vec<U256> = precisions.iter().map(|precision| *precision * PRECISION).collect();
+                CurveStableSwapMath::get_dy(balances, &rates, *amp, U256::from(a_precision), *fee, a_precision == 1, i, j, dx)
+            }
+            (CurveNativeState::CryptoSwap(state), CurveNativeVariant::CryptoSwap) => {
+                let state = CurveCryptoSwapState { precisions: precisions.to_vec(), ..state.clone() };
+                CurveCryptoSwapMath::get_dy(&state, i, j, dx)
+            }
+            _ => Err(eyre!("CURVE_NATIVE_VARIANT_MISMATCH")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 3pool like state, expected values are calculated with a port of the pool vyper code
+    fn stableswap_state(amp: U256) -> (CurveNativeState, Vec<U256>) {
+        let balances = vec![
+            U256::from(170_123_456u64) * PRECISION + U256::from(987654321u64),
+            U256::from(160_456_789_123_456u64),
+            U256::from(70_987_654_654_321u64),
+        ];
+        let precisions = vec![U256::from(1), U256::from(1_000_000_000_000u64), U256::from(1_000_000_000_000u64)];
+        (CurveNativeState::StableSwap { balances, amp, fee: U256::from(1_000_000) }, precisions)
+    }
+
+    fn stableswap_vectors() -> Vec<(usize, usize, U256)> {
+        vec![(0, 1, U256::from(1000) * PRECISION), (1, 2, U256::from(5_000_000_000_000u64)), (2, 0, U256::from(25_000_000_000_000u64))]
+    }
+
+    #[test]
+    fn test_stableswap_legacy_vectors() -> Result<()> {
+        let (state, precisions) = stableswap_state(U256::from(2000));
+        let variant = CurveNativeVariant::StableSwap { a_precision: 1 };
+
+        let expected =
+            [U256::from(999870685u64), U256::from(4995921936832u64), U256::from_str_radix("25008512952876983249432221", 10).unwrap()];
+        for ((i, j, dx), expected) in stableswap_vectors().into_iter().zip(expected) {
+            assert_eq!(CurvePoolVirtual::get_dy(&state, variant, &precisions, i, j, dx)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stableswap_a_precise_vectors() -> Result<()> {
+        let (state, precisions) = stableswap_state(U256::from(200000));
+        let variant = CurveNativeVariant::StableSwap { a_precision: 100 };
+
+        let expected =
+            [U256::from(999870685u64), U256::from(4995921936831u64), U256::from_str_radix("25008512952876983249432221", 10).unwrap()];
+        for ((i, j, dx), expected) in stableswap_vectors().into_iter().zip(expected) {
+            assert_eq!(CurvePoolVirtual::get_dy(&state, variant, &precisions, i, j, dx)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_variant_mismatch() {
+        let (state, precisions) = stableswap_state(U256::from(2000));
+        assert!(CurvePoolVirtual::get_dy(&state, CurveNativeVariant::CryptoSwap, &precisions, 0, 1, PRECISION).is_err());
+    }
+
+    #[test]
+    fn test_code_hash_samples() {
+        let mut samples = CodeHashSamples::default();
+
+        // A single sample does not decide
+        assert_eq!(samples.verdict(), CurveCodeHashVerdict::Verify);
+        samples.record(true);
+        assert_eq!(samples.verdict(), CurveCodeHashVerdict::Verify);
+
+        for _ in 1..REQUIRED_MATCHES {
+            samples.record(true);
+        }
+        assert_eq!(samples.verdict(), CurveCodeHashVerdict::Native);
+
+        // Trusted code hashes are sampled again periodically and a mismatch withdraws the trust
+        while samples.verdict() != CurveCodeHashVerdict::Verify {}
+        samples.record(false);
+        assert_eq!(samples.verdict(), CurveCodeHashVerdict::Verify);
+
+        for _ in 1..MAX_MISMATCHES {
+            samples.record(false);
+        }
+        assert_eq!(samples.verdict(), CurveCodeHashVerdict::Evm);
+
+        // Distrusted code hashes are sampled again too
+        while samples.verdict() != CurveCodeHashVerdict::Verify {}
+        samples.record(true);
+        assert_eq!(samples.verdict(), CurveCodeHashVerdict::Verify);
+    }
+}
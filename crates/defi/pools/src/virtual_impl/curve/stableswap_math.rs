@@ -0,0 +1,159 @@
+use alloy_primitives::U256;
+use eyre::{eyre, Result};
+
+pub const FEE_DENOMINATOR: U256 = U256::from_limbs([10_000_000_000, 0, 0, 0]);
+pub const PRECISION: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+const MAX_ITERATIONS: usize = 255;
+
+pub struct CurveStableSwapMath;
+
+impl CurveStableSwapMath {
+    fn converged(a: U256, b: U256) -> bool {
+        if a > b {
+            a - b <= U256::from(1)
+        } else {
+            b - a <= U256::from(1)
+        }
+    }
+
+    /// StableSwap invariant `D`. `amp` already includes `a_precision`, balances are normalized to 18 decimals.
+    pub fn get_d(xp: &[U256], amp: U256, a_precision: U256) -> Result<U256> {
+        let n_coins = U256::from(xp.len());
+        let s: U256 = xp.iter().fold(U256::ZERO, |acc, x| acc + *x);
+        if s.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let mut d = s;
+        let ann = amp * n_coins;
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut d_p = d;
+            for x in xp.iter() {
+                if x.is_zero() {
+                    return Err(eyre!("ZERO_BALANCE"));
+                }
+                d_p = d_p * d / (*x * n_coins);
+            }
+            let d_prev = d;
+            d = (ann * s / a_precision + d_p * n_coins) * d / ((ann - a_precision) * d / a_precision + (n_coins + U256::from(1)) * d_p);
+
+            if Self::converged(d, d_prev) {
+                return Ok(d);
+            }
+        }
+
+        Err(eyre!("CURVE_D_DIDNT_CONVERGE"))
+    }
+
+    /// Balance of coin `j` keeping the invariant, when balance of coin `i` is set to `x`
+    pub fn get_y(i: usize, j: usize, x: U256, xp: &[U256], amp: U256, a_precision: U256) -> Result<U256> {
+        if i == j || i >= xp.len() || j >= xp.len() {
+            return Err(eyre!("BAD_COIN_INDEX"));
+        }
+
+        let n_coins = U256::from(xp.len());
+        let d = Self::get_d(xp, amp, a_precision)?;
+        let ann = amp * n_coins;
+
+        let mut c = d;
+        let mut s = U256::ZERO;
+        for (k, xp_k) in xp.iter().enumerate() {
+            let x_k = if k == i {
+                x
+            } else if k != j {
+                *xp_k
+            } else {
+                continue;
+            };
+            if x_k.is_zero() {
+                return Err(eyre!("ZERO_BALANCE"));
+            }
+            s += x_k;
+            c = c * d / (x_k * n_coins);
+        }
+        c = c * d * a_precision / (ann * n_coins);
+        let b = s + d * a_precision / ann;
+
+        let mut y = d;
+        for _ in 0..MAX_ITERATIONS {
+            let y_prev = y;
+            y = (y * y + c) / (U256::from(2) * y + b - d);
+
+            if Self::converged(y, y_prev) {
+                return Ok(y);
+            }
+        }
+
+        Err(eyre!("CURVE_Y_DIDNT_CONVERGE"))
+    }
+
+    /// Output amount of `get_dy`. `rates` are `10^(36 - decimals)` multipliers.
+    /// Legacy pools take the fee after converting the amount back from 18 decimals.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_dy(
+        balances: &[U256],
+        rates: &[U256],
+        amp: U256,
+        a_precision: U256,
+        fee: U256,
+        legacy_fee: bool,
+        i: usize,
+        j: usize,
+        dx: U256,
+    ) -> Result<U256> {
+        let xp: Vec<U256> = balances.iter().zip(rates.iter()).map(|(balance, rate)| *balance * *rate / PRECISION).collect();
+
+        let x = xp[i] + dx * rates[i] / PRECISION;
+        let y = Self::get_y(i, j, x, &xp, amp, a_precision)?;
+        let dy = xp[j].checked_sub(y + U256::from(1)).ok_or(eyre!("CURVE_NOT_ENOUGH_BALANCE"))?;
+
+        if legacy_fee {
+            let dy = dy * PRECISION / rates[j];
+            Ok(dy - fee * dy / FEE_DENOMINATOR)
+        } else {
+            Ok((dy - fee * dy / FEE_DENOMINATOR) * PRECISION / rates[j])
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_balanced_pool_invariant_is_sum() -> Result<()> {
+        let xp = vec![PRECISION * U256::from(1_000_000); 3];
+        let d = CurveStableSwapMath::get_d(&xp, U256::from(2000), U256::from(1))?;
+        assert_eq!(d, PRECISION * U256::from(3_000_000));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_dy_scales_decimals() -> Result<()> {
+        // DAI, USDC, USDT like pool
+        let rates = vec![PRECISION, PRECISION * U256::from(1_000_000_000_000u64), PRECISION * U256::from(1_000_000_000_000u64)];
+        let balances = vec![PRECISION * U256::from(1_000_000), U256::from(1_000_000_000_000u64), U256::from(1_000_000_000_000u64)];
+        let fee = U256::from(1_000_000); // 0.01%
+
+        let dy =
+            CurveStableSwapMath::get_dy(&balances, &rates, U256::from(2000), U256::from(1), fee, true, 0, 1, PRECISION * U256::from(1000))?;
+        assert!(dy < U256::from(1_000_000_000u64));
+        assert!(dy > U256::from(999_000_000u64));
+
+        let dy_new = CurveStableSwapMath::get_dy(
+            &balances,
+            &rates,
+            U256::from(200000),
+            U256::from(100),
+            fee,
+            false,
+            0,
+            1,
+            PRECISION * U256::from(1000),
+        )?;
+        assert!(dy_new.abs_diff(dy) <= U256::from(1));
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+use alloy_primitives::{Address, U256};
+use eyre::{eyre, Result};
+use loom_defi_uniswap_v3_math::full_math::{mul_div, mul_div_rounding_up};
+use loom_defi_uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::Pool;
+
+use crate::db_reader::MaverickDBReader;
+use crate::MaverickPool;
+
+pub struct MaverickPoolVirtual;
+
+// Maverick keeps all prices and amounts in 60.18 fixed point
+const ONE: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+const Q96: U256 = U256::from_limbs([0, 1 << 32, 0, 0]);
+
+#[derive(Clone, Debug, Default)]
+pub struct MaverickTickState {
+    pub sqrt_lower_tick_price: U256,
+    pub sqrt_upper_tick_price: U256,
+    pub sqrt_price: U256,
+    pub liquidity: U256,
+    pub reserve_a: U256,
+    pub reserve_b: U256,
+    pub bins: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SwapStep {
+    amount_in: U256,
+    amount_out: U256,
+}
+
+impl MaverickPoolVirtual {
+    /// Swaps do not look further for bins than this number of ticks from the active tick
+    pub const MAX_TICK_SEARCH: i32 = 256;
+
+    fn mul(x: U256, y: U256, ceil: bool) -> Result<U256> {
+        Self::mul_div(x, y, ONE, ceil)
+    }
+
+    fn div(x: U256, y: U256, ceil: bool) -> Result<U256> {
+        Self::mul_div(x, ONE, y, ceil)
+    }
+
+    fn mul_div(x: U256, y: U256, denominator: U256, ceil: bool) -> Result<U256> {
+        if ceil {
+            Ok(mul_div_rounding_up(x, y, denominator)?)
+        } else {
+            Ok(mul_div(x, y, denominator)?)
+        }
+    }
+
+    fn sqrt(x: U256) -> U256 {
+        (x * ONE).root(2)
+    }
+
+    fn from_scale(amount: U256, scale: U256, ceil: bool) -> U256 {
+        if ceil {
+            amount.div_ceil(scale)
+        } else {
+            amount / scale
+        }
+    }
+
+    /// Square root of the price at the lower edge of the tick, sqrt(1.0001)^(tick * tick_spacing)
+    pub fn tick_sqrt_price(tick_spacing: u32, tick: i32) -> Result<U256> {
+        let sqrt_price_x96 = get_sqrt_ratio_at_tick(tick * tick_spacing as i32)?;
+        Ok(mul_div(sqrt_price_x96, ONE, Q96)?)
+    }
+
+    /// Liquidity of the tick, solves (reserve_a + L * sqrt_lower) * (reserve_b + L / sqrt_upper) = L^2
+    pub fn tick_liquidity(reserve_a: U256, reserve_b: U256, sqrt_lower_tick_price: U256, sqrt_upper_tick_price: U256) -> Result<U256> {
+        let mut reserve_a = reserve_a;
+        let mut reserve_b = reserve_b;
+
+        let mut precision_bump = 0usize;
+        if (reserve_a >> 60).is_zero() && (reserve_b >> 60).is_zero() {
+            precision_bump = 40;
+            reserve_a <<= precision_bump;
+            reserve_b <<= precision_bump;
+        }
+
+        let diff = sqrt_upper_tick_price - sqrt_lower_tick_price;
+
+        if reserve_a.is_zero() || reserve_b.is_zero() {
+            let b = Self::div(reserve_a, sqrt_upper_tick_price, false)? + Self::mul(reserve_b, sqrt_lower_tick_price, false)?;
+            Ok(Self::mul_div(b, sqrt_upper_tick_price, diff, false)? >> precision_bump)
+        } else {
+            let b = (Self::div(reserve_a, sqrt_upper_tick_price, false)? + Self::mul(reserve_b, sqrt_lower_tick_price, false)?) >> 1;
+            let d = Self::mul(b, b, false)? + Self::mul_div(Self::mul(reserve_b, reserve_a, false)?, diff, sqrt_upper_tick_price, false)?;
+            Ok(Self::mul_div(b + Self::sqrt(d), sqrt_upper_tick_price, diff, false)? >> precision_bump)
+        }
+    }
+
+    pub fn sqrt_price(
+        reserve_a: U256,
+        reserve_b: U256,
+        sqrt_lower_tick_price: U256,
+        sqrt_upper_tick_price: U256,
+        liquidity: U256,
+    ) -> Result<U256> {
+        if reserve_a.is_zero() {
+            return Ok(sqrt_lower_tick_price);
+        }
+        if reserve_b.is_zero() {
+            return Ok(sqrt_upper_tick_price);
+        }
+        let virtual_a = reserve_a + Self::mul(liquidity, sqrt_lower_tick_price, false)?;
+        let virtual_b = reserve_b + Self::div(liquidity, sqrt_upper_tick_price, false)?;
+        Ok(Self::sqrt(Self::div(virtual_a, virtual_b, false)?))
+    }
+
+    /// Reserves of all bins of the tick are swapped as a single constant product range.
+    /// Movable bins are relocated only after the swap is complete, so the stored positions are used as is.
+    pub fn tick_state(db: &LoomDBType, pool: &MaverickPool, tick: i32) -> Result<Option<MaverickTickState>> {
+        let (reserve_a, reserve_b, bins) = MaverickDBReader::tick_reserves(db, pool.get_address(), tick)?;
+        if reserve_a.is_zero() && reserve_b.is_zero() {
+            return Ok(None);
+        }
+
+        let sqrt_lower_tick_price = Self::tick_sqrt_price(pool.tick_spacing(), tick)?;
+        let sqrt_upper_tick_price = Self::tick_sqrt_price(pool.tick_spacing(), tick + 1)?;
+        let liquidity = Self::tick_liquidity(reserve_a, reserve_b, sqrt_lower_tick_price, sqrt_upper_tick_price)?;
+        if liquidity.is_zero() {
+            return Ok(None);
+        }
+        let sqrt_price = Self::sqrt_price(reserve_a, reserve_b, sqrt_lower_tick_price, sqrt_upper_tick_price, liquidity)?;
+
+        Ok(Some(MaverickTickState { sqrt_lower_tick_price, sqrt_upper_tick_price, sqrt_price, liquidity, reserve_a, reserve_b, bins }))
+    }
+
+    // Amount to move the price to the edge of the tick, excluding fee
+    fn tick_amount_in(state: &MaverickTickState, token_a_in: bool) -> Result<U256> {
+        if token_a_in {
+            Self::mul(state.liquidity, state.sqrt_upper_tick_price.saturating_sub(state.sqrt_price), true)
+        } else {
+            Ok(Self::div(state.liquidity, state.sqrt_lower_tick_price, true)?.saturating_sub(Self::div(
+                state.liquidity,
+                state.sqrt_price,
+                false,
+            )?))
+        }
+    }
+
+    fn swap_tick_exact_in(state: &MaverickTickState, amount_in: U256, fee: U256, token_a_in: bool) -> Result<SwapStep> {
+        let reserve_out = if token_a_in { state.reserve_b } else { state.reserve_a };
+        let tick_amount_in = Self::tick_amount_in(state, token_a_in)?;
+        let fee_basis = Self::mul(tick_amount_in, fee, true)?;
+
+        if amount_in >= tick_amount_in + fee_basis {
+            return Ok(SwapStep { amount_in: tick_amount_in + fee_basis, amount_out: reserve_out });
+        }
+
+        let net_amount_in = Self::div(amount_in, ONE + fee, false)?;
+        let amount_out = if token_a_in {
+            let new_sqrt_price = state.sqrt_price + Self::div(net_amount_in, state.liquidity, false)?;
+            Self::div(Self::div(net_amount_in, state.sqrt_price, false)?, new_sqrt_price, false)?
+        } else {
+            let inv_sqrt_price = Self::div(ONE, state.sqrt_price, true)? + Self::div(net_amount_in, state.liquidity, false)?;
+            let new_sqrt_price = Self::div(ONE, inv_sqrt_price, false)?;
+            Self::mul(Self::mul(net_amount_in, state.sqrt_price, false)?, new_sqrt_price, false)?
+        };
+
+        Ok(SwapStep { amount_in, amount_out: amount_out.min(reserve_out) })
+    }
+
+    fn swap_tick_exact_out(state: &MaverickTickState, amount_out: U256, fee: U256, token_a_in: bool) -> Result<SwapStep> {
+        let reserve_out = if token_a_in { state.reserve_b } else { state.reserve_a };
+
+        if amount_out >= reserve_out {
+            let tick_amount_in = Self::tick_amount_in(state, token_a_in)?;
+            let fee_basis = Self::mul(tick_amount_in, fee, true)?;
+            return Ok(SwapStep { amount_in: tick_amount_in + fee_basis, amount_out: reserve_out });
+        }
+
+        let net_amount_in = if token_a_in {
+            let inv_sqrt_price = Self::div(ONE, state.sqrt_price, false)?
+                .checked_sub(Self::div(amount_out, state.liquidity, true)?)
+                .ok_or(eyre!("MAVERICK_PRICE_OUT_OF_TICK"))?;
+            let new_sqrt_price = Self::div(ONE, inv_sqrt_price, true)?;
+            Self::mul(state.liquidity, new_sqrt_price.saturating_sub(state.sqrt_price), true)?
+        } else {
+            let new_sqrt_price =
+                state.sqrt_price.checked_sub(Self::div(amount_out, state.liquidity, true)?).ok_or(eyre!("MAVERICK_PRICE_OUT_OF_TICK"))?;
+            Self::div(state.liquidity, new_sqrt_price, true)?.saturating_sub(Self::div(state.liquidity, state.sqrt_price, false)?)
+        };
+
+        Ok(SwapStep { amount_in: net_amount_in + Self::mul(net_amount_in, fee, true)?, amount_out })
+    }
+
+    // Bin map words are loaded only around the active tick, ticks of missing words are not empty but unknown
+    fn is_tick_loaded(db: &LoomDBType, pool: &MaverickPool, tick: i32) -> Result<bool> {
+        let (map_index, _) = MaverickDBReader::bin_map_pointer(tick, 0);
+        Ok(db.ext_db.is_some() || db.is_slot(&pool.get_address(), &MaverickDBReader::bin_map_slot(map_index)?))
+    }
+
+    // Returns scaled (amount_in, amount_out) and the number of bins swapped
+    fn simulate_swap(
+        db: &LoomDBType,
+        pool: &MaverickPool,
+        token_a_in: bool,
+        amount: U256,
+        exact_output: bool,
+    ) -> Result<(U256, U256, u64)> {
+        let fee = pool.get_fee();
+        let mut tick = MaverickDBReader::active_tick(db, pool.get_address())?;
+        let start_tick = tick;
+
+        let mut amount_remaining = amount;
+        let mut total_in = U256::ZERO;
+        let mut total_out = U256::ZERO;
+        let mut total_bins = 0u64;
+
+        while !amount_remaining.is_zero() {
+            if (tick - start_tick).abs() > Self::MAX_TICK_SEARCH {
+                return Err(eyre!("NOT_ENOUGH_LIQUIDITY"));
+            }
+            if !Self::is_tick_loaded(db, pool, tick)? {
+                return Err(eyre!("MAVERICK_BINS_NOT_LOADED"));
+            }
+
+            if let Some(state) = Self::tick_state(db, pool, tick)? {
+                let step = if exact_output {
+                    Self::swap_tick_exact_out(&state, amount_remaining, fee, token_a_in)?
+                } else {
+                    Self::swap_tick_exact_in(&state, amount_remaining, fee, token_a_in)?
+                };
+
+                amount_remaining = amount_remaining.saturating_sub(if exact_output { step.amount_out } else { step.amount_in });
+                total_in += step.amount_in;
+                total_out += step.amount_out;
+                total_bins += state.bins;
+
+                if amount_remaining.is_zero() {
+                    break;
+                }
+            }
+
+            // Buying B with A pushes the price up
+            tick += if token_a_in { 1 } else { -1 };
+        }
+
+        Ok((total_in, total_out, total_bins))
+    }
+
+    /// Returns the out amount and the number of bins swapped
+    pub fn simulate_swap_in_amount(db: &LoomDBType, pool: &MaverickPool, token_in: Address, amount_in: U256) -> Result<(U256, u64)> {
+        if amount_in.is_zero() {
+            return Ok((U256::ZERO, 0));
+        }
+
+        let token_a_in = token_in == pool.token0;
+        let (scale_in, scale_out) = pool.token_scales(token_a_in);
+
+        let (_, amount_out, bins) = Self::simulate_swap(db, pool, token_a_in, amount_in * scale_in, false)?;
+
+        Ok((Self::from_scale(amount_out, scale_out, false), bins))
+    }
+
+    /// Returns the in amount and the number of bins swapped
+    pub fn simulate_swap_out_amount(db: &LoomDBType, pool: &MaverickPool, token_in: Address, amount_out: U256) -> Result<(U256, u64)> {
+        if amount_out.is_zero() {
+            return Ok((U256::ZERO, 0));
+        }
+
+        let token_a_in = token_in == pool.token0;
+        let (scale_in, scale_out) = pool.token_scales(token_a_in);
+
+        let (amount_in, _, bins) = Self::simulate_swap(db, pool, token_a_in, amount_out * scale_out, true)?;
+
+        Ok((Self::from_scale(amount_in, scale_in, true), bins))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tick_sqrt_price() -> Result<()> {
+        assert_eq!(MaverickPoolVirtual::tick_sqrt_price(10, 0)?, ONE);
+        assert!(MaverickPoolVirtual::tick_sqrt_price(10, 1)? > ONE);
+        assert!(MaverickPoolVirtual::tick_sqrt_price(10, -1)? < ONE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tick_liquidity() -> Result<()> {
+        let sqrt_lower_tick_price = MaverickPoolVirtual::tick_sqrt_price(10, 0)?;
+        let sqrt_upper_tick_price = MaverickPoolVirtual::tick_sqrt_price(10, 1)?;
+        let reserve_a = ONE * U256::from(1000);
+        let reserve_b = ONE * U256::from(1000);
+
+        let liquidity = MaverickPoolVirtual::tick_liquidity(reserve_a, reserve_b, sqrt_lower_tick_price, sqrt_upper_tick_price)?;
+        let sqrt_price = MaverickPoolVirtual::sqrt_price(reserve_a, reserve_b, sqrt_lower_tick_price, sqrt_upper_tick_price, liquidity)?;
+        assert!(sqrt_price > sqrt_lower_tick_price && sqrt_price < sqrt_upper_tick_price);
+
+        // Whole tick is drained by the amount to the tick edge
+        let state =
+            MaverickTickState { sqrt_lower_tick_price, sqrt_upper_tick_price, sqrt_price, liquidity, reserve_a, reserve_b, bins: 1 };
+        let step = MaverickPoolVirtual::swap_tick_exact_in(&state, reserve_a * U256::from(2), U256::ZERO, true)?;
+        assert_eq!(step.amount_out, reserve_b);
+
+        let step = MaverickPoolVirtual::swap_tick_exact_in(&state, ONE, U256::ZERO, true)?;
+        assert!(step.amount_out < ONE && step.amount_out > ONE * U256::from(99) / U256::from(100));
+        Ok(())
+    }
+}
@@ -1,4 +1,14 @@
+pub use algebra::AlgebraPoolVirtual;
+pub use maverick::MaverickPoolVirtual;
+pub use solidly::{SolidlyPoolState, SolidlyPoolVirtual};
 pub use uniswapv3::UniswapV3PoolVirtual;
+pub use uniswapv4::UniswapV4PoolVirtual;
 
+mod algebra;
+pub mod balancer;
+pub mod curve;
+mod maverick;
+mod solidly;
 pub mod tick_provider;
 mod uniswapv3;
+mod uniswapv4;
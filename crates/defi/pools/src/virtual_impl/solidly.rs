@@ -0,0 +1,194 @@
+use alloy_primitives::U256;
+use eyre::{eyre, Result};
+
+const ONE: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+const FEE_DENOMINATOR: U256 = U256::from_limbs([10_000, 0, 0, 0]);
+
+const MAX_ITERATIONS: usize = 255;
+
+/// Reserves and parameters of a Solidly pool, `decimals` are `10^decimals` of the tokens like in the pool contract
+#[derive(Clone, Debug, Default)]
+pub struct SolidlyPoolState {
+    pub reserve0: U256,
+    pub reserve1: U256,
+    pub decimals0: U256,
+    pub decimals1: U256,
+    pub stable: bool,
+    pub fee: U256,
+}
+
+/// Swap math of Velodrome V2 style pools with `x*y=k` volatile and `x³y+y³x=k` stable invariants
+pub struct SolidlyPoolVirtual;
+
+impl SolidlyPoolVirtual {
+    fn mul_div(x: U256, y: U256, denominator: U256) -> Result<U256> {
+        x.checked_mul(y).ok_or_else(|| eyre!("MUL_OVERFLOW"))?.checked_div(denominator).ok_or_else(|| eyre!("DIVISION_BY_ZERO"))
+    }
+
+    // x0 * y * (x0^2 + y^2)
+    fn f(x0: U256, y: U256) -> Result<U256> {
+        let a = Self::mul_div(x0, y, ONE)?;
+        let b = Self::mul_div(x0, x0, ONE)? + Self::mul_div(y, y, ONE)?;
+        Self::mul_div(a, b, ONE)
+    }
+
+    // derivative of f by y
+    fn d(x0: U256, y: U256) -> Result<U256> {
+        Ok(Self::mul_div(U256::from(3) * x0, Self::mul_div(y, y, ONE)?, ONE)? + Self::mul_div(Self::mul_div(x0, x0, ONE)?, x0, ONE)?)
+    }
+
+    /// Invariant of the pool for the given reserves
+    pub fn k(state: &SolidlyPoolState, x: U256, y: U256) -> Result<U256> {
+        if state.stable {
+            let x = Self::mul_div(x, ONE, state.decimals0)?;
+            let y = Self::mul_div(y, ONE, state.decimals1)?;
+            let a = Self::mul_div(x, y, ONE)?;
+            let b = Self::mul_div(x, x, ONE)? + Self::mul_div(y, y, ONE)?;
+            Self::mul_div(a, b, ONE)
+        } else {
+            x.checked_mul(y).ok_or_else(|| eyre!("MUL_OVERFLOW"))
+        }
+    }
+
+    // Newton iteration of the pool contract, including its rounding corrections
+    fn get_y(state: &SolidlyPoolState, x0: U256, xy: U256, mut y: U256) -> Result<U256> {
+        for _ in 0..MAX_ITERATIONS {
+            let k = Self::f(x0, y)?;
+            if k < xy {
+                let mut dy = Self::mul_div(xy - k, ONE, Self::d(x0, y)?)?;
+                if dy.is_zero() {
+                    if k == xy {
+                        return Ok(y);
+                    }
+                    if Self::k(state, x0, y + U256::from(1))? > xy {
+                        return Ok(y + U256::from(1));
+                    }
+                    dy = U256::from(1);
+                }
+                y += dy;
+            } else {
+                let mut dy = Self::mul_div(k - xy, ONE, Self::d(x0, y)?)?;
+                if dy.is_zero() {
+                    if k == xy || Self::f(x0, y - U256::from(1))? < xy {
+                        return Ok(y);
+                    }
+                    dy = U256::from(1);
+                }
+                y = y.checked_sub(dy).ok_or_else(|| eyre!("Y_UNDERFLOW"))?;
+            }
+        }
+        Err(eyre!("Y_DIDNT_CONVERGE"))
+    }
+
+    fn reserves_and_decimals(state: &SolidlyPoolState, zero_for_one: bool) -> (U256, U256, U256, U256) {
+        if zero_for_one {
+            (state.reserve0, state.reserve1, state.decimals0, state.decimals1)
+        } else {
+            (state.reserve1, state.reserve0, state.decimals1, state.decimals0)
+        }
+    }
+
+    /// Same as `getAmountOut` of the pool, fee is taken from the amount in
+    pub fn calculate_out_amount(state: &SolidlyPoolState, zero_for_one: bool, amount_in: U256) -> Result<U256> {
+        let amount_in = amount_in - Self::mul_div(amount_in, state.fee, FEE_DENOMINATOR)?;
+        let (reserve_in, reserve_out, decimals_in, decimals_out) = Self::reserves_and_decimals(state, zero_for_one);
+
+        if state.stable {
+            let xy = Self::k(state, state.reserve0, state.reserve1)?;
+            let reserve_in = Self::mul_div(reserve_in, ONE, decimals_in)?;
+            let reserve_out = Self::mul_div(reserve_out, ONE, decimals_out)?;
+            let amount_in = Self::mul_div(amount_in, ONE, decimals_in)?;
+
+            let y = Self::get_y(state, amount_in + reserve_in, xy, reserve_out)?;
+            let amount_out = reserve_out.checked_sub(y).ok_or_else(|| eyre!("RESERVE_EXCEEDED"))?;
+            Self::mul_div(amount_out, decimals_out, ONE)
+        } else {
+            Self::mul_div(amount_in, reserve_out, reserve_in + amount_in)
+        }
+    }
+
+    /// Inverse of `calculate_out_amount`, rounded up so that the out amount is at least reached
+    pub fn calculate_in_amount(state: &SolidlyPoolState, zero_for_one: bool, amount_out: U256) -> Result<U256> {
+        let (reserve_in, reserve_out, decimals_in, decimals_out) = Self::reserves_and_decimals(state, zero_for_one);
+        if amount_out >= reserve_out {
+            return Err(eyre!("RESERVE_OUT_EXCEEDED"));
+        }
+
+        let amount_in = if state.stable {
+            let xy = Self::k(state, state.reserve0, state.reserve1)?;
+            let reserve_in = Self::mul_div(reserve_in, ONE, decimals_in)?;
+            let reserve_out = Self::mul_div(reserve_out, ONE, decimals_out)?;
+            let amount_out = Self::mul_div(amount_out, ONE, decimals_out)? + U256::from(1);
+
+            // the invariant is symmetric, so the new reserve in is found the same way as the reserve out
+            let x = Self::get_y(state, reserve_out.checked_sub(amount_out).ok_or_else(|| eyre!("RESERVE_OUT_EXCEEDED"))?, xy, reserve_in)?;
+            let amount_in = x.checked_sub(reserve_in).ok_or_else(|| eyre!("AMOUNT_IN_UNDERFLOW"))?;
+            Self::mul_div(amount_in, decimals_in, ONE)? + U256::from(1)
+        } else {
+            Self::mul_div(reserve_in, amount_out, reserve_out - amount_out)? + U256::from(1)
+        };
+
+        let fee_complement = FEE_DENOMINATOR.checked_sub(state.fee).filter(|x| !x.is_zero()).ok_or_else(|| eyre!("BAD_FEE"))?;
+        Ok(Self::mul_div(amount_in, FEE_DENOMINATOR, fee_complement)? + U256::from(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn state(stable: bool) -> SolidlyPoolState {
+        // USDC (6 decimals) / DAI (18 decimals) with 10M each
+        SolidlyPoolState {
+            reserve0: U256::from(10_000_000_000_000u64),
+            reserve1: U256::from(10_000_000u64) * ONE,
+            decimals0: U256::from(1_000_000u64),
+            decimals1: ONE,
+            stable,
+            fee: U256::from(if stable { 5 } else { 30 }),
+        }
+    }
+
+    #[test]
+    fn test_volatile_out_amount() -> Result<()> {
+        let state = state(false);
+        let amount_in = U256::from(1_000_000_000u64);
+
+        let amount_out = SolidlyPoolVirtual::calculate_out_amount(&state, true, amount_in)?;
+        let amount_in_with_fee = amount_in - amount_in * state.fee / FEE_DENOMINATOR;
+        assert_eq!(amount_out, amount_in_with_fee * state.reserve1 / (state.reserve0 + amount_in_with_fee));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stable_out_amount() -> Result<()> {
+        let state = state(true);
+        // 1000 USDC
+        let amount_in = U256::from(1_000_000_000u64);
+
+        let amount_out = SolidlyPoolVirtual::calculate_out_amount(&state, true, amount_in)?;
+        // balanced stable pool swaps close to 1:1 minus the fee of 0.05%
+        assert!(amount_out < U256::from(1000) * ONE);
+        assert!(amount_out > U256::from(999) * ONE);
+
+        // invariant is kept after the swap
+        let k_before = SolidlyPoolVirtual::k(&state, state.reserve0, state.reserve1)?;
+        let k_after = SolidlyPoolVirtual::k(&state, state.reserve0 + amount_in, state.reserve1 - amount_out)?;
+        assert!(k_after >= k_before);
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_amount_reaches_out_amount() -> Result<()> {
+        for stable in [false, true] {
+            let state = state(stable);
+            let amount_out = U256::from(500) * ONE;
+
+            let amount_in = SolidlyPoolVirtual::calculate_in_amount(&state, true, amount_out)?;
+            let calculated_out = SolidlyPoolVirtual::calculate_out_amount(&state, true, amount_in)?;
+            assert!(calculated_out >= amount_out, "stable={stable}");
+            assert!(calculated_out - amount_out < ONE / U256::from(100), "stable={stable}");
+        }
+        Ok(())
+    }
+}
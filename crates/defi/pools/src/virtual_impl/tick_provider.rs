@@ -1,7 +1,9 @@
-use crate::db_reader::UniswapV3DBReader;
-use alloy_primitives::{Address, U256};
+use crate::db_reader::{UniswapV3DBReader, UniswapV4DBReader};
+use crate::state_readers::AlgebraStateReader;
+use alloy_primitives::{Address, B256, U256};
 use loom_defi_uniswap_v3_math::tick_provider::TickProvider;
 use loom_evm_db::LoomDBType;
+use revm::primitives::Env;
 
 pub struct TickProviderLoomDB<'a> {
     pub db: &'a LoomDBType,
@@ -19,3 +21,43 @@ impl<'a> TickProvider for TickProviderLoomDB<'a> {
         UniswapV3DBReader::tick_bitmap(self.db, self.pool_address, tick)
     }
 }
+
+pub struct TickProviderUniswapV4LoomDB<'a> {
+    pub db: &'a LoomDBType,
+    pub pool_manager: Address,
+    pub pool_id: B256,
+}
+
+impl<'a> TickProviderUniswapV4LoomDB<'a> {
+    pub fn new(db: &'a LoomDBType, pool_manager: Address, pool_id: B256) -> Self {
+        TickProviderUniswapV4LoomDB { db, pool_manager, pool_id }
+    }
+}
+
+impl<'a> TickProvider for TickProviderUniswapV4LoomDB<'a> {
+    // Only words around the price are loaded, the swap stops at the loaded boundary instead of crossing empty words
+    fn get_tick(&self, tick: i16) -> eyre::Result<U256> {
+        if self.db.ext_db.is_none() && !self.db.is_slot(&self.pool_manager, &UniswapV4DBReader::tick_bitmap_slot(self.pool_id, tick)?) {
+            return Err(eyre::eyre!("TICK_BITMAP_NOT_LOADED"));
+        }
+        UniswapV4DBReader::tick_bitmap(self.db, self.pool_manager, self.pool_id, tick)
+    }
+}
+
+pub struct TickProviderAlgebraEVM<'a> {
+    pub db: &'a LoomDBType,
+    pub env: Env,
+    pub pool_address: Address,
+}
+
+impl<'a> TickProviderAlgebraEVM<'a> {
+    pub fn new(db: &'a LoomDBType, env: Env, pool_address: Address) -> Self {
+        TickProviderAlgebraEVM { db, env, pool_address }
+    }
+}
+
+impl<'a> TickProvider for TickProviderAlgebraEVM<'a> {
+    fn get_tick(&self, tick: i16) -> eyre::Result<U256> {
+        AlgebraStateReader::tick_table(self.db, self.env.clone(), self.pool_address, tick)
+    }
+}
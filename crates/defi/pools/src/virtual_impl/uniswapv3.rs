@@ -50,11 +50,11 @@ pub const U256_1: U256 = U256::from_limbs([1, 0, 0, 0]);
 // Others
 
 pub struct CurrentState {
-    amount_specified_remaining: I256,
-    amount_calculated: I256,
-    sqrt_price_x_96: U256,
-    tick: i32,
-    liquidity: u128,
+    pub amount_specified_remaining: I256,
+    pub amount_calculated: I256,
+    pub sqrt_price_x_96: U256,
+    pub tick: i32,
+    pub liquidity: u128,
 }
 
 #[derive(Default)]
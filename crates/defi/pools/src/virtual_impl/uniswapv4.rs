@@ -0,0 +1,273 @@
+use alloy_primitives::{Address, I256, U256};
+use eyre::eyre;
+use loom_defi_uniswap_v3_math::tick_math::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK};
+
+use crate::db_reader::{UniswapV4DBReader, UniswapV4Slot0};
+use crate::virtual_impl::tick_provider::TickProviderUniswapV4LoomDB;
+use crate::virtual_impl::uniswapv3::{CurrentState, StepComputations, U256_1};
+use crate::UniswapV4Pool;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::Pool;
+
+pub struct UniswapV4PoolVirtual;
+
+const PIPS_DENOMINATOR: u32 = 1_000_000;
+
+impl UniswapV4PoolVirtual {
+    /// Total fee charged on a swap, protocol fee is taken from the input amount before the LP fee.
+    /// Lower 12 bits of the protocol fee are used for 0->1 swaps, upper 12 bits for 1->0 swaps.
+    pub fn swap_fee(slot0: &UniswapV4Slot0, zero_for_one: bool) -> u32 {
+        let protocol_fee = if zero_for_one { slot0.protocol_fee & 0xFFF } else { slot0.protocol_fee >> 12 };
+        if protocol_fee == 0 {
+            slot0.lp_fee
+        } else {
+            protocol_fee + slot0.lp_fee - ((protocol_fee as u64 * slot0.lp_fee as u64) / PIPS_DENOMINATOR as u64) as u32
+        }
+    }
+
+    pub fn simulate_swap_in_amount(db: &LoomDBType, pool: &UniswapV4Pool, token_in: Address, amount_in: U256) -> eyre::Result<U256> {
+        if amount_in.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let zero_for_one = token_in == pool.get_tokens()[0];
+
+        // Set sqrt_price_limit_x_96 to the max or min sqrt price in the pool depending on zero_for_one
+        let sqrt_price_limit_x_96 = if zero_for_one { MIN_SQRT_RATIO + U256_1 } else { MAX_SQRT_RATIO - U256_1 };
+
+        let pool_manager = pool.get_pool_manager();
+        let pool_id = pool.get_pool_id();
+
+        let slot0 = UniswapV4DBReader::slot0(db, pool_manager, pool_id)?;
+        let liquidity = UniswapV4DBReader::liquidity(db, pool_manager, pool_id)?;
+        let tick_spacing = pool.tick_spacing();
+        let fee = Self::swap_fee(&slot0, zero_for_one);
+
+        // Initialize a mutable state struct to hold the dynamic simulated state of the pool
+        let mut current_state = CurrentState {
+            sqrt_price_x_96: slot0.sqrt_price_x96,                 //Active price on the pool
+            amount_calculated: I256::ZERO,                         //Amount of token_out that has been calculated
+            amount_specified_remaining: I256::from_raw(amount_in), //Amount of token_in that has not been swapped
+            tick: slot0.tick,                                      //Current i24 tick of the pool
+            liquidity,                                             //Current available liquidity in the tick range
+        };
+
+        let tick_provider = TickProviderUniswapV4LoomDB::new(db, pool_manager, pool_id);
+
+        while current_state.amount_specified_remaining != I256::ZERO && current_state.sqrt_price_x_96 != sqrt_price_limit_x_96 {
+            // Initialize a new step struct to hold the dynamic state of the pool at each step
+            let mut step = StepComputations { sqrt_price_start_x_96: current_state.sqrt_price_x_96, ..Default::default() };
+
+            // Get the next tick from the current tick
+            (step.tick_next, step.initialized) = loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
+                &tick_provider,
+                current_state.tick,
+                tick_spacing,
+                zero_for_one,
+            )?;
+
+            // ensure that we do not overshoot the min/max tick, as the tick bitmap is not aware of these bounds
+            step.tick_next = step.tick_next.clamp(MIN_TICK, MAX_TICK);
+
+            // Get the next sqrt price from the input amount
+            step.sqrt_price_next_x96 = loom_defi_uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick(step.tick_next)?;
+
+            // Target spot price
+            let swap_target_sqrt_ratio = if zero_for_one {
+                if step.sqrt_price_next_x96 < sqrt_price_limit_x_96 {
+                    sqrt_price_limit_x_96
+                } else {
+                    step.sqrt_price_next_x96
+                }
+            } else if step.sqrt_price_next_x96 > sqrt_price_limit_x_96 {
+                sqrt_price_limit_x_96
+            } else {
+                step.sqrt_price_next_x96
+            };
+
+            // Compute swap step and update the current state
+            (current_state.sqrt_price_x_96, step.amount_in, step.amount_out, step.fee_amount) =
+                loom_defi_uniswap_v3_math::swap_math::compute_swap_step(
+                    current_state.sqrt_price_x_96,
+                    swap_target_sqrt_ratio,
+                    current_state.liquidity,
+                    current_state.amount_specified_remaining,
+                    fee,
+                )?;
+
+            // Decrement the amount remaining to be swapped and amount received from the step
+            current_state.amount_specified_remaining = current_state
+                .amount_specified_remaining
+                .overflowing_sub(I256::from_raw(step.amount_in.overflowing_add(step.fee_amount).0))
+                .0;
+
+            current_state.amount_calculated -= I256::from_raw(step.amount_out);
+
+            // If the price moved all the way to the next price, recompute the liquidity change for the next iteration
+            if current_state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
+                if step.initialized {
+                    let mut liquidity_net: i128 =
+                        UniswapV4DBReader::ticks_liquidity_net(db, pool_manager, pool_id, step.tick_next).unwrap_or_default();
+
+                    if zero_for_one {
+                        liquidity_net = -liquidity_net;
+                    }
+
+                    current_state.liquidity = if liquidity_net < 0 {
+                        if current_state.liquidity < (-liquidity_net as u128) {
+                            return Err(eyre!("LIQUIDITY_UNDERFLOW"));
+                        } else {
+                            current_state.liquidity - (-liquidity_net as u128)
+                        }
+                    } else {
+                        current_state.liquidity + (liquidity_net as u128)
+                    };
+                }
+                current_state.tick = if zero_for_one { step.tick_next.wrapping_sub(1) } else { step.tick_next }
+            } else if current_state.sqrt_price_x_96 != step.sqrt_price_start_x_96 {
+                current_state.tick = loom_defi_uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio(current_state.sqrt_price_x_96)?;
+            }
+        }
+
+        if current_state.amount_specified_remaining.is_zero() {
+            let amount_out = (-current_state.amount_calculated).into_raw();
+            tracing::trace!("AmountOut : {amount_out}");
+            Ok(amount_out)
+        } else {
+            Err(eyre!("NOT_ENOUGH_LIQUIDITY"))
+        }
+    }
+
+    pub fn simulate_swap_out_amount(db: &LoomDBType, pool: &UniswapV4Pool, token_in: Address, amount_out: U256) -> eyre::Result<U256> {
+        if amount_out.is_zero() {
+            return Ok(U256::ZERO);
+        }
+
+        let zero_for_one = token_in == pool.get_tokens()[0];
+
+        // Set sqrt_price_limit_x_96 to the max or min sqrt price in the pool depending on zero_for_one
+        let sqrt_price_limit_x_96 = if zero_for_one { MIN_SQRT_RATIO + U256_1 } else { MAX_SQRT_RATIO - U256_1 };
+
+        let pool_manager = pool.get_pool_manager();
+        let pool_id = pool.get_pool_id();
+
+        let slot0 = UniswapV4DBReader::slot0(db, pool_manager, pool_id)?;
+        let liquidity = UniswapV4DBReader::liquidity(db, pool_manager, pool_id)?;
+        let tick_spacing = pool.tick_spacing();
+        let fee = Self::swap_fee(&slot0, zero_for_one);
+
+        if fee >= PIPS_DENOMINATOR {
+            return Err(eyre!("INVALID_FEE_FOR_EXACT_OUT"));
+        }
+
+        // Initialize a mutable state struct to hold the dynamic simulated state of the pool
+        let mut current_state = CurrentState {
+            sqrt_price_x_96: slot0.sqrt_price_x96,                   //Active price on the pool
+            amount_calculated: I256::ZERO,                           //Amount of token_in that has been calculated
+            amount_specified_remaining: -I256::from_raw(amount_out), //Amount of token_out that has not been received
+            tick: slot0.tick,                                        //Current i24 tick of the pool
+            liquidity,                                               //Current available liquidity in the tick range
+        };
+
+        let tick_provider = TickProviderUniswapV4LoomDB::new(db, pool_manager, pool_id);
+
+        while current_state.amount_specified_remaining != I256::ZERO && current_state.sqrt_price_x_96 != sqrt_price_limit_x_96 {
+            // Initialize a new step struct to hold the dynamic state of the pool at each step
+            let mut step = StepComputations { sqrt_price_start_x_96: current_state.sqrt_price_x_96, ..Default::default() };
+
+            // Get the next tick from the current tick
+            (step.tick_next, step.initialized) = loom_defi_uniswap_v3_math::tick_bitmap::next_initialized_tick_within_one_word(
+                &tick_provider,
+                current_state.tick,
+                tick_spacing,
+                zero_for_one,
+            )?;
+
+            // ensure that we do not overshoot the min/max tick, as the tick bitmap is not aware of these bounds
+            step.tick_next = step.tick_next.clamp(MIN_TICK, MAX_TICK);
+
+            // Get the next sqrt price from the input amount
+            step.sqrt_price_next_x96 = loom_defi_uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick(step.tick_next)?;
+
+            // Target spot price
+            let swap_target_sqrt_ratio = if zero_for_one {
+                if step.sqrt_price_next_x96 < sqrt_price_limit_x_96 {
+                    sqrt_price_limit_x_96
+                } else {
+                    step.sqrt_price_next_x96
+                }
+            } else if step.sqrt_price_next_x96 > sqrt_price_limit_x_96 {
+                sqrt_price_limit_x_96
+            } else {
+                step.sqrt_price_next_x96
+            };
+
+            // Compute swap step and update the current state
+            (current_state.sqrt_price_x_96, step.amount_in, step.amount_out, step.fee_amount) =
+                loom_defi_uniswap_v3_math::swap_math::compute_swap_step(
+                    current_state.sqrt_price_x_96,
+                    swap_target_sqrt_ratio,
+                    current_state.liquidity,
+                    current_state.amount_specified_remaining,
+                    fee,
+                )?;
+
+            // Decrement the amount remaining to be received and increment the amount to be paid
+            current_state.amount_specified_remaining =
+                current_state.amount_specified_remaining.overflowing_add(I256::from_raw(step.amount_out)).0;
+
+            current_state.amount_calculated =
+                current_state.amount_calculated.overflowing_add(I256::from_raw(step.amount_in.overflowing_add(step.fee_amount).0)).0;
+
+            // If the price moved all the way to the next price, recompute the liquidity change for the next iteration
+            if current_state.sqrt_price_x_96 == step.sqrt_price_next_x96 {
+                if step.initialized {
+                    let mut liquidity_net: i128 =
+                        UniswapV4DBReader::ticks_liquidity_net(db, pool_manager, pool_id, step.tick_next).unwrap_or_default();
+
+                    if zero_for_one {
+                        liquidity_net = -liquidity_net;
+                    }
+
+                    current_state.liquidity = if liquidity_net < 0 {
+                        if current_state.liquidity < (-liquidity_net as u128) {
+                            return Err(eyre!("LIQUIDITY_UNDERFLOW"));
+                        } else {
+                            current_state.liquidity - (-liquidity_net as u128)
+                        }
+                    } else {
+                        current_state.liquidity + (liquidity_net as u128)
+                    };
+                }
+                current_state.tick = if zero_for_one { step.tick_next.wrapping_sub(1) } else { step.tick_next }
+            } else if current_state.sqrt_price_x_96 != step.sqrt_price_start_x_96 {
+                current_state.tick = loom_defi_uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio(current_state.sqrt_price_x_96)?;
+            }
+        }
+
+        if current_state.amount_specified_remaining.is_zero() {
+            let amount_in = current_state.amount_calculated.into_raw();
+            tracing::trace!("Amount In : {amount_in}");
+            Ok(amount_in)
+        } else {
+            Err(eyre!("NOT_ENOUGH_LIQUIDITY"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_swap_fee() {
+        let slot0 = UniswapV4Slot0 { lp_fee: 3000, ..Default::default() };
+        assert_eq!(UniswapV4PoolVirtual::swap_fee(&slot0, true), 3000);
+        assert_eq!(UniswapV4PoolVirtual::swap_fee(&slot0, false), 3000);
+
+        // 0.1% protocol fee for 0->1, no protocol fee for 1->0
+        let slot0 = UniswapV4Slot0 { lp_fee: 3000, protocol_fee: 1000, ..Default::default() };
+        assert_eq!(UniswapV4PoolVirtual::swap_fee(&slot0, true), 3997);
+        assert_eq!(UniswapV4PoolVirtual::swap_fee(&slot0, false), 3000);
+    }
+}
@@ -0,0 +1,215 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolInterface;
+use eyre::{eyre, ErrReport, Result};
+use loom_defi_abi::lido::IWStEth;
+use loom_defi_address_book::TokenAddress;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::required_state::RequiredState;
+use loom_types_entities::{AbiSwapEncoder, Pool, PoolClass, PoolProtocol, PreswapRequirement};
+use revm::primitives::Env;
+
+use crate::state_readers::LidoStateReader;
+
+/// Lido wstETH wrapper. wstETH amounts are stETH shares, so the rate is read from stETH storage.
+/// ETH sent to wstETH is staked and wrapped in one call.
+#[derive(Clone)]
+pub struct WstEthPool {
+    address: Address,
+    steth: Address,
+    weth: Address,
+    encoder: WstEthAbiSwapEncoder,
+}
+
+impl WstEthPool {
+    pub fn new(address: Address, steth: Address, weth: Address) -> Self {
+        Self { address, steth, weth, encoder: WstEthAbiSwapEncoder::new(address, steth, weth) }
+    }
+
+    pub fn mainnet() -> Self {
+        Self::new(TokenAddress::WSTETH, TokenAddress::STETH, TokenAddress::WETH)
+    }
+}
+
+impl Pool for WstEthPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::LidoWstEth
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::LidoWstEth
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.weth, self.steth, self.address]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.weth, self.address), (self.steth, self.address), (self.address, self.steth)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let state = LidoStateReader::lido_state(state_db, self.steth)?;
+
+        let (out_amount, gas_used) = if *token_address_from == self.weth && *token_address_to == self.address {
+            (state.submit(in_amount)?.1, 120_000)
+        } else if *token_address_from == self.steth && *token_address_to == self.address {
+            (state.shares_by_pooled_eth(in_amount)?, 80_000)
+        } else if *token_address_from == self.address && *token_address_to == self.steth {
+            (state.pooled_eth_by_shares(in_amount)?, 70_000)
+        } else {
+            return Err(eyre!("DIRECTION_NOT_SUPPORTED"));
+        };
+
+        if out_amount.is_zero() {
+            Err(eyre!("OUT_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((out_amount, gas_used))
+        }
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state_db: &LoomDBType,
+        _env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        let state = LidoStateReader::lido_state(state_db, self.steth)?;
+
+        let (in_amount, gas_used) = if *token_address_from == self.weth && *token_address_to == self.address {
+            (state.pooled_eth_by_shares_up(out_amount)?, 120_000)
+        } else if *token_address_from == self.steth && *token_address_to == self.address {
+            (state.pooled_eth_by_shares_up(out_amount)?, 80_000)
+        } else if *token_address_from == self.address && *token_address_to == self.steth {
+            (state.shares_by_pooled_eth_up(out_amount)?, 70_000)
+        } else {
+            return Err(eyre!("DIRECTION_NOT_SUPPORTED"));
+        };
+
+        if in_amount.is_zero() {
+            Err(eyre!("IN_AMOUNT_IS_ZERO"))
+        } else {
+            Ok((in_amount, gas_used))
+        }
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        false
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        &self.encoder
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        let mut state_required = RequiredState::new();
+        for slot in LidoStateReader::storage_slots() {
+            state_required.add_slot(self.steth, slot);
+        }
+        Ok(state_required)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct WstEthAbiSwapEncoder {
+    pool_address: Address,
+    steth: Address,
+    weth: Address,
+}
+
+impl WstEthAbiSwapEncoder {
+    pub fn new(pool_address: Address, steth: Address, weth: Address) -> Self {
+        Self { pool_address, steth, weth }
+    }
+}
+
+impl AbiSwapEncoder for WstEthAbiSwapEncoder {
+    fn encode_swap_in_amount_provided(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        _recipient: Address,
+        _payload: Bytes,
+    ) -> Result<Bytes> {
+        if token_from_address == self.weth && token_to_address == self.pool_address {
+            // plain eth transfer is staked and wrapped by wstETH
+            Ok(Bytes::new())
+        } else if token_from_address == self.steth && token_to_address == self.pool_address {
+            Ok(Bytes::from(IWStEth::IWStEthCalls::wrap(IWStEth::wrapCall { stETHAmount: amount }).abi_encode()))
+        } else if token_from_address == self.pool_address && token_to_address == self.steth {
+            Ok(Bytes::from(IWStEth::IWStEthCalls::unwrap(IWStEth::unwrapCall { wstETHAmount: amount }).abi_encode()))
+        } else {
+            Err(eyre!("DIRECTION_NOT_SUPPORTED"))
+        }
+    }
+
+    fn preswap_requirement(&self) -> PreswapRequirement {
+        PreswapRequirement::Base
+    }
+
+    fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x04)
+    }
+
+    fn swap_in_amount_return_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+        Some(0x00)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::BlockNumber;
+    use alloy_rpc_types::BlockId;
+    use loom_node_debug_provider::AnvilDebugProviderFactory;
+    use loom_types_entities::required_state::RequiredStateReader;
+    use std::env;
+
+    #[tokio::test]
+    async fn test_calculate_out_amount() -> Result<()> {
+        let block_number = 20935488u64;
+
+        let node_url = env::var("MAINNET_WS")?;
+        let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(block_number)).await?;
+
+        let pool = WstEthPool::mainnet();
+        let state_update =
+            RequiredStateReader::fetch_calls_and_slots(client.clone(), pool.get_state_required()?, Some(block_number)).await?;
+        let mut state_db = LoomDBType::default();
+        state_db.apply_geth_update(state_update);
+
+        let wsteth_contract = IWStEth::new(TokenAddress::WSTETH, client.clone());
+        let amount_in = U256::from(10).pow(U256::from(18));
+
+        let contract_wrap_out = wsteth_contract.getWstETHByStETH(amount_in).call().block(BlockId::from(block_number)).await?._0;
+        let (wrap_out, _) = pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::STETH, &TokenAddress::WSTETH, amount_in)?;
+        assert_eq!(wrap_out, contract_wrap_out);
+
+        let contract_unwrap_out = wsteth_contract.getStETHByWstETH(amount_in).call().block(BlockId::from(block_number)).await?._0;
+        let (unwrap_out, _) =
+            pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WSTETH, &TokenAddress::STETH, amount_in)?;
+        assert_eq!(unwrap_out, contract_unwrap_out);
+
+        let (eth_out, _) = pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WETH, &TokenAddress::WSTETH, amount_in)?;
+        assert_eq!(eth_out, contract_wrap_out);
+
+        let (wrap_in, _) = pool.calculate_in_amount(&state_db, Env::default(), &TokenAddress::STETH, &TokenAddress::WSTETH, wrap_out)?;
+        assert!(pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::STETH, &TokenAddress::WSTETH, wrap_in)?.0 >= wrap_out);
+
+        assert!(pool.calculate_out_amount(&state_db, Env::default(), &TokenAddress::WSTETH, &TokenAddress::WETH, amount_in).is_err());
+        Ok(())
+    }
+}
@@ -1,19 +1,21 @@
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::ops::{Div, Mul};
+use std::sync::Arc;
 use std::time::Duration;
 
 use alloy_network::Network;
-use alloy_primitives::{address, Address, U256};
+use alloy_primitives::{Address, U256};
 use alloy_provider::Provider;
 use alloy_transport::Transport;
 use loom_core_actors::{Accessor, Actor, ActorResult, SharedState, WorkerResult};
 use loom_core_actors_macros::Accessor;
 use loom_core_blockchain::Blockchain;
-use loom_defi_address_book::TokenAddress;
+use loom_defi_address_book::AddressBook;
 use loom_defi_pools::protocols::CurveProtocol;
 use loom_defi_pools::CurvePool;
 use loom_types_entities::{Market, Pool};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 //use market::{CurveProtocol, Market, PoolSetup};
@@ -22,10 +24,18 @@ use tracing::{debug, error, info};
 async fn price_worker<N: Network, T: Transport + Clone, P: Provider<T, N> + Clone + 'static>(
     client: P,
     market: SharedState<Market>,
+    address_book: Arc<AddressBook>,
     once: bool,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
-    let curve_tricrypto_usdc = CurveProtocol::new_u256_3_eth_to(client.clone(), address!("7F86Bf177Dd4F3494b841a37e810A34dD56c829B"));
-    let curve_tricrypto_usdt = CurveProtocol::new_u256_3_eth_to(client.clone(), address!("f5f5b97624542d72a9e06f04804bf81baa15e2b4"));
+    let weth_address = address_book.get_token("WETH")?;
+    let usdc_address = address_book.get_token("USDC")?;
+    let usdt_address = address_book.get_token("USDT")?;
+    let wbtc_address = address_book.get_token("WBTC")?;
+    let dai_address = address_book.get_token("DAI")?;
+
+    let curve_tricrypto_usdc = CurveProtocol::new_u256_3_eth_to(client.clone(), address_book.get_pool("curve", "ETH_BTC_USD")?);
+    let curve_tricrypto_usdt = CurveProtocol::new_u256_3_eth_to(client.clone(), address_book.get_pool("curve", "TRICRYPTO_USDT")?);
 
     let mut coins_hash_map: HashMap<Address, CurvePool<P, T, N>> = HashMap::new();
 
@@ -33,14 +43,14 @@ async fn price_worker<N: Network, T: Transport + Clone, P: Provider<T, N> + Clon
 
     let curve_tricrypto_usdt_pool = CurvePool::fetch_pool_data(client.clone(), curve_tricrypto_usdt).await?;
 
-    coins_hash_map.insert(TokenAddress::USDC, curve_tricrypto_usdc_pool.clone());
-    coins_hash_map.insert(TokenAddress::WBTC, curve_tricrypto_usdc_pool.clone());
-    coins_hash_map.insert(TokenAddress::USDT, curve_tricrypto_usdt_pool.clone());
+    coins_hash_map.insert(usdc_address, curve_tricrypto_usdc_pool.clone());
+    coins_hash_map.insert(wbtc_address, curve_tricrypto_usdc_pool.clone());
+    coins_hash_map.insert(usdt_address, curve_tricrypto_usdt_pool.clone());
 
     let one_ether = U256::from(10).pow(U256::from(18));
     let weth_amount = one_ether.mul(U256::from(5));
 
-    match market.read().await.get_token(&TokenAddress::WETH) {
+    match market.read().await.get_token(&weth_address) {
         Some(token) => {
             token.set_eth_price(Some(one_ether));
         }
@@ -53,7 +63,7 @@ async fn price_worker<N: Network, T: Transport + Clone, P: Provider<T, N> + Clon
         for (token_address, curve_pool) in coins_hash_map.iter() {
             debug!("Fetching price of {} at {}", token_address, curve_pool.get_address());
 
-            match curve_pool.fetch_out_amount(TokenAddress::WETH, *token_address, weth_amount).await {
+            match curve_pool.fetch_out_amount(weth_address, *token_address, weth_amount).await {
                 Ok(out_amount) => {
                     let price = out_amount.mul(one_ether).div(weth_amount);
                     info!("Price of ETH in {token_address:#20x} is {price}");
@@ -73,8 +83,8 @@ async fn price_worker<N: Network, T: Transport + Clone, P: Provider<T, N> + Clon
             }
         }
 
-        let usdt_price = market.read().await.get_token_or_default(&TokenAddress::USDT).get_eth_price();
-        let usdc_price = market.read().await.get_token_or_default(&TokenAddress::USDC).get_eth_price();
+        let usdt_price = market.read().await.get_token_or_default(&usdt_address).get_eth_price();
+        let usdc_price = market.read().await.get_token_or_default(&usdc_address).get_eth_price();
 
         let mut usd_price: Option<U256> = None;
         if let Some(usdc_price) = usdc_price {
@@ -84,12 +94,12 @@ async fn price_worker<N: Network, T: Transport + Clone, P: Provider<T, N> + Clon
         }
 
         if let Some(usd_price) = usd_price {
-            match market.read().await.get_token(&TokenAddress::DAI) {
+            match market.read().await.get_token(&dai_address) {
                 Some(tkn) => {
                     tkn.set_eth_price(Some(U256::from(10).pow(U256::from(12)).mul(usd_price)));
                 }
                 _ => {
-                    error!("Token {:#20x} not found", TokenAddress::DAI);
+                    error!("Token {:#20x} not found", dai_address);
                 }
             }
         }
@@ -97,7 +107,10 @@ async fn price_worker<N: Network, T: Transport + Clone, P: Provider<T, N> + Clon
             break;
         }
 
-        let _ = tokio::time::sleep(Duration::new(60, 0)).await;
+        tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            _ = tokio::time::sleep(Duration::new(60, 0)) => {}
+        }
     }
     Ok("PriceWorker finished".to_string())
 }
@@ -108,6 +121,7 @@ pub struct PriceActor<P, T, N> {
     only_once: bool,
     #[accessor]
     market: Option<SharedState<Market>>,
+    address_book: Arc<AddressBook>,
     _t: PhantomData<T>,
     _n: PhantomData<N>,
 }
@@ -119,15 +133,21 @@ where
     P: Provider<T, N> + Send + Sync + Clone + 'static,
 {
     pub fn new(client: P) -> Self {
-        Self { client, only_once: false, market: None, _t: PhantomData, _n: PhantomData }
+        let address_book = AddressBook::get(1).unwrap_or_else(|| Arc::new(AddressBook::mainnet()));
+        Self { client, only_once: false, market: None, address_book, _t: PhantomData, _n: PhantomData }
     }
 
     pub fn only_once(self) -> Self {
         Self { only_once: true, ..self }
     }
 
+    pub fn with_address_book(self, address_book: Arc<AddressBook>) -> Self {
+        Self { address_book, ..self }
+    }
+
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { market: Some(bc.market()), ..self }
+        let address_book = AddressBook::get(bc.chain_id()).unwrap_or(self.address_book.clone());
+        Self { market: Some(bc.market()), address_book, ..self }
     }
 }
 
@@ -138,7 +158,17 @@ where
     P: Provider<T, N> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task = tokio::task::spawn(price_worker(self.client.clone(), self.market.clone().unwrap(), self.only_once));
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(price_worker(
+            self.client.clone(),
+            self.market.clone().unwrap(),
+            self.address_book.clone(),
+            self.only_once,
+            token,
+        ));
         Ok(vec![task])
     }
 
@@ -10,6 +10,7 @@ use eyre::{eyre, Result};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace};
 
 use loom_core_blockchain::Blockchain;
@@ -21,10 +22,12 @@ use loom_core_actors_macros::{Consumer, Producer};
 use loom_evm_db::AlloyDB;
 use loom_evm_utils::evm::evm_access_list;
 use loom_evm_utils::evm_env::env_for_block;
+use loom_types_blockchain::ETHEREUM_CHAIN_ID;
 use loom_types_events::{MessageTxCompose, TxCompose, TxComposeData, TxState};
 
 async fn estimator_task<T, N>(
     client: Option<impl Provider<T, N> + 'static>,
+    chain_id: u64,
     swap_encoder: impl SwapEncoder,
     estimate_request: TxComposeData,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
@@ -57,7 +60,7 @@ where
 
     let tx_request = TransactionRequest {
         transaction_type: Some(2),
-        chain_id: Some(1),
+        chain_id: Some(chain_id),
         from: Some(tx_signer.address()),
         to: Some(TxKind::Call(to)),
         gas: Some(estimate_request.gas),
@@ -132,7 +135,7 @@ where
 
     let tx_request = TransactionRequest {
         transaction_type: Some(2),
-        chain_id: Some(1),
+        chain_id: Some(chain_id),
         from: Some(tx_signer.address()),
         to: Some(TxKind::Call(to)),
         gas: Some((gas_used * 1500) / 1000),
@@ -196,9 +199,11 @@ where
 
 async fn estimator_worker<T, N>(
     client: Option<impl Provider<T, N> + Clone + 'static>,
+    chain_id: u64,
     encoder: impl SwapEncoder + Send + Sync + Clone + 'static,
     compose_channel_rx: Broadcaster<MessageTxCompose>,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -208,6 +213,9 @@ where
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("EVM estimator worker stopped".to_string())
+            }
             msg = compose_channel_rx.recv() => {
                 let compose_request_msg : Result<MessageTxCompose, RecvError> = msg;
                 match compose_request_msg {
@@ -220,6 +228,7 @@ where
                                 async move {
                                 if let Err(e) = estimator_task(
                                         client_cloned,
+                                        chain_id,
                                         encoder_cloned,
                                         estimate_request.clone(),
                                         compose_channel_tx_cloned,
@@ -241,6 +250,7 @@ where
 pub struct EvmEstimatorActor<P, T, N, E> {
     encoder: E,
     client: Option<P>,
+    chain_id: u64,
     #[consumer]
     compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
     #[producer]
@@ -257,15 +267,36 @@ where
     E: SwapEncoder + Send + Sync + Clone + 'static,
 {
     pub fn new(encoder: E) -> Self {
-        Self { encoder, client: None, compose_channel_tx: None, compose_channel_rx: None, _t: PhantomData::<T>, _n: PhantomData::<N> }
+        Self {
+            encoder,
+            client: None,
+            chain_id: ETHEREUM_CHAIN_ID,
+            compose_channel_tx: None,
+            compose_channel_rx: None,
+            _t: PhantomData::<T>,
+            _n: PhantomData::<N>,
+        }
     }
 
     pub fn new_with_provider(encoder: E, client: Option<P>) -> Self {
-        Self { encoder, client, compose_channel_tx: None, compose_channel_rx: None, _t: PhantomData::<T>, _n: PhantomData::<N> }
+        Self {
+            encoder,
+            client,
+            chain_id: ETHEREUM_CHAIN_ID,
+            compose_channel_tx: None,
+            compose_channel_rx: None,
+            _t: PhantomData::<T>,
+            _n: PhantomData::<N>,
+        }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { compose_channel_tx: Some(bc.compose_channel()), compose_channel_rx: Some(bc.compose_channel()), ..self }
+        Self {
+            chain_id: bc.chain_id(),
+            compose_channel_tx: Some(bc.compose_channel()),
+            compose_channel_rx: Some(bc.compose_channel()),
+            ..self
+        }
     }
 }
 
@@ -277,11 +308,17 @@ where
     E: SwapEncoder + Clone + Send + Sync + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(estimator_worker(
             self.client.clone(),
+            self.chain_id,
             self.encoder.clone(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -9,6 +9,7 @@ use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use alloy_transport::Transport;
 use eyre::{eyre, Result};
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 use loom_core_blockchain::Blockchain;
@@ -18,11 +19,13 @@ use loom_types_entities::{Swap, SwapEncoder};
 use loom_broadcast_flashbots::Flashbots;
 use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
 use loom_core_actors_macros::{Consumer, Producer};
+use loom_types_blockchain::ETHEREUM_CHAIN_ID;
 use loom_types_events::{MessageTxCompose, TxCompose, TxComposeData, TxState};
 
 async fn estimator_task<T: Transport + Clone, P: Provider<T, Ethereum> + Send + Sync + Clone + 'static>(
     estimate_request: TxComposeData,
     client: Arc<Flashbots<P, T>>,
+    chain_id: u64,
     swap_encoder: impl SwapEncoder,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
 ) -> Result<()> {
@@ -51,7 +54,7 @@ async fn estimator_task<T: Transport + Clone, P: Provider<T, Ethereum> + Send +
 
     let mut tx_request = TransactionRequest {
         transaction_type: Some(2),
-        chain_id: Some(1),
+        chain_id: Some(chain_id),
         from: Some(tx_signer.address()),
         to: Some(TxKind::Call(to)),
         gas: Some(estimate_request.gas),
@@ -128,7 +131,7 @@ async fn estimator_task<T: Transport + Clone, P: Provider<T, Ethereum> + Send +
 
                         let tx_request = TransactionRequest {
                             transaction_type: Some(2),
-                            chain_id: Some(1),
+                            chain_id: Some(chain_id),
                             from: Some(tx_signer.address()),
                             to: Some(TxKind::Call(to)),
                             gas: Some((gas * 1500) / 1000),
@@ -197,14 +200,19 @@ async fn estimator_task<T: Transport + Clone, P: Provider<T, Ethereum> + Send +
 
 async fn estimator_worker<T: Transport + Clone, P: Provider<T, Ethereum> + Send + Sync + Clone + 'static>(
     client: Arc<Flashbots<P, T>>,
+    chain_id: u64,
     encoder: impl SwapEncoder + Send + Sync + Clone + 'static,
     compose_channel_rx: Broadcaster<MessageTxCompose>,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(compose_channel_rx);
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Geth estimator worker stopped".to_string())
+            }
             msg = compose_channel_rx.recv() => {
                 let compose_request_msg : Result<MessageTxCompose, RecvError> = msg;
                 match compose_request_msg {
@@ -217,6 +225,7 @@ async fn estimator_worker<T: Transport + Clone, P: Provider<T, Ethereum> + Send
                                 if let Err(e) = estimator_task(
                                     estimate_request.clone(),
                                     client_cloned,
+                                    chain_id,
                                     encoder_cloned,
                                     compose_channel_tx_cloned,
                                 ).await {
@@ -237,6 +246,7 @@ async fn estimator_worker<T: Transport + Clone, P: Provider<T, Ethereum> + Send
 pub struct GethEstimatorActor<P, T, E> {
     client: Arc<Flashbots<P, T>>,
     encoder: E,
+    chain_id: u64,
     #[consumer]
     compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
     #[producer]
@@ -250,11 +260,16 @@ where
     E: SwapEncoder + Send + Sync + Clone + 'static,
 {
     pub fn new(client: Arc<Flashbots<P, T>>, encoder: E) -> Self {
-        Self { client, encoder, compose_channel_tx: None, compose_channel_rx: None }
+        Self { client, encoder, chain_id: ETHEREUM_CHAIN_ID, compose_channel_tx: None, compose_channel_rx: None }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
-        Self { compose_channel_tx: Some(bc.compose_channel()), compose_channel_rx: Some(bc.compose_channel()), ..self }
+        Self {
+            chain_id: bc.chain_id(),
+            compose_channel_tx: Some(bc.compose_channel()),
+            compose_channel_rx: Some(bc.compose_channel()),
+            ..self
+        }
     }
 }
 
@@ -265,11 +280,17 @@ where
     E: SwapEncoder + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(estimator_worker(
             self.client.clone(),
+            self.chain_id,
             self.encoder.clone(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
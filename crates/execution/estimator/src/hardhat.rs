@@ -5,6 +5,7 @@ use alloy_provider::Provider;
 use alloy_rpc_types::{TransactionInput, TransactionRequest};
 use eyre::{eyre, Result};
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
@@ -17,11 +18,15 @@ async fn estimator_worker(
     swap_encoder: impl SwapEncoder,
     compose_channel_rx: Broadcaster<MessageTxCompose>,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(compose_channel_rx);
 
     loop {
         tokio::select! {
+                    _ = shutdown_token.cancelled() => {
+                        break Ok("Hardhat estimator worker stopped".to_string())
+                    }
                     msg = compose_channel_rx.recv() => {
                         let compose_request_msg : Result<MessageTxCompose, RecvError> = msg;
                         match compose_request_msg {
@@ -124,10 +129,15 @@ where
     E: SwapEncoder + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(estimator_worker(
             self.encoder.clone(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -1,18 +1,16 @@
-use alloy_primitives::{Address, Bytes, U256};
-use alloy_sol_types::SolInterface;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::{SolInterface, SolValue};
 
 use loom_defi_abi::balancer::IVault;
+use loom_defi_abi::integral::ITwapRelayer;
 use loom_defi_abi::lido::{IStEth, IWStEth};
+use loom_defi_abi::solidly::ISolidlyPool;
+use loom_defi_abi::uniswap4::{IUniswapV4PoolManager, IUniswapV4PoolManagerUnlock};
 use loom_defi_abi::{IMultiCaller, IERC20, IWETH};
-use loom_defi_address_book::TokenAddress;
 
 pub struct EncoderHelper;
 
 impl EncoderHelper {
-    pub fn is_weth(address: Address) -> bool {
-        address == TokenAddress::WETH
-    }
-
     pub fn encode_weth_deposit() -> Bytes {
         IWETH::IWETHCalls::deposit(IWETH::depositCall {}).abi_encode().into()
     }
@@ -113,4 +111,52 @@ impl EncoderHelper {
 
         Bytes::from(call.abi_encode())
     }
+
+    pub fn encode_solidly_get_amount_out(amount_in: U256, token_in: Address) -> Bytes {
+        let call = ISolidlyPool::ISolidlyPoolCalls::getAmountOut(ISolidlyPool::getAmountOutCall { amountIn: amount_in, tokenIn: token_in });
+
+        Bytes::from(call.abi_encode())
+    }
+
+    pub fn encode_integral_quote_sell(token_in: Address, token_out: Address, amount_in: U256) -> Bytes {
+        let call = ITwapRelayer::ITwapRelayerCalls::quoteSell(ITwapRelayer::quoteSellCall {
+            tokenIn: token_in,
+            tokenOut: token_out,
+            amountIn: amount_in,
+        });
+
+        Bytes::from(call.abi_encode())
+    }
+
+    pub fn encode_uniswap4_unlock(data: Bytes) -> Bytes {
+        let call = IUniswapV4PoolManager::IUniswapV4PoolManagerCalls::unlock(IUniswapV4PoolManager::unlockCall { data });
+
+        Bytes::from(call.abi_encode())
+    }
+
+    pub fn encode_uniswap4_sync(currency: Address) -> Bytes {
+        let call = IUniswapV4PoolManager::IUniswapV4PoolManagerCalls::sync(IUniswapV4PoolManager::syncCall { currency });
+
+        Bytes::from(call.abi_encode())
+    }
+
+    pub fn encode_uniswap4_settle() -> Bytes {
+        let call = IUniswapV4PoolManagerUnlock::IUniswapV4PoolManagerUnlockCalls::settle(IUniswapV4PoolManagerUnlock::settleCall {});
+
+        Bytes::from(call.abi_encode())
+    }
+
+    pub fn encode_uniswap4_take(currency: Address, to: Address, amount: U256) -> Bytes {
+        let call = IUniswapV4PoolManager::IUniswapV4PoolManagerCalls::take(IUniswapV4PoolManager::takeCall { currency, to, amount });
+
+        Bytes::from(call.abi_encode())
+    }
+
+    /// Reads the pool manager transient currency delta of the locker
+    pub fn encode_uniswap4_currency_delta(locker: Address, currency: Address) -> Bytes {
+        let slot: B256 = keccak256((locker, currency).abi_encode());
+        let call = IUniswapV4PoolManager::IUniswapV4PoolManagerCalls::exttload(IUniswapV4PoolManager::exttloadCall { slot });
+
+        Bytes::from(call.abi_encode())
+    }
 }
@@ -1,9 +1,11 @@
+use std::sync::Arc;
+
 use alloy_primitives::{Address, Bytes};
 use eyre::{eyre, OptionExt, Result};
 use tracing::error;
 
-use loom_types_blockchain::MulticallerCalls;
-use loom_types_entities::Swap;
+use loom_types_blockchain::{ChainParameters, MulticallerCalls};
+use loom_types_entities::{CurveTipsPolicy, Swap, TipsPolicy};
 
 use crate::SwapStepEncoder;
 
@@ -17,11 +19,29 @@ pub trait MulticallerEncoder {
 pub struct MulticallerSwapEncoder {
     pub multicaller_address: Address,
     pub swap_step_encoder: SwapStepEncoder,
+    pub tips_policy: Arc<dyn TipsPolicy>,
 }
 
 impl MulticallerSwapEncoder {
     pub fn new(multicaller_address: Address) -> Self {
-        Self { multicaller_address, swap_step_encoder: SwapStepEncoder::new(multicaller_address) }
+        Self {
+            multicaller_address,
+            swap_step_encoder: SwapStepEncoder::new(multicaller_address),
+            tips_policy: Arc::new(CurveTipsPolicy::default()),
+        }
+    }
+
+    pub fn with_tips_policy(self, tips_policy: Arc<dyn TipsPolicy>) -> Self {
+        Self { tips_policy, ..self }
+    }
+
+    /// Use chain specific periphery contracts from the address book of the chain and its wrapped native token
+    pub fn for_chain(self, chain_parameters: &ChainParameters) -> Self {
+        let mut swap_step_encoder = self.swap_step_encoder.for_chain(chain_parameters.chain_id);
+        if !chain_parameters.weth.is_zero() {
+            swap_step_encoder = swap_step_encoder.with_weth(chain_parameters.weth);
+        }
+        Self { swap_step_encoder, ..self }
     }
 
     pub fn get_contract_address(&self) -> Address {
@@ -0,0 +1,244 @@
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, OptionExt, Result};
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{PoolWrapper, PreswapRequirement, SwapAmountType};
+
+use crate::helpers::EncoderHelper;
+
+pub struct BalancerSwapEncoder {}
+
+impl BalancerSwapEncoder {
+    pub fn encode_swap_in_amount_provided(
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        swap_opcodes: &mut MulticallerCalls,
+        cur_pool: &PoolWrapper,
+        next_pool: Option<&PoolWrapper>,
+        multicaller: Address,
+    ) -> Result<()> {
+        let pool_encoder = cur_pool.get_encoder();
+        let vault_address = pool_encoder.swap_contract_address().ok_or_eyre("NO_VAULT")?;
+
+        let recipient = match next_pool {
+            Some(next_pool) => match next_pool.get_encoder().preswap_requirement() {
+                PreswapRequirement::Transfer(funds_to) => funds_to,
+                _ => multicaller,
+            },
+            None => multicaller,
+        };
+
+        let amount_offset = pool_encoder.swap_in_amount_offset(token_from_address, token_to_address).ok_or(eyre!("NO_OFFSET"))?;
+
+        // The multicaller holds and approves the input tokens, so it is the sender of the vault swap
+        let swap_call_data = pool_encoder.encode_swap_in_amount_provided_from(
+            token_from_address,
+            token_to_address,
+            U256::ZERO,
+            multicaller,
+            recipient,
+            Bytes::new(),
+        )?;
+
+        match amount_in {
+            SwapAmountType::Set(amount) => {
+                let approve_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(vault_address, amount));
+                let mut swap_opcode = MulticallerCall::new_call(
+                    vault_address,
+                    &pool_encoder.encode_swap_in_amount_provided_from(
+                        token_from_address,
+                        token_to_address,
+                        amount,
+                        multicaller,
+                        recipient,
+                        Bytes::new(),
+                    )?,
+                );
+
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+
+                swap_opcodes.add(approve_opcode).add(swap_opcode);
+            }
+            SwapAmountType::Stack0 => {
+                let mut approve_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(vault_address, U256::ZERO));
+                approve_opcode.set_call_stack(false, 0, 0x24, 0x20);
+
+                let mut swap_opcode = MulticallerCall::new_call(vault_address, &swap_call_data);
+                swap_opcode.set_call_stack(false, 0, amount_offset, 0x20);
+
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+
+                swap_opcodes.add(approve_opcode).add(swap_opcode);
+            }
+            SwapAmountType::RelativeStack(stack_offset) => {
+                let mut approve_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(vault_address, U256::ZERO));
+                approve_opcode.set_call_stack(true, stack_offset, 0x24, 0x20);
+
+                let mut swap_opcode = MulticallerCall::new_call(vault_address, &swap_call_data);
+                swap_opcode.set_call_stack(true, stack_offset, amount_offset, 0x20);
+
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+
+                swap_opcodes.add(approve_opcode).add(swap_opcode);
+            }
+            SwapAmountType::Balance(addr) => {
+                let mut balance_opcode =
+                    MulticallerCall::new_static_call(token_from_address, &EncoderHelper::encode_erc20_balance_of(addr));
+                balance_opcode.set_return_stack(true, 0, 0, 0x20);
+
+                let mut approve_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(vault_address, U256::ZERO));
+                approve_opcode.set_call_stack(true, 0, 0x24, 0x20);
+
+                let mut swap_opcode = MulticallerCall::new_call(vault_address, &swap_call_data);
+                swap_opcode.set_call_stack(true, 0, amount_offset, 0x20);
+
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+
+                swap_opcodes.add(balance_opcode).add(approve_opcode).add(swap_opcode);
+            }
+            _ => {
+                return Err(eyre!("CANNOT_ENCODE_BALANCER_SWAP"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_sol_types::SolCall;
+    use eyre::ErrReport;
+    use loom_defi_abi::balancer::IVault;
+    use loom_evm_db::LoomDBType;
+    use loom_types_entities::required_state::RequiredState;
+    use loom_types_entities::{AbiSwapEncoder, Pool};
+    use revm::primitives::Env;
+
+    #[derive(Clone)]
+    struct VaultEncoder {
+        vault: Address,
+        preswap_requirement: PreswapRequirement,
+    }
+
+    impl AbiSwapEncoder for VaultEncoder {
+        fn encode_swap_in_amount_provided_from(
+            &self,
+            token_from_address: Address,
+            token_to_address: Address,
+            amount: U256,
+            sender: Address,
+            recipient: Address,
+            payload: Bytes,
+        ) -> Result<Bytes> {
+            let swap_call = IVault::swapCall {
+                singleSwap: IVault::SingleSwap {
+                    poolId: Default::default(),
+                    kind: IVault::SwapKind::GIVEN_IN,
+                    assetIn: token_from_address,
+                    assetOut: token_to_address,
+                    amount,
+                    userData: payload,
+                },
+                funds: IVault::FundManagement { sender, fromInternalBalance: false, recipient, toInternalBalance: false },
+                limit: U256::ZERO,
+                deadline: U256::MAX,
+            };
+            Ok(Bytes::from(swap_call.abi_encode()))
+        }
+
+        fn preswap_requirement(&self) -> PreswapRequirement {
+            self.preswap_requirement.clone()
+        }
+
+        fn swap_contract_address(&self) -> Option<Address> {
+            Some(self.vault)
+        }
+
+        fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+            Some(0x164)
+        }
+    }
+
+    #[derive(Clone)]
+    struct VaultPool {
+        address: Address,
+        encoder: VaultEncoder,
+    }
+
+    impl Pool for VaultPool {
+        fn get_address(&self) -> Address {
+            self.address
+        }
+
+        fn calculate_out_amount(&self, _: &LoomDBType, _: Env, _: &Address, _: &Address, _: U256) -> Result<(U256, u64), ErrReport> {
+            Err(eyre!("NOT_IMPLEMENTED"))
+        }
+
+        fn calculate_in_amount(&self, _: &LoomDBType, _: Env, _: &Address, _: &Address, _: U256) -> Result<(U256, u64), ErrReport> {
+            Err(eyre!("NOT_IMPLEMENTED"))
+        }
+
+        fn can_flash_swap(&self) -> bool {
+            false
+        }
+
+        fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+            &self.encoder
+        }
+
+        fn get_state_required(&self) -> Result<RequiredState> {
+            Ok(RequiredState::new())
+        }
+    }
+
+    #[test]
+    fn test_vault_swap_funds() -> Result<()> {
+        let vault = Address::repeat_byte(0xba);
+        let multicaller = Address::repeat_byte(0x11);
+        let next_pool_address = Address::repeat_byte(0x22);
+        let (token_from, token_to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        let cur_pool = PoolWrapper::from(VaultPool {
+            address: Address::repeat_byte(0x33),
+            encoder: VaultEncoder { vault, preswap_requirement: PreswapRequirement::Allowance },
+        });
+        // Next pool takes the output tokens by transfer, so it is the recipient but never the sender
+        let next_pool = PoolWrapper::from(VaultPool {
+            address: next_pool_address,
+            encoder: VaultEncoder { vault, preswap_requirement: PreswapRequirement::Transfer(next_pool_address) },
+        });
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        BalancerSwapEncoder::encode_swap_in_amount_provided(
+            token_from,
+            token_to,
+            SwapAmountType::Set(U256::from(1000)),
+            &mut swap_opcodes,
+            &cur_pool,
+            Some(&next_pool),
+            multicaller,
+        )?;
+
+        let swap_opcode = swap_opcodes.get(1).unwrap();
+        assert_eq!(swap_opcode.to, vault);
+        let swap_call = IVault::swapCall::abi_decode(&swap_opcode.call_data, true)?;
+        assert_eq!(swap_call.funds.sender, multicaller);
+        assert_eq!(swap_call.funds.recipient, next_pool_address);
+        assert_eq!(swap_call.singleSwap.amount, U256::from(1000));
+        Ok(())
+    }
+}
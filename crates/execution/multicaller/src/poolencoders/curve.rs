@@ -36,12 +36,13 @@ impl CurveSwapEncoder {
         cur_pool: &PoolWrapper,
         next_pool: Option<&PoolWrapper>,
         multicaller: Address,
+        weth: Address,
     ) -> Result<()> {
         let pool_encoder = cur_pool.get_encoder();
         let pool_address = cur_pool.get_address();
 
-        let in_native = if pool_encoder.is_native() { EncoderHelper::is_weth(token_from_address) } else { false };
-        let out_native = if pool_encoder.is_native() { EncoderHelper::is_weth(token_to_address) } else { false };
+        let in_native = pool_encoder.is_native() && token_from_address == weth;
+        let out_native = pool_encoder.is_native() && token_to_address == weth;
 
         match amount_in {
             SwapAmountType::Set(amount) => {
@@ -0,0 +1,98 @@
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, Result};
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{PoolWrapper, SwapAmountType};
+
+use crate::helpers::EncoderHelper;
+
+pub struct Erc4626SwapEncoder {}
+
+impl Erc4626SwapEncoder {
+    pub fn encode_swap_in_amount_provided(
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        swap_opcodes: &mut MulticallerCalls,
+        cur_pool: &PoolWrapper,
+        next_pool: Option<&PoolWrapper>,
+        multicaller: Address,
+    ) -> Result<()> {
+        let pool_encoder = cur_pool.get_encoder();
+        let pool_address = cur_pool.get_address();
+
+        // deposit pulls asset from multicaller, redeem burns shares of multicaller
+        let is_deposit = if token_to_address == pool_address {
+            true
+        } else if token_from_address == pool_address {
+            false
+        } else {
+            return Err(eyre!("CANNOT_ENCODE_ERC4626_SWAP"));
+        };
+
+        let swap_call_data =
+            pool_encoder.encode_swap_in_amount_provided(token_from_address, token_to_address, U256::ZERO, multicaller, Bytes::new())?;
+
+        match amount_in {
+            SwapAmountType::Set(amount) => {
+                let swap_call_data =
+                    pool_encoder.encode_swap_in_amount_provided(token_from_address, token_to_address, amount, multicaller, Bytes::new())?;
+                let mut swap_opcode = MulticallerCall::new_call(pool_address, &swap_call_data);
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+
+                if is_deposit {
+                    let approve_opcode =
+                        MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(pool_address, amount));
+                    swap_opcodes.add(approve_opcode);
+                }
+                swap_opcodes.add(swap_opcode);
+            }
+            SwapAmountType::Stack0 | SwapAmountType::RelativeStack(_) => {
+                let (is_relative, stack_offset) = match amount_in {
+                    SwapAmountType::RelativeStack(stack_offset) => (true, stack_offset),
+                    _ => (false, 0),
+                };
+
+                if is_deposit {
+                    let mut approve_opcode =
+                        MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(pool_address, U256::ZERO));
+                    approve_opcode.set_call_stack(is_relative, stack_offset, 0x24, 0x20);
+                    swap_opcodes.add(approve_opcode);
+                }
+
+                let mut swap_opcode = MulticallerCall::new_call(pool_address, &swap_call_data);
+                swap_opcode.set_call_stack(is_relative, stack_offset, 0x4, 0x20);
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+                swap_opcodes.add(swap_opcode);
+            }
+            SwapAmountType::Balance(addr) => {
+                let mut balance_opcode =
+                    MulticallerCall::new_static_call(token_from_address, &EncoderHelper::encode_erc20_balance_of(addr));
+                balance_opcode.set_return_stack(true, 0, 0, 0x20);
+                swap_opcodes.add(balance_opcode);
+
+                if is_deposit {
+                    let mut approve_opcode =
+                        MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(pool_address, U256::ZERO));
+                    approve_opcode.set_call_stack(true, 0, 0x24, 0x20);
+                    swap_opcodes.add(approve_opcode);
+                }
+
+                let mut swap_opcode = MulticallerCall::new_call(pool_address, &swap_call_data);
+                swap_opcode.set_call_stack(true, 0, 0x4, 0x20);
+                if next_pool.is_some() {
+                    swap_opcode.set_return_stack(true, 0, 0, 0x20);
+                }
+                swap_opcodes.add(swap_opcode);
+            }
+            _ => {
+                return Err(eyre!("CANNOT_ENCODE_ERC4626_SWAP"));
+            }
+        }
+
+        Ok(())
+    }
+}
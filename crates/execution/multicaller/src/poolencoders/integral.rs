@@ -0,0 +1,91 @@
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, OptionExt, Result};
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{PoolWrapper, SwapAmountType};
+
+use crate::helpers::EncoderHelper;
+
+pub struct IntegralSwapEncoder {}
+
+impl IntegralSwapEncoder {
+    pub fn encode_swap_in_amount_provided(
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        swap_opcodes: &mut MulticallerCalls,
+        cur_pool: &PoolWrapper,
+        _next_pool: Option<&PoolWrapper>,
+        multicaller: Address,
+    ) -> Result<()> {
+        let pool_encoder = cur_pool.get_encoder();
+        let relayer_address = pool_encoder.swap_contract_address().ok_or_eyre("NO_RELAYER")?;
+
+        let amount_offset = pool_encoder.swap_in_amount_offset(token_from_address, token_to_address).ok_or(eyre!("NO_OFFSET"))?;
+
+        let swap_call_data =
+            pool_encoder.encode_swap_in_amount_provided(token_from_address, token_to_address, U256::ZERO, multicaller, Bytes::new())?;
+
+        // sell returns an order id, so the out amount is quoted before the sell and kept on the stack for the next pool
+        match amount_in {
+            SwapAmountType::Set(amount) => {
+                let approve_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(relayer_address, amount));
+
+                let mut quote_opcode = MulticallerCall::new_static_call(
+                    relayer_address,
+                    &EncoderHelper::encode_integral_quote_sell(token_from_address, token_to_address, amount),
+                );
+                quote_opcode.set_return_stack(true, 0, 0, 0x20);
+
+                let swap_opcode = MulticallerCall::new_call(
+                    relayer_address,
+                    &pool_encoder.encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        amount,
+                        multicaller,
+                        Bytes::new(),
+                    )?,
+                );
+
+                swap_opcodes.add(approve_opcode).add(quote_opcode).add(swap_opcode);
+            }
+            SwapAmountType::Stack0 | SwapAmountType::RelativeStack(_) | SwapAmountType::Balance(_) => {
+                let (is_relative, stack_offset) = match amount_in {
+                    SwapAmountType::RelativeStack(stack_offset) => (true, stack_offset),
+                    SwapAmountType::Balance(addr) => {
+                        let mut balance_opcode =
+                            MulticallerCall::new_static_call(token_from_address, &EncoderHelper::encode_erc20_balance_of(addr));
+                        balance_opcode.set_return_stack(true, 0, 0, 0x20);
+                        swap_opcodes.add(balance_opcode);
+                        (true, 0)
+                    }
+                    _ => (false, 0),
+                };
+
+                let mut approve_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_approve(relayer_address, U256::ZERO));
+                approve_opcode.set_call_stack(is_relative, stack_offset, 0x24, 0x20);
+
+                let mut quote_opcode = MulticallerCall::new_static_call(
+                    relayer_address,
+                    &EncoderHelper::encode_integral_quote_sell(token_from_address, token_to_address, U256::ZERO),
+                );
+                quote_opcode.set_call_stack(is_relative, stack_offset, 0x44, 0x20);
+                quote_opcode.set_return_stack(true, 0, 0, 0x20);
+
+                // the quote was pushed on top of the stack, relative offsets of the in amount are shifted by one
+                let swap_stack_offset = if is_relative { stack_offset + 1 } else { stack_offset };
+                let mut swap_opcode = MulticallerCall::new_call(relayer_address, &swap_call_data);
+                swap_opcode.set_call_stack(is_relative, swap_stack_offset, amount_offset, 0x20);
+
+                swap_opcodes.add(approve_opcode).add(quote_opcode).add(swap_opcode);
+            }
+            _ => {
+                return Err(eyre!("CANNOT_ENCODE_INTEGRAL_SWAP"));
+            }
+        }
+
+        Ok(())
+    }
+}
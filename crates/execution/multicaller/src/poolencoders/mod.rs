@@ -1,7 +1,19 @@
+pub use balancer::BalancerSwapEncoder;
 pub use curve::CurveSwapEncoder;
+pub use erc4626::Erc4626SwapEncoder;
+pub use integral::IntegralSwapEncoder;
+pub use rocketpool::RocketPoolSwapEncoder;
+pub use solidly::SolidlySwapEncoder;
 pub use steth::StEthSwapEncoder;
+pub use uniswapv4::UniswapV4SwapEncoder;
 pub use wsteth::WstEthSwapEncoder;
 
+mod balancer;
 mod curve;
+mod erc4626;
+mod integral;
+mod rocketpool;
+mod solidly;
 mod steth;
+mod uniswapv4;
 mod wsteth;
@@ -0,0 +1,75 @@
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, Result};
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{PoolWrapper, SwapAmountType};
+
+use crate::helpers::EncoderHelper;
+
+pub struct SolidlySwapEncoder {}
+
+impl SolidlySwapEncoder {
+    pub fn encode_swap_in_amount_provided(
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        swap_opcodes: &mut MulticallerCalls,
+        cur_pool: &PoolWrapper,
+        _next_pool: Option<&PoolWrapper>,
+        multicaller: Address,
+    ) -> Result<()> {
+        let pool_encoder = cur_pool.get_encoder();
+        let pool_address = cur_pool.get_address();
+
+        // out amount is taken from the pool, it includes the fee and works for both stable and volatile invariants
+        let (transfer_opcode, mut get_out_amount_opcode) = match amount_in {
+            SwapAmountType::Set(amount) => (
+                MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_transfer(pool_address, amount)),
+                MulticallerCall::new_static_call(pool_address, &EncoderHelper::encode_solidly_get_amount_out(amount, token_from_address)),
+            ),
+            SwapAmountType::Stack0 | SwapAmountType::RelativeStack(_) | SwapAmountType::Balance(_) => {
+                let (is_relative, stack_offset) = match amount_in {
+                    SwapAmountType::RelativeStack(stack_offset) => (true, stack_offset),
+                    SwapAmountType::Balance(addr) => {
+                        let mut balance_opcode =
+                            MulticallerCall::new_static_call(token_from_address, &EncoderHelper::encode_erc20_balance_of(addr));
+                        balance_opcode.set_return_stack(true, 0, 0, 0x20);
+                        swap_opcodes.add(balance_opcode);
+                        (true, 0)
+                    }
+                    _ => (false, 0),
+                };
+
+                let mut transfer_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_transfer(pool_address, U256::ZERO));
+                transfer_opcode.set_call_stack(is_relative, stack_offset, 0x24, 0x20);
+
+                let mut get_out_amount_opcode = MulticallerCall::new_static_call(
+                    pool_address,
+                    &EncoderHelper::encode_solidly_get_amount_out(U256::ZERO, token_from_address),
+                );
+                get_out_amount_opcode.set_call_stack(is_relative, stack_offset, 0x4, 0x20);
+                (transfer_opcode, get_out_amount_opcode)
+            }
+            _ => {
+                return Err(eyre!("CANNOT_ENCODE_SOLIDLY_SWAP"));
+            }
+        };
+        get_out_amount_opcode.set_return_stack(true, 0, 0, 0x20);
+
+        let mut swap_opcode = MulticallerCall::new_call(
+            pool_address,
+            &pool_encoder.encode_swap_out_amount_provided(token_from_address, token_to_address, U256::ZERO, multicaller, Bytes::new())?,
+        );
+        swap_opcode.set_call_stack(
+            true,
+            0,
+            pool_encoder.swap_out_amount_offset(token_from_address, token_to_address).ok_or_else(|| eyre!("NO_OUT_AMOUNT_OFFSET"))?,
+            0x20,
+        );
+
+        // the out amount stays on the stack for the next pool
+        swap_opcodes.add(transfer_opcode).add(get_out_amount_opcode).add(swap_opcode);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use alloy_primitives::{Address, Bytes, U256};
+use eyre::{eyre, OptionExt, Result};
+use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
+use loom_types_entities::{PoolWrapper, SwapAmountType};
+
+use crate::helpers::EncoderHelper;
+use crate::opcodes_encoder::{OpcodesEncoder, OpcodesEncoderV2};
+
+pub struct UniswapV4SwapEncoder {}
+
+impl UniswapV4SwapEncoder {
+    /// Swaps are executed in the pool manager unlock callback, the multicaller has to handle `unlockCallback` and run the inner calls.
+    /// Input tokens are synced and transferred to the pool manager before unlocking, output tokens are taken inside the callback.
+    /// The callback returns the top of its stack, which is the output currency delta of the swap, it is the input for the next pool.
+    pub fn encode_swap_in_amount_provided(
+        token_from_address: Address,
+        token_to_address: Address,
+        amount_in: SwapAmountType,
+        swap_opcodes: &mut MulticallerCalls,
+        cur_pool: &PoolWrapper,
+        next_pool: Option<&PoolWrapper>,
+        multicaller: Address,
+    ) -> Result<()> {
+        let pool_encoder = cur_pool.get_encoder();
+        let pool_manager = pool_encoder.swap_contract_address().ok_or_eyre("NO_POOL_MANAGER")?;
+
+        let sync_opcode = MulticallerCall::new_call(pool_manager, &EncoderHelper::encode_uniswap4_sync(token_from_address));
+
+        match amount_in {
+            SwapAmountType::Set(amount) => {
+                let transfer_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_transfer(pool_manager, amount));
+                swap_opcodes.add(sync_opcode).add(transfer_opcode);
+            }
+            SwapAmountType::Stack0 => {
+                let mut transfer_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_transfer(pool_manager, U256::ZERO));
+                transfer_opcode.set_call_stack(false, 0, 0x24, 0x20);
+                swap_opcodes.add(sync_opcode).add(transfer_opcode);
+            }
+            SwapAmountType::RelativeStack(stack_offset) => {
+                let mut transfer_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_transfer(pool_manager, U256::ZERO));
+                transfer_opcode.set_call_stack(true, stack_offset, 0x24, 0x20);
+                swap_opcodes.add(sync_opcode).add(transfer_opcode);
+            }
+            SwapAmountType::Balance(addr) => {
+                let mut balance_opcode =
+                    MulticallerCall::new_static_call(token_from_address, &EncoderHelper::encode_erc20_balance_of(addr));
+                balance_opcode.set_return_stack(true, 0, 0, 0x20);
+
+                let mut transfer_opcode =
+                    MulticallerCall::new_call(token_from_address, &EncoderHelper::encode_erc20_transfer(pool_manager, U256::ZERO));
+                transfer_opcode.set_call_stack(true, 0, 0x24, 0x20);
+                swap_opcodes.add(sync_opcode).add(balance_opcode).add(transfer_opcode);
+            }
+            _ => {
+                return Err(eyre!("CANNOT_ENCODE_UNISWAP_V4_SWAP"));
+            }
+        }
+
+        // Settle returns the amount paid, it is used as exact input for the swap
+        let mut settle_opcode = MulticallerCall::new_call(pool_manager, &EncoderHelper::encode_uniswap4_settle());
+        settle_opcode.set_return_stack(true, 0, 0, 0x20);
+
+        let negate_opcode = MulticallerCall::new_calculation_call(&Bytes::from(vec![0x8, 0x2A, 0x00]));
+
+        let mut swap_opcode = MulticallerCall::new_call(
+            pool_manager,
+            &pool_encoder.encode_swap_in_amount_provided(token_from_address, token_to_address, U256::ZERO, multicaller, Bytes::new())?,
+        );
+        swap_opcode.set_call_stack(
+            true,
+            0,
+            pool_encoder.swap_in_amount_offset(token_from_address, token_to_address).ok_or(eyre!("NO_OFFSET"))?,
+            0x20,
+        );
+
+        // Sync and settle only touch the input currency, so the output currency delta is the output of the swap balance delta
+        let mut delta_opcode =
+            MulticallerCall::new_static_call(pool_manager, &EncoderHelper::encode_uniswap4_currency_delta(multicaller, token_to_address));
+        delta_opcode.set_return_stack(true, 0, 0, 0x20);
+
+        let mut take_opcode =
+            MulticallerCall::new_call(pool_manager, &EncoderHelper::encode_uniswap4_take(token_to_address, multicaller, U256::ZERO));
+        take_opcode.set_call_stack(true, 0, 0x44, 0x20);
+
+        let mut unlock_opcodes = MulticallerCalls::new();
+        unlock_opcodes.add(settle_opcode).add(negate_opcode).add(swap_opcode).add(delta_opcode).add(take_opcode);
+
+        let unlock_call_data = EncoderHelper::encode_uniswap4_unlock(OpcodesEncoderV2::pack_do_calls_data(&unlock_opcodes)?);
+        let mut unlock_opcode = MulticallerCall::new_call(pool_manager, &unlock_call_data);
+        if next_pool.is_some() {
+            // unlock returns the callback bytes, the value follows the offset and length words
+            unlock_opcode.set_return_stack(true, 0, 0x40, 0x20);
+        }
+        swap_opcodes.add(unlock_opcode);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eyre::ErrReport;
+    use loom_evm_db::LoomDBType;
+    use loom_types_entities::required_state::RequiredState;
+    use loom_types_entities::{AbiSwapEncoder, Pool, PreswapRequirement};
+    use revm::primitives::Env;
+
+    #[derive(Clone)]
+    struct PoolManagerEncoder {
+        pool_manager: Address,
+    }
+
+    impl AbiSwapEncoder for PoolManagerEncoder {
+        fn encode_swap_in_amount_provided(&self, _: Address, _: Address, amount: U256, _: Address, _: Bytes) -> Result<Bytes> {
+            Ok(Bytes::from([[0u8; 0xC4].as_slice(), amount.to_be_bytes::<32>().as_slice()].concat()))
+        }
+
+        fn preswap_requirement(&self) -> PreswapRequirement {
+            PreswapRequirement::Callback
+        }
+
+        fn swap_contract_address(&self) -> Option<Address> {
+            Some(self.pool_manager)
+        }
+
+        fn swap_in_amount_offset(&self, _token_from_address: Address, _token_to_address: Address) -> Option<u32> {
+            Some(0xC4)
+        }
+    }
+
+    #[derive(Clone)]
+    struct PoolManagerPool {
+        address: Address,
+        encoder: PoolManagerEncoder,
+    }
+
+    impl Pool for PoolManagerPool {
+        fn get_address(&self) -> Address {
+            self.address
+        }
+
+        fn calculate_out_amount(&self, _: &LoomDBType, _: Env, _: &Address, _: &Address, _: U256) -> Result<(U256, u64), ErrReport> {
+            Err(eyre!("NOT_IMPLEMENTED"))
+        }
+
+        fn calculate_in_amount(&self, _: &LoomDBType, _: Env, _: &Address, _: &Address, _: U256) -> Result<(U256, u64), ErrReport> {
+            Err(eyre!("NOT_IMPLEMENTED"))
+        }
+
+        fn can_flash_swap(&self) -> bool {
+            false
+        }
+
+        fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+            &self.encoder
+        }
+
+        fn get_state_required(&self) -> Result<RequiredState> {
+            Ok(RequiredState::new())
+        }
+    }
+
+    #[test]
+    fn test_pool_manager_and_output_delta() -> Result<()> {
+        let pool_manager = Address::repeat_byte(0x44);
+        let multicaller = Address::repeat_byte(0x11);
+        let (token_from, token_to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        let cur_pool =
+            PoolWrapper::from(PoolManagerPool { address: Address::repeat_byte(0x33), encoder: PoolManagerEncoder { pool_manager } });
+        let next_pool =
+            PoolWrapper::from(PoolManagerPool { address: Address::repeat_byte(0x22), encoder: PoolManagerEncoder { pool_manager } });
+
+        let mut swap_opcodes = MulticallerCalls::new();
+        UniswapV4SwapEncoder::encode_swap_in_amount_provided(
+            token_from,
+            token_to,
+            SwapAmountType::Set(U256::from(1000)),
+            &mut swap_opcodes,
+            &cur_pool,
+            Some(&next_pool),
+            multicaller,
+        )?;
+
+        // sync, transfer and unlock, the output amount is not read from the multicaller balance
+        assert_eq!(swap_opcodes.opcodes_vec.len(), 3);
+        assert!(swap_opcodes.opcodes_vec.iter().all(|opcode| opcode.to == pool_manager || opcode.to == token_from));
+
+        let unlock_opcode = swap_opcodes.get(2).unwrap();
+        assert_eq!(unlock_opcode.to, pool_manager);
+        assert_ne!(unlock_opcode.return_stack, 0);
+        Ok(())
+    }
+}
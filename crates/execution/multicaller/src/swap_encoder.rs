@@ -74,7 +74,8 @@ impl SwapEncoder for MulticallerSwapEncoder {
 
         let tips_vec =
             if let (Some(tips_pct), Some(sender_address), Some(sender_eth_balance)) = (tips_pct, sender_address, sender_eth_balance) {
-                let (tips_vec, _call_value) = tips_and_value_for_swap_type(&swap, Some(tips_pct), gas_cost, sender_eth_balance)?;
+                let (tips_vec, _call_value) =
+                    tips_and_value_for_swap_type(&swap, Some(tips_pct), gas_cost, sender_eth_balance, self.tips_policy.as_ref())?;
                 for tips in &tips_vec {
                     swap_opcodes = self.swap_step_encoder.encode_tips(
                         swap_opcodes,
@@ -10,16 +10,25 @@ use loom_types_entities::{PoolClass, PoolWrapper, PreswapRequirement, SwapAmount
 
 use crate::helpers::EncoderHelper;
 use crate::opcodes_encoder::{OpcodesEncoder, OpcodesEncoderV2};
-use crate::poolencoders::{CurveSwapEncoder, StEthSwapEncoder, WstEthSwapEncoder};
+use crate::poolencoders::{
+    BalancerSwapEncoder, CurveSwapEncoder, Erc4626SwapEncoder, IntegralSwapEncoder, RocketPoolSwapEncoder, SolidlySwapEncoder,
+    StEthSwapEncoder, UniswapV4SwapEncoder, WstEthSwapEncoder,
+};
 
 #[derive(Clone)]
 pub struct SwapLineEncoder {
     multicaller: Address,
+    weth: Address,
 }
 
 impl SwapLineEncoder {
     pub fn new(multicaller: Address) -> SwapLineEncoder {
-        SwapLineEncoder { multicaller }
+        SwapLineEncoder { multicaller, weth: TokenAddress::WETH }
+    }
+
+    /// Set the wrapped native token of the chain, the mainnet WETH by default
+    pub fn with_weth(self, weth: Address) -> SwapLineEncoder {
+        SwapLineEncoder { weth, ..self }
     }
 
     pub fn encode_flash_swap_line_in_amount(
@@ -615,7 +624,7 @@ impl SwapLineEncoder {
 
                     swap_opcodes.add(swap_opcode);
                 }
-                PoolClass::UniswapV3 => {
+                PoolClass::UniswapV3 | PoolClass::Algebra => {
                     let inside_call_payload = Bytes::from(token_from_address.to_vec());
 
                     let mut swap_opcode = if i == 0 {
@@ -730,6 +739,7 @@ impl SwapLineEncoder {
                         cur_pool,
                         next_pool,
                         self.multicaller,
+                        self.weth,
                     )?;
                 }
                 PoolClass::LidoWstEth => {
@@ -755,6 +765,72 @@ impl SwapLineEncoder {
                         self.multicaller,
                     )?;
                 }
+                PoolClass::RocketPool => {
+                    RocketPoolSwapEncoder::encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        if i == 0 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) },
+                        &mut swap_opcodes,
+                        cur_pool,
+                        next_pool,
+                        self.multicaller,
+                    )?;
+                }
+                PoolClass::UniswapV4 => {
+                    UniswapV4SwapEncoder::encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        if i == 0 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) },
+                        &mut swap_opcodes,
+                        cur_pool,
+                        next_pool,
+                        self.multicaller,
+                    )?;
+                }
+                PoolClass::Balancer => {
+                    BalancerSwapEncoder::encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        if i == 0 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) },
+                        &mut swap_opcodes,
+                        cur_pool,
+                        next_pool,
+                        self.multicaller,
+                    )?;
+                }
+                PoolClass::Solidly => {
+                    SolidlySwapEncoder::encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        if i == 0 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) },
+                        &mut swap_opcodes,
+                        cur_pool,
+                        next_pool,
+                        self.multicaller,
+                    )?;
+                }
+                PoolClass::Erc4626 => {
+                    Erc4626SwapEncoder::encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        if i == 0 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) },
+                        &mut swap_opcodes,
+                        cur_pool,
+                        next_pool,
+                        self.multicaller,
+                    )?;
+                }
+                PoolClass::Integral => {
+                    IntegralSwapEncoder::encode_swap_in_amount_provided(
+                        token_from_address,
+                        token_to_address,
+                        if i == 0 { swap_path.amount_in } else { SwapAmountType::RelativeStack(0) },
+                        &mut swap_opcodes,
+                        cur_pool,
+                        next_pool,
+                        self.multicaller,
+                    )?;
+                }
                 _ => {
                     return Err(eyre!("POOL_TYPE_NOT_SUPPORTED"));
                 }
@@ -773,7 +849,7 @@ impl SwapLineEncoder {
     ) -> Result<MulticallerCalls> {
         let mut tips_opcodes = swap_opcodes.clone();
 
-        let call_data = if token_address == TokenAddress::WETH {
+        let call_data = if token_address == self.weth {
             trace!("encode_multicaller_transfer_tips_weth");
             EncoderHelper::encode_multicaller_transfer_tips_weth(min_balance, tips, to)
         } else {
@@ -784,3 +860,29 @@ impl SwapLineEncoder {
         Ok(tips_opcodes)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn test_encode_tips_chain_weth() -> Result<()> {
+        let base_weth = address!("4200000000000000000000000000000000000006");
+        let owner = Address::repeat_byte(0x11);
+        let encoder = SwapLineEncoder::new(Address::repeat_byte(0x22)).with_weth(base_weth);
+
+        let tips_opcodes = encoder.encode_tips(MulticallerCalls::new(), base_weth, U256::ZERO, U256::from(1000), owner)?;
+        assert_eq!(
+            tips_opcodes.get(0).unwrap().call_data,
+            EncoderHelper::encode_multicaller_transfer_tips_weth(U256::ZERO, U256::from(1000), owner)
+        );
+
+        let tips_opcodes = encoder.encode_tips(MulticallerCalls::new(), TokenAddress::WETH, U256::ZERO, U256::from(1000), owner)?;
+        assert_eq!(
+            tips_opcodes.get(0).unwrap().call_data,
+            EncoderHelper::encode_multicaller_transfer_tips(TokenAddress::WETH, U256::ZERO, U256::from(1000), owner)
+        );
+        Ok(())
+    }
+}
@@ -1,27 +1,42 @@
 use alloy_primitives::{Address, Bytes, U256};
 use eyre::Result;
-use lazy_static::lazy_static;
 use tracing::{debug, trace};
 
 use crate::helpers::EncoderHelper;
 use crate::opcodes_encoder::{OpcodesEncoder, OpcodesEncoderV2};
 use crate::SwapLineEncoder;
+use loom_defi_address_book::{AddressBook, PeripheryAddress};
 use loom_types_blockchain::{MulticallerCall, MulticallerCalls};
 use loom_types_entities::{SwapAmountType, SwapStep};
 
-lazy_static! {
-    static ref BALANCER_VAULT_ADDRESS: Address = "0xBA12222222228d8Ba445958a75a0704d566BF2C8".parse().unwrap();
-}
-
 #[derive(Clone)]
 pub struct SwapStepEncoder {
     pub multicaller: Address,
+    pub balancer_vault: Address,
     pub swap_line_encoder: SwapLineEncoder,
 }
 
 impl SwapStepEncoder {
     pub fn new(multicaller: Address) -> Self {
-        Self { multicaller, swap_line_encoder: SwapLineEncoder::new(multicaller) }
+        Self { multicaller, balancer_vault: PeripheryAddress::BALANCER_VAULT, swap_line_encoder: SwapLineEncoder::new(multicaller) }
+    }
+
+    /// Set the Balancer vault used for flash loans, the mainnet vault by default
+    pub fn with_balancer_vault(self, balancer_vault: Address) -> Self {
+        Self { balancer_vault, ..self }
+    }
+
+    /// Set the wrapped native token unwrapped for tips and native Curve pools, the mainnet WETH by default
+    pub fn with_weth(self, weth: Address) -> Self {
+        Self { swap_line_encoder: self.swap_line_encoder.with_weth(weth), ..self }
+    }
+
+    /// Use the Balancer vault from the address book of the chain
+    pub fn for_chain(self, chain_id: u64) -> Self {
+        match AddressBook::find_periphery(chain_id, "BALANCER_VAULT") {
+            Some(balancer_vault) => self.with_balancer_vault(balancer_vault),
+            None => self,
+        }
     }
 
     pub fn get_contract_address(&self) -> Address {
@@ -84,7 +99,7 @@ impl SwapStepEncoder {
 
         let flash_call_data = EncoderHelper::encode_balancer_flashloan(token.get_address(), in_amount, inside_call_bytes, self.multicaller);
 
-        flash_opcodes.add(MulticallerCall::new_call(*BALANCER_VAULT_ADDRESS, &flash_call_data));
+        flash_opcodes.add(MulticallerCall::new_call(self.balancer_vault, &flash_call_data));
 
         Ok(flash_opcodes)
     }
@@ -105,6 +105,8 @@ pub mod storage {
 pub mod strategy {
     #[cfg(feature = "strategy-backrun")]
     pub use loom_strategy_backrun as backrun;
+    #[cfg(feature = "strategy-core")]
+    pub use loom_strategy_core as core;
     #[cfg(feature = "strategy-merger")]
     pub use loom_strategy_merger as merger;
 }
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use influxdb::{Timestamp, WriteQuery};
+use loom_core_actors::{Actor, ActorResult, ActorsRegistry, Broadcaster, WorkerResult};
+use loom_core_blockchain::Blockchain;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+
+async fn actors_stats_worker(
+    bc: Blockchain,
+    influx_channel_tx: Broadcaster<WriteQuery>,
+    interval: Duration,
+    shutdown_token: CancellationToken,
+) -> WorkerResult {
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Actors stats worker stopped".to_string())
+            }
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        let current_timestamp = chrono::Utc::now();
+        let mut write_queries: Vec<WriteQuery> = Vec::new();
+
+        for (name, stats) in bc.channels_stats().await {
+            write_queries.push(
+                WriteQuery::new(Timestamp::from(current_timestamp), "channel_stats")
+                    .add_tag("channel", name)
+                    .add_field("subscribers", stats.subscribers as u64)
+                    .add_field("queued", stats.queued as u64)
+                    .add_field("sent", stats.sent)
+                    .add_field("lag_events", stats.lag_events)
+                    .add_field("lagged_messages", stats.lagged_messages)
+                    .add_field("dropped", stats.dropped),
+            );
+        }
+
+        let now_millis = current_timestamp.timestamp_millis() as u64;
+        for actor in ActorsRegistry::actors() {
+            let mut write_query = WriteQuery::new(Timestamp::from(current_timestamp), "actor_stats")
+                .add_tag("actor", actor.name.clone())
+                .add_field("alive", actor.is_alive())
+                .add_field("alive_workers", actor.alive_workers as u64)
+                .add_field("restarts", actor.restarts as u64);
+            if let Some(last_activity) = actor.last_activity {
+                write_query = write_query.add_field("idle_ms", now_millis.saturating_sub(last_activity));
+            }
+            write_queries.push(write_query);
+        }
+
+        for write_query in write_queries {
+            if let Err(e) = influx_channel_tx.send(write_query).await {
+                error!("Failed to send actors stats to influxdb: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Periodically writes channel stats and actor liveness to influxdb
+pub struct ActorsStatsRecorderActor {
+    interval: Duration,
+    bc: Option<Blockchain>,
+    influxdb_write_channel_tx: Option<Broadcaster<WriteQuery>>,
+}
+
+impl Default for ActorsStatsRecorderActor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActorsStatsRecorderActor {
+    pub fn new() -> Self {
+        Self { interval: Duration::from_secs(10), bc: None, influxdb_write_channel_tx: None }
+    }
+
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self { bc: Some(bc.clone()), influxdb_write_channel_tx: Some(bc.influxdb_write_channel()), ..self }
+    }
+}
+
+impl Actor for ActorsStatsRecorderActor {
+    fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(actors_stats_worker(
+            self.bc.clone().unwrap(),
+            self.influxdb_write_channel_tx.clone().unwrap(),
+            self.interval,
+            token,
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "ActorsStatsRecorderActor"
+    }
+}
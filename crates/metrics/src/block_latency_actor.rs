@@ -7,18 +7,26 @@ use loom_core_actors_macros::{Accessor, Consumer, Producer};
 use loom_core_blockchain::Blockchain;
 use loom_types_events::MessageBlockHeader;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 async fn block_latency_worker(
     block_header_update_rx: Broadcaster<MessageBlockHeader>,
     influx_channel_tx: Broadcaster<WriteQuery>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     let mut last_block_number = 0;
     let mut last_block_hash = Default::default();
 
     subscribe!(block_header_update_rx);
     loop {
-        let block_header = match block_header_update_rx.recv().await {
+        let msg = tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Block latency worker stopped".to_string())
+            }
+            msg = block_header_update_rx.recv() => msg,
+        };
+        let block_header = match msg {
             Ok(block) => block,
             Err(e) => match e {
                 RecvError::Closed => {
@@ -78,9 +86,14 @@ impl BlockLatencyRecorderActor {
 
 impl Actor for BlockLatencyRecorderActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(block_latency_worker(
             self.block_header_rx.clone().unwrap(),
             self.influxdb_write_channel_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
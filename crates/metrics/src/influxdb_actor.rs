@@ -5,13 +5,30 @@ use loom_core_actors::{Actor, ActorResult, Broadcaster, Consumer, WorkerResult};
 use loom_core_actors_macros::Consumer;
 use loom_core_blockchain::Blockchain;
 use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
+/// Writes still arriving from stopping actors are drained until the channel stays idle this long
+const SHUTDOWN_IDLE_TIMEOUT: Duration = Duration::from_millis(200);
+
+async fn write_event(client: &Client, tags: &HashMap<String, String>, mut event: WriteQuery) {
+    for (key, value) in tags.iter() {
+        event = event.add_tag(key, value.clone());
+    }
+    let write_result = client.query(event).await;
+    if write_result.is_err() {
+        error!("Write failed: {:?}", write_result.err().unwrap());
+    }
+}
+
 pub async fn start_influxdb_worker(
     url: String,
     database: String,
     tags: HashMap<String, String>,
     event_receiver: Broadcaster<WriteQuery>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     let client = Client::new(url, database.clone());
     let create_db_stmt = format!("CREATE DATABASE {}", database);
@@ -22,23 +39,33 @@ pub async fn start_influxdb_worker(
     }
     let mut event_receiver = event_receiver.subscribe().await;
     loop {
-        let event_result = event_receiver.recv().await;
-        match event_result {
-            Ok(mut event) => {
-                for (key, value) in tags.iter() {
-                    event = event.add_tag(key, value.clone());
-                }
-                let write_result = client.query(event).await;
-                if write_result.is_err() {
-                    error!("Write failed: {:?}", write_result.err().unwrap());
+        let event_result = tokio::select! {
+            event_result = event_receiver.recv() => event_result,
+            _ = shutdown_token.cancelled() => {
+                // flush queued writes and the ones sent by actors still stopping
+                let mut flushed = 0;
+                loop {
+                    match tokio::time::timeout(SHUTDOWN_IDLE_TIMEOUT, event_receiver.recv()).await {
+                        Ok(Ok(event)) => {
+                            write_event(&client, &tags, event).await;
+                            flushed += 1;
+                        }
+                        Ok(Err(RecvError::Lagged(lagged))) => warn!("InfluxDB lagged on shutdown: {:?}", lagged),
+                        Ok(Err(RecvError::Closed)) | Err(_) => break,
+                    }
                 }
+                info!("InfluxDB writer stopped, {} queued writes flushed", flushed);
+                return Ok("InfluxDB writer stopped".to_string());
             }
+        };
+        match event_result {
+            Ok(event) => write_event(&client, &tags, event).await,
             Err(e) => match e {
-                tokio::sync::broadcast::error::RecvError::Closed => {
+                RecvError::Closed => {
                     error!("InfluxDB channel closed");
                     return Err(eyre!("INFLUXDB_CHANNEL_CLOSED"));
                 }
-                tokio::sync::broadcast::error::RecvError::Lagged(lagged) => {
+                RecvError::Lagged(lagged) => {
                     warn!("InfluxDB lagged: {:?}", lagged);
                     continue;
                 }
@@ -69,6 +96,10 @@ impl InfluxDbWriterActor {
 #[async_trait]
 impl Actor for InfluxDbWriterActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let influxdb_write_channel_rx = match &self.influxdb_write_channel_rx {
             Some(rx) => rx.clone(),
             None => {
@@ -81,6 +112,7 @@ impl Actor for InfluxDbWriterActor {
             self.database.clone(),
             self.tags.clone(),
             influxdb_write_channel_rx.clone(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -1,5 +1,7 @@
+mod actors_stats_actor;
 mod block_latency_actor;
 mod influxdb_actor;
 
+pub use actors_stats_actor::ActorsStatsRecorderActor;
 pub use block_latency_actor::BlockLatencyRecorderActor;
 pub use influxdb_actor::InfluxDbWriterActor;
@@ -19,6 +19,7 @@ use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace};
 
 use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
@@ -38,6 +39,7 @@ pub async fn reth_node_worker<P, T>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -61,6 +63,9 @@ where
 
     loop {
         tokio::select! {
+        _ = shutdown_token.cancelled() => {
+            break Ok("Reth node worker stopped".to_string())
+        }
         block_msg = stream.next() => {
             let Some(block) = block_msg else {
                     continue
@@ -251,6 +256,7 @@ pub fn reth_node_worker_starter<P, T>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    shutdown_token: CancellationToken,
 ) -> ActorResult
 where
     T: Transport + Clone,
@@ -263,6 +269,7 @@ where
         new_block_with_tx_channel,
         new_block_logs_channel,
         new_block_state_update_channel,
+        shutdown_token,
     ));
     Ok(vec![handler])
 }
@@ -323,6 +330,10 @@ where
     P: Provider<T, Ethereum> + DebugProviderExt<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         reth_node_worker_starter(
             self.client.clone(),
             self.reth_db_path.clone(),
@@ -330,8 +341,10 @@ where
             self.block_with_tx_channel.clone(),
             self.block_logs_channel.clone(),
             self.block_state_update_channel.clone(),
+            token,
         )
     }
+
     fn name(&self) -> &'static str {
         self.name()
     }
@@ -5,6 +5,7 @@ use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer};
 use loom_core_actors_macros::Producer;
 use loom_core_blockchain::Blockchain;
 use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageMempoolDataUpdate};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Producer)]
 pub struct NodeExExGrpcActor {
@@ -47,6 +48,10 @@ impl NodeExExGrpcActor {
 
 impl Actor for NodeExExGrpcActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let handler = tokio::task::spawn(node_exex_grpc_worker(
             Some(self.url.clone()),
             self.block_header_channel.clone().unwrap(),
@@ -54,6 +59,7 @@ impl Actor for NodeExExGrpcActor {
             self.block_logs_channel.clone().unwrap(),
             self.block_state_update_channel.clone().unwrap(),
             self.mempool_update_channel.clone().unwrap(),
+            token,
         ));
         Ok(vec![handler])
     }
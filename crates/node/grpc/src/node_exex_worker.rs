@@ -12,6 +12,7 @@ use reth_rpc::eth::EthTxBuilder;
 use revm::db::states::StorageSlot;
 use revm::db::{BundleAccount, StorageWithOriginalValues};
 use tokio::select;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 use loom_core_actors::{Broadcaster, WorkerResult};
@@ -132,6 +133,7 @@ pub async fn node_exex_grpc_worker(
     logs_channel: Broadcaster<MessageBlockLogs>,
     state_update_channel: Broadcaster<MessageBlockStateUpdate>,
     mempool_channel: Broadcaster<MessageMempoolDataUpdate>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     let client = ExExClient::connect(url.unwrap_or("http://[::1]:10000".to_string())).await?;
 
@@ -152,6 +154,9 @@ pub async fn node_exex_grpc_worker(
 
     loop {
         select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("ExEx gRPC worker stopped".to_string())
+            }
             /*notification = stream_exex.next() => {
                 if let Some(notification) = notification {
                     if let Some(chain) = get_current_chain(notification){
@@ -4,6 +4,7 @@ use alloy_network::Ethereum;
 use alloy_provider::Provider;
 use alloy_transport::Transport;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 use crate::node_block_hash_worker::new_node_block_header_worker;
 use crate::node_block_logs_worker::new_node_block_logs_worker;
@@ -22,6 +23,7 @@ pub fn new_node_block_workers_starter<P, T>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    shutdown_token: CancellationToken,
 ) -> ActorResult
 where
     T: Transport + Clone,
@@ -31,19 +33,39 @@ where
     let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
 
     if let Some(channel) = new_block_with_tx_channel {
-        tasks.push(tokio::task::spawn(new_block_with_tx_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_block_with_tx_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            shutdown_token.clone(),
+        )));
     }
 
     if let Some(channel) = new_block_headers_channel {
-        tasks.push(tokio::task::spawn(new_node_block_header_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_node_block_header_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            shutdown_token.clone(),
+        )));
     }
 
     if let Some(channel) = new_block_logs_channel {
-        tasks.push(tokio::task::spawn(new_node_block_logs_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_node_block_logs_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            shutdown_token.clone(),
+        )));
     }
 
     if let Some(channel) = new_block_state_update_channel {
-        tasks.push(tokio::task::spawn(new_node_block_state_worker(client.clone(), new_header_internal_channel.clone(), channel)));
+        tasks.push(tokio::task::spawn(new_node_block_state_worker(
+            client.clone(),
+            new_header_internal_channel.clone(),
+            channel,
+            shutdown_token.clone(),
+        )));
     }
 
     Ok(tasks)
@@ -102,14 +124,20 @@ where
     P: Provider<T, Ethereum> + DebugProviderExt<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         new_node_block_workers_starter(
             self.client.clone(),
             self.block_header_channel.clone(),
             self.block_with_tx_channel.clone(),
             self.block_logs_channel.clone(),
             self.block_state_update_channel.clone(),
+            token,
         )
     }
+
     fn name(&self) -> &'static str {
         self.name()
     }
@@ -11,6 +11,7 @@ use eyre::Result;
 use futures::StreamExt;
 use loom_core_actors::{run_async, Broadcaster, WorkerResult};
 use loom_types_events::{BlockHeader, MessageBlockHeader};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 #[allow(dead_code)]
@@ -43,6 +44,7 @@ pub async fn new_node_block_header_worker<P, T>(
     client: P,
     new_block_header_channel: Broadcaster<Header>,
     block_header_channel: Broadcaster<MessageBlockHeader>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -56,6 +58,9 @@ where
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Node block header worker stopped".to_string())
+            }
             block_msg = stream.next() => {
                 if let Some(block_header) = block_msg {
                     let block : Block = block_header;
@@ -5,6 +5,7 @@ use alloy_provider::Provider;
 use alloy_rpc_types::{Filter, Header};
 use alloy_transport::Transport;
 use tokio::sync::broadcast::Receiver;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
 use loom_core_actors::{subscribe, Broadcaster, WorkerResult};
@@ -14,11 +15,18 @@ pub async fn new_node_block_logs_worker<T: Transport + Clone, N: Network, P: Pro
     client: P,
     block_header_receiver: Broadcaster<Header>,
     sender: Broadcaster<MessageBlockLogs>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(block_header_receiver);
 
     loop {
-        if let Ok(block_header) = block_header_receiver.recv().await {
+        let msg = tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("BlockLogs worker stopped".to_string())
+            }
+            msg = block_header_receiver.recv() => msg,
+        };
+        if let Ok(block_header) = msg {
             let (block_number, block_hash) = (block_header.number, block_header.hash);
             debug!("BlockLogs header received {} {}", block_number, block_hash);
             let filter = Filter::new().at_block_hash(block_header.hash());
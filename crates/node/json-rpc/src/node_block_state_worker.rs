@@ -3,6 +3,7 @@ use alloy_network::Network;
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, Header};
 use alloy_transport::Transport;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
 use loom_core_actors::{subscribe, Broadcaster, WorkerResult};
@@ -14,6 +15,7 @@ pub async fn new_node_block_state_worker<P, T, N>(
     client: P,
     block_header_receiver: Broadcaster<Header>,
     sender: Broadcaster<MessageBlockStateUpdate>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -23,7 +25,13 @@ where
     subscribe!(block_header_receiver);
 
     loop {
-        if let Ok(block_header) = block_header_receiver.recv().await {
+        let msg = tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("BlockState worker stopped".to_string())
+            }
+            msg = block_header_receiver.recv() => msg,
+        };
+        if let Ok(block_header) = msg {
             let (block_number, block_hash) = (block_header.number, block_header.hash);
             debug!("BlockState header received {} {}", block_number, block_hash);
 
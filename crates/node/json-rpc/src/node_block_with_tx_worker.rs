@@ -4,12 +4,14 @@ use alloy_rpc_types::{BlockTransactionsKind, Header};
 use alloy_transport::Transport;
 use loom_core_actors::{subscribe, Broadcaster, WorkerResult};
 use loom_types_events::{Message, MessageBlock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
 pub async fn new_block_with_tx_worker<P, T>(
     client: P,
     block_header_receiver: Broadcaster<Header>,
     sender: Broadcaster<MessageBlock>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     T: Transport + Clone,
@@ -18,7 +20,13 @@ where
     subscribe!(block_header_receiver);
 
     loop {
-        if let Ok(block_header) = block_header_receiver.recv().await {
+        let msg = tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("BlockWithTx worker stopped".to_string())
+            }
+            msg = block_header_receiver.recv() => msg,
+        };
+        if let Ok(block_header) = msg {
             let (block_number, block_hash) = (block_header.number, block_header.hash);
             debug!("BlockWithTx header received {} {}", block_number, block_hash);
 
@@ -5,6 +5,7 @@ use alloy_primitives::TxHash;
 use alloy_provider::Provider;
 use alloy_transport::Transport;
 use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
 use tracing::error;
 
 use loom_core_actors::{Actor, ActorResult, Broadcaster, Producer, WorkerResult};
@@ -14,7 +15,12 @@ use loom_types_blockchain::MempoolTx;
 use loom_types_events::{MessageMempoolDataUpdate, NodeMempoolDataUpdate};
 
 /// Worker listens for new transactions in the node mempool and broadcasts [`MessageMempoolDataUpdate`].
-pub async fn new_node_mempool_worker<P, T>(client: P, name: String, mempool_tx: Broadcaster<MessageMempoolDataUpdate>) -> WorkerResult
+pub async fn new_node_mempool_worker<P, T>(
+    client: P,
+    name: String,
+    mempool_tx: Broadcaster<MessageMempoolDataUpdate>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult
 where
     T: Transport + Clone,
     P: Provider<T, Ethereum> + Send + Sync + 'static,
@@ -22,7 +28,14 @@ where
     let mempool_subscription = client.subscribe_full_pending_transactions().await?;
     let mut stream = mempool_subscription.into_stream();
 
-    while let Some(tx) = stream.next().await {
+    loop {
+        let tx = tokio::select! {
+            _ = shutdown_token.cancelled() => break,
+            tx = stream.next() => match tx {
+                Some(tx) => tx,
+                None => break,
+            },
+        };
         let tx_hash: TxHash = tx.hash;
         let update_msg: MessageMempoolDataUpdate = MessageMempoolDataUpdate::new_with_source(
             NodeMempoolDataUpdate { tx_hash, mempool_tx: MempoolTx { tx: Some(tx), ..MempoolTx::default() } },
@@ -73,8 +86,16 @@ where
     P: Provider<T, Ethereum> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
-        let task =
-            tokio::task::spawn(new_node_mempool_worker(self.client.clone(), self.name.to_string(), self.mempool_tx.clone().unwrap()));
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(new_node_mempool_worker(
+            self.client.clone(),
+            self.name.to_string(),
+            self.mempool_tx.clone().unwrap(),
+            token,
+        ));
         Ok(vec![task])
     }
 
@@ -15,6 +15,7 @@ use loom_types_blockchain::Mempool;
 use loom_types_entities::MarketState;
 use loom_types_events::{MessageBlock, MessageBlockHeader, MessageBlockLogs, MessageBlockStateUpdate, MessageTxCompose};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Producer, Consumer, Accessor)]
 pub struct NodeBlockPlayerActor<P, T, N> {
@@ -83,10 +84,14 @@ where
     N: Send + Sync,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let mut handles: Vec<JoinHandle<WorkerResult>> = Vec::new();
         if let Some(mempool) = self.mempool.clone() {
             if let Some(compose_channel) = self.compose_channel.clone() {
-                let handle = tokio::task::spawn(replayer_compose_worker(mempool, compose_channel));
+                let handle = tokio::task::spawn(replayer_compose_worker(mempool, compose_channel, token.clone()));
                 handles.push(handle);
             }
         }
@@ -101,6 +106,7 @@ where
             self.block_with_tx_channel.clone(),
             self.block_logs_channel.clone(),
             self.block_state_update_channel.clone(),
+            token,
         ));
         handles.push(handle);
         Ok(handles)
@@ -3,13 +3,21 @@ use loom_evm_utils::reth_types::decode_into_transaction;
 use loom_types_blockchain::Mempool;
 use loom_types_events::{MessageTxCompose, RlpState, TxCompose};
 use tokio::select;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
-pub(crate) async fn replayer_compose_worker(mempool: SharedState<Mempool>, compose_channel: Broadcaster<MessageTxCompose>) -> WorkerResult {
+pub(crate) async fn replayer_compose_worker(
+    mempool: SharedState<Mempool>,
+    compose_channel: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult {
     let mut compose_channel_rx = compose_channel.subscribe().await;
 
     loop {
         select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Replayer compose worker stopped".to_string())
+            }
             msg = compose_channel_rx.recv() => {
                 if let Ok(msg) = msg {
                     if let TxCompose::Broadcast(broadcast_msg) = msg.inner {
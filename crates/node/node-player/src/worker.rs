@@ -13,6 +13,7 @@ use loom_types_events::{
 };
 use std::ops::RangeInclusive;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
 #[allow(clippy::too_many_arguments)]
@@ -26,11 +27,15 @@ pub async fn node_player_worker<P>(
     new_block_with_tx_channel: Option<Broadcaster<MessageBlock>>,
     new_block_logs_channel: Option<Broadcaster<MessageBlockLogs>>,
     new_block_state_update_channel: Option<Broadcaster<MessageBlockStateUpdate>>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     P: Provider<HttpCachedTransport, Ethereum> + DebugProviderExt<HttpCachedTransport, Ethereum> + Send + Sync + Clone + 'static,
 {
     for _ in RangeInclusive::new(start_block, end_block) {
+        if shutdown_token.is_cancelled() {
+            return Ok("Node block player worker stopped".to_string());
+        }
         let curblock_number = provider.client().transport().fetch_next_block().await?;
         let block = provider.get_block_by_number(curblock_number.into(), false).await?;
 
@@ -169,7 +174,10 @@ where
             }
         }
 
-        tokio::time::sleep(Duration::from_millis(1000)).await;
+        tokio::select! {
+            _ = shutdown_token.cancelled() => {}
+            _ = tokio::time::sleep(Duration::from_millis(1000)) => {}
+        }
     }
 
     Ok("Node block player worker finished".to_string())
@@ -0,0 +1,33 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActorsResponse {
+    pub actors: Vec<Actor>,
+    pub channels: Vec<Channel>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Actor {
+    pub name: String,
+    pub alive: bool,
+    pub workers: usize,
+    pub alive_workers: usize,
+    pub restarts: usize,
+    /// Unix timestamp in milliseconds
+    pub started_at: u64,
+    /// Unix timestamp in milliseconds of the last received message
+    pub last_activity: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Channel {
+    pub name: String,
+    pub capacity: usize,
+    pub subscribers: usize,
+    pub queued: usize,
+    pub sent: u64,
+    pub lag_events: u64,
+    pub lagged_messages: u64,
+    pub dropped: u64,
+}
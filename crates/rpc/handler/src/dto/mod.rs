@@ -1,3 +1,4 @@
+pub mod actors;
 pub mod block;
 pub mod flashbots;
 pub mod pagination;
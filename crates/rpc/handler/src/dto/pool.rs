@@ -46,10 +46,16 @@ pub enum PoolClass {
     Unknown,
     UniswapV2,
     UniswapV3,
+    UniswapV4,
     Curve,
     LidoStEth,
     LidoWstEth,
     RocketPool,
+    Balancer,
+    Erc4626,
+    Solidly,
+    Algebra,
+    Integral,
     Custom(u64),
 }
 impl From<loom_types_entities::PoolClass> for PoolClass {
@@ -58,10 +64,16 @@ impl From<loom_types_entities::PoolClass> for PoolClass {
             loom_types_entities::PoolClass::Unknown => PoolClass::Unknown,
             loom_types_entities::PoolClass::UniswapV2 => PoolClass::UniswapV2,
             loom_types_entities::PoolClass::UniswapV3 => PoolClass::UniswapV3,
+            loom_types_entities::PoolClass::UniswapV4 => PoolClass::UniswapV4,
             loom_types_entities::PoolClass::Curve => PoolClass::Curve,
             loom_types_entities::PoolClass::LidoStEth => PoolClass::LidoStEth,
             loom_types_entities::PoolClass::LidoWstEth => PoolClass::LidoWstEth,
             loom_types_entities::PoolClass::RocketPool => PoolClass::RocketPool,
+            loom_types_entities::PoolClass::Balancer => PoolClass::Balancer,
+            loom_types_entities::PoolClass::Erc4626 => PoolClass::Erc4626,
+            loom_types_entities::PoolClass::Solidly => PoolClass::Solidly,
+            loom_types_entities::PoolClass::Algebra => PoolClass::Algebra,
+            loom_types_entities::PoolClass::Integral => PoolClass::Integral,
             loom_types_entities::PoolClass::Custom(id) => PoolClass::Custom(id),
         }
     }
@@ -82,6 +94,7 @@ pub enum PoolProtocol {
     Shibaswap,
     UniswapV3,
     UniswapV3Like,
+    UniswapV4,
     PancakeV3,
     Integral,
     Maverick,
@@ -90,6 +103,14 @@ pub enum PoolProtocol {
     LidoWstEth,
     RocketEth,
     OgPepe,
+    Balancer,
+    Erc4626,
+    Solidly,
+    Velodrome,
+    Aerodrome,
+    Algebra,
+    QuickswapV3,
+    CamelotV3,
     Custom(u64),
 }
 impl From<loom_types_entities::PoolProtocol> for PoolProtocol {
@@ -107,6 +128,7 @@ impl From<loom_types_entities::PoolProtocol> for PoolProtocol {
             loom_types_entities::PoolProtocol::Shibaswap => PoolProtocol::Shibaswap,
             loom_types_entities::PoolProtocol::UniswapV3 => PoolProtocol::UniswapV3,
             loom_types_entities::PoolProtocol::UniswapV3Like => PoolProtocol::UniswapV3Like,
+            loom_types_entities::PoolProtocol::UniswapV4 => PoolProtocol::UniswapV4,
             loom_types_entities::PoolProtocol::PancakeV3 => PoolProtocol::PancakeV3,
             loom_types_entities::PoolProtocol::Integral => PoolProtocol::Integral,
             loom_types_entities::PoolProtocol::Maverick => PoolProtocol::Maverick,
@@ -115,6 +137,14 @@ impl From<loom_types_entities::PoolProtocol> for PoolProtocol {
             loom_types_entities::PoolProtocol::LidoWstEth => PoolProtocol::LidoWstEth,
             loom_types_entities::PoolProtocol::RocketEth => PoolProtocol::RocketEth,
             loom_types_entities::PoolProtocol::OgPepe => PoolProtocol::OgPepe,
+            loom_types_entities::PoolProtocol::Balancer => PoolProtocol::Balancer,
+            loom_types_entities::PoolProtocol::Erc4626 => PoolProtocol::Erc4626,
+            loom_types_entities::PoolProtocol::Solidly => PoolProtocol::Solidly,
+            loom_types_entities::PoolProtocol::Velodrome => PoolProtocol::Velodrome,
+            loom_types_entities::PoolProtocol::Aerodrome => PoolProtocol::Aerodrome,
+            loom_types_entities::PoolProtocol::Algebra => PoolProtocol::Algebra,
+            loom_types_entities::PoolProtocol::QuickswapV3 => PoolProtocol::QuickswapV3,
+            loom_types_entities::PoolProtocol::CamelotV3 => PoolProtocol::CamelotV3,
             loom_types_entities::PoolProtocol::Custom(id) => PoolProtocol::Custom(id),
         }
     }
@@ -135,6 +165,7 @@ impl From<&PoolProtocol> for loom_types_entities::PoolProtocol {
             PoolProtocol::Shibaswap => loom_types_entities::PoolProtocol::Shibaswap,
             PoolProtocol::UniswapV3 => loom_types_entities::PoolProtocol::UniswapV3,
             PoolProtocol::UniswapV3Like => loom_types_entities::PoolProtocol::UniswapV3Like,
+            PoolProtocol::UniswapV4 => loom_types_entities::PoolProtocol::UniswapV4,
             PoolProtocol::PancakeV3 => loom_types_entities::PoolProtocol::PancakeV3,
             PoolProtocol::Integral => loom_types_entities::PoolProtocol::Integral,
             PoolProtocol::Maverick => loom_types_entities::PoolProtocol::Maverick,
@@ -143,6 +174,14 @@ impl From<&PoolProtocol> for loom_types_entities::PoolProtocol {
             PoolProtocol::LidoWstEth => loom_types_entities::PoolProtocol::LidoWstEth,
             PoolProtocol::RocketEth => loom_types_entities::PoolProtocol::RocketEth,
             PoolProtocol::OgPepe => loom_types_entities::PoolProtocol::OgPepe,
+            PoolProtocol::Balancer => loom_types_entities::PoolProtocol::Balancer,
+            PoolProtocol::Erc4626 => loom_types_entities::PoolProtocol::Erc4626,
+            PoolProtocol::Solidly => loom_types_entities::PoolProtocol::Solidly,
+            PoolProtocol::Velodrome => loom_types_entities::PoolProtocol::Velodrome,
+            PoolProtocol::Aerodrome => loom_types_entities::PoolProtocol::Aerodrome,
+            PoolProtocol::Algebra => loom_types_entities::PoolProtocol::Algebra,
+            PoolProtocol::QuickswapV3 => loom_types_entities::PoolProtocol::QuickswapV3,
+            PoolProtocol::CamelotV3 => loom_types_entities::PoolProtocol::CamelotV3,
             PoolProtocol::Custom(id) => loom_types_entities::PoolProtocol::Custom(*id),
         }
     }
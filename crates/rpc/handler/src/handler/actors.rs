@@ -0,0 +1,52 @@
+use crate::dto::actors::{Actor, ActorsResponse, Channel};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use loom_core_actors::ActorsRegistry;
+use loom_rpc_state::AppState;
+
+/// Get actors and channels
+///
+/// Get liveness of actors and stats of blockchain channels
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "actors",
+    tags = [],
+    responses(
+    (status = 200, description = "Actors and channels", body = ActorsResponse),
+    )
+)]
+pub async fn actors(State(app_state): State<AppState>) -> Result<Json<ActorsResponse>, (StatusCode, String)> {
+    let actors = ActorsRegistry::actors()
+        .into_iter()
+        .map(|actor| Actor {
+            alive: actor.is_alive(),
+            name: actor.name,
+            workers: actor.workers,
+            alive_workers: actor.alive_workers,
+            restarts: actor.restarts,
+            started_at: actor.started_at,
+            last_activity: actor.last_activity,
+        })
+        .collect();
+
+    let channels = app_state
+        .bc
+        .channels_stats()
+        .await
+        .into_iter()
+        .map(|(name, stats)| Channel {
+            name: name.to_string(),
+            capacity: stats.capacity,
+            subscribers: stats.subscribers,
+            queued: stats.queued,
+            sent: stats.sent,
+            lag_events: stats.lag_events,
+            lagged_messages: stats.lagged_messages,
+            dropped: stats.dropped,
+        })
+        .collect();
+
+    Ok(Json(ActorsResponse { actors, channels }))
+}
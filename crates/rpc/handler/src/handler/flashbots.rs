@@ -6,7 +6,6 @@ use axum::Json;
 use loom_evm_utils::evm::evm_transact;
 use loom_evm_utils::evm_tx_env::env_from_signed_tx;
 use loom_rpc_state::AppState;
-use loom_types_blockchain::ChainParameters;
 use revm::primitives::{BlockEnv, Env, CANCUN};
 use revm::Evm;
 use tracing::{error, info};
@@ -30,7 +29,7 @@ pub async fn flashbots(
             ));
         }
         let next_block_timestamp = last_block_header.timestamp + 12 * (target_block - last_block_header.number);
-        let next_block_base_fee = ChainParameters::ethereum().calc_next_block_base_fee(
+        let next_block_base_fee = app_state.bc.chain_parameters().calc_next_block_base_fee(
             last_block_header.gas_used,
             last_block_header.gas_limit,
             last_block_header.base_fee_per_gas.unwrap_or_default(),
@@ -1,3 +1,4 @@
+pub mod actors;
 pub mod blocks;
 pub mod flashbots;
 pub mod pools;
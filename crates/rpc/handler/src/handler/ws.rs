@@ -6,7 +6,6 @@ use axum::{
 
 use crate::dto::block::{BlockHeader, WebSocketMessage};
 use loom_rpc_state::AppState;
-use loom_types_blockchain::ChainParameters;
 use std::net::SocketAddr;
 use tracing::{error, warn};
 
@@ -31,7 +30,7 @@ async fn on_upgrade(mut socket: WebSocket, _who: SocketAddr, app_state: AppState
             number: header.inner.header.number,
             timestamp: header.inner.header.timestamp,
             base_fee_per_gas: header.inner.header.base_fee_per_gas,
-            next_block_base_fee: ChainParameters::ethereum().calc_next_block_base_fee_from_header(&header.inner.header),
+            next_block_base_fee: app_state.bc.chain_parameters().calc_next_block_base_fee_from_header(&header.inner.header),
         });
         match serde_json::to_string(&ws_msg) {
             Ok(json) => {
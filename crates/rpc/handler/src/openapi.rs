@@ -1,3 +1,4 @@
+use crate::dto::actors::{Actor, ActorsResponse, Channel};
 use crate::dto::block::BlockHeader;
 use crate::dto::pool::MarketStats;
 use crate::dto::pool::Pool;
@@ -7,6 +8,7 @@ use crate::dto::pool::PoolProtocol;
 use crate::dto::pool::PoolResponse;
 use crate::dto::quote::QuoteRequest;
 use crate::dto::quote::QuoteResponse;
+use crate::handler::actors::__path_actors;
 use crate::handler::blocks::__path_latest_block;
 use crate::handler::pools::__path_market_stats;
 use crate::handler::pools::__path_pool;
@@ -34,11 +36,22 @@ pub struct BlockApi;
 )]
 pub struct MarketApi;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(actors),
+    tags(
+        (name = "actors", description = "Actors")
+    ),
+    components(schemas(ActorsResponse, Actor, Channel))
+)]
+pub struct ActorsApi;
+
 #[derive(OpenApi)]
 #[openapi(
     nest(
         (path = "/api/v1/block/", api = BlockApi),
-        (path = "/api/v1/markets", api = MarketApi)
+        (path = "/api/v1/markets", api = MarketApi),
+        (path = "/api/v1/actors", api = ActorsApi)
     )
 )]
 pub struct ApiDoc;
@@ -1,3 +1,4 @@
+use crate::handler::actors::actors;
 use crate::handler::blocks::latest_block;
 use crate::handler::flashbots::flashbots;
 use crate::handler::pools::{market_stats, pool, pool_quote, pools};
@@ -16,7 +17,8 @@ pub fn router(app_state: AppState) -> Router<()> {
             Router::new()
                 .nest("/block", router_block()) // rename to node
                 .nest("/markets", router_market())
-                .nest("/flashbots", Router::new().route("/", post(flashbots))),
+                .nest("/flashbots", Router::new().route("/", post(flashbots)))
+                .nest("/actors", Router::new().route("/", get(actors))),
         )
         .route("/ws", get(ws_handler))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
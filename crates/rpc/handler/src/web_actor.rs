@@ -17,6 +17,7 @@ pub async fn start_web_server_worker<S>(
     bc: Blockchain,
     db_pool: DbPool,
     shutdown_token: CancellationToken,
+    actors_shutdown_token: CancellationToken,
 ) -> WorkerResult
 where
     S: Clone + Send + Sync + 'static,
@@ -33,7 +34,10 @@ where
     let listener = TcpListener::bind(host).await?;
     axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(async move {
-            shutdown_token.cancelled().await;
+            tokio::select! {
+                _ = shutdown_token.cancelled() => {}
+                _ = actors_shutdown_token.cancelled() => {}
+            }
             info!("Shutting down webserver...");
         })
         .await?;
@@ -70,12 +74,17 @@ where
     Router: From<Router<S>>,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::spawn(start_web_server_worker(
             self.host.clone(),
             self.extra_router.clone(),
             self.bc.clone().unwrap(),
             self.db_pool.clone(),
             self.shutdown_token.clone(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -32,7 +32,8 @@ pub fn bench_swap_calculator(c: &mut Criterion) {
 
             let mut market = Market::default();
             // Add basic token for start/end
-            let weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+            let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+            weth_token.set_wrapped_native();
             market.add_token(weth_token)?;
 
             for (pool_address, pool_class) in pool_addresses.iter() {
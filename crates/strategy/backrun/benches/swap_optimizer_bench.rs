@@ -0,0 +1,107 @@
+use alloy_primitives::utils::parse_units;
+use alloy_primitives::{address, BlockNumber, U256};
+use std::collections::BTreeMap;
+use std::env;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use loom_defi_address_book::TokenAddress;
+use loom_defi_pools::{UniswapV2Pool, UniswapV3Pool};
+use loom_evm_db::LoomDBType;
+use loom_node_debug_provider::AnvilDebugProviderFactory;
+use loom_types_entities::required_state::RequiredStateReader;
+use loom_types_entities::{
+    build_swap_path_vec, ConstantProductOptimizer, GoldenSectionOptimizer, Market, PoolClass, PoolWrapper, StepSearchOptimizer, SwapLine,
+    SwapLineOptimizer, Token,
+};
+use revm::primitives::Env;
+
+pub fn bench_swap_optimizer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("swap_optimizer");
+
+    let pool_addresses = vec![
+        (address!("322bba387c825180ebfb62bd8e6969ebe5b5e52d"), PoolClass::UniswapV2),
+        (address!("f382839b955ab57cc1e041f2c987a909c9a48af1"), PoolClass::UniswapV2),
+        (address!("49af5fb5de94c93ee83ad488fe8cab30b0ef35f2"), PoolClass::UniswapV3),
+    ];
+
+    let block_number = 20935488u64;
+    let rt = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+
+    let mut state_db = LoomDBType::default();
+    let swap_paths = rt
+        .block_on(async {
+            let node_url = env::var("MAINNET_WS")?;
+            let client = AnvilDebugProviderFactory::from_node_on_block(node_url, BlockNumber::from(block_number)).await?;
+
+            let mut market = Market::default();
+            let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+            weth_token.set_wrapped_native();
+            market.add_token(weth_token)?;
+
+            for (pool_address, pool_class) in pool_addresses.iter() {
+                let pool: PoolWrapper = match pool_class {
+                    PoolClass::UniswapV2 => UniswapV2Pool::fetch_pool_data(client.clone(), *pool_address).await?.into(),
+                    PoolClass::UniswapV3 => UniswapV3Pool::fetch_pool_data(client.clone(), *pool_address).await?.into(),
+                    _ => panic!("Unknown pool class"),
+                };
+
+                let state_required = pool.get_state_required()?;
+                let state_update = RequiredStateReader::fetch_calls_and_slots(client.clone(), state_required, Some(block_number)).await?;
+                state_db.apply_geth_update(state_update);
+                market.add_pool(pool)?;
+            }
+
+            // paths over the uniswap v2 pools only and paths passing the uniswap v3 pool
+            let mut directions = BTreeMap::new();
+            for (pool_address, _) in pool_addresses.iter() {
+                let pool = market.get_pool(pool_address).unwrap();
+                directions.insert(pool.clone(), pool.get_swap_directions());
+            }
+
+            build_swap_path_vec(&market, &directions)
+        })
+        .expect("Could not fetch state");
+
+    rt.shutdown_background();
+
+    let start_in_amount = parse_units("0.01", "ether").unwrap().get_absolute();
+
+    let optimizers: Vec<(&str, Box<dyn SwapLineOptimizer>)> = vec![
+        ("step_search", Box::new(StepSearchOptimizer {})),
+        ("golden_section", Box::new(GoldenSectionOptimizer::default())),
+        ("constant_product", Box::new(ConstantProductOptimizer::default())),
+    ];
+
+    for (path_idx, swap_path) in swap_paths.into_iter().enumerate() {
+        let swap_line = SwapLine { path: swap_path, ..Default::default() };
+        println!("SwapLine {path_idx}: {swap_line}");
+
+        for (name, optimizer) in optimizers.iter() {
+            let mut optimized = swap_line.clone();
+            match optimizer.optimize(&mut optimized, &state_db, Env::default(), start_in_amount) {
+                Ok(evaluations) => println!(
+                    "{name} : in {} profit {} evaluations {evaluations}",
+                    optimized.amount_in.unwrap_or_zero(),
+                    optimized.profit().unwrap_or_default()
+                ),
+                Err(e) => println!("{name} : {}", e.msg),
+            }
+
+            group.bench_function(format!("{name}_{path_idx}"), |b| {
+                b.iter(|| {
+                    let _ = optimizer.optimize(
+                        black_box(&mut swap_line.clone()),
+                        black_box(&state_db),
+                        black_box(Env::default()),
+                        black_box(start_in_amount),
+                    );
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_swap_optimizer);
+criterion_main!(benches);
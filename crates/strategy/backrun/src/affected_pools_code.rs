@@ -12,7 +12,7 @@ use tracing::{debug, error};
 use loom_core_actors::SharedState;
 use loom_defi_pools::protocols::{UniswapV2Protocol, UniswapV3Protocol};
 use loom_defi_pools::state_readers::{UniswapV2StateReader, UniswapV3StateReader};
-use loom_defi_pools::{MaverickPool, PancakeV3Pool, UniswapV2Pool, UniswapV3Pool};
+use loom_defi_pools::{MaverickPool, PancakeV3Pool, SolidlyPool, UniswapV2Pool, UniswapV3Pool};
 use loom_evm_db::LoomDB;
 use loom_types_blockchain::GethStateUpdateVec;
 use loom_types_entities::{get_protocol_by_factory, Market, MarketState, Pool, PoolProtocol, PoolWrapper};
@@ -39,11 +39,26 @@ where
                     match market.read().await.get_pool(address) {
                         None => {
                             debug!(?address, "Loading UniswapV2 class pool");
-                            let env = Env::default();
+                            let mut env = Env::default();
+                            env.cfg.chain_id = client.get_chain_id().await?;
 
                             let state_db = LoomDB::new_with_ro_db_and_provider(Some(market_state.state_db.clone()), client.clone())?;
 
                             match UniswapV2StateReader::factory(&state_db, env.clone(), *address) {
+                                // Solidly pools have the same swap, mint and sync functions
+                                Ok(factory_address) if get_protocol_by_factory(env.cfg.chain_id, factory_address).is_solidly() => {
+                                    match SolidlyPool::fetch_pool_data_evm(&state_db, env.clone(), *address) {
+                                        Ok(pool) => {
+                                            let pool = PoolWrapper::new(Arc::new(pool));
+                                            debug!(?address, protocol = ?pool.get_protocol(), "Solidly pool loaded");
+                                            let swap_directions = pool.get_swap_directions();
+                                            ret.insert(pool, swap_directions);
+                                        }
+                                        Err(err) => {
+                                            error!(?address, %err, "Error loading Solidly pool");
+                                        }
+                                    }
+                                }
                                 Ok(_factory_address) => match UniswapV2Pool::fetch_pool_data_evm(&state_db, env.clone(), *address) {
                                     Ok(pool) => {
                                         let pool = PoolWrapper::new(Arc::new(pool));
@@ -70,12 +85,13 @@ where
                     match market.read().await.get_pool(address) {
                         None => {
                             debug!(?address, "Loading UniswapV3 class pool");
-                            let env = Env::default();
+                            let mut env = Env::default();
+                            env.cfg.chain_id = client.get_chain_id().await?;
                             // TODO : Fix factory
                             let state_db = LoomDB::new_with_ro_db_and_provider(Some(market_state.state_db.clone()), client.clone())?;
                             match UniswapV3StateReader::factory(&state_db, env.clone(), *address) {
                                 Ok(factory_address) => {
-                                    match get_protocol_by_factory(factory_address) {
+                                    match get_protocol_by_factory(env.cfg.chain_id, factory_address) {
                                         PoolProtocol::PancakeV3 => {
                                             let pool = PancakeV3Pool::fetch_pool_data_evm(&state_db, env.clone(), *address);
                                             match pool {
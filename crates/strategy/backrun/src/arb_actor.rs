@@ -4,6 +4,7 @@ use alloy_network::Network;
 use alloy_provider::Provider;
 use alloy_transport::Transport;
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
 use loom_core_actors::{Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
@@ -79,16 +80,23 @@ where
     P: Provider<T, N> + DebugProviderExt<T, N> + Send + Sync + Clone + 'static,
 {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let searcher_pool_update_channel = Broadcaster::new(100);
         let mut tasks: Vec<JoinHandle<WorkerResult>> = Vec::new();
 
         let mut state_update_searcher = StateChangeArbSearcherActor::new(self.backrun_config.clone());
+        if let Some(market_events_tx) = self.market_events_tx.clone() {
+            state_update_searcher.consume(market_events_tx);
+        }
         match state_update_searcher
             .access(self.market.clone().unwrap())
             .consume(searcher_pool_update_channel.clone())
             .produce(self.compose_channel_tx.clone().unwrap())
             .produce(self.pool_health_monitor_tx.clone().unwrap())
-            .start()
+            .start_with_token(token.clone())
         {
             Err(e) => {
                 panic!("{}", e)
@@ -109,7 +117,7 @@ where
                 .consume(self.mempool_events_tx.clone().unwrap())
                 .consume(self.market_events_tx.clone().unwrap())
                 .produce(searcher_pool_update_channel.clone())
-                .start()
+                .start_with_token(token.clone())
             {
                 Err(e) => {
                     panic!("{}", e)
@@ -128,7 +136,7 @@ where
                 .access(self.block_history.clone().unwrap())
                 .consume(self.market_events_tx.clone().unwrap())
                 .produce(searcher_pool_update_channel.clone())
-                .start()
+                .start_with_token(token.clone())
             {
                 Err(e) => {
                     panic!("{}", e)
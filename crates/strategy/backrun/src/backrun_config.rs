@@ -1,7 +1,13 @@
-use alloy_primitives::Address;
+use std::collections::HashSet;
+
+use alloy_primitives::{Address, U256};
 use loom_types_entities::config::StrategyConfig;
+use loom_types_entities::{SwapLine, SwapPath};
 use serde::Deserialize;
 
+/// Origin of the state updates of new blocks, other origins are pending txs
+pub const BLOCK_ORIGIN: &str = "block_searcher";
+
 #[derive(Clone, Deserialize, Debug)]
 pub struct BackrunConfigSection {
     pub backrun_strategy: BackrunConfig,
@@ -11,6 +17,48 @@ pub struct BackrunConfigSection {
 pub struct BackrunConfig {
     eoa: Option<Address>,
     smart: bool,
+    /// Backrun state changes of new blocks
+    #[serde(default = "default_enabled")]
+    block: bool,
+    /// Backrun state changes of pending txs
+    #[serde(default = "default_enabled")]
+    mempool: bool,
+    #[serde(flatten)]
+    limits: BackrunLimits,
+}
+
+/// Profit, gas and path limits of backrun candidates
+#[derive(Clone, Default, Deserialize, Debug)]
+pub struct BackrunLimits {
+    /// Minimum profit in ETH
+    #[serde(default)]
+    min_profit_eth: Option<f64>,
+    /// Minimum profit per gas in gwei
+    #[serde(default)]
+    min_profit_per_gas_gwei: Option<f64>,
+    /// Maximum gas of a bundle, the gas limits of the stuffing txs and the gas of the swap
+    #[serde(default)]
+    max_gas: Option<u64>,
+    /// Maximum number of pools of a swap path
+    #[serde(default)]
+    max_path_len: Option<usize>,
+    /// Maximum number of swaps sent to compose per state update, the most profitable are kept
+    #[serde(default)]
+    max_candidates: Option<usize>,
+    /// Swap paths pass only these tokens if not empty
+    #[serde(default)]
+    token_allowlist: HashSet<Address>,
+    #[serde(default)]
+    token_denylist: HashSet<Address>,
+    /// Swap paths pass only these pools if not empty
+    #[serde(default)]
+    pool_allowlist: HashSet<Address>,
+    #[serde(default)]
+    pool_denylist: HashSet<Address>,
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 impl StrategyConfig for BackrunConfig {
@@ -20,17 +68,211 @@ impl StrategyConfig for BackrunConfig {
 }
 
 impl BackrunConfig {
+    pub fn new(eoa: Option<Address>, smart: bool) -> Self {
+        Self { eoa, smart, ..Self::default() }
+    }
+
+    pub fn with_origins(self, block: bool, mempool: bool) -> Self {
+        Self { block, mempool, ..self }
+    }
+
+    pub fn with_limits(self, limits: BackrunLimits) -> Self {
+        Self { limits, ..self }
+    }
+
     pub fn smart(&self) -> bool {
         self.smart
     }
 
     pub fn new_dumb() -> Self {
-        Self { eoa: None, smart: false }
+        Self { smart: false, ..Self::default() }
+    }
+
+    pub fn max_candidates(&self) -> Option<usize> {
+        self.limits.max_candidates
+    }
+
+    pub fn min_profit(&self) -> U256 {
+        self.limits.min_profit_eth.map_or(U256::ZERO, |min_profit_eth| U256::from((min_profit_eth * 1e18).round() as u128))
+    }
+
+    pub fn min_profit_per_gas(&self) -> U256 {
+        self.limits
+            .min_profit_per_gas_gwei
+            .map_or(U256::ZERO, |min_profit_per_gas_gwei| U256::from((min_profit_per_gas_gwei * 1e9).round() as u128))
+    }
+
+    /// Check if state updates of the origin are backrun
+    pub fn is_origin_enabled(&self, origin: &str) -> bool {
+        if origin == BLOCK_ORIGIN {
+            self.block
+        } else {
+            self.mempool
+        }
+    }
+
+    /// Check path length and the token and pool lists before calculation
+    pub fn is_swap_path_allowed(&self, swap_path: &SwapPath) -> bool {
+        let limits = &self.limits;
+        if limits.max_path_len.is_some_and(|max_path_len| swap_path.pools.len() > max_path_len) {
+            return false;
+        }
+        let is_token_allowed = |token_address: Address| {
+            (limits.token_allowlist.is_empty() || limits.token_allowlist.contains(&token_address))
+                && !limits.token_denylist.contains(&token_address)
+        };
+        let is_pool_allowed = |pool_address: Address| {
+            (limits.pool_allowlist.is_empty() || limits.pool_allowlist.contains(&pool_address))
+                && !limits.pool_denylist.contains(&pool_address)
+        };
+        swap_path.tokens.iter().all(|token| is_token_allowed(token.get_address()))
+            && swap_path.pools.iter().all(|pool| is_pool_allowed(pool.get_address()))
+    }
+
+    /// Check profit and gas of a calculated swap line, `stuffing_gas` is the gas of the txs backrun in the bundle
+    pub fn is_swap_line_allowed(&self, swap_line: &SwapLine, gas: u64, stuffing_gas: u64) -> bool {
+        if self.limits.max_gas.is_some_and(|max_gas| gas.saturating_add(stuffing_gas) > max_gas) {
+            return false;
+        }
+        let profit = swap_line.abs_profit_eth();
+        profit >= self.min_profit() && profit >= self.min_profit_per_gas() * U256::from(gas)
+    }
+
+    /// Most profitable candidates up to `max_candidates`
+    pub fn select_candidates<T>(&self, mut candidates: Vec<(U256, T)>) -> Vec<T> {
+        candidates.sort_by(|a, b| b.0.cmp(&a.0));
+        if let Some(max_candidates) = self.limits.max_candidates {
+            candidates.truncate(max_candidates);
+        }
+        candidates.into_iter().map(|(_, candidate)| candidate).collect()
     }
 }
 
 impl Default for BackrunConfig {
     fn default() -> Self {
-        Self { eoa: None, smart: true }
+        Self { eoa: None, smart: true, block: true, mempool: true, limits: BackrunLimits::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::utils::parse_units;
+    use loom_defi_address_book::TokenAddress;
+    use loom_defi_pools::UniswapV2Pool;
+    use loom_types_entities::{SwapAmountType, Token};
+    use std::sync::Arc;
+
+    // WETH -> TKN -> WETH through pools 0x02.. and 0x03..
+    fn swap_path() -> SwapPath {
+        let mut weth = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth.set_wrapped_native();
+        let weth = Arc::new(weth);
+        let token = Arc::new(Token::new(Address::repeat_byte(1)));
+        let pool = |address: Address| {
+            UniswapV2Pool::new_with_data(address, weth.get_address(), token.get_address(), Address::ZERO, U256::ZERO, U256::ZERO)
+        };
+        SwapPath::new(vec![weth.clone(), token.clone(), weth.clone()], vec![pool(Address::repeat_byte(2)), pool(Address::repeat_byte(3))])
+    }
+
+    fn swap_line_with_profit(profit: &str) -> SwapLine {
+        let amount_in = parse_units("1", "ether").unwrap().get_absolute();
+        let amount_out = amount_in + parse_units(profit, "ether").unwrap().get_absolute();
+        SwapLine {
+            path: swap_path(),
+            amount_in: SwapAmountType::Set(amount_in),
+            amount_out: SwapAmountType::Set(amount_out),
+            ..SwapLine::default()
+        }
+    }
+
+    #[test]
+    fn test_backrun_config_section() {
+        let config: BackrunConfigSection = toml::from_str(
+            r#"
+            [backrun_strategy]
+            smart = true
+            mempool = false
+            min_profit_eth = 0.001
+            min_profit_per_gas_gwei = 2.5
+            max_gas = 500000
+            max_candidates = 3
+            token_denylist = ["0x0000000000000000000000000000000000000001"]
+            "#,
+        )
+        .unwrap();
+        let config = config.backrun_strategy;
+
+        assert!(config.is_origin_enabled(BLOCK_ORIGIN));
+        assert!(!config.is_origin_enabled("pending_tx_searcher"));
+        assert_eq!(config.min_profit(), parse_units("0.001", "ether").unwrap().get_absolute());
+        assert_eq!(config.min_profit_per_gas(), U256::from(2_500_000_000u64));
+        assert_eq!(config.max_candidates(), Some(3));
+        assert!(config.limits.token_denylist.contains(&Address::with_last_byte(1)));
+    }
+
+    #[test]
+    fn test_backrun_config_default() {
+        let config = BackrunConfig::new(None, false);
+
+        assert!(!config.smart());
+        assert!(config.is_origin_enabled(BLOCK_ORIGIN) && config.is_origin_enabled("pending_tx_searcher"));
+        assert_eq!(config.min_profit(), U256::ZERO);
+        assert!(config.is_swap_path_allowed(&SwapPath::default()));
+    }
+
+    #[test]
+    fn test_is_swap_path_allowed() {
+        let swap_path = swap_path();
+        let limits = |limits: BackrunLimits| BackrunConfig::default().with_limits(limits);
+
+        assert!(BackrunConfig::default().is_swap_path_allowed(&swap_path));
+        assert!(limits(BackrunLimits { max_path_len: Some(2), ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+        assert!(!limits(BackrunLimits { max_path_len: Some(1), ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+
+        let tokens = HashSet::from([TokenAddress::WETH, Address::repeat_byte(1)]);
+        assert!(limits(BackrunLimits { token_allowlist: tokens, ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+        let tokens = HashSet::from([TokenAddress::WETH]);
+        assert!(!limits(BackrunLimits { token_allowlist: tokens, ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+        let tokens = HashSet::from([Address::repeat_byte(1)]);
+        assert!(!limits(BackrunLimits { token_denylist: tokens, ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+
+        let pools = HashSet::from([Address::repeat_byte(2), Address::repeat_byte(3)]);
+        assert!(limits(BackrunLimits { pool_allowlist: pools, ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+        let pools = HashSet::from([Address::repeat_byte(2)]);
+        assert!(!limits(BackrunLimits { pool_allowlist: pools, ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+        let pools = HashSet::from([Address::repeat_byte(3)]);
+        assert!(!limits(BackrunLimits { pool_denylist: pools, ..BackrunLimits::default() }).is_swap_path_allowed(&swap_path));
+    }
+
+    #[test]
+    fn test_is_swap_line_allowed() {
+        let swap_line = swap_line_with_profit("0.01");
+        let limits = |limits: BackrunLimits| BackrunConfig::default().with_limits(limits);
+
+        assert!(BackrunConfig::default().is_swap_line_allowed(&swap_line, 300_000, 0));
+
+        let config = limits(BackrunLimits { min_profit_eth: Some(0.01), ..BackrunLimits::default() });
+        assert!(config.is_swap_line_allowed(&swap_line, 300_000, 0));
+        assert!(!config.is_swap_line_allowed(&swap_line_with_profit("0.009"), 300_000, 0));
+
+        // 0.01 ETH profit for 200k gas is 50 gwei per gas
+        let config = limits(BackrunLimits { min_profit_per_gas_gwei: Some(50.0), ..BackrunLimits::default() });
+        assert!(config.is_swap_line_allowed(&swap_line, 200_000, 1_000_000));
+        assert!(!config.is_swap_line_allowed(&swap_line, 200_001, 0));
+
+        // stuffing txs count to the bundle gas
+        let config = limits(BackrunLimits { max_gas: Some(500_000), ..BackrunLimits::default() });
+        assert!(config.is_swap_line_allowed(&swap_line, 300_000, 200_000));
+        assert!(!config.is_swap_line_allowed(&swap_line, 300_000, 200_001));
+    }
+
+    #[test]
+    fn test_select_candidates() {
+        let candidates = vec![(U256::from(1), "a"), (U256::from(3), "b"), (U256::from(2), "c")];
+
+        assert_eq!(BackrunConfig::default().select_candidates(candidates.clone()), vec!["b", "c", "a"]);
+        let config = BackrunConfig::default().with_limits(BackrunLimits { max_candidates: Some(2), ..BackrunLimits::default() });
+        assert_eq!(config.select_candidates(candidates), vec!["b", "c"]);
     }
 }
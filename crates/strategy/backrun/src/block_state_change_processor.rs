@@ -1,4 +1,5 @@
 use super::affected_pools::get_affected_pools;
+use crate::backrun_config::BLOCK_ORIGIN;
 use eyre::eyre;
 use loom_core_actors::{run_async, subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
 use loom_core_actors_macros::{Accessor, Consumer, Producer};
@@ -7,6 +8,7 @@ use loom_types_blockchain::ChainParameters;
 use loom_types_entities::{BlockHistory, Market};
 use loom_types_events::{MarketEvents, StateUpdateEvent};
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::error;
 
 pub async fn block_state_change_worker(
@@ -15,11 +17,18 @@ pub async fn block_state_change_worker(
     block_history: SharedState<BlockHistory>,
     market_events_rx: Broadcaster<MarketEvents>,
     state_updates_broadcaster: Broadcaster<StateUpdateEvent>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(market_events_rx);
 
     loop {
-        let market_event = match market_events_rx.recv().await {
+        let msg = tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Block state change worker stopped".to_string())
+            }
+            msg = market_events_rx.recv() => msg,
+        };
+        let market_event = match msg {
             Ok(market_event) => market_event,
             Err(e) => match e {
                 RecvError::Closed => {
@@ -73,7 +82,7 @@ pub async fn block_state_change_worker(
             affected_pools,
             Vec::new(),
             Vec::new(),
-            "block_searcher".to_string(),
+            BLOCK_ORIGIN.to_string(),
             90_00,
         );
         run_async!(state_updates_broadcaster.send(request));
@@ -123,12 +132,17 @@ impl Default for BlockStateChangeProcessorActor {
 
 impl Actor for BlockStateChangeProcessorActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(block_state_change_worker(
             self.chain_parameters.clone(),
             self.market.clone().unwrap(),
             self.block_history.clone().unwrap(),
             self.market_events_rx.clone().unwrap(),
             self.state_updates_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
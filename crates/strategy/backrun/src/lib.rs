@@ -1,5 +1,5 @@
 pub use arb_actor::StateChangeArbActor;
-pub use backrun_config::{BackrunConfig, BackrunConfigSection};
+pub use backrun_config::{BackrunConfig, BackrunConfigSection, BackrunLimits};
 pub use block_state_change_processor::BlockStateChangeProcessorActor;
 pub use pending_tx_state_change_processor::PendingTxStateChangeProcessorActor;
 pub use state_change_arb_searcher::StateChangeArbSearcherActor;
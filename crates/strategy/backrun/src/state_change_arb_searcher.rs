@@ -8,6 +8,7 @@ use eyre::{eyre, Result};
 use rayon::prelude::*;
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 #[cfg(not(debug_assertions))]
 use tracing::warn;
 use tracing::{debug, error, info, trace};
@@ -21,7 +22,7 @@ use loom_types_blockchain::SwapError;
 use loom_types_entities::config::StrategyConfig;
 use loom_types_entities::{Market, PoolWrapper, Swap, SwapLine, SwapPath};
 use loom_types_events::{
-    BestTxCompose, HealthEvent, Message, MessageHealthEvent, MessageTxCompose, StateUpdateEvent, TxCompose, TxComposeData,
+    BestTxCompose, HealthEvent, MarketEvents, Message, MessageHealthEvent, MessageTxCompose, StateUpdateEvent, TxCompose, TxComposeData,
 };
 
 async fn state_change_arb_searcher_task(
@@ -29,11 +30,17 @@ async fn state_change_arb_searcher_task(
     backrun_config: BackrunConfig,
     state_update_event: StateUpdateEvent,
     market: SharedState<Market>,
+    disabled_pools: SharedState<HashSet<Address>>,
     swap_request_tx: Broadcaster<MessageTxCompose>,
     pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
 ) -> Result<()> {
     debug!("Message received {} stuffing : {:?}", state_update_event.origin, state_update_event.stuffing_tx_hash());
 
+    if !backrun_config.is_origin_enabled(&state_update_event.origin) {
+        trace!("Origin disabled : {}", state_update_event.origin);
+        return Ok(());
+    }
+
     let mut db = state_update_event.market_state().clone();
     db.apply_geth_update_vec(state_update_event.state_update().clone());
 
@@ -43,10 +50,8 @@ async fn state_change_arb_searcher_task(
     let market_guard_read = market.read().await;
     for (pool, v) in state_update_event.directions().iter() {
         let pool_paths: Vec<SwapPath> = match market_guard_read.get_pool_paths(&pool.get_address()) {
-            Some(paths) => paths
-                .into_iter()
-                .filter(|swap_path| !swap_path.pools.iter().any(|pool| !market_guard_read.is_pool_ok(&pool.get_address())))
-                .collect(),
+            // paths of disabled pools are removed from the market
+            Some(paths) => paths,
             None => {
                 let mut pool_direction: BTreeMap<PoolWrapper, Vec<(Address, Address)>> = BTreeMap::new();
                 pool_direction.insert(pool.clone(), v.clone());
@@ -54,7 +59,7 @@ async fn state_change_arb_searcher_task(
             }
         };
 
-        swap_path_vec.extend(pool_paths)
+        swap_path_vec.extend(pool_paths.into_iter().filter(|swap_path| backrun_config.is_swap_path_allowed(swap_path)))
     }
     drop(market_guard_read);
 
@@ -75,6 +80,9 @@ async fn state_change_arb_searcher_task(
 
     let market_state_clone = db.clone();
     let swap_path_vec_len = swap_path_vec.len();
+    let backrun_config_clone = backrun_config.clone();
+    // gas limits of the stuffing txs, the bundle gas of a swap line adds its own gas
+    let stuffing_gas: u64 = state_update_event.stuffing_txs().iter().map(|tx| tx.gas).sum();
 
     tokio::task::spawn(async move {
         thread_pool.install(|| {
@@ -97,7 +105,10 @@ async fn state_change_arb_searcher_task(
                         trace!("Calc result received: {}", mut_item);
 
                         if let Ok(profit) = mut_item.profit() {
-                            if profit.is_positive() && mut_item.abs_profit_eth() > U256::from(state_update_event.next_base_fee * 100_000) {
+                            if profit.is_positive()
+                                && mut_item.abs_profit_eth() > U256::from(state_update_event.next_base_fee * 100_000)
+                                && backrun_config_clone.is_swap_line_allowed(&mut_item, mut_item.gas_used.unwrap_or(300000), stuffing_gas)
+                            {
                                 if let Err(error) = swap_path_tx.try_send(Ok(mut_item)) {
                                     error!(%error, "swap_path_tx.try_send")
                                 }
@@ -133,6 +144,9 @@ async fn state_change_arb_searcher_task(
     let arc_db = Arc::new(db);
 
     let mut answers = 0;
+    let mut candidates = 0;
+    // candidates are ranked by profit when limited, otherwise sent as they arrive
+    let mut ranked_candidates: Vec<(U256, TxCompose)> = Vec::new();
 
     let mut best_answers = BestTxCompose::new_with_pct(U256::from(9000));
 
@@ -141,6 +155,17 @@ async fn state_change_arb_searcher_task(
     while let Some(swap_line_result) = swap_line_rx.recv().await {
         match swap_line_result {
             Ok(swap_line) => {
+                let disabled_pools_guard = disabled_pools.read().await;
+                let has_disabled_pool = swap_line.path.pools.iter().any(|pool| disabled_pools_guard.contains(&pool.get_address()));
+                drop(disabled_pools_guard);
+
+                // the pool was disabled while calculating
+                if has_disabled_pool {
+                    trace!("Swap line has disabled pool: {}", swap_line);
+                    answers += 1;
+                    continue;
+                }
+
                 let encode_request = TxCompose::Route(TxComposeData {
                     eoa: backrun_config.eoa(),
                     next_block_number: state_update_event.next_block_number,
@@ -158,8 +183,13 @@ async fn state_change_arb_searcher_task(
                 });
 
                 if !backrun_config.smart() || best_answers.check(&encode_request) {
-                    if let Err(e) = swap_request_tx_clone.send(Message::new(encode_request)).await {
-                        error!("swap_request_tx_clone.send {}", e)
+                    if backrun_config.max_candidates().is_some() {
+                        ranked_candidates.push((encode_request.swap.abs_profit_eth(), encode_request));
+                    } else {
+                        candidates += 1;
+                        if let Err(e) = swap_request_tx_clone.send(Message::new(encode_request)).await {
+                            error!("swap_request_tx_clone.send {}", e)
+                        }
                     }
                 }
             }
@@ -174,10 +204,19 @@ async fn state_change_arb_searcher_task(
 
         answers += 1;
     }
+
+    for encode_request in backrun_config.select_candidates(ranked_candidates) {
+        candidates += 1;
+        if let Err(e) = swap_request_tx_clone.send(Message::new(encode_request)).await {
+            error!("swap_request_tx_clone.send {}", e)
+        }
+    }
+
     info!(
         origin = %state_update_event.origin,
         swap_path_vec_len,
         answers,
+        candidates,
         elapsed = %(chrono::Local::now() - start_time),
         stuffing_hash = %state_update_event.stuffing_tx_hash(),
         "Calculation finished"
@@ -190,10 +229,19 @@ pub async fn state_change_arb_searcher_worker(
     backrun_config: BackrunConfig,
     market: SharedState<Market>,
     search_request_rx: Broadcaster<StateUpdateEvent>,
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
     swap_request_tx: Broadcaster<MessageTxCompose>,
     pool_health_monitor_tx: Broadcaster<MessageHealthEvent>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(search_request_rx);
+    let mut market_events_rx = match market_events_rx {
+        Some(market_events_rx) => Some(market_events_rx.subscribe().await),
+        None => None,
+    };
+
+    // pools disabled in the market, results of running calculations passing them are dropped
+    let disabled_pools: SharedState<HashSet<Address>> = SharedState::new(HashSet::new());
 
     let cpus = num_cpus::get();
     info!("Starting state arb searcher cpus={cpus}, tasks={}", cpus / 2);
@@ -201,6 +249,9 @@ pub async fn state_change_arb_searcher_worker(
 
     loop {
         tokio::select! {
+                _ = shutdown_token.cancelled() => {
+                    break Ok("State change arb searcher worker stopped".to_string())
+                }
                 msg = search_request_rx.recv() => {
                 let pool_update_msg : Result<StateUpdateEvent, RecvError> = msg;
                 if let Ok(msg) = pool_update_msg {
@@ -210,16 +261,45 @@ pub async fn state_change_arb_searcher_worker(
                             backrun_config.clone(),
                             msg,
                             market.clone(),
+                            disabled_pools.clone(),
                             swap_request_tx.clone(),
                             pool_health_monitor_tx.clone()
                         )
                     );
                 }
             }
+            msg = async { market_events_rx.as_mut().unwrap().recv().await }, if market_events_rx.is_some() => {
+                let market_event_msg : Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(market_event) => {
+                        update_disabled_pools(&disabled_pools, &market_event).await;
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        market_events_rx = None;
+                    }
+                    _ => {}
+                }
+            }
         }
     }
 }
 
+// Results of running calculations passing disabled pools are dropped until the pools are enabled again
+async fn update_disabled_pools(disabled_pools: &SharedState<HashSet<Address>>, market_event: &MarketEvents) {
+    match market_event {
+        MarketEvents::PoolDisabled { pool_address } => {
+            debug!("Dropping calculations for disabled pool {:?}", pool_address);
+            disabled_pools.write().await.insert(*pool_address);
+        }
+        MarketEvents::PoolEnabled { pool_address } => {
+            debug!("Keeping calculations for enabled pool {:?}", pool_address);
+            disabled_pools.write().await.remove(pool_address);
+        }
+        _ => {}
+    }
+}
+
 #[derive(Accessor, Consumer, Producer)]
 pub struct StateChangeArbSearcherActor {
     backrun_config: BackrunConfig,
@@ -227,6 +307,8 @@ pub struct StateChangeArbSearcherActor {
     market: Option<SharedState<Market>>,
     #[consumer]
     state_update_rx: Option<Broadcaster<StateUpdateEvent>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
     #[producer]
     compose_tx: Option<Broadcaster<MessageTxCompose>>,
     #[producer]
@@ -235,7 +317,14 @@ pub struct StateChangeArbSearcherActor {
 
 impl StateChangeArbSearcherActor {
     pub fn new(backrun_config: BackrunConfig) -> StateChangeArbSearcherActor {
-        StateChangeArbSearcherActor { backrun_config, market: None, state_update_rx: None, compose_tx: None, pool_health_monitor_tx: None }
+        StateChangeArbSearcherActor {
+            backrun_config,
+            market: None,
+            state_update_rx: None,
+            market_events_rx: None,
+            compose_tx: None,
+            pool_health_monitor_tx: None,
+        }
     }
 
     pub fn on_bc(self, bc: &Blockchain) -> Self {
@@ -244,6 +333,7 @@ impl StateChangeArbSearcherActor {
             compose_tx: Some(bc.compose_channel()),
             pool_health_monitor_tx: Some(bc.pool_health_monitor_channel()),
             state_update_rx: Some(bc.state_update_channel()),
+            market_events_rx: Some(bc.market_events_channel()),
             ..self
         }
     }
@@ -251,12 +341,18 @@ impl StateChangeArbSearcherActor {
 
 impl Actor for StateChangeArbSearcherActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(state_change_arb_searcher_worker(
             self.backrun_config.clone(),
             self.market.clone().unwrap(),
             self.state_update_rx.clone().unwrap(),
+            self.market_events_rx.clone(),
             self.compose_tx.clone().unwrap(),
             self.pool_health_monitor_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -265,3 +361,25 @@ impl Actor for StateChangeArbSearcherActor {
         "StateChangeArbSearcherActor"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_disable_enable_pool_round_trip() {
+        let disabled_pools: SharedState<HashSet<Address>> = SharedState::new(HashSet::new());
+        let (pool_address, other_pool_address) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        update_disabled_pools(&disabled_pools, &MarketEvents::PoolDisabled { pool_address }).await;
+        update_disabled_pools(&disabled_pools, &MarketEvents::PoolDisabled { pool_address: other_pool_address }).await;
+        assert!(disabled_pools.read().await.contains(&pool_address));
+
+        update_disabled_pools(&disabled_pools, &MarketEvents::PoolEnabled { pool_address }).await;
+        assert!(!disabled_pools.read().await.contains(&pool_address));
+        assert!(disabled_pools.read().await.contains(&other_pool_address));
+
+        update_disabled_pools(&disabled_pools, &MarketEvents::BlockStateUpdate { block_hash: Default::default() }).await;
+        assert_eq!(disabled_pools.read().await.len(), 1);
+    }
+}
@@ -3,7 +3,7 @@ use alloy_primitives::U256;
 use lazy_static::lazy_static;
 use loom_evm_db::LoomDBType;
 use loom_types_blockchain::SwapError;
-use loom_types_entities::SwapLine;
+use loom_types_entities::{ConstantProductOptimizer, SwapLine, SwapLineOptimizer};
 use revm::primitives::Env;
 
 lazy_static! {
@@ -13,12 +13,24 @@ lazy_static! {
 pub struct SwapCalculator {}
 
 impl SwapCalculator {
+    /// Optimize the in amount with the closed form for constant product paths and a golden-section search for other paths
     #[inline]
     pub fn calculate<'a>(path: &'a mut SwapLine, state: &LoomDBType, env: Env) -> eyre::Result<&'a mut SwapLine, SwapError> {
+        Self::calculate_with_optimizer(path, state, env, &ConstantProductOptimizer::default())
+    }
+
+    /// Optimize the in amount with the given optimizer, the search starts at an amount worth 0.01 ETH
+    #[inline]
+    pub fn calculate_with_optimizer<'a>(
+        path: &'a mut SwapLine,
+        state: &LoomDBType,
+        env: Env,
+        optimizer: &dyn SwapLineOptimizer,
+    ) -> eyre::Result<&'a mut SwapLine, SwapError> {
         let first_token = path.get_first_token().unwrap();
         if let Some(amount_in) = first_token.calc_token_value_from_eth(*START_OPTIMIZE_INPUT) {
             //trace!("calculate : {} amount in : {}",first_token.get_symbol(), first_token.to_float(amount_in) );
-            path.optimize_with_in_amount(state, env, amount_in)
+            path.optimize_with(optimizer, state, env, amount_in)
         } else {
             Err(path.to_error("PRICE_NOT_SET".to_string()))
         }
@@ -0,0 +1,5 @@
+pub use strategy::{Strategy, StrategyCandidate, StrategyContext};
+pub use strategy_actor::StrategyActor;
+
+mod strategy;
+mod strategy_actor;
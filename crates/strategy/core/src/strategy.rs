@@ -0,0 +1,70 @@
+use alloy_primitives::Address;
+use async_trait::async_trait;
+use eyre::Result;
+use loom_core_actors::SharedState;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::{LatestBlock, Market, Swap};
+use loom_types_events::{MarketEvents, StateUpdateEvent, TxComposeData};
+use std::sync::Arc;
+
+/// Shared state available to strategies
+#[derive(Clone)]
+pub struct StrategyContext {
+    pub market: SharedState<Market>,
+    pub latest_block: SharedState<LatestBlock>,
+    /// EOA set to the compose requests built from swaps
+    pub eoa: Option<Address>,
+}
+
+/// Candidate returned by a strategy
+#[derive(Clone, Debug)]
+pub enum StrategyCandidate {
+    /// Swap backrunning the state update, the compose request is built from the state update
+    Swap(Swap),
+    /// Compose request built by the strategy
+    Compose(TxComposeData),
+}
+
+impl StrategyCandidate {
+    /// Compose request of the candidate, `poststate` is the market state with the state update applied
+    pub fn into_compose_data(
+        self,
+        context: &StrategyContext,
+        state_update: &StateUpdateEvent,
+        poststate: Option<Arc<LoomDBType>>,
+    ) -> TxComposeData {
+        match self {
+            StrategyCandidate::Swap(swap) => TxComposeData {
+                eoa: context.eoa,
+                next_block_number: state_update.next_block_number,
+                next_block_timestamp: state_update.next_block_timestamp,
+                next_block_base_fee: state_update.next_base_fee,
+                gas: swap.pre_estimate_gas(),
+                stuffing_txs: state_update.stuffing_txs.clone(),
+                stuffing_txs_hashes: state_update.stuffing_txs_hashes.clone(),
+                swap,
+                origin: Some(state_update.origin.clone()),
+                tips_pct: Some(state_update.tips_pct),
+                poststate,
+                poststate_update: Some(state_update.state_update().clone()),
+                ..TxComposeData::default()
+            },
+            StrategyCandidate::Compose(compose_data) => compose_data,
+        }
+    }
+}
+
+/// Searcher hosted by the [`StrategyActor`](crate::StrategyActor).
+/// Candidates are routed to the compose channel, so they are estimated, signed and broadcasted like backrun swaps.
+#[async_trait]
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Called for every state update of blocks and pending txs
+    async fn on_state_update(&self, context: &StrategyContext, state_update: &StateUpdateEvent) -> Result<Vec<StrategyCandidate>>;
+
+    /// Called for every market event
+    async fn on_market_event(&self, _context: &StrategyContext, _market_event: &MarketEvents) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use std::sync::{Arc, OnceLock};
+
+use alloy_primitives::Address;
+use eyre::eyre;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+use crate::{Strategy, StrategyContext};
+use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_evm_db::LoomDBType;
+use loom_types_entities::{LatestBlock, Market};
+use loom_types_events::{MarketEvents, Message, MessageTxCompose, StateUpdateEvent, TxCompose};
+
+// Running state update tasks of a strategy, events arriving while all are running are skipped for the strategy
+const DEFAULT_MAX_TASKS_PER_STRATEGY: usize = 4;
+
+// Market state with the state update applied, computed once for all strategies of the event
+type SharedPoststate = Arc<OnceLock<Arc<LoomDBType>>>;
+
+async fn strategy_state_update_task(
+    strategy: Arc<dyn Strategy>,
+    context: StrategyContext,
+    state_update_event: Arc<StateUpdateEvent>,
+    poststate: SharedPoststate,
+    compose_tx: Broadcaster<MessageTxCompose>,
+    _permit: OwnedSemaphorePermit,
+) {
+    let start_time = chrono::Local::now();
+    let candidates = match strategy.on_state_update(&context, &state_update_event).await {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            debug!(strategy = strategy.name(), "Strategy state update error : {}", e);
+            return;
+        }
+    };
+
+    if candidates.is_empty() {
+        return;
+    }
+
+    let poststate = poststate
+        .get_or_init(|| {
+            let mut poststate = state_update_event.market_state().clone();
+            poststate.apply_geth_update_vec(state_update_event.state_update().clone());
+            Arc::new(poststate)
+        })
+        .clone();
+
+    let candidates_len = candidates.len();
+    for candidate in candidates {
+        let compose_data = candidate.into_compose_data(&context, &state_update_event, Some(poststate.clone()));
+        if let Err(e) = compose_tx.send(Message::new(TxCompose::Route(compose_data))).await {
+            error!("compose_tx.send {}", e)
+        }
+    }
+
+    info!(
+        strategy = strategy.name(),
+        origin = %state_update_event.origin,
+        candidates_len,
+        elapsed = %(chrono::Local::now() - start_time),
+        "Strategy candidates routed"
+    );
+}
+
+async fn strategy_market_event_task(strategy: Arc<dyn Strategy>, context: StrategyContext, market_event: MarketEvents) {
+    if let Err(e) = strategy.on_market_event(&context, &market_event).await {
+        debug!(strategy = strategy.name(), "Strategy market event error : {}", e);
+    }
+}
+
+pub async fn strategy_worker(
+    strategies: Vec<Arc<dyn Strategy>>,
+    max_tasks_per_strategy: usize,
+    context: StrategyContext,
+    state_update_rx: Broadcaster<StateUpdateEvent>,
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    compose_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(state_update_rx);
+    let mut market_events_rx = match market_events_rx {
+        Some(market_events_rx) => Some(market_events_rx.subscribe().await),
+        None => None,
+    };
+
+    info!("Starting strategies : {:?}", strategies.iter().map(|strategy| strategy.name()).collect::<Vec<_>>());
+
+    let strategies_tasks: Vec<Arc<Semaphore>> =
+        strategies.iter().map(|_| Arc::new(Semaphore::new(max_tasks_per_strategy.max(1)))).collect();
+
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Strategy worker stopped".to_string())
+            }
+            msg = state_update_rx.recv() => {
+                let state_update_msg : Result<StateUpdateEvent, RecvError> = msg;
+                match state_update_msg {
+                    Ok(state_update_event) => {
+                        let state_update_event = Arc::new(state_update_event);
+                        let poststate = SharedPoststate::default();
+                        // strategies run concurrently, a slow strategy does not delay the others
+                        for (strategy, strategy_tasks) in strategies.iter().zip(strategies_tasks.iter()) {
+                            let Ok(permit) = strategy_tasks.clone().try_acquire_owned() else {
+                                warn!(strategy = strategy.name(), origin = %state_update_event.origin, "Strategy busy, state update skipped");
+                                continue;
+                            };
+                            tokio::task::spawn(strategy_state_update_task(
+                                strategy.clone(),
+                                context.clone(),
+                                state_update_event.clone(),
+                                poststate.clone(),
+                                compose_tx.clone(),
+                                permit,
+                            ));
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("State update channel closed");
+                        break Err(eyre!("STATE_UPDATE_RX_CLOSED"))
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("State update channel lagged by {} messages", lag);
+                    }
+                }
+            }
+            msg = async { market_events_rx.as_mut().unwrap().recv().await }, if market_events_rx.is_some() => {
+                let market_event_msg : Result<MarketEvents, RecvError> = msg;
+                match market_event_msg {
+                    Ok(market_event) => {
+                        for strategy in strategies.iter() {
+                            tokio::task::spawn(strategy_market_event_task(strategy.clone(), context.clone(), market_event.clone()));
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        error!("Market events channel closed");
+                        market_events_rx = None;
+                    }
+                    Err(RecvError::Lagged(lag)) => {
+                        error!("Market events channel lagged by {} messages", lag);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Hosts [`Strategy`] implementations. State updates and market events are passed to every strategy and
+/// the candidates are sent to the compose channel as route requests. State updates arriving while a strategy
+/// handles the maximum number of updates are skipped for it.
+#[derive(Accessor, Consumer, Producer)]
+pub struct StrategyActor {
+    strategies: Vec<Arc<dyn Strategy>>,
+    max_tasks_per_strategy: usize,
+    eoa: Option<Address>,
+    #[accessor]
+    market: Option<SharedState<Market>>,
+    #[accessor]
+    latest_block: Option<SharedState<LatestBlock>>,
+    #[consumer]
+    state_update_rx: Option<Broadcaster<StateUpdateEvent>>,
+    #[consumer]
+    market_events_rx: Option<Broadcaster<MarketEvents>>,
+    #[producer]
+    compose_tx: Option<Broadcaster<MessageTxCompose>>,
+}
+
+impl StrategyActor {
+    pub fn new() -> StrategyActor {
+        StrategyActor {
+            strategies: Vec::new(),
+            max_tasks_per_strategy: DEFAULT_MAX_TASKS_PER_STRATEGY,
+            eoa: None,
+            market: None,
+            latest_block: None,
+            state_update_rx: None,
+            market_events_rx: None,
+            compose_tx: None,
+        }
+    }
+
+    pub fn with_strategy(self, strategy: Arc<dyn Strategy>) -> Self {
+        let mut strategies = self.strategies;
+        strategies.push(strategy);
+        Self { strategies, ..self }
+    }
+
+    pub fn with_strategies(self, strategies: Vec<Arc<dyn Strategy>>) -> Self {
+        strategies.into_iter().fold(self, |actor, strategy| actor.with_strategy(strategy))
+    }
+
+    /// Maximum number of state updates a strategy handles concurrently
+    pub fn with_max_tasks_per_strategy(self, max_tasks_per_strategy: usize) -> Self {
+        Self { max_tasks_per_strategy, ..self }
+    }
+
+    pub fn with_eoa(self, eoa: Address) -> Self {
+        Self { eoa: Some(eoa), ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            market: Some(bc.market()),
+            latest_block: Some(bc.latest_block()),
+            state_update_rx: Some(bc.state_update_channel()),
+            market_events_rx: Some(bc.market_events_channel()),
+            compose_tx: Some(bc.compose_channel()),
+            ..self
+        }
+    }
+}
+
+impl Default for StrategyActor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Actor for StrategyActor {
+    fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let context =
+            StrategyContext { market: self.market.clone().unwrap(), latest_block: self.latest_block.clone().unwrap(), eoa: self.eoa };
+        let task = tokio::task::spawn(strategy_worker(
+            self.strategies.clone(),
+            self.max_tasks_per_strategy,
+            context,
+            self.state_update_rx.clone().unwrap(),
+            self.market_events_rx.clone(),
+            self.compose_tx.clone().unwrap(),
+            token,
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "StrategyActor"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use eyre::Result;
+    use tokio::sync::Notify;
+
+    use super::*;
+    use crate::StrategyCandidate;
+    use loom_types_events::TxComposeData;
+
+    // Routes one compose request per state update, blocks until released if `blocking`
+    #[derive(Default)]
+    struct MockStrategy {
+        blocking: bool,
+        calls: AtomicUsize,
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl Strategy for MockStrategy {
+        fn name(&self) -> &'static str {
+            "MockStrategy"
+        }
+
+        async fn on_state_update(&self, _context: &StrategyContext, state_update: &StateUpdateEvent) -> Result<Vec<StrategyCandidate>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.blocking {
+                self.release.notified().await;
+            }
+            Ok(vec![StrategyCandidate::Compose(TxComposeData { origin: Some(state_update.origin.clone()), ..TxComposeData::default() })])
+        }
+    }
+
+    fn state_update_event(origin: &str) -> StateUpdateEvent {
+        StateUpdateEvent::new(1, 0, 0, LoomDBType::default(), vec![], None, BTreeMap::new(), vec![], vec![], origin.to_string(), 0)
+    }
+
+    // Starts the worker and waits until it is subscribed to the state updates
+    async fn start_worker(
+        strategy: Arc<MockStrategy>,
+        max_tasks: usize,
+    ) -> (Broadcaster<StateUpdateEvent>, Broadcaster<MessageTxCompose>, CancellationToken) {
+        let state_update_tx = Broadcaster::new(10);
+        let compose_tx = Broadcaster::new(10);
+        let token = CancellationToken::new();
+        let context = StrategyContext {
+            market: SharedState::new(Market::default()),
+            latest_block: SharedState::new(LatestBlock::new(0, Default::default())),
+            eoa: None,
+        };
+
+        tokio::task::spawn(strategy_worker(
+            vec![strategy],
+            max_tasks,
+            context,
+            state_update_tx.clone(),
+            None,
+            compose_tx.clone(),
+            token.clone(),
+        ));
+        while state_update_tx.stats().await.subscribers == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        (state_update_tx, compose_tx, token)
+    }
+
+    #[tokio::test]
+    async fn test_state_update_candidate_routed_to_compose() -> Result<()> {
+        let strategy = Arc::new(MockStrategy::default());
+        let (state_update_tx, compose_tx, token) = start_worker(strategy.clone(), 1).await;
+        let mut compose_rx = compose_tx.subscribe().await;
+
+        state_update_tx.send(state_update_event("test_origin")).await.unwrap();
+
+        let compose = tokio::time::timeout(Duration::from_secs(1), compose_rx.recv()).await??;
+        let TxCompose::Route(compose_data) = compose.inner else { panic!("Route compose request expected") };
+        assert_eq!(compose_data.origin, Some("test_origin".to_string()));
+        assert_eq!(strategy.calls.load(Ordering::SeqCst), 1);
+
+        token.cancel();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_busy_strategy_skips_state_updates() -> Result<()> {
+        let strategy = Arc::new(MockStrategy { blocking: true, ..MockStrategy::default() });
+        let (state_update_tx, compose_tx, token) = start_worker(strategy.clone(), 2).await;
+        let mut compose_rx = compose_tx.subscribe().await;
+
+        for _ in 0..5 {
+            state_update_tx.send(state_update_event("test_origin")).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(strategy.calls.load(Ordering::SeqCst), 2);
+
+        strategy.release.notify_waiters();
+        for _ in 0..2 {
+            tokio::time::timeout(Duration::from_secs(1), compose_rx.recv()).await??;
+        }
+
+        // finished tasks release their slots
+        state_update_tx.send(state_update_event("test_origin")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(strategy.calls.load(Ordering::SeqCst), 3);
+
+        token.cancel();
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 mod diffpath_merger_actor;
+mod packing_merger_actor;
 mod samepath_merger_actor;
 mod swappath_merger_actor;
 
 pub use diffpath_merger_actor::DiffPathMergerActor;
+pub use packing_merger_actor::PackingMergerActor;
 pub use samepath_merger_actor::SamePathMergerActor;
 pub use swappath_merger_actor::ArbSwapPathMergerActor;
@@ -0,0 +1,594 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alloy_primitives::{BlockNumber, TxHash, U256};
+use alloy_rpc_types::{Transaction, TransactionRequest};
+use eyre::{eyre, OptionExt, Result};
+use revm::primitives::{TxEnv, CANCUN};
+use revm::Evm;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, trace};
+
+use loom_core_actors::{subscribe, Actor, ActorResult, Broadcaster, Consumer, Producer, WorkerResult};
+use loom_core_actors_macros::{Accessor, Consumer, Producer};
+use loom_core_blockchain::Blockchain;
+use loom_evm_db::LoomDBType;
+use loom_evm_utils::evm::evm_transact;
+use loom_evm_utils::evm_env::env_for_block;
+use loom_evm_utils::NWETH;
+use loom_types_entities::{CurveTipsPolicy, Swap, SwapAmountType, SwapLine, TipsPolicy};
+use loom_types_events::{MarketEvents, MessageTxCompose, TxCompose, TxComposeData, TxState};
+
+const DEFAULT_TIME_BUDGET: Duration = Duration::from_millis(5);
+const DEFAULT_MAX_CANDIDATES: usize = 64;
+
+/// Two backruns conflict if they touch the same pool or if they backrun partially overlapping stuffing txs
+fn is_conflict(a: &TxComposeData, b: &TxComposeData) -> bool {
+    if a.cross_pools(&b.swap.get_pool_address_vec()) {
+        return true;
+    }
+    let shared_stuffing = a.stuffing_txs_hashes.iter().any(|tx_hash| b.stuffing_txs_hashes.contains(tx_hash));
+    shared_stuffing && !a.same_stuffing(&b.stuffing_txs_hashes)
+}
+
+struct IndependentSetSearch<'a> {
+    weights: &'a [U256],
+    conflicts: &'a [Vec<bool>],
+    remaining_weights: Vec<U256>,
+    deadline: Instant,
+    best: Vec<usize>,
+    best_weight: U256,
+}
+
+impl IndependentSetSearch<'_> {
+    fn search(&mut self, idx: usize, selected: &mut Vec<usize>, weight: U256) {
+        if weight > self.best_weight {
+            self.best.clone_from(selected);
+            self.best_weight = weight;
+        }
+        if idx >= self.weights.len() || weight + self.remaining_weights[idx] <= self.best_weight || Instant::now() >= self.deadline {
+            return;
+        }
+        if selected.iter().all(|&s| !self.conflicts[s][idx]) {
+            selected.push(idx);
+            self.search(idx + 1, selected, weight + self.weights[idx]);
+            selected.pop();
+        }
+        self.search(idx + 1, selected, weight);
+    }
+}
+
+/// Find the max weight set of vertices without conflicts with branch and bound. Vertices are expected to be sorted by
+/// weight in descending order. The search starts from the greedy solution, so at least the greedy set is returned
+/// if the deadline passes before the search completes.
+pub(crate) fn max_weight_independent_set(weights: &[U256], conflicts: &[Vec<bool>], deadline: Instant) -> Vec<usize> {
+    let mut remaining_weights = vec![U256::ZERO; weights.len() + 1];
+    for idx in (0..weights.len()).rev() {
+        remaining_weights[idx] = remaining_weights[idx + 1] + weights[idx];
+    }
+
+    let mut greedy: Vec<usize> = Vec::new();
+    for idx in 0..weights.len() {
+        if greedy.iter().all(|&s| !conflicts[s][idx]) {
+            greedy.push(idx);
+        }
+    }
+    let greedy_weight: U256 = greedy.iter().map(|&idx| weights[idx]).sum();
+
+    let mut search = IndependentSetSearch { weights, conflicts, remaining_weights, deadline, best: greedy, best_weight: greedy_weight };
+    search.search(0, &mut Vec::new(), U256::ZERO);
+    search.best
+}
+
+/// Backrun tx of an estimated candidate
+fn backrun_tx(candidate: &TxComposeData) -> Option<&TransactionRequest> {
+    candidate.tx_bundle.as_ref()?.iter().rev().find_map(|tx_state| match tx_state {
+        TxState::SignatureRequired(tx_request) => Some(tx_request),
+        _ => None,
+    })
+}
+
+// nonce and chain id are not checked, the backruns of a signer are estimated with the same nonce
+fn backrun_tx_env(tx_request: &TransactionRequest) -> TxEnv {
+    TxEnv {
+        caller: tx_request.from.unwrap_or_default(),
+        transact_to: tx_request.to.unwrap_or_default(),
+        data: tx_request.input.input().cloned().unwrap_or_default(),
+        value: tx_request.value.unwrap_or_default(),
+        gas_limit: tx_request.gas.unwrap_or_default(),
+        gas_price: U256::from(tx_request.max_fee_per_gas.unwrap_or(tx_request.gas_price.unwrap_or_default())),
+        gas_priority_fee: tx_request.max_priority_fee_per_gas.map(U256::from),
+        ..TxEnv::default()
+    }
+}
+
+/// Execute the backruns of the selected candidates one after another on the combined poststate of their stuffing txs.
+/// Each swap line is recalculated on the state left by the previous backruns, candidates with less profit than
+/// estimated are not executed. Returns the combined poststate, the recalculated swap lines and whether each
+/// candidate kept its profit.
+fn simulate_sequential(candidates: &[&TxComposeData]) -> Result<(LoomDBType, Vec<SwapLine>, Vec<bool>)> {
+    let first = candidates.first().ok_or_eyre("NO_CANDIDATES")?;
+    let mut db = first.poststate.as_ref().ok_or_eyre("NO_POSTSTATE")?.as_ref().clone();
+    for candidate in candidates.iter().skip(1) {
+        db.apply_geth_update_vec(candidate.poststate_update.clone().ok_or_eyre("NO_STATE_UPDATE")?);
+    }
+
+    let env = env_for_block(first.next_block_number, first.next_block_timestamp);
+    let mut evm = Evm::builder().with_spec_id(CANCUN).with_db(db.clone()).with_env(Box::new(env.clone())).build();
+
+    let mut swap_lines = Vec::new();
+    let mut kept_profit = Vec::new();
+    for candidate in candidates.iter() {
+        let Swap::BackrunSwapLine(estimated_swap_line) = &candidate.swap else {
+            return Err(eyre!("NOT_SWAP_LINE"));
+        };
+        let SwapAmountType::Set(amount_in) = estimated_swap_line.amount_in else {
+            return Err(eyre!("AMOUNT_IN_NOT_SET"));
+        };
+        let backrun_tx = backrun_tx(candidate).ok_or_eyre("NO_BACKRUN_TX")?;
+
+        let mut swap_line = estimated_swap_line.clone();
+        let is_profitable = match swap_line.calculate_with_in_amount(&evm.context.evm.db, env.clone(), amount_in) {
+            Ok((amount_out, gas_used, calculation_results)) => {
+                swap_line.amount_out = SwapAmountType::Set(amount_out);
+                swap_line.gas_used = Some(gas_used);
+                swap_line.calculation_results = calculation_results;
+                swap_line.abs_profit() >= estimated_swap_line.abs_profit()
+            }
+            Err(e) => {
+                trace!("Swap line recalculation failed {}", e);
+                swap_line.amount_out = SwapAmountType::Set(U256::ZERO);
+                false
+            }
+        };
+
+        // the state of the next candidates includes the backruns kept before them
+        let is_executed = is_profitable && {
+            evm.context.evm.env.tx = backrun_tx_env(backrun_tx);
+            match evm_transact(&mut evm) {
+                Ok(_) => true,
+                Err(e) => {
+                    trace!("Backrun execution failed {}", e);
+                    false
+                }
+            }
+        };
+
+        swap_lines.push(swap_line);
+        kept_profit.push(is_executed);
+    }
+    Ok((db, swap_lines, kept_profit))
+}
+
+/// Select the max profit set of non-conflicting candidates and combine them into one bundle. Candidates that lose
+/// their profit when executed after the more profitable ones are removed and the remaining set is simulated again.
+/// When the time budget runs out, the backruns kept by the last simulation are packed with all the stuffing txs it executed.
+fn pack_candidates(candidates: &[TxComposeData], time_budget: Duration, tips_policy: &dyn TipsPolicy) -> Result<Option<TxComposeData>> {
+    let deadline = Instant::now() + time_budget;
+
+    let weights: Vec<U256> = candidates.iter().map(|c| c.swap.abs_profit_eth()).collect();
+    let conflicts: Vec<Vec<bool>> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, a)| candidates.iter().enumerate().map(|(j, b)| i != j && is_conflict(a, b)).collect())
+        .collect();
+
+    let mut selected: Vec<&TxComposeData> =
+        max_weight_independent_set(&weights, &conflicts, deadline).into_iter().map(|idx| &candidates[idx]).collect();
+
+    loop {
+        if selected.len() < 2 {
+            return Ok(None);
+        }
+
+        let (db, swap_lines, kept_profit) = simulate_sequential(&selected)?;
+
+        if kept_profit.iter().all(|x| *x) || Instant::now() >= deadline {
+            let swap_lines: Vec<SwapLine> =
+                swap_lines.into_iter().zip(kept_profit.iter()).filter_map(|(swap_line, is_kept)| is_kept.then_some(swap_line)).collect();
+            if swap_lines.len() < 2 {
+                return Ok(None);
+            }
+
+            let mut stuffing_txs_hashes: Vec<TxHash> = Vec::new();
+            let mut stuffing_txs: Vec<Transaction> = Vec::new();
+            for candidate in selected.iter() {
+                for tx in candidate.stuffing_txs.iter() {
+                    if !stuffing_txs_hashes.contains(&tx.hash) {
+                        stuffing_txs_hashes.push(tx.hash);
+                        stuffing_txs.push(tx.clone());
+                    }
+                }
+            }
+
+            let swap = Swap::Multiple(swap_lines.into_iter().map(Swap::BackrunSwapLine).collect());
+            let tips_pct = tips_policy.tips_pct(&swap, swap.abs_profit_eth(), None);
+
+            return Ok(Some(TxComposeData {
+                stuffing_txs_hashes,
+                stuffing_txs,
+                gas: swap.pre_estimate_gas(),
+                swap,
+                origin: Some("packing_merger".to_string()),
+                tips_pct: Some(tips_pct),
+                tx_bundle: None,
+                poststate: Some(Arc::new(db)),
+                poststate_update: None,
+                ..selected[0].clone()
+            }));
+        }
+
+        selected =
+            selected.into_iter().zip(kept_profit).filter_map(|(candidate, is_profitable)| is_profitable.then_some(candidate)).collect();
+    }
+}
+
+async fn packing_merger_worker(
+    time_budget: Duration,
+    max_candidates: usize,
+    tips_policy: Arc<dyn TipsPolicy>,
+    market_events_rx: Broadcaster<MarketEvents>,
+    compose_channel_rx: Broadcaster<MessageTxCompose>,
+    compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
+) -> WorkerResult {
+    subscribe!(market_events_rx);
+    subscribe!(compose_channel_rx);
+
+    let mut candidates: BTreeMap<BlockNumber, Vec<TxComposeData>> = BTreeMap::new();
+    let mut best_packed: HashMap<BlockNumber, U256> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Packing merger worker stopped".to_string())
+            }
+            msg = market_events_rx.recv() => {
+                if let Ok(MarketEvents::BlockHeaderUpdate{block_number, ..}) = msg {
+                    debug!("Block header update {}, dropping packing candidates", block_number);
+                    candidates.retain(|next_block_number, _| *next_block_number > block_number);
+                    best_packed.retain(|next_block_number, _| *next_block_number > block_number);
+                }
+            }
+
+            msg = compose_channel_rx.recv() => {
+                let msg : Result<MessageTxCompose, RecvError> = msg;
+                match msg {
+                    Ok(compose_request) => {
+                        let TxCompose::Sign(sign_request) = compose_request.inner() else {
+                            continue
+                        };
+                        if !matches!(sign_request.swap, Swap::BackrunSwapLine(_)) || sign_request.poststate.is_none() || sign_request.poststate_update.is_none() || backrun_tx(sign_request).is_none() {
+                            continue
+                        }
+
+                        let block_candidates = candidates.entry(sign_request.next_block_number).or_default();
+                        block_candidates.push(sign_request.clone());
+                        block_candidates.sort_by(|a, b| b.swap.abs_profit_eth().cmp(&a.swap.abs_profit_eth()));
+                        block_candidates.truncate(max_candidates);
+
+                        if block_candidates.len() < 2 {
+                            continue
+                        }
+
+                        let start_time = Instant::now();
+                        // simulations block, so packing runs off the runtime threads
+                        let packing_candidates = block_candidates.clone();
+                        let packing_tips_policy = tips_policy.clone();
+                        let packing_result = tokio::task::spawn_blocking(move || {
+                            pack_candidates(&packing_candidates, time_budget, packing_tips_policy.as_ref())
+                        })
+                        .await
+                        .unwrap_or_else(|e| Err(eyre!("PACKING_TASK_FAILED : {}", e)));
+                        match packing_result {
+                            Ok(Some(packed_request)) => {
+                                let profit = packed_request.swap.abs_profit_eth();
+                                let best_profit = best_packed.entry(sign_request.next_block_number).or_default();
+                                if profit > *best_profit {
+                                    *best_profit = profit;
+                                    info!("+++ Packing finished. Candidates : {} packed : {} profit : {} elapsed : {:?}",
+                                        block_candidates.len(),
+                                        if let Swap::Multiple(swap_vec) = &packed_request.swap { swap_vec.len() } else { 0 },
+                                        NWETH::to_float(profit),
+                                        start_time.elapsed()
+                                    );
+                                    if let Err(e) = compose_channel_tx.send(MessageTxCompose::route(packed_request)).await {
+                                        error!("{}", e)
+                                    }
+                                }
+                            }
+                            Ok(None) => {
+                                trace!("No packing for {} candidates", block_candidates.len())
+                            }
+                            Err(e) => {
+                                error!("Packing error : {}", e)
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("{e}")
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Packs the non-conflicting backruns of a block with max total profit into one bundle
+#[derive(Consumer, Producer, Accessor)]
+pub struct PackingMergerActor {
+    time_budget: Duration,
+    max_candidates: usize,
+    tips_policy: Arc<dyn TipsPolicy>,
+    #[consumer]
+    market_events: Option<Broadcaster<MarketEvents>>,
+    #[consumer]
+    compose_channel_rx: Option<Broadcaster<MessageTxCompose>>,
+    #[producer]
+    compose_channel_tx: Option<Broadcaster<MessageTxCompose>>,
+}
+
+impl Default for PackingMergerActor {
+    fn default() -> Self {
+        Self {
+            time_budget: DEFAULT_TIME_BUDGET,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            tips_policy: Arc::new(CurveTipsPolicy::default()),
+            market_events: None,
+            compose_channel_rx: None,
+            compose_channel_tx: None,
+        }
+    }
+}
+
+impl PackingMergerActor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time limit for the selection of the candidates
+    pub fn with_time_budget(self, time_budget: Duration) -> Self {
+        Self { time_budget, ..self }
+    }
+
+    /// Maximum number of the most profitable candidates kept per block
+    pub fn with_max_candidates(self, max_candidates: usize) -> Self {
+        Self { max_candidates, ..self }
+    }
+
+    /// Tips policy of the packed bundles
+    pub fn with_tips_policy(self, tips_policy: Arc<dyn TipsPolicy>) -> Self {
+        Self { tips_policy, ..self }
+    }
+
+    pub fn on_bc(self, bc: &Blockchain) -> Self {
+        Self {
+            market_events: Some(bc.market_events_channel()),
+            compose_channel_tx: Some(bc.compose_channel()),
+            compose_channel_rx: Some(bc.compose_channel()),
+            ..self
+        }
+    }
+}
+
+impl Actor for PackingMergerActor {
+    fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
+        let task = tokio::task::spawn(packing_merger_worker(
+            self.time_budget,
+            self.max_candidates,
+            self.tips_policy.clone(),
+            self.market_events.clone().unwrap(),
+            self.compose_channel_rx.clone().unwrap(),
+            self.compose_channel_tx.clone().unwrap(),
+            token,
+        ));
+        Ok(vec![task])
+    }
+
+    fn name(&self) -> &'static str {
+        "PackingMergerActor"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{Address, Bytes, TxKind};
+    use eyre::ErrReport;
+    use loom_types_entities::required_state::RequiredState;
+    use loom_types_entities::{AbiSwapEncoder, FixedTipsPolicy, Pool, PoolWrapper, SwapPath, Token};
+    use revm::primitives::{AccountInfo, Bytecode, Env, KECCAK_EMPTY};
+    use revm::DatabaseRef;
+
+    const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+
+    // Swaps at the rate in basis points stored in slot 0 of the pool
+    #[derive(Clone)]
+    struct RatePool {
+        address: Address,
+    }
+
+    impl Pool for RatePool {
+        fn get_address(&self) -> Address {
+            self.address
+        }
+
+        fn calculate_out_amount(
+            &self,
+            state: &LoomDBType,
+            _env: Env,
+            _token_address_from: &Address,
+            _token_address_to: &Address,
+            in_amount: U256,
+        ) -> Result<(U256, u64), ErrReport> {
+            let rate = state.storage_ref(self.address, U256::ZERO)?;
+            Ok((in_amount * rate / U256::from(10000), 100_000))
+        }
+
+        fn calculate_in_amount(
+            &self,
+            _state: &LoomDBType,
+            _env: Env,
+            _token_address_from: &Address,
+            _token_address_to: &Address,
+            _out_amount: U256,
+        ) -> Result<(U256, u64), ErrReport> {
+            Err(eyre!("NOT_IMPLEMENTED"))
+        }
+
+        fn can_flash_swap(&self) -> bool {
+            false
+        }
+
+        fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+            panic!("Not implemented")
+        }
+
+        fn get_state_required(&self) -> Result<RequiredState> {
+            Ok(RequiredState::new())
+        }
+    }
+
+    // Pool with a rate, the code of the pool sets the rate of 9000 when called
+    fn insert_pool(db: &mut LoomDBType, address: Address, rate: u64) {
+        let code = Bytecode::new_raw(Bytes::from(vec![0x61, 0x23, 0x28, 0x60, 0x00, 0x55, 0x00]));
+        db.insert_account_info(address, AccountInfo::new(U256::ZERO, 0, KECCAK_EMPTY, code));
+        db.insert_account_storage(address, U256::ZERO, U256::from(rate)).unwrap();
+    }
+
+    // WETH -> TKN -> WETH backrun through two pools, the backrun tx calls `tx_to`
+    fn candidate(db: &Arc<LoomDBType>, pools: [Address; 2], tx_to: Address) -> TxComposeData {
+        let mut weth = Token::new_with_data(Address::repeat_byte(0x11), Some("WETH".to_string()), None, Some(18), true, false);
+        weth.set_wrapped_native();
+        let weth = Arc::new(weth);
+        let token = Arc::new(Token::new(Address::repeat_byte(0x22)));
+        let path = SwapPath::new(
+            vec![weth.clone(), token, weth],
+            pools.into_iter().map(|address| PoolWrapper::from(RatePool { address })).collect::<Vec<_>>(),
+        );
+
+        let amount_in = U256::from(ONE_ETHER);
+        let mut swap_line = SwapLine { path, amount_in: SwapAmountType::Set(amount_in), ..SwapLine::default() };
+        let (amount_out, _, _) = swap_line.calculate_with_in_amount(db, Env::default(), amount_in).unwrap();
+        swap_line.amount_out = SwapAmountType::Set(amount_out);
+
+        let tx_request = TransactionRequest {
+            from: Some(Address::repeat_byte(0x33)),
+            to: Some(TxKind::Call(tx_to)),
+            gas: Some(100_000),
+            ..TransactionRequest::default()
+        };
+
+        TxComposeData {
+            next_block_number: 1,
+            swap: Swap::BackrunSwapLine(swap_line),
+            tx_bundle: Some(vec![TxState::SignatureRequired(tx_request)]),
+            poststate: Some(db.clone()),
+            poststate_update: Some(Vec::new()),
+            ..TxComposeData::default()
+        }
+    }
+
+    fn conflicts_from_edges(len: usize, edges: &[(usize, usize)]) -> Vec<Vec<bool>> {
+        let mut conflicts = vec![vec![false; len]; len];
+        for (a, b) in edges {
+            conflicts[*a][*b] = true;
+            conflicts[*b][*a] = true;
+        }
+        conflicts
+    }
+
+    #[test]
+    fn test_max_weight_independent_set() {
+        // greedy takes 0 and 3 for 13, the best set is 1, 2 and 3 for 16
+        let weights: Vec<U256> = [10u64, 6, 6, 4].into_iter().map(U256::from).collect();
+        let conflicts = conflicts_from_edges(4, &[(0, 1), (0, 2)]);
+
+        let selected = max_weight_independent_set(&weights, &conflicts, Instant::now() + Duration::from_secs(1));
+        assert_eq!(selected, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_max_weight_independent_set_deadline() {
+        let weights: Vec<U256> = [10u64, 6, 6, 4].into_iter().map(U256::from).collect();
+        let conflicts = conflicts_from_edges(4, &[(0, 1), (0, 2)]);
+
+        // the greedy set is returned if the deadline has passed
+        let selected = max_weight_independent_set(&weights, &conflicts, Instant::now());
+        assert_eq!(selected, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_pack_candidates_out_of_time_budget() -> Result<()> {
+        let pools: Vec<Address> = (1..=6).map(Address::with_last_byte).collect();
+        let mut db = LoomDBType::default();
+        for (pool, rate) in pools.iter().zip([11000, 11000, 10500, 10500, 10800, 10800]) {
+            insert_pool(&mut db, *pool, rate);
+        }
+        let db = Arc::new(db);
+
+        let candidates = vec![
+            candidate(&db, [pools[0], pools[1]], pools[2]),
+            candidate(&db, [pools[2], pools[3]], Address::repeat_byte(0x44)),
+            candidate(&db, [pools[4], pools[5]], Address::repeat_byte(0x44)),
+        ];
+
+        // the backruns kept by the first simulation are packed
+        let packed = pack_candidates(&candidates, Duration::ZERO, &FixedTipsPolicy { pct: 7000 })?.ok_or_eyre("NOT_PACKED")?;
+        assert_eq!(packed.swap.get_pool_address_vec(), vec![pools[0], pools[1], pools[4], pools[5]]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_packing_merger_drops_backrun_losing_profit() -> Result<()> {
+        let pools: Vec<Address> = (1..=6).map(Address::with_last_byte).collect();
+        let mut db = LoomDBType::default();
+        for (pool, rate) in pools.iter().zip([11000, 11000, 10500, 10500, 10800, 10800]) {
+            insert_pool(&mut db, *pool, rate);
+        }
+        let db = Arc::new(db);
+
+        // the most profitable backrun calls the first pool of the least profitable one
+        let backrun_0 = candidate(&db, [pools[0], pools[1]], pools[2]);
+        let backrun_1 = candidate(&db, [pools[2], pools[3]], Address::repeat_byte(0x44));
+        let backrun_2 = candidate(&db, [pools[4], pools[5]], Address::repeat_byte(0x44));
+
+        let market_events_channel: Broadcaster<MarketEvents> = Broadcaster::new(10);
+        let sign_channel: Broadcaster<MessageTxCompose> = Broadcaster::new(10);
+        let route_channel: Broadcaster<MessageTxCompose> = Broadcaster::new(10);
+        let mut route_rx = route_channel.subscribe().await;
+        let token = CancellationToken::new();
+
+        tokio::task::spawn(packing_merger_worker(
+            Duration::from_millis(10),
+            DEFAULT_MAX_CANDIDATES,
+            Arc::new(FixedTipsPolicy { pct: 7000 }),
+            market_events_channel,
+            sign_channel.clone(),
+            route_channel,
+            token.clone(),
+        ));
+        while sign_channel.stats().await.subscribers == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        for backrun in [backrun_0, backrun_1, backrun_2] {
+            sign_channel.send(MessageTxCompose::sign(backrun)).await?;
+        }
+
+        let packed = tokio::time::timeout(Duration::from_secs(1), route_rx.recv()).await??;
+        let TxCompose::Route(packed) = packed.inner else { panic!("Route compose request expected") };
+        assert_eq!(packed.swap.get_pool_address_vec(), vec![pools[0], pools[1], pools[4], pools[5]]);
+        assert_eq!(packed.tips_pct, Some(7000));
+        assert!(packed.tx_bundle.is_none());
+
+        token.cancel();
+        Ok(())
+    }
+}
@@ -4,6 +4,7 @@ use alloy_primitives::{Address, U256};
 use eyre::{eyre, Result};
 use revm::primitives::Env;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 use loom_core_actors::{subscribe, Accessor, Actor, ActorResult, Broadcaster, Consumer, Producer, SharedState, WorkerResult};
@@ -54,6 +55,7 @@ async fn arb_swap_path_merger_worker(
     market_events_rx: Broadcaster<MarketEvents>,
     compose_channel_rx: Broadcaster<MessageTxCompose>,
     compose_channel_tx: Broadcaster<MessageTxCompose>,
+    shutdown_token: CancellationToken,
 ) -> WorkerResult {
     subscribe!(market_events_rx);
     subscribe!(compose_channel_rx);
@@ -62,6 +64,9 @@ async fn arb_swap_path_merger_worker(
 
     loop {
         tokio::select! {
+            _ = shutdown_token.cancelled() => {
+                break Ok("Swap path merger worker stopped".to_string())
+            }
             msg = market_events_rx.recv() => {
                 let msg : Result<MarketEvents, RecvError> = msg;
                 match msg {
@@ -193,12 +198,17 @@ impl ArbSwapPathMergerActor {
 
 impl Actor for ArbSwapPathMergerActor {
     fn start(&self) -> ActorResult {
+        self.start_with_token(CancellationToken::new())
+    }
+
+    fn start_with_token(&self, token: CancellationToken) -> ActorResult {
         let task = tokio::task::spawn(arb_swap_path_merger_worker(
             self.encoder.clone(),
             self.latest_block.clone().unwrap(),
             self.market_events.clone().unwrap(),
             self.compose_channel_rx.clone().unwrap(),
             self.compose_channel_tx.clone().unwrap(),
+            token,
         ));
         Ok(vec![task])
     }
@@ -1,15 +1,140 @@
 use alloy_eips::eip1559::BaseFeeParams;
+use alloy_primitives::{address, Address};
 use alloy_rpc_types_eth::Header;
+use loom_defi_address_book::{AddressBook, FactoryAddress, TokenAddress};
+use tracing::warn;
+
+pub const ETHEREUM_CHAIN_ID: u64 = 1;
+pub const BASE_CHAIN_ID: u64 = 8453;
+pub const ARBITRUM_CHAIN_ID: u64 = 42161;
+
+/// Token known at startup, added to the market of the blockchain
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainToken {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+    pub basic: bool,
+    pub middle: bool,
+}
+
+impl ChainToken {
+    pub fn new(address: Address, symbol: &str, decimals: u8, basic: bool, middle: bool) -> Self {
+        Self { address, symbol: symbol.to_string(), decimals, basic, middle }
+    }
+}
+
+/// Pool factory deployed on the chain, `name` follows the address book, e.g. `UNISWAP_V2`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainFactory {
+    pub name: String,
+    pub address: Address,
+}
+
+impl ChainFactory {
+    pub fn new(name: &str, address: Address) -> Self {
+        Self { name: name.to_string(), address }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct ChainParameters {
     pub chain_id: u64,
     pub base_fee_params: BaseFeeParams,
+    /// Wrapped native token
+    pub weth: Address,
+    pub tokens: Vec<ChainToken>,
+    pub factories: Vec<ChainFactory>,
 }
 
 impl ChainParameters {
+    /// Parameters of unknown chain, tokens and factories are expected from config
+    pub fn new(chain_id: u64) -> ChainParameters {
+        ChainParameters { chain_id, base_fee_params: BaseFeeParams::ethereum(), weth: Address::ZERO, tokens: vec![], factories: vec![] }
+    }
+
     pub fn ethereum() -> ChainParameters {
-        ChainParameters { chain_id: 1, base_fee_params: BaseFeeParams::ethereum() }
+        ChainParameters {
+            chain_id: ETHEREUM_CHAIN_ID,
+            base_fee_params: BaseFeeParams::ethereum(),
+            weth: TokenAddress::WETH,
+            tokens: vec![
+                ChainToken::new(TokenAddress::WETH, "WETH", 18, true, false),
+                ChainToken::new(TokenAddress::USDC, "USDC", 6, true, false),
+                ChainToken::new(TokenAddress::USDT, "USDT", 6, true, false),
+                ChainToken::new(TokenAddress::DAI, "DAI", 18, true, false),
+                ChainToken::new(TokenAddress::WBTC, "WBTC", 8, true, false),
+                ChainToken::new(TokenAddress::THREECRV, "3Crv", 18, false, true),
+            ],
+            factories: vec![
+                ChainFactory::new("UNISWAP_V2", FactoryAddress::UNISWAP_V2),
+                ChainFactory::new("SUSHISWAP_V2", FactoryAddress::SUSHISWAP_V2),
+                ChainFactory::new("UNISWAP_V3", FactoryAddress::UNISWAP_V3),
+                ChainFactory::new("SUSHISWAP_V3", FactoryAddress::SUSHISWAP_V3),
+                ChainFactory::new("PANCAKE_V3", FactoryAddress::PANCAKE_V3),
+                ChainFactory::new("MAVERICK", FactoryAddress::MAVERICK),
+            ],
+        }
+    }
+
+    pub fn base() -> ChainParameters {
+        let weth = address!("4200000000000000000000000000000000000006");
+        ChainParameters {
+            chain_id: BASE_CHAIN_ID,
+            // OP stack after Canyon
+            base_fee_params: BaseFeeParams::new(250, 6),
+            weth,
+            tokens: vec![
+                ChainToken::new(weth, "WETH", 18, true, false),
+                ChainToken::new(address!("833589fcd6edb6e08f4c7c32d4f71b54bda02913"), "USDC", 6, true, false),
+            ],
+            factories: vec![
+                ChainFactory::new("UNISWAP_V2", address!("8909dc15e40173ff4699343b6eb8132c65e18ec6")),
+                ChainFactory::new("UNISWAP_V3", address!("33128a8fc17869897dce68ed026d694621f6fdfd")),
+            ],
+        }
+    }
+
+    pub fn arbitrum() -> ChainParameters {
+        let weth = address!("82af49447d8a07e3bd95bd0d56f35241523fbab1");
+        ChainParameters {
+            chain_id: ARBITRUM_CHAIN_ID,
+            // Arbitrum base fee does not follow EIP-1559, keep it unchanged between blocks
+            base_fee_params: BaseFeeParams::new(u64::MAX as u128, 1),
+            weth,
+            tokens: vec![
+                ChainToken::new(weth, "WETH", 18, true, false),
+                ChainToken::new(address!("af88d065e77c8cc2239327c5edb3a432268e5831"), "USDC", 6, true, false),
+                ChainToken::new(address!("fd086bc7cd5c481dcc9c85ebe478a1c0b69fcbb9"), "USDT", 6, true, false),
+            ],
+            factories: vec![
+                ChainFactory::new("UNISWAP_V2", address!("f1d7cc64fb4452f05c498126312ebe29f30fbcf9")),
+                ChainFactory::new("UNISWAP_V3", FactoryAddress::UNISWAP_V3),
+            ],
+        }
+    }
+
+    pub fn get_factory(&self, name: &str) -> Option<Address> {
+        self.factories.iter().find(|factory| factory.name == name).map(|factory| factory.address)
+    }
+
+    pub fn get_factory_name(&self, address: &Address) -> Option<&str> {
+        self.factories.iter().find(|factory| factory.address == *address).map(|factory| factory.name.as_str())
+    }
+
+    /// Tokens, wrapped native token and factories as address book entries
+    pub fn to_address_book(&self) -> AddressBook {
+        let mut address_book = AddressBook::new(self.chain_id);
+        for token in self.tokens.iter() {
+            address_book.tokens.insert(token.symbol.clone(), token.address);
+        }
+        if !self.weth.is_zero() {
+            address_book.tokens.insert("WETH".to_string(), self.weth);
+        }
+        for factory in self.factories.iter() {
+            address_book.factories.insert(factory.name.clone(), factory.address);
+        }
+        address_book
     }
 
     pub fn calc_next_block_base_fee(&self, gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
@@ -29,8 +154,40 @@ impl Default for ChainParameters {
 impl From<u64> for ChainParameters {
     fn from(chain_id: u64) -> Self {
         match chain_id {
-            1 => ChainParameters::ethereum(),
-            _ => unimplemented!(),
+            ETHEREUM_CHAIN_ID => ChainParameters::ethereum(),
+            BASE_CHAIN_ID => ChainParameters::base(),
+            ARBITRUM_CHAIN_ID => ChainParameters::arbitrum(),
+            _ => {
+                warn!("No built-in parameters for chain {chain_id}");
+                ChainParameters::new(chain_id)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_chain_id() {
+        assert_eq!(ChainParameters::from(1).weth, TokenAddress::WETH);
+        assert_eq!(ChainParameters::from(BASE_CHAIN_ID).chain_id, BASE_CHAIN_ID);
+        assert_eq!(ChainParameters::from(ARBITRUM_CHAIN_ID).get_factory("UNISWAP_V3"), Some(FactoryAddress::UNISWAP_V3));
+        assert_eq!(ChainParameters::from(10).weth, Address::ZERO);
+    }
+
+    #[test]
+    fn test_to_address_book() {
+        let address_book = ChainParameters::base().to_address_book();
+        assert_eq!(address_book.chain_id, BASE_CHAIN_ID);
+        assert_eq!(address_book.token("WETH"), Some(ChainParameters::base().weth));
+        assert_eq!(address_book.factory_name(&address!("33128a8fc17869897dce68ed026d694621f6fdfd")), Some("UNISWAP_V3"));
+    }
+
+    #[test]
+    fn test_arbitrum_base_fee_unchanged() {
+        let chain_parameters = ChainParameters::arbitrum();
+        assert_eq!(chain_parameters.calc_next_block_base_fee(1_000_000, 1_125_899_906_842_624, 10_000_000), 10_000_000);
+    }
+}
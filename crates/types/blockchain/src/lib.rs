@@ -1,5 +1,5 @@
 pub use accountnoncetx::AccountNonceAndTransactions;
-pub use chain_parameters::ChainParameters;
+pub use chain_parameters::{ChainFactory, ChainParameters, ChainToken, ARBITRUM_CHAIN_ID, BASE_CHAIN_ID, ETHEREUM_CHAIN_ID};
 pub use fetchstate::FetchState;
 pub use mempool::Mempool;
 pub use mempool_tx::MempoolTx;
@@ -15,10 +15,15 @@ pub use signers::{TxSigner, TxSigners};
 pub use swap::Swap;
 pub use swap_encoder::SwapEncoder;
 pub use swapline::{SwapAmountType, SwapLine};
+pub use swapline_optimizer::{ConstantProductOptimizer, GoldenSectionOptimizer, StepSearchOptimizer, SwapLineOptimizer};
 pub use swappath::{SwapPath, SwapPaths};
-pub use swappath_builder::build_swap_path_vec;
+pub use swappath_builder::{build_swap_path_vec, HopLiquidityFn, SwapPathBuilder, SwapPathBuilderConfig};
 pub use swapstep::SwapStep;
-pub use token::{Token, TokenWrapper};
+pub use tips_policy::{
+    CompetitionTipsConfig, CompetitionTipsPolicy, CurveTipsConfig, CurveTipsPolicy, FixedTipsConfig, FixedTipsPolicy, LandedBundle, TipsConfig,
+    TipsPolicy, TipsPolicyConfig, TokenTipsPolicy,
+};
+pub use token::{Token, TokenWrapper, TransferTax, TAX_DENOMINATOR};
 
 mod block_history;
 mod latest_block;
@@ -26,6 +31,7 @@ mod market;
 mod market_state;
 mod pool;
 mod swapline;
+mod swapline_optimizer;
 mod swappath;
 mod token;
 
@@ -47,3 +53,4 @@ mod mock_pool;
 mod swap;
 mod swap_encoder;
 pub mod tips;
+mod tips_policy;
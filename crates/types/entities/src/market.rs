@@ -5,10 +5,9 @@ use alloy_primitives::Address;
 use eyre::{eyre, OptionExt, Result};
 use tracing::debug;
 
-use crate::build_swap_path_vec;
+use crate::{build_swap_path_vec, SwapPathBuilder, SwapPathBuilderConfig};
 use crate::{PoolClass, PoolWrapper, Token};
 use crate::{SwapPath, SwapPaths};
-use loom_defi_address_book::TokenAddress;
 
 /// The market struct contains all the pools and tokens.
 /// It keeps track if a pool is disabled or not and the swap paths.
@@ -22,12 +21,18 @@ pub struct Market {
     tokens: HashMap<Address, Arc<Token>>,
     // token_from -> token_to
     token_tokens: HashMap<Address, Vec<Address>>,
+    // token_to -> token_from
+    token_from_tokens: HashMap<Address, Vec<Address>>,
     // token_from -> token_to -> pool_addresses
     token_token_pools: HashMap<Address, HashMap<Address, Vec<Address>>>,
     // token -> pool
     token_pools: HashMap<Address, Vec<Address>>,
     // swap_paths
     swap_paths: SwapPaths,
+    // pool_address -> swap paths removed when the pool was disabled
+    disabled_swap_paths: HashMap<Address, Vec<SwapPath>>,
+    // limits of the swap path search
+    swap_path_config: SwapPathBuilderConfig,
 }
 
 impl Market {
@@ -47,12 +52,18 @@ impl Market {
         }
     }
 
-    /// Check if the given address is the WETH address.
-    pub fn is_weth(address: &Address) -> bool {
-        address.eq(&TokenAddress::WETH)
+    /// Check if the token is flagged as honeypot.
+    pub fn is_honeypot_token(&self, address: &Address) -> bool {
+        self.tokens.get(address).map_or(false, |token| token.is_honeypot())
+    }
+
+    /// Check if the given address is the wrapped native token address.
+    pub fn is_weth(&self, address: &Address) -> bool {
+        self.tokens.get(address).map_or(false, |token| token.is_weth())
     }
 
     /// Add a new pool to the market if it does not exist or the class is unknown.
+    /// Pools with a token flagged as honeypot are refused.
     pub fn add_pool<T: Into<PoolWrapper>>(&mut self, pool: T) -> Result<()> {
         let pool_contract = pool.into();
         let pool_address = pool_contract.get_address();
@@ -61,13 +72,23 @@ impl Market {
             return Err(eyre!("Pool already exists {:?}", pool.get_address()));
         }
 
+        if let Some(token_address) = pool_contract.get_tokens().into_iter().find(|token_address| self.is_honeypot_token(token_address)) {
+            return Err(eyre!("Pool {:?} has honeypot token {:?}", pool_address, token_address));
+        }
+
         debug!("Adding pool {:?}", pool_address);
 
         for (token_from_address, token_to_address) in pool_contract.get_swap_directions().into_iter() {
             self.token_token_pools.entry(token_from_address).or_default().entry(token_to_address).or_default().push(pool_address);
             self.token_tokens.entry(token_from_address).or_default().push(token_to_address);
-            // Swap directions are bidirectional, for that reason we only need to add the token_from_address
-            self.token_pools.entry(token_from_address).or_default().push(pool_address);
+            self.token_from_tokens.entry(token_to_address).or_default().push(token_from_address);
+            // Swap directions can be one way, e.g. for staking pools, so both tokens are added
+            for token_address in [token_from_address, token_to_address] {
+                let token_pools = self.token_pools.entry(token_address).or_default();
+                if !token_pools.contains(&pool_address) {
+                    token_pools.push(pool_address);
+                }
+            }
         }
 
         self.pools.insert(pool_address, pool_contract);
@@ -103,7 +124,11 @@ impl Market {
     }
 
     /// Set the pool status to ok or not ok.
+    /// Swap paths passing a disabled pool are removed and restored when the pool is enabled again. Pools added while the pool
+    /// was disabled have no swap paths through it, so its paths are also built again.
+    /// Callers announce the change with `MarketEvents::PoolDisabled` or `MarketEvents::PoolEnabled`.
     pub fn set_pool_ok(&mut self, address: Address, ok: bool) {
+        let was_ok = self.is_pool_ok(&address);
         *self.pools_disabled.entry(address).or_insert(false) = ok;
 
         let pool_contract = match self.pools.get(&address) {
@@ -129,6 +154,72 @@ impl Market {
                 self.token_token_pools.entry(token_from_address).or_default().entry(token_to_address).or_default().push(address);
             }
         }
+
+        if !ok && was_ok {
+            let swap_paths = self.swap_paths.remove_pool_paths(&address);
+            self.disabled_swap_paths.entry(address).or_default().extend(swap_paths);
+        }
+
+        if ok && !was_ok {
+            self.restore_swap_paths(&address);
+            match self.build_pool_swap_paths(&address) {
+                Ok(swap_paths) => self.add_paths(swap_paths),
+                Err(e) => debug!("Swap paths for pool {:?} not built : {}", address, e),
+            }
+        }
+    }
+
+    // Add the swap paths removed when the pool was disabled. Paths passing another disabled pool wait for that pool.
+    fn restore_swap_paths(&mut self, address: &Address) {
+        let Some(swap_paths) = self.disabled_swap_paths.remove(address) else {
+            return;
+        };
+        for swap_path in swap_paths {
+            match swap_path.pools.iter().map(|pool| pool.get_address()).find(|pool_address| !self.is_pool_ok(pool_address)) {
+                Some(disabled_pool_address) => self.disabled_swap_paths.entry(disabled_pool_address).or_default().push(swap_path),
+                None => self.swap_paths.add(swap_path),
+            }
+        }
+    }
+
+    /// Remove the pool with its swap paths from the market.
+    pub fn remove_pool(&mut self, address: &Address) -> Option<PoolWrapper> {
+        let pool_contract = self.pools.remove(address)?;
+        debug!("Removing pool {:?}", address);
+
+        for (token_from_address, token_to_address) in pool_contract.get_swap_directions().into_iter() {
+            if let Some(pool_addresses) =
+                self.token_token_pools.get_mut(&token_from_address).and_then(|token_from_map| token_from_map.get_mut(&token_to_address))
+            {
+                pool_addresses.retain(|pool_address| pool_address != address);
+            }
+            // token_tokens and token_from_tokens have an entry per pool
+            if let Some(token_tokens) = self.token_tokens.get_mut(&token_from_address) {
+                if let Some(idx) = token_tokens.iter().position(|token_address| *token_address == token_to_address) {
+                    token_tokens.remove(idx);
+                }
+            }
+            if let Some(token_from_tokens) = self.token_from_tokens.get_mut(&token_to_address) {
+                if let Some(idx) = token_from_tokens.iter().position(|token_address| *token_address == token_from_address) {
+                    token_from_tokens.remove(idx);
+                }
+            }
+            for token_address in [token_from_address, token_to_address] {
+                if let Some(token_pools) = self.token_pools.get_mut(&token_address) {
+                    token_pools.retain(|pool_address| pool_address != address);
+                }
+            }
+        }
+
+        self.pools_disabled.remove(address);
+        self.swap_paths.remove_pool_paths(address);
+        self.disabled_swap_paths.remove(address);
+        // paths disabled by other pools can't be restored
+        for swap_paths in self.disabled_swap_paths.values_mut() {
+            swap_paths.retain(|swap_path| !swap_path.pools.iter().any(|pool| pool.get_address() == *address));
+        }
+
+        Some(pool_contract)
     }
 
     /// Check if the pool is ok.
@@ -141,6 +232,11 @@ impl Market {
         self.tokens.get(address).map_or(Arc::new(Token::new(*address)), |t| t.clone())
     }
 
+    /// Get a reference to the tokens map in the market.
+    pub fn tokens(&self) -> &HashMap<Address, Arc<Token>> {
+        &self.tokens
+    }
+
     /// Get a [`Token`] reference from the market by the address of the token.
     pub fn get_token(&self, address: &Address) -> Option<Arc<Token>> {
         self.tokens.get(address).cloned()
@@ -182,6 +278,11 @@ impl Market {
         None
     }
 
+    /// Get all token addresses that allow to swap to `token_to_address`.
+    pub fn get_token_from_tokens(&self, token_to_address: &Address) -> Option<Vec<Address>> {
+        self.token_from_tokens.get(token_to_address).cloned()
+    }
+
     /// Get all pool addresses that allow to swap `token_address`.
     pub fn get_token_pools(&self, token_from_address: &Address) -> Option<Vec<Address>> {
         if let Some(token_vec) = self.token_pools.get(token_from_address) {
@@ -203,6 +304,22 @@ impl Market {
         build_swap_path_vec(self, directions)
     }
 
+    /// Build all swap paths passing the pool.
+    pub fn build_pool_swap_paths(&self, pool_address: &Address) -> Result<Vec<SwapPath>> {
+        let pool = self.get_pool(pool_address).ok_or_eyre("POOL_NOT_FOUND")?;
+        SwapPathBuilder::new(self, &self.swap_path_config).build_pool(pool)
+    }
+
+    /// Get the limits of the swap path search.
+    pub fn swap_path_config(&self) -> &SwapPathBuilderConfig {
+        &self.swap_path_config
+    }
+
+    /// Set the limits of the swap path search, e.g. the maximum number of hops.
+    pub fn set_swap_path_config(&mut self, swap_path_config: SwapPathBuilderConfig) {
+        self.swap_path_config = swap_path_config;
+    }
+
     /// get a [`SwapPath`] from the given token and pool addresses.
     pub fn swap_path(&self, token_address_vec: Vec<Address>, pool_address_vec: Vec<Address>) -> Result<SwapPath> {
         let mut tokens: Vec<Arc<Token>> = Vec::new();
@@ -222,9 +339,11 @@ impl Market {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mock_pool::MockPool;
-    use alloy_primitives::Address;
+    use crate::mock_pool::{MockOneWayPool, MockPool};
+    use crate::HopLiquidityFn;
+    use alloy_primitives::{Address, U256};
     use eyre::Result;
+    use loom_defi_address_book::TokenAddress;
 
     #[test]
     fn test_add_pool() {
@@ -261,6 +380,23 @@ mod tests {
         assert_eq!(market.get_token(&token_address).unwrap().get_address(), token_address);
     }
 
+    #[test]
+    fn test_add_pool_with_honeypot_token() {
+        let mut market = Market::default();
+        let pool_address = Address::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let mut honeypot_token = Token::new(token1);
+        honeypot_token.set_honeypot();
+        market.add_token(honeypot_token).unwrap();
+
+        let result = market.add_pool(MockPool { address: pool_address, token0, token1 });
+
+        assert!(result.is_err());
+        assert!(market.is_honeypot_token(&token1));
+        assert!(!market.is_pool(&pool_address));
+    }
+
     #[test]
     fn test_get_token_default() {
         let market = Market::default();
@@ -375,7 +511,8 @@ mod tests {
         let mut market = Market::default();
 
         // Add basic token for start/end
-        let weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
         market.add_token(weth_token);
 
         // Swap pool: token weth -> token1
@@ -436,7 +573,8 @@ mod tests {
         let mut market = Market::default();
 
         // Add basic token for start/end
-        let weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
         market.add_token(weth_token);
 
         // tokens
@@ -503,4 +641,276 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_build_swap_path_vec_one_way_pools() -> Result<()> {
+        let mut market = Market::default();
+
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+
+        // staked and wrapped staked tokens
+        let token1 = Address::random();
+        let token2 = Address::random();
+
+        // Deposit pool: weth -> token1 only
+        let pool_address1 = Address::random();
+        let mock_pool1 = PoolWrapper::new(Arc::new(MockOneWayPool { address: pool_address1, token0: TokenAddress::WETH, token1 }));
+        market.add_pool(mock_pool1.clone());
+
+        // Wrapper pool: token1 -> token2 only
+        let pool_address2 = Address::random();
+        let mock_pool2 = PoolWrapper::new(Arc::new(MockOneWayPool { address: pool_address2, token0: token1, token1: token2 }));
+        market.add_pool(mock_pool2.clone());
+
+        // Swap pool: token2 <-> weth
+        let pool_address3 = Address::random();
+        let mock_pool3 = PoolWrapper::new(Arc::new(MockPool { address: pool_address3, token0: token2, token1: TokenAddress::WETH }));
+        market.add_pool(mock_pool3.clone());
+
+        assert!(market.get_token_pools(&token1).unwrap().contains(&pool_address1));
+        assert_eq!(market.get_token_from_tokens(&token2), Some(vec![token1, TokenAddress::WETH]));
+
+        // paths are found from every pool of the cycle, but only in swap direction
+        for pool in [mock_pool1, mock_pool2, mock_pool3] {
+            let mut directions = BTreeMap::new();
+            directions.insert(pool.clone(), pool.get_swap_directions());
+            let swap_paths = market.build_swap_path_vec(&directions)?;
+
+            assert_eq!(swap_paths.len(), 1, "{}", format!("Missing path for pool={:?}", pool.get_address()));
+            let tokens = swap_paths[0].tokens.iter().map(|token| token.get_address()).collect::<Vec<Address>>();
+            assert_eq!(tokens, vec![TokenAddress::WETH, token1, token2, TokenAddress::WETH]);
+            let pools = swap_paths[0].pools.iter().map(|pool| pool.get_address()).collect::<Vec<Address>>();
+            assert_eq!(pools, vec![pool_address1, pool_address2, pool_address3]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_swap_path_vec_four_hops() -> Result<()> {
+        let mut market = Market::default();
+
+        // basic tokens for start/end
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+        let usdc = Address::random();
+        market.add_token(Token::new_with_data(usdc, Some("USDC".to_string()), None, Some(6), true, false));
+
+        // middle tokens
+        let token1 = Address::random();
+        let token2 = Address::random();
+        market.add_token(Token::new_with_data(token1, None, None, Some(18), false, true));
+        market.add_token(Token::new_with_data(token2, None, None, Some(18), false, true));
+
+        // weth -> token1 -> usdc -> token2 -> weth
+        let pool_address1 = Address::random();
+        let mock_pool1 = PoolWrapper::new(Arc::new(MockPool { address: pool_address1, token0: TokenAddress::WETH, token1 }));
+        market.add_pool(mock_pool1.clone());
+        let pool_address2 = Address::random();
+        market.add_pool(MockPool { address: pool_address2, token0: token1, token1: usdc });
+        let pool_address3 = Address::random();
+        market.add_pool(MockPool { address: pool_address3, token0: usdc, token1: token2 });
+        let pool_address4 = Address::random();
+        market.add_pool(MockPool { address: pool_address4, token0: token2, token1: TokenAddress::WETH });
+
+        let mut directions = BTreeMap::new();
+        directions.insert(mock_pool1.clone(), mock_pool1.get_swap_directions());
+        let swap_paths = market.build_swap_path_vec(&directions)?;
+
+        // both directions of the cycle, starting at weth
+        assert_eq!(swap_paths.len(), 2);
+        let (first_path, second_path) = if swap_paths[0].pools[0].get_address() == pool_address1 {
+            (&swap_paths[0], &swap_paths[1])
+        } else {
+            (&swap_paths[1], &swap_paths[0])
+        };
+
+        let tokens = first_path.tokens.iter().map(|token| token.get_address()).collect::<Vec<Address>>();
+        assert_eq!(tokens, vec![TokenAddress::WETH, token1, usdc, token2, TokenAddress::WETH]);
+        let pools = first_path.pools.iter().map(|pool| pool.get_address()).collect::<Vec<Address>>();
+        assert_eq!(pools, vec![pool_address1, pool_address2, pool_address3, pool_address4]);
+
+        let tokens = second_path.tokens.iter().map(|token| token.get_address()).collect::<Vec<Address>>();
+        assert_eq!(tokens, vec![TokenAddress::WETH, token2, usdc, token1, TokenAddress::WETH]);
+        let pools = second_path.pools.iter().map(|pool| pool.get_address()).collect::<Vec<Address>>();
+        assert_eq!(pools, vec![pool_address4, pool_address3, pool_address2, pool_address1]);
+
+        // limited to three hops
+        market.set_swap_path_config(SwapPathBuilderConfig::default().with_max_hops(3));
+        assert!(market.build_swap_path_vec(&directions)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_swap_path_vec_rotation_dedup() -> Result<()> {
+        let mut market = Market::default();
+
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+        let usdc = Address::random();
+        market.add_token(Token::new_with_data(usdc, Some("USDC".to_string()), None, Some(6), true, false));
+
+        let pool_address1 = Address::random();
+        let mock_pool1 = PoolWrapper::new(Arc::new(MockPool { address: pool_address1, token0: TokenAddress::WETH, token1: usdc }));
+        market.add_pool(mock_pool1.clone());
+        let pool_address2 = Address::random();
+        market.add_pool(MockPool { address: pool_address2, token0: usdc, token1: TokenAddress::WETH });
+
+        let mut directions = BTreeMap::new();
+        directions.insert(mock_pool1.clone(), mock_pool1.get_swap_directions());
+        let swap_paths = market.build_swap_path_vec(&directions)?;
+
+        // usdc -> weth -> usdc is a rotation of weth -> usdc -> weth
+        assert_eq!(swap_paths.len(), 2);
+        for swap_path in swap_paths.iter() {
+            let tokens = swap_path.tokens.iter().map(|token| token.get_address()).collect::<Vec<Address>>();
+            assert_eq!(tokens, vec![TokenAddress::WETH, usdc, TokenAddress::WETH]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_swap_path_vec_pruned_pools() -> Result<()> {
+        let mut market = Market::default();
+
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+
+        let token1 = Address::random();
+        let token2 = Address::random();
+        let pool_address1 = Address::random();
+        market.add_pool(MockPool { address: pool_address1, token0: TokenAddress::WETH, token1 });
+        let pool_address2 = Address::random();
+        market.add_pool(MockPool { address: pool_address2, token0: token1, token1: token2 });
+        let pool_address3 = Address::random();
+        let mock_pool3 = PoolWrapper::new(Arc::new(MockPool { address: pool_address3, token0: token2, token1: TokenAddress::WETH }));
+        market.add_pool(mock_pool3.clone());
+
+        let mut directions = BTreeMap::new();
+        directions.insert(mock_pool3.clone(), mock_pool3.get_swap_directions());
+        assert_eq!(market.build_swap_path_vec(&directions)?.len(), 2);
+
+        // low liquidity hop
+        let liquidity: HopLiquidityFn = Arc::new(
+            move |pool: &PoolWrapper, _: &Address, _: &Address| {
+                if pool.get_address() == pool_address2 {
+                    Some(U256::from(1))
+                } else {
+                    None
+                }
+            },
+        );
+        market.set_swap_path_config(SwapPathBuilderConfig::default().with_liquidity(U256::from(100), liquidity));
+        assert!(market.build_swap_path_vec(&directions)?.is_empty());
+
+        // disabled pool
+        market.set_swap_path_config(SwapPathBuilderConfig::default());
+        market.set_pool_ok(pool_address2, false);
+        assert!(market.build_swap_path_vec(&directions)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_pool_ok_builds_swap_paths() -> Result<()> {
+        let mut market = Market::default();
+
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+
+        let token1 = Address::random();
+        let pool_address1 = Address::random();
+        market.add_pool(MockPool { address: pool_address1, token0: TokenAddress::WETH, token1 });
+        market.set_pool_ok(pool_address1, false);
+
+        // pool added while pool1 is disabled
+        let pool_address2 = Address::random();
+        market.add_pool(MockPool { address: pool_address2, token0: TokenAddress::WETH, token1 });
+        let swap_paths = market.build_pool_swap_paths(&pool_address2)?;
+        assert!(swap_paths.is_empty());
+        assert!(market.get_pool_paths(&pool_address1).is_none());
+
+        market.set_pool_ok(pool_address1, true);
+        assert_eq!(market.get_pool_paths(&pool_address1).map(|paths| paths.len()), Some(2));
+        assert_eq!(market.get_pool_paths(&pool_address2).map(|paths| paths.len()), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_pool_ok_removes_swap_paths() -> Result<()> {
+        let mut market = Market::default();
+
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+
+        // three pools for weth <-> token1
+        let token1 = Address::random();
+        let pool_addresses = [Address::random(), Address::random(), Address::random()];
+        for pool_address in pool_addresses {
+            market.add_pool(MockPool { address: pool_address, token0: TokenAddress::WETH, token1 });
+        }
+        for pool_address in pool_addresses {
+            let swap_paths = market.build_pool_swap_paths(&pool_address)?;
+            market.add_paths(swap_paths);
+        }
+        assert_eq!(market.get_pool_paths(&pool_addresses[0]).map(|paths| paths.len()), Some(4));
+
+        market.set_pool_ok(pool_addresses[0], false);
+        assert!(market.get_pool_paths(&pool_addresses[0]).is_none());
+        assert_eq!(market.get_pool_paths(&pool_addresses[1]).map(|paths| paths.len()), Some(2));
+
+        // paths passing pool 0 and pool 1 wait for both pools
+        market.set_pool_ok(pool_addresses[1], false);
+        market.set_pool_ok(pool_addresses[0], true);
+        assert_eq!(market.get_pool_paths(&pool_addresses[0]).map(|paths| paths.len()), Some(2));
+        assert!(market.get_pool_paths(&pool_addresses[1]).is_none());
+
+        market.set_pool_ok(pool_addresses[1], true);
+        for pool_address in pool_addresses {
+            assert_eq!(market.get_pool_paths(&pool_address).map(|paths| paths.len()), Some(4));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_pool() -> Result<()> {
+        let mut market = Market::default();
+
+        let mut weth_token = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth_token.set_wrapped_native();
+        market.add_token(weth_token);
+
+        let token1 = Address::random();
+        let pool_address1 = Address::random();
+        let pool_address2 = Address::random();
+        market.add_pool(MockPool { address: pool_address1, token0: TokenAddress::WETH, token1 });
+        market.add_pool(MockPool { address: pool_address2, token0: TokenAddress::WETH, token1 });
+        let swap_paths = market.build_pool_swap_paths(&pool_address1)?;
+        market.add_paths(swap_paths);
+
+        let removed = market.remove_pool(&pool_address1);
+
+        assert_eq!(removed.map(|pool| pool.get_address()), Some(pool_address1));
+        assert!(!market.is_pool(&pool_address1));
+        assert_eq!(market.get_token_token_pools(&TokenAddress::WETH, &token1), Some(vec![pool_address2]));
+        assert_eq!(market.get_token_tokens(&TokenAddress::WETH), Some(vec![token1]));
+        assert_eq!(market.get_token_from_tokens(&TokenAddress::WETH), Some(vec![token1]));
+        assert_eq!(market.get_token_pools(&token1), Some(vec![pool_address2]));
+        assert!(market.get_pool_paths(&pool_address1).is_none());
+        assert!(market.get_pool_paths(&pool_address2).is_none());
+        assert!(market.remove_pool(&pool_address1).is_none());
+
+        Ok(())
+    }
 }
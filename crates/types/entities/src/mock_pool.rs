@@ -68,3 +68,67 @@ impl Pool for MockPool {
         panic!("Not implemented")
     }
 }
+
+/// Pool allowing swaps only from token0 to token1, e.g. staking deposits
+#[derive(Clone)]
+pub struct MockOneWayPool {
+    pub(crate) token0: Address,
+    pub(crate) token1: Address,
+    pub(crate) address: Address,
+}
+
+impl Pool for MockOneWayPool {
+    fn get_class(&self) -> PoolClass {
+        PoolClass::LidoStEth
+    }
+
+    fn get_protocol(&self) -> PoolProtocol {
+        PoolProtocol::LidoStEth
+    }
+
+    fn get_address(&self) -> Address {
+        self.address
+    }
+
+    fn get_tokens(&self) -> Vec<Address> {
+        vec![self.token0, self.token1]
+    }
+
+    fn get_swap_directions(&self) -> Vec<(Address, Address)> {
+        vec![(self.token0, self.token1)]
+    }
+
+    fn calculate_out_amount(
+        &self,
+        state: &LoomDB,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        in_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        panic!("Not implemented")
+    }
+
+    fn calculate_in_amount(
+        &self,
+        state: &LoomDB,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+        out_amount: U256,
+    ) -> Result<(U256, u64), ErrReport> {
+        panic!("Not implemented")
+    }
+
+    fn can_flash_swap(&self) -> bool {
+        panic!("Not implemented")
+    }
+
+    fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+        panic!("Not implemented")
+    }
+
+    fn get_state_required(&self) -> Result<RequiredState> {
+        panic!("Not implemented")
+    }
+}
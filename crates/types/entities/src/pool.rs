@@ -7,37 +7,17 @@ use std::sync::Arc;
 use crate::required_state::RequiredState;
 use alloy_primitives::{Address, Bytes, U256};
 use eyre::{eyre, ErrReport, Result};
-use loom_defi_address_book::FactoryAddress;
+use loom_defi_address_book::AddressBook;
 use loom_evm_db::LoomDBType;
 use revm::primitives::Env;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString, VariantNames};
 
-pub fn get_protocol_by_factory(factory_address: Address) -> PoolProtocol {
-    if factory_address == FactoryAddress::UNISWAP_V2 {
-        PoolProtocol::UniswapV2
-    } else if factory_address == FactoryAddress::UNISWAP_V3 {
-        PoolProtocol::UniswapV3
-    } else if factory_address == FactoryAddress::PANCAKE_V3 {
-        PoolProtocol::PancakeV3
-    } else if factory_address == FactoryAddress::NOMISWAP {
-        PoolProtocol::NomiswapStable
-    } else if factory_address == FactoryAddress::SUSHISWAP_V2 {
-        PoolProtocol::Sushiswap
-    } else if factory_address == FactoryAddress::SUSHISWAP_V3 {
-        PoolProtocol::SushiswapV3
-    } else if factory_address == FactoryAddress::DOOARSWAP {
-        PoolProtocol::DooarSwap
-    } else if factory_address == FactoryAddress::SAFESWAP {
-        PoolProtocol::Safeswap
-    } else if factory_address == FactoryAddress::MINISWAP {
-        PoolProtocol::Miniswap
-    } else if factory_address == FactoryAddress::SHIBASWAP {
-        PoolProtocol::Shibaswap
-    } else if factory_address == FactoryAddress::MAVERICK {
-        PoolProtocol::Maverick
-    } else {
-        PoolProtocol::Unknown
+/// Protocol of the pool factory, factories are looked up in the registered address book of the chain
+pub fn get_protocol_by_factory(chain_id: u64, factory_address: Address) -> PoolProtocol {
+    match AddressBook::find_factory_name(chain_id, &factory_address) {
+        Some(factory_name) => PoolProtocol::from_factory_name(&factory_name),
+        None => PoolProtocol::Unknown,
     }
 }
 
@@ -54,6 +34,9 @@ pub enum PoolClass {
     #[serde(rename = "uniswap3")]
     #[strum(serialize = "uniswap3")]
     UniswapV3,
+    #[serde(rename = "uniswap4")]
+    #[strum(serialize = "uniswap4")]
+    UniswapV4,
     #[serde(rename = "curve")]
     #[strum(serialize = "curve")]
     Curve,
@@ -66,6 +49,21 @@ pub enum PoolClass {
     #[serde(rename = "rocketpool")]
     #[strum(serialize = "rocketpool")]
     RocketPool,
+    #[serde(rename = "balancer")]
+    #[strum(serialize = "balancer")]
+    Balancer,
+    #[serde(rename = "erc4626")]
+    #[strum(serialize = "erc4626")]
+    Erc4626,
+    #[serde(rename = "solidly")]
+    #[strum(serialize = "solidly")]
+    Solidly,
+    #[serde(rename = "algebra")]
+    #[strum(serialize = "algebra")]
+    Algebra,
+    #[serde(rename = "integral")]
+    #[strum(serialize = "integral")]
+    Integral,
     #[serde(rename = "custom")]
     #[strum(serialize = "custom")]
     Custom(u64),
@@ -86,6 +84,7 @@ pub enum PoolProtocol {
     Shibaswap,
     UniswapV3,
     UniswapV3Like,
+    UniswapV4,
     PancakeV3,
     Integral,
     Maverick,
@@ -93,9 +92,55 @@ pub enum PoolProtocol {
     LidoStEth,
     LidoWstEth,
     RocketEth,
+    Balancer,
+    Erc4626,
+    Solidly,
+    Velodrome,
+    Aerodrome,
+    Algebra,
+    QuickswapV3,
+    CamelotV3,
     Custom(u64),
 }
 
+impl PoolProtocol {
+    /// Protocol by factory name used in the address book
+    pub fn from_factory_name(factory_name: &str) -> PoolProtocol {
+        match factory_name {
+            "UNISWAP_V2" => PoolProtocol::UniswapV2,
+            "SUSHISWAP_V2" => PoolProtocol::Sushiswap,
+            "NOMISWAP" => PoolProtocol::NomiswapStable,
+            "DOOARSWAP" => PoolProtocol::DooarSwap,
+            "SAFESWAP" => PoolProtocol::Safeswap,
+            "MINISWAP" => PoolProtocol::Miniswap,
+            "SHIBASWAP" => PoolProtocol::Shibaswap,
+            "OG_PEPE" => PoolProtocol::OgPepe,
+            "UNISWAP_V3" => PoolProtocol::UniswapV3,
+            "SUSHISWAP_V3" => PoolProtocol::SushiswapV3,
+            "PANCAKE_V3" => PoolProtocol::PancakeV3,
+            "MAVERICK" => PoolProtocol::Maverick,
+            "SOLIDLY" => PoolProtocol::Solidly,
+            "VELODROME_V2" => PoolProtocol::Velodrome,
+            "AERODROME" => PoolProtocol::Aerodrome,
+            "ALGEBRA" => PoolProtocol::Algebra,
+            "QUICKSWAP_V3" => PoolProtocol::QuickswapV3,
+            "CAMELOT_V3" => PoolProtocol::CamelotV3,
+            "INTEGRAL" => PoolProtocol::Integral,
+            _ => PoolProtocol::Unknown,
+        }
+    }
+
+    /// Algebra forks with dynamic fee concentrated liquidity pools
+    pub fn is_algebra(&self) -> bool {
+        matches!(self, PoolProtocol::Algebra | PoolProtocol::QuickswapV3 | PoolProtocol::CamelotV3)
+    }
+
+    /// Solidly forks with stable and volatile pools
+    pub fn is_solidly(&self) -> bool {
+        matches!(self, PoolProtocol::Solidly | PoolProtocol::Velodrome | PoolProtocol::Aerodrome)
+    }
+}
+
 impl Display for PoolProtocol {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let protocol_name = match self {
@@ -103,6 +148,7 @@ impl Display for PoolProtocol {
             Self::UniswapV2 => "UniswapV2",
             Self::UniswapV2Like => "UniswapV2Like",
             Self::UniswapV3 => "UniswapV3",
+            Self::UniswapV4 => "UniswapV4",
             Self::PancakeV3 => "PancakeV3",
             Self::UniswapV3Like => "UniswapV3Like",
             Self::NomiswapStable => "NomiswapStable",
@@ -119,6 +165,14 @@ impl Display for PoolProtocol {
             Self::LidoWstEth => "WstEth",
             Self::LidoStEth => "StEth",
             Self::RocketEth => "RocketEth",
+            Self::Balancer => "Balancer",
+            Self::Erc4626 => "Erc4626",
+            Self::Solidly => "Solidly",
+            Self::Velodrome => "Velodrome",
+            Self::Aerodrome => "Aerodrome",
+            Self::Algebra => "Algebra",
+            Self::QuickswapV3 => "QuickswapV3",
+            Self::CamelotV3 => "CamelotV3",
             Self::Custom(x) => "Custom",
         };
         write!(f, "{}", protocol_name)
@@ -237,6 +291,18 @@ pub trait Pool: Sync + Send {
         out_amount: U256,
     ) -> Result<(U256, u64), ErrReport>;
 
+    /// Returns (reserve_in, reserve_out, fee) of a constant product pool for the swap direction, the fee as numerator of 10000.
+    /// Used to calculate the optimal in amount of swap lines, None for other pools or if transfer taxes apply.
+    fn get_constant_product_reserves(
+        &self,
+        state: &LoomDBType,
+        env: Env,
+        token_address_from: &Address,
+        token_address_to: &Address,
+    ) -> Option<(U256, U256, U256)> {
+        None
+    }
+
     fn can_flash_swap(&self) -> bool;
 
     fn can_calculate_in_amount(&self) -> bool {
@@ -250,6 +316,11 @@ pub trait Pool: Sync + Send {
     }
 
     fn get_state_required(&self) -> Result<RequiredState>;
+
+    /// State missing at the current position of the pool in the state db, for pools that load only a part of their ticks
+    fn get_state_required_update(&self, _state_db: &LoomDBType) -> Result<Option<RequiredState>> {
+        Ok(None)
+    }
 }
 
 pub struct DefaultAbiSwapEncoder {}
@@ -286,10 +357,28 @@ pub trait AbiSwapEncoder {
     ) -> Result<Bytes> {
         Err(eyre!("NOT_IMPLEMENTED"))
     }
+    /// Encode a swap where the input tokens are pulled from `sender`. Only encoders of contracts that take explicit
+    /// funds like the Balancer vault need the sender, the others encode the swap as usual.
+    fn encode_swap_in_amount_provided_from(
+        &self,
+        token_from_address: Address,
+        token_to_address: Address,
+        amount: U256,
+        _sender: Address,
+        recipient: Address,
+        payload: Bytes,
+    ) -> Result<Bytes> {
+        self.encode_swap_in_amount_provided(token_from_address, token_to_address, amount, recipient, payload)
+    }
     fn preswap_requirement(&self) -> PreswapRequirement {
         PreswapRequirement::Unknown
     }
 
+    /// Contract that is called for the swap and takes the input tokens if it is not the pool, like a vault or a pool manager
+    fn swap_contract_address(&self) -> Option<Address> {
+        None
+    }
+
     fn is_native(&self) -> bool {
         false
     }
@@ -316,11 +405,19 @@ pub trait AbiSwapEncoder {
 
 #[cfg(test)]
 mod test {
-    use crate::PoolClass;
+    use crate::{get_protocol_by_factory, PoolClass, PoolProtocol};
+    use loom_defi_address_book::FactoryAddress;
 
     #[test]
     fn test_strum() {
         println!("{}", PoolClass::Unknown);
         println!("{}", PoolClass::UniswapV2);
     }
+
+    #[test]
+    fn test_protocol_by_factory() {
+        assert_eq!(get_protocol_by_factory(1, FactoryAddress::UNISWAP_V2), PoolProtocol::UniswapV2);
+        assert_eq!(get_protocol_by_factory(1, FactoryAddress::INTEGRAL), PoolProtocol::Integral);
+        assert_eq!(get_protocol_by_factory(8453, FactoryAddress::INTEGRAL), PoolProtocol::Unknown);
+    }
 }
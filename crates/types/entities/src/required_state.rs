@@ -1,15 +1,26 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
 use std::fmt::Debug;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use alloy_network::Network;
-use alloy_primitives::{Address, BlockNumber, Bytes, TxKind, U256};
+use alloy_network::{BlockResponse, HeaderResponse, Network};
+use alloy_primitives::{Address, BlockHash, BlockNumber, Bytes, TxKind, B256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, BlockNumberOrTag, TransactionInput, TransactionRequest};
 use alloy_rpc_types_trace::geth::AccountState;
+use alloy_sol_types::SolCall;
 use alloy_transport::Transport;
-use eyre::{eyre, Result};
-use tracing::{error, trace};
+use eyre::{eyre, OptionExt, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use revm::primitives::{AccountInfo, Bytecode, Env, CANCUN, KECCAK_EMPTY};
+use revm::{Database, Evm};
+use tokio::sync::{oneshot, OnceCell, RwLock};
+use tracing::{debug, error, trace};
 
+use loom_defi_abi::IMulticall3;
+use loom_defi_address_book::AddressBook;
 use loom_node_debug_provider::DebugProviderExt;
 use loom_types_blockchain::{debug_trace_call_pre_state, GethStateUpdate, GethStateUpdateVec};
 
@@ -62,6 +73,35 @@ impl RequiredState {
         }
         self
     }
+
+    /// Add calls and slots of other required state, e.g. to fetch the state of many pools at once
+    pub fn merge(&mut self, other: RequiredState) -> &mut Self {
+        self.calls.extend(other.calls);
+        self.slots.extend(other.slots);
+        self.empty_slots.extend(other.empty_slots);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty() && self.slots.is_empty() && self.empty_slots.is_empty()
+    }
+
+    fn call_keys(&self) -> impl Iterator<Item = CallKey> + '_ {
+        self.calls
+            .iter()
+            .map(|req| (req.to.unwrap_or_default().to().map_or(Address::ZERO, |x| *x), req.input.input().cloned().unwrap_or_default()))
+    }
+}
+
+fn merge_state_update(state: &mut GethStateUpdate, update: GethStateUpdate) {
+    for (address, account_state) in update.into_iter() {
+        match state.get_mut(&address) {
+            Some(entry) => entry.storage.extend(account_state.storage),
+            None => {
+                state.insert(address, account_state);
+            }
+        }
+    }
 }
 
 pub struct RequiredStateReader {}
@@ -125,6 +165,468 @@ impl RequiredStateReader {
     }
 }
 
+/// Batching of the [`BatchRequiredStateReader`]
+#[derive(Clone, Debug)]
+pub struct BatchRequiredStateReaderConfig {
+    /// Time requests are collected to be fetched together
+    pub batch_window: Duration,
+    /// Number of calls traced in one multicall
+    pub calls_per_batch: usize,
+    /// Number of slots read with one `eth_getProof`
+    pub slots_per_batch: usize,
+    /// Number of concurrent requests
+    pub concurrency: usize,
+    /// Retries of a failed request
+    pub retries: usize,
+    /// Multicall3 contract, MULTICALL3 of the chain address book if not set
+    pub multicall: Option<Address>,
+}
+
+impl Default for BatchRequiredStateReaderConfig {
+    fn default() -> Self {
+        Self {
+            batch_window: Duration::from_millis(50),
+            calls_per_batch: 20,
+            slots_per_batch: 100,
+            concurrency: 8,
+            retries: 3,
+            multicall: None,
+        }
+    }
+}
+
+// target and call data of a required call
+type CallKey = (Address, Bytes);
+
+#[derive(Default)]
+struct BlockStateCache {
+    block_hash: BlockHash,
+    calls: HashMap<CallKey, GethStateUpdate>,
+    slots: HashMap<(Address, U256), U256>,
+}
+
+impl BlockStateCache {
+    // Drops the cached state if it was fetched at another block
+    fn set_block_hash(&mut self, block_hash: BlockHash) {
+        if self.block_hash != block_hash {
+            *self = BlockStateCache { block_hash, ..BlockStateCache::default() };
+        }
+    }
+
+    // Fills cached states of the calls and slots, returns the calls and slots not cached
+    fn get_cached(
+        &self,
+        required_state: &RequiredState,
+        call_updates: &mut HashMap<CallKey, Option<GethStateUpdate>>,
+        slot_values: &mut HashMap<(Address, U256), U256>,
+    ) -> (Vec<CallKey>, Vec<(Address, U256)>) {
+        let mut calls = Vec::new();
+        for key in required_state.call_keys() {
+            if call_updates.contains_key(&key) {
+                continue;
+            }
+            match self.calls.get(&key) {
+                Some(update) => {
+                    call_updates.insert(key, Some(update.clone()));
+                }
+                None => {
+                    call_updates.insert(key.clone(), None);
+                    calls.push(key);
+                }
+            }
+        }
+
+        let mut slots = Vec::new();
+        for (address, slot) in required_state.slots.iter() {
+            if slot_values.contains_key(&(*address, *slot)) || slots.contains(&(*address, *slot)) {
+                continue;
+            }
+            match self.slots.get(&(*address, *slot)) {
+                Some(value) => {
+                    slot_values.insert((*address, *slot), *value);
+                }
+                None => slots.push((*address, *slot)),
+            }
+        }
+        (calls, slots)
+    }
+}
+
+struct PendingRequest {
+    required_state: RequiredState,
+    result_tx: oneshot::Sender<Result<GethStateUpdate>>,
+}
+
+type PendingRequests = Arc<Mutex<HashMap<Option<BlockNumber>, Vec<PendingRequest>>>>;
+
+// Drops requests collected by a leading request that is cancelled while waiting, their callers get an error
+struct PendingGuard<'a> {
+    pending: &'a PendingRequests,
+    block_number: Option<BlockNumber>,
+}
+
+impl PendingGuard<'_> {
+    fn take(self) -> Vec<PendingRequest> {
+        let requests = self.pending.lock().ok().and_then(|mut pending| pending.remove(&self.block_number)).unwrap_or_default();
+        std::mem::forget(self);
+        requests
+    }
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&self.block_number);
+        }
+    }
+}
+
+/// Fetches required state of many pools with few requests. Requests arriving within the batch window are merged and
+/// fetched together: calls are coalesced into Multicall3 calls whose pre-state is traced at once, slots are read with
+/// `eth_getProof` per account and fall back to `eth_getStorageAt` if it is not supported. The state of each call is
+/// found by executing it on the traced state, and cached with the slots for the block hash they were fetched at.
+/// As with [`RequiredStateReader`] a required call that reverts fails the request.
+#[derive(Clone, Default)]
+pub struct BatchRequiredStateReader {
+    config: BatchRequiredStateReaderConfig,
+    cache: Arc<RwLock<BlockStateCache>>,
+    pending: PendingRequests,
+    chain_id: Arc<OnceCell<u64>>,
+}
+
+impl BatchRequiredStateReader {
+    pub fn new(config: BatchRequiredStateReaderConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub async fn fetch_calls_and_slots<T: Transport + Clone, N: Network, C: DebugProviderExt<T, N> + Provider<T, N> + Clone + 'static>(
+        &self,
+        client: C,
+        required_state: RequiredState,
+        block_number: Option<BlockNumber>,
+    ) -> Result<GethStateUpdate> {
+        let (result_tx, result_rx) = oneshot::channel();
+        let is_leading = {
+            let mut pending = self.pending.lock().map_err(|_| eyre!("PENDING_LOCK_POISONED"))?;
+            let requests = pending.entry(block_number).or_default();
+            requests.push(PendingRequest { required_state, result_tx });
+            requests.len() == 1
+        };
+
+        // the first request waits for others and fetches the state of all of them
+        if is_leading {
+            let guard = PendingGuard { pending: &self.pending, block_number };
+            tokio::time::sleep(self.config.batch_window).await;
+            let requests = guard.take();
+
+            let required_states: Vec<RequiredState> = requests.iter().map(|request| request.required_state.clone()).collect();
+            match self.fetch_merged(client, required_states, block_number).await {
+                Ok(updates) => {
+                    for (request, update) in requests.into_iter().zip(updates) {
+                        let _ = request.result_tx.send(update);
+                    }
+                }
+                Err(e) => {
+                    for request in requests.into_iter() {
+                        let _ = request.result_tx.send(Err(eyre!("STATE_FETCH_FAILED : {}", e)));
+                    }
+                }
+            }
+        }
+
+        result_rx.await.map_err(|_| eyre!("STATE_FETCH_CANCELLED"))?
+    }
+
+    // Fetches the merged required state once and returns the state of each request
+    async fn fetch_merged<T: Transport + Clone, N: Network, C: DebugProviderExt<T, N> + Provider<T, N> + Clone + 'static>(
+        &self,
+        client: C,
+        required_states: Vec<RequiredState>,
+        block_number: Option<BlockNumber>,
+    ) -> Result<Vec<Result<GethStateUpdate>>> {
+        let chain_id = *self.chain_id.get_or_try_init(|| async { Ok::<_, eyre::Report>(client.get_chain_id().await?) }).await?;
+        let multicall = self
+            .config
+            .multicall
+            .or_else(|| AddressBook::find_periphery(chain_id, "MULTICALL3"))
+            .ok_or_else(|| eyre!("MULTICALL3_NOT_FOUND"))?;
+
+        let block_number_or_tag = block_number.map_or(BlockNumberOrTag::Latest, BlockNumberOrTag::Number);
+        let block = self
+            .with_retries(|| async { Ok(client.get_block_by_number(block_number_or_tag, false).await?) })
+            .await?
+            .ok_or_eyre("BLOCK_NOT_FOUND")?;
+        let block_hash = block.header().hash();
+        // all requests are done on the block hash, so the state is consistent even if a new block arrives
+        let block_id = BlockId::hash(block_hash);
+
+        let mut env = Env::default();
+        env.cfg.chain_id = chain_id;
+        env.block.number = U256::from(block.header().number());
+        env.block.timestamp = U256::from(block.header().timestamp());
+
+        let mut merged_state = RequiredState::new();
+        for required_state in required_states.iter() {
+            merged_state.merge(required_state.clone());
+        }
+
+        let mut call_updates: HashMap<CallKey, Option<GethStateUpdate>> = HashMap::new();
+        let mut slot_values: HashMap<(Address, U256), U256> = HashMap::new();
+        let (calls, slots) = {
+            let mut cache = self.cache.write().await;
+            cache.set_block_hash(block_hash);
+            cache.get_cached(&merged_state, &mut call_updates, &mut slot_values)
+        };
+
+        debug!(
+            "Fetching state of {} requests at {} : {} calls {} slots, {} calls {} slots cached",
+            required_states.len(),
+            block_hash,
+            calls.len(),
+            slots.len(),
+            call_updates.len() - calls.len(),
+            slot_values.len()
+        );
+
+        let fetched_calls: Vec<Vec<(CallKey, Option<GethStateUpdate>)>> =
+            stream::iter(calls.chunks(self.config.calls_per_batch.max(1)).map(|batch| batch.to_vec()))
+                .map(|batch| self.fetch_calls(client.clone(), multicall, batch, block_id, &env))
+                .buffer_unordered(self.config.concurrency.max(1))
+                .try_collect()
+                .await?;
+
+        let mut slots_by_account: BTreeMap<Address, Vec<U256>> = BTreeMap::new();
+        for (address, slot) in slots.into_iter() {
+            slots_by_account.entry(address).or_default().push(slot);
+        }
+        let slots_batches: Vec<(Address, Vec<U256>)> = slots_by_account
+            .into_iter()
+            .flat_map(|(address, slots)| {
+                slots.chunks(self.config.slots_per_batch.max(1)).map(|batch| (address, batch.to_vec())).collect::<Vec<_>>()
+            })
+            .collect();
+
+        let fetched_slots: Vec<Vec<(Address, U256, U256)>> = stream::iter(slots_batches)
+            .map(|(address, slots)| self.fetch_slots(client.clone(), address, slots, block_id))
+            .buffer_unordered(self.config.concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        {
+            let mut cache = self.cache.write().await;
+            let cache_valid = cache.block_hash == block_hash;
+
+            for (key, update) in fetched_calls.into_iter().flatten() {
+                if let (true, Some(update)) = (cache_valid, update.as_ref()) {
+                    cache.calls.insert(key.clone(), update.clone());
+                }
+                call_updates.insert(key, update);
+            }
+            for (address, slot, value) in fetched_slots.into_iter().flatten() {
+                if cache_valid {
+                    cache.slots.insert((address, slot), value);
+                }
+                slot_values.insert((address, slot), value);
+            }
+        }
+
+        Ok(required_states.iter().map(|required_state| assemble_state_update(required_state, &call_updates, &slot_values)).collect())
+    }
+
+    // Traces the pre-state of the calls executed by one multicall and finds the state of each call, None for calls that failed
+    async fn fetch_calls<T: Transport + Clone, N: Network, C: DebugProviderExt<T, N> + Provider<T, N> + Clone + 'static>(
+        &self,
+        client: C,
+        multicall: Address,
+        calls: Vec<CallKey>,
+        block_id: BlockId,
+        env: &Env,
+    ) -> Result<Vec<(CallKey, Option<GethStateUpdate>)>> {
+        let multicall_calls: Vec<IMulticall3::Call3> = calls
+            .iter()
+            .map(|(target, call_data)| IMulticall3::Call3 { target: *target, allowFailure: true, callData: call_data.clone() })
+            .collect();
+        let call_data = IMulticall3::aggregate3Call { calls: multicall_calls.clone() }.abi_encode();
+
+        let req = TransactionRequest {
+            gas: Some(1_000_000 * calls.len() as u64 + 100_000),
+            to: Some(TxKind::Call(multicall)),
+            input: TransactionInput::new(call_data.into()),
+            ..TransactionRequest::default()
+        };
+
+        // the trace has the state of all calls, the call returns which of them failed
+        let (mut update, results) = tokio::try_join!(
+            self.with_retries(|| debug_trace_call_pre_state(client.clone(), req.clone(), block_id, None)),
+            self.with_retries(|| async {
+                let multicall = IMulticall3::new(multicall, client.clone());
+                Ok(multicall.aggregate3(multicall_calls.clone()).block(block_id).call().await?.returnData)
+            })
+        )?;
+        update.remove(&multicall);
+
+        if results.len() != calls.len() {
+            return Err(eyre!("MULTICALL_RESULTS_MISMATCH"));
+        }
+
+        Ok(calls
+            .into_iter()
+            .zip(results)
+            .map(|(key, result)| {
+                let call_update = result.success.then(|| call_state_update(&update, &key, multicall, env));
+                (key, call_update)
+            })
+            .collect())
+    }
+
+    // Reads slots of an account with one eth_getProof, or slot by slot if the node does not support it
+    async fn fetch_slots<T: Transport + Clone, N: Network, C: Provider<T, N> + Clone + 'static>(
+        &self,
+        client: C,
+        address: Address,
+        slots: Vec<U256>,
+        block_id: BlockId,
+    ) -> Result<Vec<(Address, U256, U256)>> {
+        let keys: Vec<B256> = slots.iter().map(|slot| B256::from(*slot)).collect();
+        match client.get_proof(address, keys).block_id(block_id).await {
+            Ok(proof) if proof.storage_proof.len() == slots.len() => {
+                Ok(slots.into_iter().zip(proof.storage_proof).map(|(slot, storage_proof)| (address, slot, storage_proof.value)).collect())
+            }
+            result => {
+                if let Err(e) = result {
+                    trace!("eth_getProof {:#20x} failed : {}", address, e);
+                }
+                let mut ret = Vec::new();
+                for slot in slots.into_iter() {
+                    let value = self.with_retries(|| async { Ok(client.get_storage_at(address, slot).block_id(block_id).await?) }).await?;
+                    ret.push((address, slot, value));
+                }
+                Ok(ret)
+            }
+        }
+    }
+
+    async fn with_retries<R, F, Fut>(&self, f: F) -> Result<R>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Ok(ret) => return Ok(ret),
+                Err(e) if attempt < self.config.retries => {
+                    attempt += 1;
+                    debug!("Request failed, retry {} of {} : {}", attempt, self.config.retries, e);
+                    tokio::time::sleep(Duration::from_millis(100 * (1 << attempt))).await;
+                }
+                Err(e) => {
+                    error!("Request failed after {} retries : {}", attempt, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+// State of a request from the fetched calls and slots, fails if one of its calls failed
+fn assemble_state_update(
+    required_state: &RequiredState,
+    call_updates: &HashMap<CallKey, Option<GethStateUpdate>>,
+    slot_values: &HashMap<(Address, U256), U256>,
+) -> Result<GethStateUpdate> {
+    let mut ret = GethStateUpdate::new();
+    for key in required_state.call_keys() {
+        match call_updates.get(&key) {
+            Some(Some(update)) => merge_state_update(&mut ret, update.clone()),
+            _ => {
+                error!("Contract call failed {:#20x} {}", key.0, key.1);
+                return Err(eyre!("CONTRACT_CALL_FAILED"));
+            }
+        }
+    }
+    for (address, slot) in required_state.slots.iter() {
+        let value = slot_values.get(&(*address, *slot)).ok_or_else(|| eyre!("SLOT_NOT_FETCHED"))?;
+        ret.entry(*address).or_default().storage.insert((*slot).into(), (*value).into());
+    }
+    for (address, slot) in required_state.empty_slots.iter() {
+        ret.entry(*address).or_default().storage.insert((*slot).into(), B256::ZERO);
+    }
+    Ok(ret)
+}
+
+// Records accounts and slots read by a call from the traced state of a multicall
+struct StateAccessRecorder<'a> {
+    state: &'a GethStateUpdate,
+    accessed: GethStateUpdate,
+}
+
+impl<'a> StateAccessRecorder<'a> {
+    fn record_account(&mut self, address: Address) -> Option<&'a AccountState> {
+        let account = self.state.get(&address)?;
+        self.accessed.entry(address).or_insert_with(|| AccountState { storage: BTreeMap::new(), ..account.clone() });
+        Some(account)
+    }
+}
+
+impl Database for StateAccessRecorder<'_> {
+    type Error = Infallible;
+
+    fn basic(&mut self, address: Address) -> std::result::Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.record_account(address).map(|account| {
+            let code = account.code.clone().map(Bytecode::new_raw);
+            AccountInfo {
+                balance: account.balance.unwrap_or_default(),
+                nonce: account.nonce.unwrap_or_default(),
+                code_hash: code.as_ref().map_or(KECCAK_EMPTY, |code| code.hash_slow()),
+                code,
+            }
+        }))
+    }
+
+    // code is returned with the account
+    fn code_by_hash(&mut self, _code_hash: B256) -> std::result::Result<Bytecode, Self::Error> {
+        Ok(Bytecode::default())
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> std::result::Result<U256, Self::Error> {
+        let slot = B256::from(index);
+        let Some(value) = self.record_account(address).map(|account| account.storage.get(&slot).copied().unwrap_or_default()) else {
+            return Ok(U256::ZERO);
+        };
+        if let Some(entry) = self.accessed.get_mut(&address) {
+            entry.storage.insert(slot, value);
+        }
+        Ok(value.into())
+    }
+
+    fn block_hash(&mut self, _number: u64) -> std::result::Result<B256, Self::Error> {
+        Ok(B256::ZERO)
+    }
+}
+
+// State read by the call, found by executing it on the traced state of the multicall. The whole traced state is
+// returned if the execution differs from the traced one.
+fn call_state_update(state: &GethStateUpdate, key: &CallKey, caller: Address, env: &Env) -> GethStateUpdate {
+    let mut env = env.clone();
+    env.tx.caller = caller;
+    env.tx.transact_to = TxKind::Call(key.0);
+    env.tx.data = key.1.clone();
+    env.tx.gas_limit = 1_000_000;
+    env.tx.gas_price = U256::ZERO;
+
+    let recorder = StateAccessRecorder { state, accessed: GethStateUpdate::new() };
+    let mut evm = Evm::builder().with_spec_id(CANCUN).with_db(recorder).with_env(Box::new(env)).build();
+
+    match evm.transact() {
+        Ok(result) if result.result.is_success() => std::mem::take(&mut evm.context.evm.db.accessed),
+        result => {
+            trace!("Call {:#20x} execution differs from trace : {:?}", key.0, result.map(|result| result.result));
+            state.clone()
+        }
+    }
+}
+
 pub fn accounts_len(state: &BTreeMap<Address, AccountState>) -> (usize, usize) {
     let accounts = state.len();
     let storage = state.values().map(|item| item.storage.clone().len()).sum();
@@ -134,3 +636,147 @@ pub fn accounts_len(state: &BTreeMap<Address, AccountState>) -> (usize, usize) {
 pub fn accounts_vec_len(state: &GethStateUpdateVec) -> usize {
     state.iter().map(|item| accounts_len(item).1).sum()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::hex;
+
+    // returns the value of the storage slot
+    fn sload_code(slot: u8) -> Bytes {
+        Bytes::from(vec![0x60, slot, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3])
+    }
+
+    fn account_state(storage: &[(u8, u8)]) -> AccountState {
+        AccountState {
+            storage: storage.iter().map(|(slot, value)| (B256::with_last_byte(*slot), B256::with_last_byte(*value))).collect(),
+            ..AccountState::default()
+        }
+    }
+
+    fn required_state(calls: &[CallKey], slots: &[(Address, u64)]) -> RequiredState {
+        let mut required_state = RequiredState::new();
+        for (target, call_data) in calls.iter() {
+            required_state.add_call(*target, call_data.clone());
+        }
+        for (address, slot) in slots.iter() {
+            required_state.add_slot(*address, U256::from(*slot));
+        }
+        required_state
+    }
+
+    #[test]
+    fn test_merge_state_update() {
+        let (address_0, address_1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut state = GethStateUpdate::from([(address_0, account_state(&[(1, 1), (2, 2)]))]);
+        let update = GethStateUpdate::from([(address_0, account_state(&[(2, 3), (4, 4)])), (address_1, account_state(&[(1, 1)]))]);
+
+        merge_state_update(&mut state, update);
+
+        assert_eq!(state.len(), 2);
+        assert_eq!(state[&address_0].storage, account_state(&[(1, 1), (2, 3), (4, 4)]).storage);
+        assert_eq!(state[&address_1].storage.len(), 1);
+    }
+
+    #[test]
+    fn test_required_state_merge() {
+        let call: CallKey = (Address::repeat_byte(1), Bytes::from(hex!("0902f1ac")));
+        let mut merged = required_state(&[call.clone()], &[(Address::repeat_byte(1), 8)]);
+
+        merged.merge(required_state(&[call.clone()], &[(Address::repeat_byte(2), 0)]));
+        merged.add_empty_slot(Address::repeat_byte(3), U256::from(1));
+
+        assert_eq!(merged.call_keys().collect::<Vec<_>>(), vec![call.clone(), call]);
+        assert_eq!(merged.slots.len(), 2);
+        assert_eq!(merged.empty_slots.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_hits_and_block_hash_invalidation() {
+        let call: CallKey = (Address::repeat_byte(1), Bytes::from(hex!("0902f1ac")));
+        let other_call: CallKey = (Address::repeat_byte(2), Bytes::from(hex!("0902f1ac")));
+        let slot = (Address::repeat_byte(1), U256::from(8));
+        let required_state = required_state(&[call.clone(), other_call.clone(), call.clone()], &[(slot.0, 8), (slot.0, 8)]);
+        let call_update = GethStateUpdate::from([(call.0, account_state(&[(8, 1)]))]);
+
+        let mut cache = BlockStateCache::default();
+        cache.set_block_hash(B256::repeat_byte(1));
+        cache.calls.insert(call.clone(), call_update.clone());
+        cache.slots.insert(slot, U256::from(5));
+
+        let (mut call_updates, mut slot_values) = (HashMap::new(), HashMap::new());
+        let (calls, slots) = cache.get_cached(&required_state, &mut call_updates, &mut slot_values);
+        assert_eq!(calls, vec![other_call.clone()]);
+        assert!(slots.is_empty());
+        assert_eq!(call_updates[&call], Some(call_update));
+        assert_eq!(slot_values[&slot], U256::from(5));
+
+        // the same block keeps the cache
+        cache.set_block_hash(B256::repeat_byte(1));
+        assert_eq!(cache.calls.len(), 1);
+
+        cache.set_block_hash(B256::repeat_byte(2));
+        let (mut call_updates, mut slot_values) = (HashMap::new(), HashMap::new());
+        let (calls, slots) = cache.get_cached(&required_state, &mut call_updates, &mut slot_values);
+        assert_eq!(calls, vec![call, other_call]);
+        assert_eq!(slots, vec![slot]);
+    }
+
+    #[test]
+    fn test_failed_call_fails_only_its_request() {
+        let call: CallKey = (Address::repeat_byte(1), Bytes::from(hex!("0902f1ac")));
+        let failed_call: CallKey = (Address::repeat_byte(2), Bytes::from(hex!("0902f1ac")));
+        let empty_slot = (Address::repeat_byte(3), U256::from(1));
+
+        let call_updates =
+            HashMap::from([(call.clone(), Some(GethStateUpdate::from([(call.0, account_state(&[(8, 1)]))]))), (failed_call.clone(), None)]);
+        let slot_values = HashMap::from([((call.0, U256::from(9)), U256::from(7))]);
+
+        let mut state = required_state(&[call.clone()], &[(call.0, 9)]);
+        state.add_empty_slot(empty_slot.0, empty_slot.1);
+        let update = assemble_state_update(&state, &call_updates, &slot_values).unwrap();
+        assert_eq!(update[&call.0].storage, account_state(&[(8, 1), (9, 7)]).storage);
+        assert_eq!(update[&empty_slot.0].storage[&B256::from(empty_slot.1)], B256::ZERO);
+
+        let failed_state = required_state(&[call, failed_call], &[]);
+        let result = assemble_state_update(&failed_state, &call_updates, &slot_values);
+        assert_eq!(result.unwrap_err().to_string(), "CONTRACT_CALL_FAILED");
+    }
+
+    #[test]
+    fn test_call_state_update() {
+        let (contract_0, contract_1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let multicall = Address::repeat_byte(3);
+        // state traced for a multicall of both contracts
+        let state = GethStateUpdate::from([
+            (contract_0, AccountState { code: Some(sload_code(1)), ..account_state(&[(1, 10), (2, 20)]) }),
+            (contract_1, AccountState { code: Some(sload_code(2)), ..account_state(&[(1, 30), (2, 40)]) }),
+        ]);
+
+        let update = call_state_update(&state, &(contract_0, Bytes::new()), multicall, &Env::default());
+        assert_eq!(update.len(), 1);
+        assert_eq!(update[&contract_0].storage, account_state(&[(1, 10)]).storage);
+        assert_eq!(update[&contract_0].code, Some(sload_code(1)));
+
+        let update = call_state_update(&state, &(contract_1, Bytes::new()), multicall, &Env::default());
+        assert_eq!(update.len(), 1);
+        assert_eq!(update[&contract_1].storage, account_state(&[(2, 40)]).storage);
+
+        // reverting execution returns the whole traced state
+        let reverting =
+            GethStateUpdate::from([(contract_0, AccountState { code: Some(Bytes::from(vec![0xfd])), ..AccountState::default() })]);
+        assert_eq!(call_state_update(&reverting, &(contract_0, Bytes::new()), multicall, &Env::default()), reverting);
+    }
+
+    #[test]
+    fn test_cancelled_leading_request_drops_pending() {
+        let pending = PendingRequests::default();
+        let (result_tx, mut result_rx) = oneshot::channel();
+        pending.lock().unwrap().insert(None, vec![PendingRequest { required_state: RequiredState::new(), result_tx }]);
+
+        drop(PendingGuard { pending: &pending, block_number: None });
+
+        assert!(pending.lock().unwrap().is_empty());
+        assert!(result_rx.try_recv().is_err());
+    }
+}
@@ -5,13 +5,12 @@ use std::sync::Arc;
 use alloy_primitives::{Address, I256, U256};
 use eyre::{eyre, Result};
 use revm::primitives::Env;
-use tracing::debug;
 
 use loom_evm_db::LoomDBType;
 use loom_types_blockchain::SwapError;
 
 use crate::swappath::SwapPath;
-use crate::{CalculationResult, PoolWrapper, SwapStep, Token};
+use crate::{CalculationResult, PoolWrapper, StepSearchOptimizer, SwapLineOptimizer, SwapStep, Token};
 
 #[derive(Clone, Copy, Debug, Default)]
 pub enum SwapAmountType {
@@ -290,7 +289,8 @@ impl SwapLine {
         Err(eyre!("CANNOT_CALCULATE"))
     }
 
-    /// Calculate the out amount for the swap line for a given in amount
+    /// Calculate the out amount for the swap line for a given in amount. Transfer taxes of the tokens are deducted
+    /// from the amounts pools receive and send.
     pub fn calculate_with_in_amount(
         &self,
         state: &LoomDBType,
@@ -305,8 +305,10 @@ impl SwapLine {
         for (i, pool) in self.pools().iter().enumerate() {
             let token_from = &self.tokens()[i];
             let token_to = &self.tokens()[i + 1];
-            match pool.calculate_out_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), current_in_amount) {
+            let pool_in_amount = token_from.get_tax().deduct_in(current_in_amount);
+            match pool.calculate_out_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), pool_in_amount) {
                 Ok((out_amount_result, gas_result)) => {
+                    let out_amount_result = token_to.get_tax().deduct_out(out_amount_result);
                     if out_amount_result.is_zero() {
                         return Err(SwapError {
                             msg: "ZERO_AMOUNT".to_string(),
@@ -338,7 +340,7 @@ impl SwapLine {
         Ok((final_out_amount, gas_used, calculation_results))
     }
 
-    /// Calculate the in amount for the swap line for a given out amount
+    /// Calculate the in amount for the swap line for a given out amount. Amounts are grossed up by the transfer taxes of the tokens.
     pub fn calculate_with_out_amount(
         &self,
         state: &LoomDBType,
@@ -359,8 +361,18 @@ impl SwapLine {
         for (i, pool) in pool_reverse.iter().enumerate() {
             let token_from = &tokens_reverse[i + 1];
             let token_to = &tokens_reverse[i];
-            match pool.calculate_in_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), current_out_amount) {
+            let pool_out_amount = token_to.get_tax().gross_up_out(current_out_amount).ok_or_else(|| SwapError {
+                msg: "OUT_AMOUNT_FULLY_TAXED".to_string(),
+                pool: pool.get_address(),
+                token_from: token_from.get_address(),
+                token_to: token_to.get_address(),
+                is_in_amount: false,
+                amount: current_out_amount,
+            })?;
+            match pool.calculate_in_amount(state, env.clone(), &token_from.get_address(), &token_to.get_address(), pool_out_amount) {
                 Ok((in_amount_result, gas_result)) => {
+                    // the pool must receive the calculated amount, more is sent for taxed tokens
+                    let in_amount_result = token_from.get_tax().gross_up_in(in_amount_result).unwrap_or(U256::MAX);
                     if in_amount_result == U256::MAX || in_amount_result == U256::ZERO {
                         return Err(SwapError {
                             msg: "ZERO_AMOUNT".to_string(),
@@ -393,98 +405,21 @@ impl SwapLine {
         Ok((final_in_amount, gas_used, calculation_results))
     }
 
-    /// Optimize the swap line for a given in amount
+    /// Optimize the swap line for a given in amount with the [`StepSearchOptimizer`]
     pub fn optimize_with_in_amount(&mut self, state: &LoomDBType, env: Env, in_amount: U256) -> Result<&mut Self, SwapError> {
-        let mut current_in_amount = in_amount;
-        let mut best_profit: Option<I256> = None;
-        let mut current_step = U256::from(10000);
-        let mut inc_direction = true;
-        let mut first_step_change = false;
-        let mut next_amount = current_in_amount;
-        let mut prev_in_amount = U256::ZERO;
-        let mut counter = 0;
-        let denominator = U256::from(1000);
-
-        loop {
-            counter += 1;
-            //let next_amount  = current_in_amount + (current_in_amount * current_step / 10000);
-
-            if counter > 30 {
-                debug!("optimize_swap_path_in_amount iterations exceeded : {self} {current_in_amount} {current_step}");
-                return Ok(self);
-            }
-
-            let (current_out_amount, current_gas_used, calculation_results) =
-                match self.calculate_with_in_amount(state, env.clone(), next_amount) {
-                    Ok(ret) => ret,
-                    Err(e) => {
-                        if counter == 1 {
-                            // break if first swap already fails
-                            return Err(e);
-                        }
-                        (U256::ZERO, 0, vec![])
-                    }
-                };
-
-            let current_profit = I256::from_raw(current_out_amount) - I256::from_raw(next_amount);
-
-            if best_profit.is_none() {
-                best_profit = Some(current_profit);
-                self.amount_in = SwapAmountType::Set(next_amount);
-                self.amount_out = SwapAmountType::Set(current_out_amount);
-                self.gas_used = Some(current_gas_used);
-                self.calculation_results = calculation_results;
-                current_in_amount = next_amount;
-                if current_out_amount.is_zero() || current_profit.is_negative() {
-                    return Ok(self);
-                }
-            } else if best_profit.unwrap() > current_profit || current_out_amount.is_zero()
-            /*|| next_profit < current_profit*/
-            {
-                if first_step_change && inc_direction && current_step < denominator {
-                    inc_direction = false;
-                    //TODO : Check why not used
-                    next_amount = prev_in_amount;
-                    current_in_amount = prev_in_amount;
-                    first_step_change = true;
-                    //debug!("inc direction changed {} {} {}", next_amount, current_profit, bestprofit.unwrap());
-                } else if first_step_change && !inc_direction {
-                    //TODO : Check why is self aligned
-                    inc_direction = true;
-                    current_step /= U256::from(10);
-                    best_profit = Some(current_profit);
-                    first_step_change = true;
-                    //debug!("dec direction changed  {} {} {}", next_amount, current_profit, bestprofit.unwrap());
-
-                    if current_step == U256::from(1) {
-                        break;
-                    }
-                } else {
-                    current_step /= U256::from(10);
-                    first_step_change = true;
-                    if current_step == U256::from(1) {
-                        break;
-                    }
-                }
-            } else {
-                best_profit = Some(current_profit);
-                self.amount_in = SwapAmountType::Set(next_amount);
-                self.amount_out = SwapAmountType::Set(current_out_amount);
-                self.gas_used = Some(current_gas_used);
-                self.calculation_results = calculation_results;
-                current_in_amount = next_amount;
-                first_step_change = false;
-            }
-
-            prev_in_amount = current_in_amount;
-            if inc_direction {
-                next_amount = current_in_amount + (current_in_amount * current_step / denominator);
-            } else {
-                next_amount = current_in_amount - (current_in_amount * current_step / denominator);
-            }
-            //trace!("opt step : {} direction {} first_step {} step : {} current_in_amount : {} next_amount: {} profit : {} {}", counter, inc_direction, first_step_change,  current_step, current_in_amount , next_amount, current_profit, bestprofit.unwrap());
-        }
+        StepSearchOptimizer {}.optimize(self, state, env, in_amount)?;
+        Ok(self)
+    }
 
+    /// Optimize the swap line for a given in amount with the given optimizer
+    pub fn optimize_with(
+        &mut self,
+        optimizer: &dyn SwapLineOptimizer,
+        state: &LoomDBType,
+        env: Env,
+        in_amount: U256,
+    ) -> Result<&mut Self, SwapError> {
+        optimizer.optimize(self, state, env, in_amount)?;
         Ok(self)
     }
 }
@@ -498,7 +433,9 @@ mod tests {
     use std::sync::Arc;
 
     fn default_swap_line() -> (MockPool, MockPool, SwapLine) {
-        let token0 = Arc::new(Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false));
+        let mut token0 = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        token0.set_wrapped_native();
+        let token0 = Arc::new(token0);
         let token1 = Arc::new(Token::new_with_data(TokenAddress::USDT, Some("USDT".to_string()), None, Some(6), true, false));
         let pool1 = MockPool { token0: TokenAddress::WETH, token1: TokenAddress::USDT, address: UniswapV3PoolAddress::WETH_USDT_3000 };
         let pool2_address = Address::random();
@@ -0,0 +1,551 @@
+use alloy_primitives::{I256, U256};
+use loom_evm_db::LoomDBType;
+use loom_types_blockchain::SwapError;
+use revm::primitives::Env;
+use tracing::debug;
+
+use crate::{CalculationResult, SwapAmountType, SwapLine};
+
+/// Denominator of the fees of constant product pools
+const FEE_DENOMINATOR: u64 = 10000;
+
+/// Finds the in amount with the highest profit for a [`SwapLine`].
+pub trait SwapLineOptimizer: Send + Sync {
+    /// Set the amounts, gas used and calculation results of the best in amount found starting at `in_amount`.
+    /// Returns the number of evaluated in amounts.
+    fn optimize(&self, swap_line: &mut SwapLine, state: &LoomDBType, env: Env, in_amount: U256) -> Result<usize, SwapError>;
+}
+
+struct Evaluation {
+    in_amount: U256,
+    out_amount: U256,
+    gas_used: u64,
+    calculation_results: Vec<CalculationResult>,
+    // profit minus gas costs in the first token
+    score: I256,
+}
+
+impl Evaluation {
+    fn profit(&self) -> I256 {
+        I256::from_raw(self.out_amount) - I256::from_raw(self.in_amount)
+    }
+
+    fn apply(self, swap_line: &mut SwapLine) {
+        swap_line.amount_in = SwapAmountType::Set(self.in_amount);
+        swap_line.amount_out = SwapAmountType::Set(self.out_amount);
+        swap_line.gas_used = Some(self.gas_used);
+        swap_line.calculation_results = self.calculation_results;
+    }
+}
+
+// Profit of a swap line for an in amount, optionally reduced by the gas costs at the block base fee
+struct Objective<'a> {
+    swap_line: &'a SwapLine,
+    state: &'a LoomDBType,
+    env: Env,
+    with_gas_costs: bool,
+    evaluations: usize,
+}
+
+impl<'a> Objective<'a> {
+    fn new(swap_line: &'a SwapLine, state: &'a LoomDBType, env: Env, with_gas_costs: bool) -> Self {
+        Self { swap_line, state, env, with_gas_costs, evaluations: 0 }
+    }
+
+    fn evaluate(&mut self, in_amount: U256) -> Result<Evaluation, SwapError> {
+        self.evaluations += 1;
+        let (out_amount, gas_used, calculation_results) =
+            self.swap_line.calculate_with_in_amount(self.state, self.env.clone(), in_amount)?;
+
+        let mut score = I256::from_raw(out_amount) - I256::from_raw(in_amount);
+        if self.with_gas_costs {
+            let gas_costs = U256::from(gas_used) * self.env.block.basefee;
+            if let Some(gas_costs) = self.swap_line.get_first_token().and_then(|token| token.calc_token_value_from_eth(gas_costs)) {
+                score -= I256::from_raw(gas_costs);
+            }
+        }
+
+        Ok(Evaluation { in_amount, out_amount, gas_used, calculation_results, score })
+    }
+
+    fn score(&mut self, in_amount: U256) -> Option<Evaluation> {
+        if in_amount.is_zero() {
+            return None;
+        }
+        self.evaluate(in_amount).ok()
+    }
+}
+
+fn score_of(evaluation: &Option<Evaluation>) -> I256 {
+    evaluation.as_ref().map_or(I256::MIN, |evaluation| evaluation.score)
+}
+
+/// Step search changing the in amount by decreasing percentages, limited to 30 evaluations.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StepSearchOptimizer {}
+
+impl SwapLineOptimizer for StepSearchOptimizer {
+    fn optimize(&self, swap_line: &mut SwapLine, state: &LoomDBType, env: Env, in_amount: U256) -> Result<usize, SwapError> {
+        let mut current_in_amount = in_amount;
+        let mut best_profit: Option<I256> = None;
+        let mut current_step = U256::from(10000);
+        let mut inc_direction = true;
+        let mut first_step_change = false;
+        let mut next_amount = current_in_amount;
+        let mut prev_in_amount = U256::ZERO;
+        let mut counter = 0;
+        let denominator = U256::from(1000);
+
+        loop {
+            counter += 1;
+            //let next_amount  = current_in_amount + (current_in_amount * current_step / 10000);
+
+            if counter > 30 {
+                debug!("optimize_swap_path_in_amount iterations exceeded : {swap_line} {current_in_amount} {current_step}");
+                return Ok(counter - 1);
+            }
+
+            let (current_out_amount, current_gas_used, calculation_results) =
+                match swap_line.calculate_with_in_amount(state, env.clone(), next_amount) {
+                    Ok(ret) => ret,
+                    Err(e) => {
+                        if counter == 1 {
+                            // break if first swap already fails
+                            return Err(e);
+                        }
+                        (U256::ZERO, 0, vec![])
+                    }
+                };
+
+            let current_profit = I256::from_raw(current_out_amount) - I256::from_raw(next_amount);
+
+            if best_profit.is_none() {
+                best_profit = Some(current_profit);
+                swap_line.amount_in = SwapAmountType::Set(next_amount);
+                swap_line.amount_out = SwapAmountType::Set(current_out_amount);
+                swap_line.gas_used = Some(current_gas_used);
+                swap_line.calculation_results = calculation_results;
+                current_in_amount = next_amount;
+                if current_out_amount.is_zero() || current_profit.is_negative() {
+                    return Ok(counter);
+                }
+            } else if best_profit.unwrap() > current_profit || current_out_amount.is_zero()
+            /*|| next_profit < current_profit*/
+            {
+                if first_step_change && inc_direction && current_step < denominator {
+                    inc_direction = false;
+                    //TODO : Check why not used
+                    next_amount = prev_in_amount;
+                    current_in_amount = prev_in_amount;
+                    first_step_change = true;
+                    //debug!("inc direction changed {} {} {}", next_amount, current_profit, bestprofit.unwrap());
+                } else if first_step_change && !inc_direction {
+                    //TODO : Check why is self aligned
+                    inc_direction = true;
+                    current_step /= U256::from(10);
+                    best_profit = Some(current_profit);
+                    first_step_change = true;
+                    //debug!("dec direction changed  {} {} {}", next_amount, current_profit, bestprofit.unwrap());
+
+                    if current_step == U256::from(1) {
+                        break;
+                    }
+                } else {
+                    current_step /= U256::from(10);
+                    first_step_change = true;
+                    if current_step == U256::from(1) {
+                        break;
+                    }
+                }
+            } else {
+                best_profit = Some(current_profit);
+                swap_line.amount_in = SwapAmountType::Set(next_amount);
+                swap_line.amount_out = SwapAmountType::Set(current_out_amount);
+                swap_line.gas_used = Some(current_gas_used);
+                swap_line.calculation_results = calculation_results;
+                current_in_amount = next_amount;
+                first_step_change = false;
+            }
+
+            prev_in_amount = current_in_amount;
+            if inc_direction {
+                next_amount = current_in_amount + (current_in_amount * current_step / denominator);
+            } else {
+                next_amount = current_in_amount - (current_in_amount * current_step / denominator);
+            }
+            //trace!("opt step : {} direction {} first_step {} step : {} current_in_amount : {} next_amount: {} profit : {} {}", counter, inc_direction, first_step_change,  current_step, current_in_amount , next_amount, current_profit, bestprofit.unwrap());
+        }
+
+        Ok(counter)
+    }
+}
+
+/// Brackets the best in amount by doubling or halving the start amount and narrows the bracket with a golden-section search.
+/// The profit is reduced by the gas costs of the pools at the block base fee, so gas used depending on the amount is respected.
+#[derive(Clone, Copy, Debug)]
+pub struct GoldenSectionOptimizer {
+    /// Maximum number of evaluated in amounts
+    pub max_evaluations: usize,
+    /// Maximum number of doublings or halvings of the start amount to bracket the best amount
+    pub max_bracket_steps: usize,
+    /// The search stops if the bracket is narrower than the best amount divided by the precision
+    pub precision: u64,
+}
+
+impl Default for GoldenSectionOptimizer {
+    fn default() -> Self {
+        Self { max_evaluations: 40, max_bracket_steps: 16, precision: 1000 }
+    }
+}
+
+impl SwapLineOptimizer for GoldenSectionOptimizer {
+    fn optimize(&self, swap_line: &mut SwapLine, state: &LoomDBType, env: Env, in_amount: U256) -> Result<usize, SwapError> {
+        let mut objective = Objective::new(swap_line, state, env, true);
+
+        // break if first swap already fails
+        let start = objective.evaluate(in_amount)?;
+
+        // bracket (low, mid, high) with the best amount found at mid
+        let mut low = U256::ZERO;
+        let mut mid_amount = in_amount;
+        let mut mid = Some(start);
+        let mut high_amount = in_amount.saturating_mul(U256::from(2));
+        let mut high = objective.score(high_amount);
+
+        if score_of(&high) > score_of(&mid) {
+            // grow while the profit increases, the last high amount is not evaluated if the steps are exceeded
+            let mut steps = 0;
+            while score_of(&high) > score_of(&mid) {
+                low = mid_amount;
+                mid_amount = high_amount;
+                mid = high;
+                high_amount = high_amount.saturating_mul(U256::from(2));
+                steps += 1;
+                if steps >= self.max_bracket_steps {
+                    break;
+                }
+                high = objective.score(high_amount);
+            }
+        } else {
+            // shrink while the profit increases
+            for _ in 0..self.max_bracket_steps {
+                let low_amount = mid_amount / U256::from(2);
+                let low_eval = objective.score(low_amount);
+                if score_of(&low_eval) <= score_of(&mid) {
+                    low = low_amount;
+                    break;
+                }
+                high_amount = mid_amount;
+                mid_amount = low_amount;
+                mid = low_eval;
+            }
+        }
+
+        // golden section, the next amount is placed in the larger part of the bracket
+        while objective.evaluations < self.max_evaluations {
+            let width = high_amount - low;
+            if width.is_zero() || width <= mid_amount / U256::from(self.precision) {
+                break;
+            }
+
+            let next_amount = if high_amount - mid_amount > mid_amount - low {
+                mid_amount + (high_amount - mid_amount) * U256::from(382) / U256::from(1000)
+            } else {
+                mid_amount - (mid_amount - low) * U256::from(382) / U256::from(1000)
+            };
+            if next_amount == mid_amount {
+                break;
+            }
+
+            let next = objective.score(next_amount);
+            if score_of(&next) > score_of(&mid) {
+                if next_amount > mid_amount {
+                    low = mid_amount;
+                } else {
+                    high_amount = mid_amount;
+                }
+                mid_amount = next_amount;
+                mid = next;
+            } else if next_amount > mid_amount {
+                high_amount = next_amount;
+            } else {
+                low = next_amount;
+            }
+        }
+
+        let evaluations = objective.evaluations;
+        if let Some(best) = mid {
+            best.apply(swap_line);
+        }
+        Ok(evaluations)
+    }
+}
+
+/// Calculates the optimal in amount of swap lines with constant product pools only in closed form from the virtual reserves
+/// of the path. Other swap lines are optimized with the fallback optimizer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConstantProductOptimizer {
+    pub fallback: GoldenSectionOptimizer,
+}
+
+impl ConstantProductOptimizer {
+    /// Optimal in amount if all pools are constant product pools and no token is taxed, zero if the swap line is not profitable
+    pub fn optimal_in_amount(swap_line: &SwapLine, state: &LoomDBType, env: Env) -> Option<U256> {
+        let fee_denominator = U256::from(FEE_DENOMINATOR);
+        let tokens = swap_line.tokens();
+        if tokens.iter().any(|token| token.is_taxed()) {
+            return None;
+        }
+
+        // reserves of a single pool with the same out amounts as the path and the fee of the first pool
+        let mut virtual_reserves: Option<(U256, U256, U256)> = None;
+
+        for (i, pool) in swap_line.pools().iter().enumerate() {
+            let (reserve_in, reserve_out, fee) =
+                pool.get_constant_product_reserves(state, env.clone(), &tokens[i].get_address(), &tokens[i + 1].get_address())?;
+
+            virtual_reserves = match virtual_reserves {
+                None => Some((reserve_in, reserve_out, fee)),
+                Some((virtual_in, virtual_out, first_fee)) => {
+                    let denominator = reserve_in.checked_mul(fee_denominator)?.checked_add(virtual_out.checked_mul(fee)?)?;
+                    let virtual_in = virtual_in.checked_mul(reserve_in)?.checked_mul(fee_denominator)?.checked_div(denominator)?;
+                    let virtual_out = virtual_out.checked_mul(fee)?.checked_mul(reserve_out)?.checked_div(denominator)?;
+                    Some((virtual_in, virtual_out, first_fee))
+                }
+            };
+        }
+
+        let (virtual_in, virtual_out, fee) = virtual_reserves?;
+        if fee.is_zero() {
+            return None;
+        }
+
+        // maximum of fee * virtual_out * x / (virtual_in + fee * x) - x
+        let root = fee.checked_mul(fee_denominator)?.checked_mul(virtual_in)?.checked_mul(virtual_out)?.root(2);
+        Some(root.saturating_sub(virtual_in.checked_mul(fee_denominator)?) / fee)
+    }
+}
+
+impl SwapLineOptimizer for ConstantProductOptimizer {
+    fn optimize(&self, swap_line: &mut SwapLine, state: &LoomDBType, env: Env, in_amount: U256) -> Result<usize, SwapError> {
+        let Some(optimal_in_amount) = Self::optimal_in_amount(swap_line, state, env.clone()) else {
+            return self.fallback.optimize(swap_line, state, env, in_amount);
+        };
+
+        let mut objective = Objective::new(swap_line, state, env.clone(), false);
+        let evaluation = match optimal_in_amount.is_zero() {
+            // not profitable, amounts are set for the start amount
+            true => objective.evaluate(in_amount)?,
+            false => match objective.evaluate(optimal_in_amount) {
+                Ok(evaluation) if !evaluation.profit().is_negative() => evaluation,
+                _ => {
+                    debug!("Closed form in amount {} not profitable for {}", optimal_in_amount, swap_line);
+                    return self.fallback.optimize(swap_line, state, env, in_amount).map(|evaluations| evaluations + 1);
+                }
+            },
+        };
+
+        let evaluations = objective.evaluations;
+        evaluation.apply(swap_line);
+        Ok(evaluations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::DefaultAbiSwapEncoder;
+    use crate::required_state::RequiredState;
+    use crate::{AbiSwapEncoder, Pool, PoolWrapper, SwapPath, Token, TransferTax};
+    use alloy_primitives::utils::parse_units;
+    use alloy_primitives::Address;
+    use eyre::{eyre, ErrReport, Result};
+    use loom_defi_address_book::TokenAddress;
+    use std::sync::Arc;
+
+    // Constant product pool with fixed reserves
+    #[derive(Clone)]
+    struct ConstantProductPool {
+        address: Address,
+        token0: Address,
+        token1: Address,
+        reserve0: U256,
+        reserve1: U256,
+        fee: U256,
+    }
+
+    impl ConstantProductPool {
+        fn reserves(&self, token_address_from: &Address) -> (U256, U256) {
+            if *token_address_from == self.token0 {
+                (self.reserve0, self.reserve1)
+            } else {
+                (self.reserve1, self.reserve0)
+            }
+        }
+    }
+
+    impl Pool for ConstantProductPool {
+        fn get_address(&self) -> Address {
+            self.address
+        }
+
+        fn get_tokens(&self) -> Vec<Address> {
+            vec![self.token0, self.token1]
+        }
+
+        fn calculate_out_amount(
+            &self,
+            _state: &LoomDBType,
+            _env: Env,
+            token_address_from: &Address,
+            _token_address_to: &Address,
+            in_amount: U256,
+        ) -> Result<(U256, u64), ErrReport> {
+            let (reserve_in, reserve_out) = self.reserves(token_address_from);
+            let amount_in_with_fee = in_amount * self.fee;
+            let out_amount = amount_in_with_fee * reserve_out / (reserve_in * U256::from(FEE_DENOMINATOR) + amount_in_with_fee);
+            Ok((out_amount, 100_000))
+        }
+
+        fn calculate_in_amount(
+            &self,
+            _state: &LoomDBType,
+            _env: Env,
+            _token_address_from: &Address,
+            _token_address_to: &Address,
+            _out_amount: U256,
+        ) -> Result<(U256, u64), ErrReport> {
+            Err(eyre!("NOT_IMPLEMENTED"))
+        }
+
+        fn get_constant_product_reserves(
+            &self,
+            _state: &LoomDBType,
+            _env: Env,
+            token_address_from: &Address,
+            _token_address_to: &Address,
+        ) -> Option<(U256, U256, U256)> {
+            let (reserve_in, reserve_out) = self.reserves(token_address_from);
+            Some((reserve_in, reserve_out, self.fee))
+        }
+
+        fn can_flash_swap(&self) -> bool {
+            false
+        }
+
+        fn get_encoder(&self) -> &dyn AbiSwapEncoder {
+            &DefaultAbiSwapEncoder {}
+        }
+
+        fn get_state_required(&self) -> Result<RequiredState> {
+            Ok(RequiredState::new())
+        }
+    }
+
+    // WETH -> token -> WETH with the token price of the second pool lower
+    fn swap_line(price_0: u64, price_1: u64) -> SwapLine {
+        swap_line_with_tax(price_0, price_1, TransferTax::default())
+    }
+
+    fn swap_line_with_tax(price_0: u64, price_1: u64, tax: TransferTax) -> SwapLine {
+        let mut weth = Token::new_with_data(TokenAddress::WETH, Some("WETH".to_string()), None, Some(18), true, false);
+        weth.set_wrapped_native();
+        let weth = Arc::new(weth);
+        let mut token = Token::new_with_data(Address::repeat_byte(1), Some("TKN".to_string()), None, Some(18), false, false);
+        token.set_tax(tax);
+        let token = Arc::new(token);
+        let weth_reserve: U256 = parse_units("100", "ether").unwrap().get_absolute();
+
+        let pool_0 = ConstantProductPool {
+            address: Address::repeat_byte(2),
+            token0: weth.get_address(),
+            token1: token.get_address(),
+            reserve0: weth_reserve,
+            reserve1: weth_reserve * U256::from(price_0),
+            fee: U256::from(9970),
+        };
+        let pool_1 = ConstantProductPool {
+            address: Address::repeat_byte(3),
+            token0: weth.get_address(),
+            token1: token.get_address(),
+            reserve0: weth_reserve,
+            reserve1: weth_reserve * U256::from(price_1),
+            fee: U256::from(9970),
+        };
+
+        SwapLine::from(SwapPath::new(vec![weth.clone(), token, weth], vec![PoolWrapper::from(pool_0), PoolWrapper::from(pool_1)]))
+    }
+
+    fn start_amount() -> U256 {
+        parse_units("0.01", "ether").unwrap().get_absolute()
+    }
+
+    #[test]
+    fn test_constant_product_optimal_in_amount() {
+        let state = LoomDBType::default();
+        let mut swap_line = swap_line(2000, 1900);
+
+        let evaluations = ConstantProductOptimizer::default().optimize(&mut swap_line, &state, Env::default(), start_amount()).unwrap();
+        assert_eq!(evaluations, 1);
+
+        let in_amount = swap_line.amount_in.unwrap();
+        let profit = swap_line.profit().unwrap();
+        assert!(profit.is_positive());
+
+        // no better amount around the optimum
+        for amount in [in_amount * U256::from(99) / U256::from(100), in_amount * U256::from(101) / U256::from(100)] {
+            let (out_amount, _, _) = swap_line.calculate_with_in_amount(&state, Env::default(), amount).unwrap();
+            assert!(I256::from_raw(out_amount) - I256::from_raw(amount) <= profit);
+        }
+    }
+
+    #[test]
+    fn test_golden_section_close_to_optimum() {
+        let state = LoomDBType::default();
+
+        let mut optimal_swap_line = swap_line(2000, 1900);
+        ConstantProductOptimizer::default().optimize(&mut optimal_swap_line, &state, Env::default(), start_amount()).unwrap();
+        let optimal_profit = optimal_swap_line.profit().unwrap();
+
+        let mut swap_line = swap_line(2000, 1900);
+        let optimizer = GoldenSectionOptimizer::default();
+        let evaluations = optimizer.optimize(&mut swap_line, &state, Env::default(), start_amount()).unwrap();
+        assert!(evaluations <= optimizer.max_evaluations);
+
+        let profit = swap_line.profit().unwrap();
+        assert!(profit <= optimal_profit);
+        assert!(profit > optimal_profit - optimal_profit / I256::from_raw(U256::from(1000)));
+
+        // the step search finds less profit than the optimum
+        let mut step_swap_line = self::swap_line(2000, 1900);
+        StepSearchOptimizer {}.optimize(&mut step_swap_line, &state, Env::default(), start_amount()).unwrap();
+        assert!(step_swap_line.profit().unwrap() <= optimal_profit);
+    }
+
+    #[test]
+    fn test_not_profitable() {
+        let state = LoomDBType::default();
+
+        for optimizer in [&ConstantProductOptimizer::default() as &dyn SwapLineOptimizer, &GoldenSectionOptimizer::default()] {
+            let mut swap_line = swap_line(2000, 2000);
+            optimizer.optimize(&mut swap_line, &state, Env::default(), start_amount()).unwrap();
+            assert!(swap_line.profit().unwrap().is_negative());
+        }
+    }
+
+    #[test]
+    fn test_taxed_token() {
+        let state = LoomDBType::default();
+        let swap_line = swap_line(2000, 1900);
+        // 5% tax on buys and sells of the intermediate token
+        let taxed_swap_line = swap_line_with_tax(2000, 1900, TransferTax::new(500, 500));
+
+        assert!(ConstantProductOptimizer::optimal_in_amount(&taxed_swap_line, &state, Env::default()).is_none());
+
+        let (out_amount, _, _) = swap_line.calculate_with_in_amount(&state, Env::default(), start_amount()).unwrap();
+        let (taxed_out_amount, _, _) = taxed_swap_line.calculate_with_in_amount(&state, Env::default(), start_amount()).unwrap();
+
+        assert!(taxed_out_amount >= out_amount * U256::from(9000) / U256::from(10000));
+        assert!(taxed_out_amount <= out_amount * U256::from(9025) / U256::from(10000));
+    }
+}
@@ -137,6 +137,42 @@ impl SwapPaths {
     pub fn get_pool_paths_vec(&self, pool_address: &Address) -> Option<Vec<SwapPath>> {
         self.get_pool_paths_hashset(pool_address).map(|set| set.iter().cloned().collect())
     }
+
+    /// Remove all paths passing the pool from the paths and the index of the other pools of the paths. Returns the removed paths.
+    pub fn remove_pool_paths(&mut self, pool_address: &Address) -> Vec<SwapPath> {
+        let Some(pool_paths) = self.pool_paths.remove(pool_address) else {
+            return Vec::new();
+        };
+
+        let mut removed: Vec<SwapPath> = Vec::new();
+        for path in pool_paths.iter() {
+            if !self.paths.remove(path) {
+                continue;
+            }
+            for pool in path.pools.iter() {
+                let address = pool.get_address();
+                if address == *pool_address {
+                    continue;
+                }
+                if let Some(other_pool_paths) = self.pool_paths.get_mut(&address) {
+                    Arc::make_mut(other_pool_paths).retain(|other_path| other_path != path);
+                    if other_pool_paths.is_empty() {
+                        self.pool_paths.remove(&address);
+                    }
+                }
+            }
+            removed.push(path.clone());
+        }
+        removed
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -223,6 +259,42 @@ mod test {
         println!("{paths:?}")
     }
 
+    #[test]
+    fn test_remove_pool_paths() {
+        let basic_token = Token::new(Address::repeat_byte(0x11));
+        let pool_0 = PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(0x20))));
+
+        // all paths pass pool_0 and a pool of their own
+        let paths_vec: Vec<SwapPath> = (0..3)
+            .map(|i| {
+                SwapPath::new(
+                    vec![basic_token.clone(), Token::new(Address::repeat_byte(i)), basic_token.clone()],
+                    vec![pool_0.clone(), PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(i + 1))))],
+                )
+            })
+            .collect();
+        let extra_path = SwapPath::new(
+            vec![basic_token.clone(), Token::new(Address::repeat_byte(0x30)), basic_token.clone()],
+            vec![
+                PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(1)))),
+                PoolWrapper::new(Arc::new(EmptyPool::new(Address::repeat_byte(0x31)))),
+            ],
+        );
+
+        let mut paths = SwapPaths::from(paths_vec.clone());
+        paths.add(extra_path.clone());
+        assert_eq!(paths.len(), 4);
+
+        let removed = paths.remove_pool_paths(&pool_0.get_address());
+
+        assert_eq!(removed.len(), 3);
+        assert_eq!(paths.len(), 1);
+        assert!(paths.get_pool_paths_vec(&pool_0.get_address()).is_none());
+        assert!(paths.get_pool_paths_vec(&Address::repeat_byte(2)).is_none());
+        assert_eq!(paths.get_pool_paths_vec(&Address::repeat_byte(1)), Some(vec![extra_path]));
+        assert!(paths.remove_pool_paths(&pool_0.get_address()).is_empty());
+    }
+
     #[tokio::test]
     async fn async_test() {
         let basic_token = Token::new(Address::repeat_byte(0x11));
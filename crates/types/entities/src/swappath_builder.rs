@@ -2,9 +2,40 @@ use std::collections::{BTreeMap, HashSet};
 use std::sync::Arc;
 
 use crate::{Market, PoolWrapper, SwapPath};
-use alloy_primitives::Address;
+use alloy_primitives::{Address, U256};
 use eyre::Result;
 
+/// Liquidity of a pool for a swap direction. Hops with unknown liquidity are not pruned.
+pub type HopLiquidityFn = Arc<dyn Fn(&PoolWrapper, &Address, &Address) -> Option<U256> + Send + Sync>;
+
+/// Limits of the swap path cycle search
+#[derive(Clone)]
+pub struct SwapPathBuilderConfig {
+    /// Maximum number of pools in a cycle
+    pub max_hops: usize,
+    /// Cycles with more hops only pass basic or middle tokens outside of the pool the search starts from
+    pub max_any_token_hops: usize,
+    /// Hops with a known liquidity below are pruned
+    pub min_liquidity: U256,
+    pub liquidity: Option<HopLiquidityFn>,
+}
+
+impl Default for SwapPathBuilderConfig {
+    fn default() -> Self {
+        Self { max_hops: 4, max_any_token_hops: 3, min_liquidity: U256::ZERO, liquidity: None }
+    }
+}
+
+impl SwapPathBuilderConfig {
+    pub fn with_max_hops(self, max_hops: usize) -> Self {
+        Self { max_hops, ..self }
+    }
+
+    pub fn with_liquidity(self, min_liquidity: U256, liquidity: HopLiquidityFn) -> Self {
+        Self { min_liquidity, liquidity: Some(liquidity), ..self }
+    }
+}
+
 struct SwapPathSet {
     set: HashSet<SwapPath>,
 }
@@ -14,11 +45,10 @@ impl SwapPathSet {
         SwapPathSet { set: HashSet::new() }
     }
 
-    pub fn extend(&mut self, path_vec: Vec<SwapPath>) {
-        for path in path_vec {
-            self.set.insert(path);
-        }
+    pub fn insert(&mut self, path: SwapPath) {
+        self.set.insert(path);
     }
+
     pub fn vec(self) -> Vec<SwapPath> {
         self.set.into_iter().collect()
     }
@@ -28,418 +58,134 @@ impl SwapPathSet {
     }
 }
 
-// (Basic -> Token1) -> (Token1 -> Basic)
-fn build_swap_path_two_hopes_basic_in(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    let Some(token_token_pools) = market.get_token_token_pools(&token_to_address, &token_from_address) else {
-        return Ok(ret);
-    };
-    for pool_address in token_token_pools.iter() {
-        if !market.is_pool_ok(pool_address) {
-            continue;
-        }
-
-        let Some(loop_pool) = market.get_pool(pool_address) else { continue };
-        let token_from = market.get_token_or_default(&token_from_address);
-        let token_to = market.get_token_or_default(&token_to_address);
-
-        let mut swap_path = SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-        if !swap_path.contains_pool(loop_pool) {
-            swap_path.push_swap_hope(token_to, token_from, loop_pool.clone())?;
-            ret.push(swap_path)
-        }
-    }
-    Ok(ret)
+/// Enumerates the cycles through a pool over the token/pool adjacency of the [`Market`].
+/// Only enabled pools are used and a pool or token is passed once per cycle. Cycles are rotated to start at
+/// WETH or the basic token with the lowest address, so every rotation of a cycle results in the same [`SwapPath`].
+/// Cycles without basic token are skipped.
+pub struct SwapPathBuilder<'a> {
+    market: &'a Market,
+    config: &'a SwapPathBuilderConfig,
 }
-// (Basic -> Token1) -> (Token1 -> Token2) -> (Token2 -> Basic)
-fn build_swap_path_three_hopes_basic_in(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    let Some(token_tokens) = market.get_token_tokens(&token_to_address) else {
-        return Ok(ret);
-    };
-    for token_middle_address in token_tokens.iter() {
-        let Some(token_token_pools_1) = market.get_token_token_pools(&token_to_address, token_middle_address) else { continue };
-        let Some(token_token_pools_2) = market.get_token_token_pools(token_middle_address, &token_from_address) else { continue };
-
-        for pool_address_1 in token_token_pools_1.iter() {
-            if !market.is_pool_ok(pool_address_1) {
-                continue;
-            }
-
-            for pool_address_2 in token_token_pools_2.iter() {
-                if !market.is_pool_ok(pool_address_2) {
-                    continue;
-                }
-
-                let Some(pool_1) = market.get_pool(pool_address_1) else { continue };
-                let Some(pool_2) = market.get_pool(pool_address_2) else { continue };
 
-                let token_from = market.get_token_or_default(&token_from_address);
-                let token_to = market.get_token_or_default(&token_to_address);
-                let token_middle = market.get_token_or_default(token_middle_address);
+impl<'a> SwapPathBuilder<'a> {
+    pub fn new(market: &'a Market, config: &'a SwapPathBuilderConfig) -> Self {
+        Self { market, config }
+    }
 
-                let mut swap = SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-                if !swap.contains_pool(pool_1) {
-                    let _ = swap.push_swap_hope(token_to, token_middle.clone(), pool_1.clone());
-                } else {
-                    continue;
-                }
+    /// Build the cycles passing the pools in the given directions
+    pub fn build(&self, directions: &BTreeMap<PoolWrapper, Vec<(Address, Address)>>) -> Result<Vec<SwapPath>> {
+        let mut ret = SwapPathSet::new();
 
-                if !swap.contains_pool(pool_2) {
-                    let _ = swap.push_swap_hope(token_middle, token_from, pool_2.clone());
-                } else {
+        for (pool, directions) in directions.iter() {
+            for (token_from_address, token_to_address) in directions.iter() {
+                if !self.is_hop_ok(pool, token_from_address, token_to_address) {
                     continue;
                 }
-
-                ret.push(swap)
+                let mut tokens = vec![*token_from_address, *token_to_address];
+                let mut pools = vec![pool.clone()];
+                self.search(&mut tokens, &mut pools, &mut ret);
             }
         }
-    }
-    Ok(ret)
-}
-// (Basic -> Token) -> (Token -> Token) -> (Token -> Token) -> (Token -> Basic)
-fn build_swap_path_four_hopes_basic_in(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    if let Some(token_tokens) = market.get_token_tokens(&token_to_address) {
-        for token_middle_address in token_tokens.iter() {
-            if !market.get_token_or_default(token_middle_address).is_middle() {
-                continue;
-            }
-
-            if let Some(token_tokens_0) = market.get_token_tokens(token_middle_address) {
-                for token_middle_address_0 in token_tokens_0.iter() {
-                    /*if !market.get_token_or_default(token_middle_address_0).is_basic() {
-                        continue;
-                    }
-                     */
 
-                    if let Some(token_token_pools_1) = market.get_token_token_pools(&token_to_address, token_middle_address) {
-                        if let Some(token_token_pools_2) = market.get_token_token_pools(token_middle_address, token_middle_address_0) {
-                            if let Some(token_token_pools_3) = market.get_token_token_pools(token_middle_address_0, &token_from_address) {
-                                for pool_address_1 in token_token_pools_1.iter() {
-                                    if !market.is_pool_ok(pool_address_1) {
-                                        continue;
-                                    }
-                                    for pool_address_2 in token_token_pools_2.iter() {
-                                        if !market.is_pool_ok(pool_address_2) {
-                                            continue;
-                                        }
-                                        for pool_address_3 in token_token_pools_3.iter() {
-                                            if !market.is_pool_ok(pool_address_3) {
-                                                continue;
-                                            }
-                                            if let Some(pool_1) = market.get_pool(pool_address_1) {
-                                                if let Some(pool_2) = market.get_pool(pool_address_2) {
-                                                    if let Some(pool_3) = market.get_pool(pool_address_3) {
-                                                        let token_from = market.get_token_or_default(&token_from_address);
-                                                        let token_to = market.get_token_or_default(&token_to_address);
-                                                        let token_middle = market.get_token_or_default(token_middle_address);
-                                                        let token_middle_0 = market.get_token_or_default(token_middle_address_0);
-
-                                                        let mut swap =
-                                                            SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-                                                        if !swap.contains_pool(pool_1) {
-                                                            let _ = swap.push_swap_hope(token_to, token_middle.clone(), pool_1.clone());
-                                                        } else {
-                                                            continue;
-                                                        }
-
-                                                        if !swap.contains_pool(pool_2) {
-                                                            let _ =
-                                                                swap.push_swap_hope(token_middle, token_middle_0.clone(), pool_2.clone());
-                                                        } else {
-                                                            continue;
-                                                        }
+        Ok(ret.vec())
+    }
 
-                                                        if !swap.contains_pool(pool_3) {
-                                                            let _ = swap.push_swap_hope(token_middle_0, token_from, pool_3.clone());
-                                                        } else {
-                                                            continue;
-                                                        }
+    /// Build the cycles passing the pool in all its swap directions
+    pub fn build_pool(&self, pool: &PoolWrapper) -> Result<Vec<SwapPath>> {
+        let mut directions = BTreeMap::new();
+        directions.insert(pool.clone(), pool.get_swap_directions());
+        self.build(&directions)
+    }
 
-                                                        ret.push(swap)
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+    // tokens has one more entry than pools while searching, the cycle is closed by a pool swapping the last token back to the first one
+    fn search(&self, tokens: &mut Vec<Address>, pools: &mut Vec<PoolWrapper>, ret: &mut SwapPathSet) {
+        let (Some(&token_start_address), Some(&token_last_address)) = (tokens.first(), tokens.last()) else {
+            return;
+        };
+
+        if let Some(pool_addresses) = self.market.get_token_token_pools_ptr(&token_last_address, &token_start_address) {
+            for pool_address in pool_addresses.iter() {
+                let Some(pool) = self.next_pool(pools, pool_address, &token_last_address, &token_start_address) else { continue };
+                pools.push(pool);
+                if let Some(swap_path) = self.cycle_swap_path(tokens, pools) {
+                    ret.insert(swap_path);
                 }
+                pools.pop();
             }
         }
-    }
-    Ok(ret)
-}
-
-fn build_swap_path_two_hopes_basic_out(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    if let Some(token_token_pools) = market.get_token_token_pools(&token_to_address, &token_from_address) {
-        for pool_address in token_token_pools.iter() {
-            if !market.is_pool_ok(pool_address) {
-                continue;
-            }
 
-            if let Some(loop_pool) = market.get_pool(pool_address) {
-                let token_from = market.get_token_or_default(&token_from_address);
-                let token_to = market.get_token_or_default(&token_to_address);
-
-                let mut swap = SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-                if !swap.contains_pool(loop_pool) {
-                    let _ = swap.insert_swap_hope(token_to, token_from, loop_pool.clone());
-                    ret.push(swap)
-                }
-            }
+        // at least one hop to the next token and one to close the cycle
+        let min_hops = pools.len() + 2;
+        if min_hops > self.config.max_hops {
+            return;
         }
-    }
-    Ok(ret)
-}
 
-fn build_swap_path_three_hopes_basic_out(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    let Some(token_tokens) = market.get_token_tokens(&token_from_address) else {
-        return Ok(vec![]);
-    };
-    for token_middle_address in token_tokens.iter() {
-        let Some(token_token_pools_1) = market.get_token_token_pools(&token_to_address, token_middle_address) else { continue };
-        let Some(token_token_pools_2) = market.get_token_token_pools(token_middle_address, &token_from_address) else { continue };
-        for pool_address_1 in token_token_pools_1.iter() {
-            if !market.is_pool_ok(pool_address_1) {
+        let Some(token_next_addresses) = self.market.get_token_tokens_ptr(&token_last_address) else {
+            return;
+        };
+
+        // token_tokens has an entry per pool
+        let mut visited: HashSet<Address> = HashSet::new();
+        for token_next_address in token_next_addresses.iter() {
+            if tokens.contains(token_next_address) || !visited.insert(*token_next_address) {
                 continue;
             }
 
-            for pool_address_2 in token_token_pools_2.iter() {
-                if !market.is_pool_ok(pool_address_2) {
-                    continue;
-                }
-                let Some(pool_1) = market.get_pool(pool_address_1) else { continue };
-                let Some(pool_2) = market.get_pool(pool_address_2) else { continue };
-                let token_from = market.get_token_or_default(&token_from_address);
-                let token_to = market.get_token_or_default(&token_to_address);
-                let token_middle = market.get_token_or_default(token_middle_address);
-
-                let mut swap = SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-                if !swap.contains_pool(pool_2) {
-                    let _ = swap.insert_swap_hope(token_middle.clone(), token_from.clone(), pool_2.clone());
-                } else {
+            if min_hops > self.config.max_any_token_hops {
+                let token_next = self.market.get_token_or_default(token_next_address);
+                if !token_next.is_basic() && !token_next.is_middle() {
                     continue;
                 }
+            }
 
-                if !swap.contains_pool(pool_1) {
-                    let _ = swap.insert_swap_hope(token_to, token_middle, pool_1.clone());
-                } else {
-                    continue;
-                }
+            let Some(pool_addresses) = self.market.get_token_token_pools_ptr(&token_last_address, token_next_address) else { continue };
 
-                ret.push(swap)
+            for pool_address in pool_addresses.iter() {
+                let Some(pool) = self.next_pool(pools, pool_address, &token_last_address, token_next_address) else { continue };
+                pools.push(pool);
+                tokens.push(*token_next_address);
+                self.search(tokens, pools, ret);
+                tokens.pop();
+                pools.pop();
             }
         }
     }
-    Ok(ret)
-}
-
-fn build_swap_path_four_hopes_basic_out(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    if let Some(token_tokens) = market.get_token_tokens(&token_from_address) {
-        for token_middle_address in token_tokens.iter() {
-            if !market.get_token_or_default(token_middle_address).is_middle() {
-                continue;
-            }
-            if let Some(token_tokens_2) = market.get_token_tokens(token_middle_address) {
-                for token_middle_address_0 in token_tokens_2.iter() {
-                    /*if !market.get_token_or_default(token_middle_address_0).is_basic() {
-                        continue;
-                    }
-                     */
-
-                    if let Some(token_token_pools_0) = market.get_token_token_pools(&token_to_address, token_middle_address_0) {
-                        if let Some(token_token_pools_1) = market.get_token_token_pools(token_middle_address_0, token_middle_address) {
-                            if let Some(token_token_pools_2) = market.get_token_token_pools(token_middle_address, &token_from_address) {
-                                for pool_address_0 in token_token_pools_0.iter() {
-                                    if !market.is_pool_ok(pool_address_0) {
-                                        continue;
-                                    }
 
-                                    for pool_address_1 in token_token_pools_1.iter() {
-                                        if !market.is_pool_ok(pool_address_1) {
-                                            continue;
-                                        }
-
-                                        for pool_address_2 in token_token_pools_2.iter() {
-                                            if !market.is_pool_ok(pool_address_2) {
-                                                continue;
-                                            }
-
-                                            if let Some(pool_0) = market.get_pool(pool_address_0) {
-                                                if let Some(pool_1) = market.get_pool(pool_address_1) {
-                                                    if let Some(pool_2) = market.get_pool(pool_address_2) {
-                                                        let token_from = market.get_token_or_default(&token_from_address);
-                                                        let token_to = market.get_token_or_default(&token_to_address);
-                                                        let token_middle = market.get_token_or_default(token_middle_address);
-                                                        let token_middle_0 = market.get_token_or_default(token_middle_address_0);
-
-                                                        let mut swap =
-                                                            SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-
-                                                        if !swap.contains_pool(pool_2) {
-                                                            let _ = swap.insert_swap_hope(
-                                                                token_middle.clone(),
-                                                                token_from.clone(),
-                                                                pool_2.clone(),
-                                                            );
-                                                        } else {
-                                                            continue;
-                                                        }
-
-                                                        if !swap.contains_pool(pool_1) {
-                                                            let _ =
-                                                                swap.insert_swap_hope(token_middle_0.clone(), token_middle, pool_1.clone());
-                                                        } else {
-                                                            continue;
-                                                        }
-
-                                                        if !swap.contains_pool(pool_0) {
-                                                            let _ = swap.insert_swap_hope(token_to, token_middle_0, pool_0.clone());
-                                                        } else {
-                                                            continue;
-                                                        }
-
-                                                        ret.push(swap)
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    fn next_pool(&self, pools: &[PoolWrapper], pool_address: &Address, token_from: &Address, token_to: &Address) -> Option<PoolWrapper> {
+        if pools.iter().any(|pool| pool.get_address() == *pool_address) {
+            return None;
         }
+        let pool = self.market.get_pool(pool_address)?;
+        self.is_hop_ok(pool, token_from, token_to).then(|| pool.clone())
     }
-    Ok(ret)
-}
-
-// (Token -> Token) -> (Token -> Token) -> (Token -> Token)
-fn build_swap_path_three_hopes_no_basic(
-    market: &Market,
-    pool: &PoolWrapper,
-    token_from_address: Address,
-    token_to_address: Address,
-) -> Result<Vec<SwapPath>> {
-    let mut ret: Vec<SwapPath> = Vec::new();
-    if let Some(token_tokens) = market.get_token_tokens(&token_from_address) {
-        for token_basic_address in token_tokens.iter() {
-            let token_basic = market.get_token_or_default(token_basic_address);
-            if !token_basic.is_basic() {
-                continue;
-            }
-
-            if let Some(token_token_pools_1) = market.get_token_token_pools(token_basic_address, &token_from_address) {
-                if let Some(token_token_pools_2) = market.get_token_token_pools(&token_to_address, token_basic_address) {
-                    for pool_address_1 in token_token_pools_1.iter() {
-                        if !market.is_pool_ok(pool_address_1) {
-                            continue;
-                        }
-
-                        for pool_address_2 in token_token_pools_2.iter() {
-                            if !market.is_pool_ok(pool_address_2) {
-                                continue;
-                            }
-
-                            if let Some(pool_1) = market.get_pool(pool_address_1) {
-                                if let Some(pool_2) = market.get_pool(pool_address_2) {
-                                    let token_from = market.get_token_or_default(&token_from_address);
-                                    let token_to = market.get_token_or_default(&token_to_address);
-
-                                    let mut swap = SwapPath::new_swap(token_from.clone(), token_to.clone(), pool.clone());
-                                    if !swap.contains_pool(pool_1) {
-                                        let _ = swap.insert_swap_hope(token_basic.clone(), token_from.clone(), pool_1.clone());
-                                    } else {
-                                        continue;
-                                    }
 
-                                    if !swap.contains_pool(pool_2) {
-                                        let _ = swap.push_swap_hope(token_to, token_basic.clone(), pool_2.clone());
-                                    } else {
-                                        continue;
-                                    }
-
-                                    ret.push(swap)
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    fn is_hop_ok(&self, pool: &PoolWrapper, token_from: &Address, token_to: &Address) -> bool {
+        if !self.market.is_pool_ok(&pool.get_address()) {
+            return false;
+        }
+        match &self.config.liquidity {
+            Some(liquidity) => liquidity(pool, token_from, token_to).map_or(true, |liquidity| liquidity >= self.config.min_liquidity),
+            None => true,
         }
     }
-    Ok(ret)
-}
 
-pub fn build_swap_path_vec(market: &Market, directions: &BTreeMap<PoolWrapper, Vec<(Address, Address)>>) -> Result<Vec<SwapPath>> {
-    let mut ret_map = SwapPathSet::new();
-
-    for (pool, directions) in directions.iter() {
-        for direction in directions.iter() {
-            let token_from_address = direction.0;
-            let token_to_address = direction.1;
-
-            if market.is_basic_token(&token_to_address) {
-                ret_map.extend(build_swap_path_two_hopes_basic_out(market, pool, token_from_address, token_to_address)?);
-                ret_map.extend(build_swap_path_three_hopes_basic_out(market, pool, token_from_address, token_to_address)?);
-                ret_map.extend(build_swap_path_four_hopes_basic_out(market, pool, token_from_address, token_to_address)?);
-            }
-
-            if market.is_basic_token(&token_from_address) {
-                ret_map.extend(build_swap_path_two_hopes_basic_in(market, pool, token_from_address, token_to_address)?);
-                ret_map.extend(build_swap_path_three_hopes_basic_in(market, pool, token_from_address, token_to_address)?);
-
-                if market.is_basic_token(&token_to_address) {
-                    ret_map.extend(build_swap_path_four_hopes_basic_in(market, pool, token_from_address, token_to_address)?);
-                }
-            }
-
-            if (!market.is_basic_token(&token_from_address) && !market.is_basic_token(&token_to_address))
-                || (!Market::is_weth(&token_from_address) && !Market::is_weth(&token_to_address))
-            {
-                ret_map.extend(build_swap_path_three_hopes_no_basic(market, pool, token_from_address, token_to_address)?);
-            }
+    // Rotate the cycle to start at the preferred basic token, tokens and pools have the same length for a closed cycle
+    fn cycle_swap_path(&self, tokens: &[Address], pools: &[PoolWrapper]) -> Option<SwapPath> {
+        let start = (0..pools.len())
+            .filter(|&i| self.market.is_basic_token(&tokens[i]))
+            .min_by_key(|&i| (!self.market.is_weth(&tokens[i]), tokens[i]))?;
+
+        let mut swap_path = SwapPath::default();
+        for i in 0..pools.len() {
+            let idx = (start + i) % pools.len();
+            let token_from = self.market.get_token_or_default(&tokens[idx]);
+            let token_to = self.market.get_token_or_default(&tokens[(idx + 1) % pools.len()]);
+            swap_path.push_swap_hope(token_from, token_to, pools[idx].clone()).ok()?;
         }
+        Some(swap_path)
     }
+}
 
-    Ok(ret_map.vec())
+pub fn build_swap_path_vec(market: &Market, directions: &BTreeMap<PoolWrapper, Vec<(Address, Address)>>) -> Result<Vec<SwapPath>> {
+    SwapPathBuilder::new(market, market.swap_path_config()).build(directions)
 }
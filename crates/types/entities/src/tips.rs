@@ -2,13 +2,11 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
-use crate::{Swap, Token};
+use crate::{Swap, TipsPolicy, Token};
 use alloy_primitives::utils::format_units;
 use alloy_primitives::{Address, U256};
 use eyre::{eyre, OptionExt, Result};
-use lazy_static::lazy_static;
 use loom_evm_utils::NWETH;
-use rand::random;
 use tracing::{error, info};
 
 #[derive(Clone, Debug)]
@@ -34,42 +32,16 @@ impl Display for Tips {
     }
 }
 
-lazy_static! {
-    static ref START_PCT : U256 = U256::from(9900);
-
-    static ref SLOPES : Vec<(U256,U256)> = vec![
-//x        (U256::from(0), U256::from(10000)),
-        (U256::from(10).pow(U256::from(19)), U256::from(7000)),
-        (U256::from(10).pow(U256::from(19))*U256::from(5), U256::from(5000))
-    ];
-}
-pub fn tips_pct_advanced(profit: &U256) -> u32 {
-    let mut start_point = U256::ZERO;
-    let mut start_pct = *START_PCT;
-    for (x, y) in SLOPES.iter() {
-        if x > profit {
-            return (start_pct - ((start_pct - y) * (profit - start_point) / (x - start_point))).to::<u32>();
-        }
-        start_point = *x;
-        start_pct = *y;
-    }
-    start_pct.to()
-}
-
-pub fn randomize_tips_pct(tips_pct: u32) -> u32 {
-    let rnd: u32 = random::<u32>() % 50;
-    tips_pct - rnd
-}
-
 pub fn tips_and_value_for_swap_type(
     swap: &Swap,
     tips_pct: Option<u32>,
     gas_cost: Option<U256>,
     eth_balance: U256,
+    tips_policy: &dyn TipsPolicy,
 ) -> Result<(Vec<Tips>, U256)> {
     let total_profit_eth = swap.abs_profit_eth();
     info!("Total profit eth : {}", format_units(total_profit_eth, "ether").unwrap_or_default());
-    let tips_pct = randomize_tips_pct(tips_pct.unwrap_or(tips_pct_advanced(&total_profit_eth)));
+    let tips_pct = tips_policy.tips_pct(swap, total_profit_eth, tips_pct);
 
     if let Some(gas_cost) = gas_cost {
         if total_profit_eth < gas_cost {
@@ -0,0 +1,415 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
+
+use alloy_primitives::{Address, U256};
+use eyre::{eyre, Result};
+use rand::random;
+use serde::Deserialize;
+
+use crate::Swap;
+
+const PCT_DENOMINATOR: u32 = 10000;
+
+/// Bundle landed for a stuffing tx we backrun, tips are the coinbase payment of the winning backrun
+#[derive(Clone, Debug)]
+pub struct LandedBundle {
+    pub token: Address,
+    /// Profit of our best backrun for the stuffing tx in ETH
+    pub profit_eth: U256,
+    pub tips: U256,
+}
+
+/// Decides which share of the profit is paid as tips
+pub trait TipsPolicy: Send + Sync + Debug {
+    /// Share of the profit after gas in basis points, `requested_pct` is the share set for the compose request
+    fn tips_pct(&self, swap: &Swap, profit_eth: U256, requested_pct: Option<u32>) -> u32;
+
+    /// Share of the best profit or tips of the block a later bundle needs to be broadcast by the smart broadcaster, in basis points
+    fn broadcast_validity_pct(&self) -> u32 {
+        8000
+    }
+
+    /// Called for bundles landed for stuffing txs
+    fn on_landed_bundle(&self, _landed_bundle: &LandedBundle) {}
+}
+
+/// Piecewise linear share decreasing with profit, randomized to not bid the same amount as the last time
+#[derive(Clone, Debug)]
+pub struct CurveTipsPolicy {
+    pub start_pct: u32,
+    /// (profit in ETH wei, pct) points of the curve, profits are increasing
+    pub slopes: Vec<(U256, u32)>,
+    /// Maximum random decrease of the share
+    pub randomize_pct: u32,
+}
+
+impl Default for CurveTipsPolicy {
+    fn default() -> Self {
+        Self {
+            start_pct: 9900,
+            slopes: vec![(U256::from(10).pow(U256::from(19)), 7000), (U256::from(10).pow(U256::from(19)) * U256::from(5), 5000)],
+            randomize_pct: 50,
+        }
+    }
+}
+
+impl CurveTipsPolicy {
+    /// Check that profits of the points are increasing and shares are not
+    pub fn validate(&self) -> Result<()> {
+        if self.start_pct > PCT_DENOMINATOR {
+            return Err(eyre!("TIPS_CURVE_START_PCT_TOO_HIGH"));
+        }
+        let mut prev_profit = U256::ZERO;
+        let mut prev_pct = self.start_pct;
+        for (profit, pct) in self.slopes.iter() {
+            if *profit <= prev_profit {
+                return Err(eyre!("TIPS_CURVE_PROFITS_NOT_INCREASING"));
+            }
+            if *pct > prev_pct {
+                return Err(eyre!("TIPS_CURVE_PCT_INCREASING"));
+            }
+            prev_profit = *profit;
+            prev_pct = *pct;
+        }
+        Ok(())
+    }
+
+    pub fn curve_pct(&self, profit_eth: U256) -> u32 {
+        let mut start_point = U256::ZERO;
+        let mut start_pct = U256::from(self.start_pct);
+        for (x, y) in self.slopes.iter() {
+            let y = U256::from(*y);
+            if *x > profit_eth {
+                return (start_pct - ((start_pct - y) * (profit_eth - start_point) / (x - start_point))).to::<u32>();
+            }
+            start_point = *x;
+            start_pct = y;
+        }
+        start_pct.to()
+    }
+}
+
+impl TipsPolicy for CurveTipsPolicy {
+    fn tips_pct(&self, _swap: &Swap, profit_eth: U256, requested_pct: Option<u32>) -> u32 {
+        let tips_pct = requested_pct.unwrap_or_else(|| self.curve_pct(profit_eth));
+        if self.randomize_pct == 0 {
+            tips_pct
+        } else {
+            tips_pct.saturating_sub(random::<u32>() % self.randomize_pct)
+        }
+    }
+}
+
+/// Same share for every swap, the requested share is ignored
+#[derive(Clone, Debug)]
+pub struct FixedTipsPolicy {
+    pub pct: u32,
+}
+
+impl TipsPolicy for FixedTipsPolicy {
+    fn tips_pct(&self, _swap: &Swap, _profit_eth: U256, _requested_pct: Option<u32>) -> u32 {
+        self.pct
+    }
+}
+
+/// Bids a percentile of the shares paid by the bundles landed for the last stuffing txs plus a margin,
+/// so a single outlier does not set the bid. Requested shares bound the bid to `requested_pct +- deviation_pct`.
+/// The fallback policy is used until bundles landed.
+#[derive(Debug)]
+pub struct CompetitionTipsPolicy {
+    pub fallback: Arc<dyn TipsPolicy>,
+    /// Number of landed bundles considered
+    pub window: usize,
+    /// Percentile of the landed shares in basis points
+    pub percentile_pct: u32,
+    pub margin_pct: u32,
+    /// Maximum distance of the bid from the requested share
+    pub deviation_pct: u32,
+    pub max_pct: u32,
+    landed_pct: RwLock<VecDeque<u32>>,
+}
+
+impl CompetitionTipsPolicy {
+    pub fn new(
+        fallback: Arc<dyn TipsPolicy>,
+        window: usize,
+        percentile_pct: u32,
+        margin_pct: u32,
+        deviation_pct: u32,
+        max_pct: u32,
+    ) -> Self {
+        Self { fallback, window, percentile_pct, margin_pct, deviation_pct, max_pct, landed_pct: RwLock::new(VecDeque::new()) }
+    }
+
+    pub fn competitive_pct(&self) -> Option<u32> {
+        let mut landed_pct: Vec<u32> = self.landed_pct.read().ok()?.iter().copied().collect();
+        if landed_pct.is_empty() {
+            return None;
+        }
+        landed_pct.sort_unstable();
+        // nearest rank
+        let rank = (landed_pct.len() * self.percentile_pct.min(PCT_DENOMINATOR) as usize).div_ceil(PCT_DENOMINATOR as usize);
+        let pct = landed_pct[rank.saturating_sub(1)];
+        Some((pct + self.margin_pct).min(self.max_pct))
+    }
+}
+
+impl TipsPolicy for CompetitionTipsPolicy {
+    fn tips_pct(&self, swap: &Swap, profit_eth: U256, requested_pct: Option<u32>) -> u32 {
+        let Some(competitive_pct) = self.competitive_pct() else {
+            return self.fallback.tips_pct(swap, profit_eth, requested_pct);
+        };
+        match requested_pct {
+            Some(requested_pct) => competitive_pct
+                .clamp(requested_pct.saturating_sub(self.deviation_pct), requested_pct.saturating_add(self.deviation_pct))
+                .min(self.max_pct),
+            None => competitive_pct,
+        }
+    }
+
+    fn on_landed_bundle(&self, landed_bundle: &LandedBundle) {
+        if landed_bundle.profit_eth.is_zero() {
+            return;
+        }
+        let pct =
+            (landed_bundle.tips * U256::from(PCT_DENOMINATOR) / landed_bundle.profit_eth).min(U256::from(PCT_DENOMINATOR)).to::<u32>();
+        if let Ok(mut landed_pct) = self.landed_pct.write() {
+            landed_pct.push_back(pct);
+            while landed_pct.len() > self.window {
+                landed_pct.pop_front();
+            }
+        }
+    }
+}
+
+/// Policies for swaps starting with a token, other swaps use the default policy
+#[derive(Debug)]
+pub struct TokenTipsPolicy {
+    pub default: Arc<dyn TipsPolicy>,
+    pub tokens: HashMap<Address, Arc<dyn TipsPolicy>>,
+    pub broadcast_validity_pct: u32,
+}
+
+impl TokenTipsPolicy {
+    fn policy(&self, token: Option<Address>) -> &Arc<dyn TipsPolicy> {
+        token.and_then(|token| self.tokens.get(&token)).unwrap_or(&self.default)
+    }
+}
+
+impl TipsPolicy for TokenTipsPolicy {
+    fn tips_pct(&self, swap: &Swap, profit_eth: U256, requested_pct: Option<u32>) -> u32 {
+        let token = match swap {
+            Swap::Multiple(swap_vec) => swap_vec.first().and_then(|swap| swap.get_first_token()),
+            _ => swap.get_first_token(),
+        };
+        self.policy(token.map(|token| token.get_address())).tips_pct(swap, profit_eth, requested_pct)
+    }
+
+    fn broadcast_validity_pct(&self) -> u32 {
+        self.broadcast_validity_pct
+    }
+
+    fn on_landed_bundle(&self, landed_bundle: &LandedBundle) {
+        self.policy(Some(landed_bundle.token)).on_landed_bundle(landed_bundle)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CurveTipsConfig {
+    pub start_pct: Option<u32>,
+    /// (profit in ETH, pct) points of the curve
+    pub slopes: Option<Vec<(f64, u32)>>,
+    pub randomize_pct: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FixedTipsConfig {
+    pub pct: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CompetitionTipsConfig {
+    pub window: Option<usize>,
+    pub percentile_pct: Option<u32>,
+    pub margin_pct: Option<u32>,
+    pub deviation_pct: Option<u32>,
+    pub max_pct: Option<u32>,
+    pub fallback: Option<Box<TipsPolicyConfig>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum TipsPolicyConfig {
+    #[serde(rename = "curve")]
+    Curve(CurveTipsConfig),
+    #[serde(rename = "fixed")]
+    Fixed(FixedTipsConfig),
+    #[serde(rename = "competition")]
+    Competition(CompetitionTipsConfig),
+}
+
+impl TipsPolicyConfig {
+    pub fn build(&self) -> Result<Arc<dyn TipsPolicy>> {
+        let policy: Arc<dyn TipsPolicy> = match self {
+            TipsPolicyConfig::Curve(config) => {
+                let default = CurveTipsPolicy::default();
+                let policy = CurveTipsPolicy {
+                    start_pct: config.start_pct.unwrap_or(default.start_pct),
+                    slopes: config
+                        .slopes
+                        .as_ref()
+                        .map(|slopes| slopes.iter().map(|(profit, pct)| (U256::from((profit * 1e18).round() as u128), *pct)).collect())
+                        .unwrap_or(default.slopes),
+                    randomize_pct: config.randomize_pct.unwrap_or(default.randomize_pct),
+                };
+                policy.validate()?;
+                Arc::new(policy)
+            }
+            TipsPolicyConfig::Fixed(config) => Arc::new(FixedTipsPolicy { pct: config.pct }),
+            TipsPolicyConfig::Competition(config) => {
+                let fallback = match config.fallback.as_ref() {
+                    Some(fallback) => fallback.build()?,
+                    None => Arc::new(CurveTipsPolicy::default()),
+                };
+                Arc::new(CompetitionTipsPolicy::new(
+                    fallback,
+                    config.window.unwrap_or(20),
+                    config.percentile_pct.unwrap_or(5000),
+                    config.margin_pct.unwrap_or(100),
+                    config.deviation_pct.unwrap_or(1000),
+                    config.max_pct.unwrap_or(9900),
+                ))
+            }
+        };
+        Ok(policy)
+    }
+}
+
+/// `[tips]` section
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct TipsConfig {
+    pub policy: Option<TipsPolicyConfig>,
+    /// Policies for swaps starting with a token
+    #[serde(default)]
+    pub tokens: HashMap<Address, TipsPolicyConfig>,
+    pub broadcast_validity_pct: Option<u32>,
+}
+
+impl TipsConfig {
+    pub fn build(&self) -> Result<Arc<dyn TipsPolicy>> {
+        let default = match self.policy.as_ref() {
+            Some(policy) => policy.build()?,
+            None => Arc::new(CurveTipsPolicy::default()),
+        };
+        let tokens = self.tokens.iter().map(|(token, config)| Ok((*token, config.build()?))).collect::<Result<HashMap<_, _>>>()?;
+        Ok(Arc::new(TokenTipsPolicy {
+            broadcast_validity_pct: self.broadcast_validity_pct.unwrap_or(default.broadcast_validity_pct()),
+            default,
+            tokens,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PoolWrapper, SwapLine, SwapPath, Token};
+    use alloy_primitives::utils::parse_units;
+
+    fn eth(value: &str) -> U256 {
+        parse_units(value, "ether").unwrap().get_absolute()
+    }
+
+    #[test]
+    fn test_curve_tips_policy() {
+        let policy = CurveTipsPolicy { randomize_pct: 0, ..CurveTipsPolicy::default() };
+
+        assert_eq!(policy.curve_pct(U256::ZERO), 9900);
+        assert_eq!(policy.curve_pct(eth("5")), 8450);
+        assert_eq!(policy.curve_pct(eth("10")), 7000);
+        assert_eq!(policy.curve_pct(eth("100")), 5000);
+        assert_eq!(policy.tips_pct(&Swap::None, eth("100"), Some(9000)), 9000);
+
+        let policy = CurveTipsPolicy::default();
+        let tips_pct = policy.tips_pct(&Swap::None, eth("10"), None);
+        assert!(tips_pct > 7000 - 50 && tips_pct <= 7000);
+    }
+
+    #[test]
+    fn test_competition_tips_policy() {
+        let policy = CompetitionTipsPolicy::new(Arc::new(FixedTipsPolicy { pct: 9000 }), 3, 5000, 100, 1000, 9500);
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), None), 9000);
+
+        let landed_bundle = |tips: &str| LandedBundle { token: Address::ZERO, profit_eth: eth("1"), tips: eth(tips) };
+        policy.on_landed_bundle(&landed_bundle("0.9"));
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), None), 9100);
+
+        // an outlier does not move the median
+        policy.on_landed_bundle(&landed_bundle("0.5"));
+        policy.on_landed_bundle(&landed_bundle("0.6"));
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), None), 6100);
+
+        // the oldest bundle leaves the window
+        policy.on_landed_bundle(&landed_bundle("2"));
+        policy.on_landed_bundle(&landed_bundle("2"));
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), None), 9500);
+
+        // bids stay around the requested share
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), Some(8000)), 9000);
+        policy.on_landed_bundle(&landed_bundle("0.5"));
+        policy.on_landed_bundle(&landed_bundle("0.5"));
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), Some(8000)), 7000);
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), Some(6000)), 5100);
+    }
+
+    #[test]
+    fn test_competition_percentile() {
+        let policy = CompetitionTipsPolicy::new(Arc::new(FixedTipsPolicy { pct: 9000 }), 10, 9000, 0, 0, PCT_DENOMINATOR);
+        for pct in 1..=10 {
+            policy.on_landed_bundle(&LandedBundle { token: Address::ZERO, profit_eth: eth("1"), tips: eth("0.1") * U256::from(pct) });
+        }
+        assert_eq!(policy.competitive_pct(), Some(9000));
+
+        let policy = CompetitionTipsPolicy { percentile_pct: PCT_DENOMINATOR, ..policy };
+        assert_eq!(policy.competitive_pct(), Some(10000));
+    }
+
+    #[test]
+    fn test_tips_config() {
+        let config: TipsConfig = toml::from_str(
+            r#"
+            broadcast_validity_pct = 9000
+            policy = { type = "competition", window = 10, fallback = { type = "curve", slopes = [[1.0, 8000]], randomize_pct = 0 } }
+
+            [tokens]
+            0x0000000000000000000000000000000000000001 = { type = "fixed", pct = 5000 }
+            "#,
+        )
+        .unwrap();
+        let policy = config.build().unwrap();
+
+        let token = Token::new(Address::with_last_byte(1));
+        let swap_line = SwapLine { path: SwapPath::new(vec![token], Vec::<PoolWrapper>::new()), ..SwapLine::default() };
+
+        assert_eq!(policy.broadcast_validity_pct(), 9000);
+        assert_eq!(policy.tips_pct(&Swap::BackrunSwapLine(swap_line), eth("1"), None), 5000);
+        assert_eq!(policy.tips_pct(&Swap::None, eth("1"), None), 8000);
+        assert_eq!(TipsConfig::default().build().unwrap().broadcast_validity_pct(), 8000);
+    }
+
+    #[test]
+    fn test_tips_config_invalid_curve() {
+        let build = |policy: &str| toml::from_str::<TipsConfig>(&format!("policy = {policy}")).unwrap().build();
+
+        assert!(build(r#"{ type = "curve", slopes = [[1.0, 8000], [5.0, 6000]] }"#).is_ok());
+        // share growing with profit
+        assert!(build(r#"{ type = "curve", slopes = [[1.0, 8000], [5.0, 9000]] }"#).is_err());
+        assert!(build(r#"{ type = "curve", start_pct = 7000, slopes = [[1.0, 8000]] }"#).is_err());
+        // profits not increasing
+        assert!(build(r#"{ type = "curve", slopes = [[5.0, 8000], [1.0, 6000]] }"#).is_err());
+        assert!(build(r#"{ type = "curve", start_pct = 12000 }"#).is_err());
+        // nested in the fallback
+        assert!(build(r#"{ type = "competition", fallback = { type = "curve", slopes = [[1.0, 8000], [5.0, 9000]] } }"#).is_err());
+    }
+}
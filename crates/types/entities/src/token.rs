@@ -6,19 +6,81 @@ use std::sync::RwLock;
 
 use alloy_primitives::utils::Unit;
 use alloy_primitives::{Address, I256, U256};
-use loom_defi_address_book::TokenAddress;
 
 const ONE_ETHER: U256 = Unit::ETHER.wei_const();
 
+/// Denominator of transfer taxes, taxes are stored in basis points
+pub const TAX_DENOMINATOR: u32 = 10000;
+
+/// Fee-on-transfer of a token in basis points. `tax_in` is taken when the token is sent to a pool (sell),
+/// `tax_out` when the token is sent from a pool (buy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferTax {
+    pub tax_in: u32,
+    pub tax_out: u32,
+}
+
+impl TransferTax {
+    pub fn new(tax_in: u32, tax_out: u32) -> Self {
+        Self { tax_in: tax_in.min(TAX_DENOMINATOR), tax_out: tax_out.min(TAX_DENOMINATOR) }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.tax_in == 0 && self.tax_out == 0
+    }
+
+    /// Amount received by the pool if `amount` is sent to it
+    pub fn deduct_in(&self, amount: U256) -> U256 {
+        Self::deduct(amount, self.tax_in)
+    }
+
+    /// Amount received from the pool if the pool sends `amount`
+    pub fn deduct_out(&self, amount: U256) -> U256 {
+        Self::deduct(amount, self.tax_out)
+    }
+
+    /// Amount to send to the pool that it receives `amount`. None if the whole transfer is taxed.
+    pub fn gross_up_in(&self, amount: U256) -> Option<U256> {
+        Self::gross_up(amount, self.tax_in)
+    }
+
+    /// Amount the pool has to send that `amount` is received. None if the whole transfer is taxed.
+    pub fn gross_up_out(&self, amount: U256) -> Option<U256> {
+        Self::gross_up(amount, self.tax_out)
+    }
+
+    fn deduct(amount: U256, tax: u32) -> U256 {
+        if tax == 0 {
+            amount
+        } else {
+            amount.saturating_mul(U256::from(TAX_DENOMINATOR - tax)) / U256::from(TAX_DENOMINATOR)
+        }
+    }
+
+    fn gross_up(amount: U256, tax: u32) -> Option<U256> {
+        if tax == 0 {
+            return Some(amount);
+        }
+        let net = U256::from(TAX_DENOMINATOR.checked_sub(tax).filter(|net| *net > 0)?);
+        let numerator = amount.checked_mul(U256::from(TAX_DENOMINATOR))?;
+        // round up, the deducted amount must not be less than the requested one
+        Some(numerator.checked_add(net - U256::from(1))? / net)
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Token {
     address: Address,
     basic: bool,
     middle: bool,
+    wrapped_native: bool,
     decimals: u8,
     name: Option<String>,
     symbol: Option<String>,
     eth_price: Arc<RwLock<Option<U256>>>,
+    tax: TransferTax,
+    rebasing: bool,
+    honeypot: bool,
 }
 
 pub type TokenWrapper = Arc<Token>;
@@ -133,6 +195,46 @@ impl Token {
         self
     }
 
+    /// Mark token as wrapped native token of the chain
+    pub fn set_wrapped_native(&mut self) -> &mut Self {
+        self.wrapped_native = true;
+        self
+    }
+
+    pub fn get_tax(&self) -> TransferTax {
+        self.tax
+    }
+
+    /// Set fee-on-transfer taxes of the token
+    pub fn set_tax(&mut self, tax: TransferTax) -> &mut Self {
+        self.tax = tax;
+        self
+    }
+
+    pub fn is_taxed(&self) -> bool {
+        !self.tax.is_zero()
+    }
+
+    pub fn is_rebasing(&self) -> bool {
+        self.rebasing
+    }
+
+    /// Mark token as rebasing, balances change without transfers
+    pub fn set_rebasing(&mut self) -> &mut Self {
+        self.rebasing = true;
+        self
+    }
+
+    pub fn is_honeypot(&self) -> bool {
+        self.honeypot
+    }
+
+    /// Mark token as honeypot, it can be bought but not sold
+    pub fn set_honeypot(&mut self) -> &mut Self {
+        self.honeypot = true;
+        self
+    }
+
     pub fn to_float(&self, value: U256) -> f64 {
         if self.decimals == 0 {
             0f64
@@ -168,13 +270,14 @@ impl Token {
     }
 
     pub fn is_weth(&self) -> bool {
-        self.address == TokenAddress::WETH
+        self.wrapped_native
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use loom_defi_address_book::TokenAddress;
 
     #[test]
     fn test_to_float() {
@@ -188,4 +291,20 @@ mod test {
 
         println!("{}", weth_token.to_float(one_ether));
     }
+
+    #[test]
+    fn test_transfer_tax() {
+        let tax = TransferTax::new(500, 1000);
+
+        assert_eq!(tax.deduct_in(U256::from(10000)), U256::from(9500));
+        assert_eq!(tax.deduct_out(U256::from(10000)), U256::from(9000));
+        assert_eq!(tax.gross_up_in(U256::from(9500)), Some(U256::from(10000)));
+        assert_eq!(tax.gross_up_out(U256::from(9001)), Some(U256::from(10002)));
+        assert!(tax.deduct_out(tax.gross_up_out(U256::from(9001)).unwrap()) >= U256::from(9001));
+
+        let full_tax = TransferTax::new(TAX_DENOMINATOR, 0);
+        assert_eq!(full_tax.deduct_in(U256::from(10000)), U256::ZERO);
+        assert_eq!(full_tax.gross_up_in(U256::from(1)), None);
+        assert_eq!(full_tax.gross_up_out(U256::from(1)), Some(U256::from(1)));
+    }
 }
@@ -1,11 +1,32 @@
-use alloy_primitives::{BlockHash, BlockNumber, TxHash};
+use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash};
 
 #[derive(Clone, Debug)]
 pub enum MarketEvents {
-    BlockHeaderUpdate { block_number: BlockNumber, block_hash: BlockHash, timestamp: u64, base_fee: u64, next_base_fee: u64 },
-    BlockTxUpdate { block_number: BlockNumber, block_hash: BlockHash },
-    BlockLogsUpdate { block_number: BlockNumber, block_hash: BlockHash },
-    BlockStateUpdate { block_hash: BlockHash },
+    BlockHeaderUpdate {
+        block_number: BlockNumber,
+        block_hash: BlockHash,
+        timestamp: u64,
+        base_fee: u64,
+        next_base_fee: u64,
+    },
+    BlockTxUpdate {
+        block_number: BlockNumber,
+        block_hash: BlockHash,
+    },
+    BlockLogsUpdate {
+        block_number: BlockNumber,
+        block_hash: BlockHash,
+    },
+    BlockStateUpdate {
+        block_hash: BlockHash,
+    },
+    PoolDisabled {
+        pool_address: Address,
+    },
+    /// Swap paths through the pool were restored by `Market::set_pool_ok(true)`
+    PoolEnabled {
+        pool_address: Address,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -1,8 +1,13 @@
 use alloy_primitives::Address;
+use loom_defi_abi::uniswap4::PoolKey;
 use loom_types_entities::PoolClass;
 
 #[derive(Clone, Debug)]
 pub enum Task {
     FetchAndAddPools(Vec<(Address, PoolClass)>),
     FetchStateAndAddPools(Vec<(Address, PoolClass)>),
+    /// Uniswap V4 pools have no own contract, they are loaded by pool manager and pool key
+    FetchAndAddUniswapV4Pools(Vec<(Address, PoolKey)>),
+    /// Fetch state that pools are missing at their current position, e.g. ticks after a large price move
+    FetchPoolsStateUpdate(Vec<Address>),
 }